@@ -1,11 +1,19 @@
-extern crate bindgen;
-extern crate cc;
-
-use std::env;
-use std::path::PathBuf;
-use std::process::Command;
+//! Generates the fbdev bindings with bindgen when the `generate-bindings` feature
+//! is enabled. Otherwise the hand-written `src/linuxfb/bindings.rs` is used, and
+//! no libclang is needed for the target.
 
 fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "generate-bindings")]
+    generate_bindings();
+}
+
+#[cfg(feature = "generate-bindings")]
+fn generate_bindings() {
+    use std::env;
+    use std::path::PathBuf;
+    use std::process::Command;
+
     println!("cargo:rerun-if-changed=bindings.h");
 
     let mut builder = bindgen::Builder::default()
@@ -19,11 +27,21 @@ fn main() {
         .allowlist_var("FBIOPUT_VSCREENINFO")
         .allowlist_var("FBIOGET_FSCREENINFO")
         .allowlist_var("FB_ACTIVATE_NOW")
+        .allowlist_var("FB_ACTIVATE_FORCE")
+        .allowlist_var("FBIOPAN_DISPLAY")
+        .allowlist_var("FB_VMODE_YWRAP")
         .allowlist_var("FBIOBLANK")
         .allowlist_var("FB_BLANK_.*")
         .allowlist_var("KDSETMODE")
         .allowlist_var("KD_TEXT")
-        .allowlist_var("KD_GRAPHICS");
+        .allowlist_var("KD_GRAPHICS")
+        .allowlist_var("KDGKBMODE")
+        .allowlist_var("KDSKBMODE")
+        .allowlist_var("K_RAW")
+        .allowlist_var("K_XLATE")
+        .allowlist_var("K_MEDIUMRAW")
+        .allowlist_var("K_UNICODE")
+        .allowlist_var("K_OFF");
 
     let build_helper = cc::Build::new();
     let compiler = build_helper.get_compiler();
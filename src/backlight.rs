@@ -0,0 +1,75 @@
+//! 屏幕背光控制
+//!
+//! 通过 sysfs 的 `/sys/class/backlight/<设备>/brightness` 接口调节背光亮度。
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// sysfs 背光设备目录
+const BACKLIGHT_CLASS_DIR: &str = "/sys/class/backlight";
+
+/// sysfs 背光设备
+#[derive(Debug)]
+pub(crate) struct Backlight {
+    path: PathBuf,
+    max_brightness: u32,
+}
+
+impl Backlight {
+    /// 打开背光设备；`path` 为 `None` 时使用 `/sys/class/backlight` 下的第一个设备
+    pub fn open(path: Option<&Path>) -> io::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let mut devices: Vec<PathBuf> = fs::read_dir(BACKLIGHT_CLASS_DIR)?
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .collect();
+                devices.sort();
+                devices
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "未找到背光设备"))?
+            }
+        };
+        let max_brightness = read_u32(&path.join("max_brightness"))?;
+        tracing::info!("使用背光设备: {:?} (最大亮度 {})", path, max_brightness);
+        Ok(Self { path, max_brightness })
+    }
+
+    pub fn max_brightness(&self) -> u32 {
+        self.max_brightness
+    }
+
+    pub fn brightness(&self) -> io::Result<u32> {
+        read_u32(&self.path.join("brightness"))
+    }
+
+    pub fn set_brightness(&self, value: u32) -> io::Result<()> {
+        fs::write(self.path.join("brightness"), value.min(self.max_brightness).to_string())
+    }
+
+    /// 把亮度设置为最大亮度的 `percent`%，返回设置的值
+    pub fn set_percent(&self, percent: u8) -> io::Result<u32> {
+        let target = (self.max_brightness as u64 * percent.min(100) as u64 / 100) as u32;
+        self.set_brightness(target)?;
+        Ok(target)
+    }
+
+    /// 按最大亮度的百分比调整亮度，返回调整后的值
+    pub fn adjust_percent(&self, percent: i32) -> io::Result<u32> {
+        let step = (self.max_brightness as i64 * percent as i64 / 100).abs().max(1);
+        let current = self.brightness()? as i64;
+        let target = if percent >= 0 { current + step } else { current - step };
+        let target = target.clamp(0, self.max_brightness as i64) as u32;
+        self.set_brightness(target)?;
+        Ok(target)
+    }
+}
+
+fn read_u32(path: &Path) -> io::Result<u32> {
+    fs::read_to_string(path)?
+        .trim()
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{:?}: {}", path, e)))
+}
@@ -0,0 +1,214 @@
+//! 2D 加速 (blitter)
+//!
+//! 呈现线程把影子缓冲区复制到 Framebuffer、首帧之前填充初始背景色等整帧像素操作都经过 [`Blitter`]。
+//! 默认的 [`CpuBlitter`] 使用 memcpy 和逐像素循环；i.MX6/8 等带有 2D 引擎的 SoC 可以通过
+//! [`LinuxFbPlatformBuilder::with_blitter`](crate::LinuxFbPlatformBuilder::with_blitter)
+//! 换成硬件实现 (启用 `g2d` 特性后的 `G2dBlitter`)，把这些复制从 CPU 上移走。
+//!
+//! 硬件只能访问物理连续的内存，因此 [`Surface`] 带有可选的物理地址：Framebuffer 的后缓冲区由
+//! [`FramebufferDevice::back_buffer_phys_addr`](crate::device::FramebufferDevice::back_buffer_phys_addr)
+//! 提供，影子缓冲区通过 [`Blitter::allocate`] 由 blitter 自己分配。没有物理地址的操作由实现退回 CPU 完成。
+
+use crate::error::Error;
+use crate::mirror::read_pixel;
+use crate::pixels::{PixelAbgr8888, PixelBgra8888, PixelFormat, PixelRgb565, PixelRgba8888};
+use crate::window::Rotation;
+use i_slint_core::platform::software_renderer::TargetPixel;
+use i_slint_core::Color;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "g2d")]
+mod g2d;
+#[cfg(feature = "g2d")]
+pub use g2d::G2dBlitter;
+
+/// 呈现线程与事件循环共享的 blitter
+pub(crate) type SharedBlitter = Arc<Mutex<Box<dyn Blitter>>>;
+
+/// blitter 读取的像素缓冲区，行间没有填充
+#[derive(Debug, Clone, Copy)]
+pub struct Surface<'a> {
+    pub bytes: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    pub format: PixelFormat,
+    /// 首字节的物理地址，只能由 CPU 访问时为 `None`
+    pub phys_addr: Option<u64>,
+}
+
+/// blitter 写入的像素缓冲区，行间没有填充
+#[derive(Debug)]
+pub struct SurfaceMut<'a> {
+    pub bytes: &'a mut [u8],
+    pub width: u32,
+    pub height: u32,
+    pub format: PixelFormat,
+    /// 首字节的物理地址，只能由 CPU 访问时为 `None`
+    pub phys_addr: Option<u64>,
+}
+
+/// 2D 像素操作
+///
+/// 呈现线程模式下 blitter 在呈现线程中使用，因此要求 `Send`。
+pub trait Blitter: Send {
+    /// 把 `src` 复制到 `dst`，同时按 `rotation` 顺时针旋转并转换像素格式 (CSC)
+    ///
+    /// 旋转 90 或 270 度时 `dst` 的宽高与 `src` 互换。呈现线程用它把影子缓冲区复制到后缓冲区
+    /// (不旋转，格式相同)。方法返回时写入必须已经完成。
+    fn blit(&mut self, src: &Surface, dst: &mut SurfaceMut, rotation: Rotation) -> Result<(), Error>;
+
+    /// 用纯色填充 `dst` (忽略颜色的透明度)
+    fn fill(&mut self, dst: &mut SurfaceMut, color: Color) -> Result<(), Error>;
+
+    /// 分配 `len` 字节、内容为 0 的缓冲区，用作影子缓冲区
+    ///
+    /// 默认在堆上分配 (没有物理地址)；硬件实现应分配物理连续的内存，使影子缓冲区可以作为 [`blit`](Self::blit) 的源。
+    fn allocate(&mut self, len: usize) -> Result<BlitBuffer, Error> {
+        Ok(BlitBuffer::heap(len))
+    }
+}
+
+/// 用 CPU 完成所有操作的 blitter (默认)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CpuBlitter;
+
+impl Blitter for CpuBlitter {
+    fn blit(&mut self, src: &Surface, dst: &mut SurfaceMut, rotation: Rotation) -> Result<(), Error> {
+        let (width, height) = rotation.rotate_size(src.width, src.height);
+        if (width, height) != (dst.width, dst.height) {
+            return Err(Error::Other(format!(
+                "blit 尺寸不匹配: {}x{} 旋转 {:?} 后不是 {}x{}",
+                src.width, src.height, rotation, dst.width, dst.height
+            )));
+        }
+        if src.format == dst.format && rotation == Rotation::Rotate0 {
+            dst.bytes.copy_from_slice(src.bytes);
+            return Ok(());
+        }
+        match dst.format {
+            PixelFormat::Abgr8888 => blit_pixels::<PixelAbgr8888>(src, dst.bytes, rotation),
+            PixelFormat::Rgba8888 => blit_pixels::<PixelRgba8888>(src, dst.bytes, rotation),
+            PixelFormat::Bgra8888 => blit_pixels::<PixelBgra8888>(src, dst.bytes, rotation),
+            PixelFormat::Rgb565 => blit_pixels::<PixelRgb565>(src, dst.bytes, rotation),
+            PixelFormat::Unknown => return Err(Error::UnsupportedPixelFormat),
+        }
+        Ok(())
+    }
+
+    fn fill(&mut self, dst: &mut SurfaceMut, color: Color) -> Result<(), Error> {
+        if dst.format == PixelFormat::Unknown {
+            return Err(Error::UnsupportedPixelFormat);
+        }
+        dst.format.fill(dst.bytes, color);
+        Ok(())
+    }
+}
+
+/// 逐像素读取 `src`，转换为 `P` 后写到旋转后的位置
+fn blit_pixels<P: TargetPixel + bytemuck::Pod>(src: &Surface, dst: &mut [u8], rotation: Rotation) {
+    let pixels: &mut [P] = bytemuck::cast_slice_mut(dst);
+    let (dst_width, _) = rotation.rotate_size(src.width, src.height);
+    for y in 0..src.height as i32 {
+        for x in 0..src.width as i32 {
+            let [b, g, r, _] = read_pixel(src.bytes, src.format, (y * src.width as i32 + x) as usize).to_le_bytes();
+            let (dx, dy) = rotation.rotate_point(x, y, src.width, src.height);
+            pixels[dy as usize * dst_width as usize + dx as usize] = P::from_rgb(r, g, b);
+        }
+    }
+}
+
+/// [`Blitter::allocate`] 分配的缓冲区
+pub struct BlitBuffer {
+    storage: Storage,
+    phys_addr: Option<u64>,
+}
+
+enum Storage {
+    Heap(Vec<u8>),
+    Foreign { ptr: *mut u8, len: usize, release: Option<Box<dyn FnOnce() + Send>> },
+}
+
+// SAFETY: 外部内存由 BlitBuffer 独占，`from_raw_parts` 的调用方保证它可以在线程间转移
+unsafe impl Send for BlitBuffer {}
+
+impl BlitBuffer {
+    /// 在堆上分配，内容为 0
+    pub fn heap(len: usize) -> Self {
+        Self { storage: Storage::Heap(vec![0; len]), phys_addr: None }
+    }
+
+    /// 封装 blitter 分配的内存，缓冲区销毁时调用 `release`
+    ///
+    /// # Safety
+    ///
+    /// `ptr` 必须指向 `len` 字节可读写、已初始化的内存，在调用 `release` 之前一直有效且不被其他代码访问。
+    pub unsafe fn from_raw_parts(
+        ptr: *mut u8,
+        len: usize,
+        phys_addr: Option<u64>,
+        release: impl FnOnce() + Send + 'static,
+    ) -> Self {
+        Self { storage: Storage::Foreign { ptr, len, release: Some(Box::new(release)) }, phys_addr }
+    }
+
+    /// 首字节的物理地址，堆上分配的缓冲区为 `None`
+    pub fn phys_addr(&self) -> Option<u64> {
+        self.phys_addr
+    }
+}
+
+impl Deref for BlitBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.storage {
+            Storage::Heap(bytes) => bytes,
+            // SAFETY: 见 `from_raw_parts`
+            Storage::Foreign { ptr, len, .. } => unsafe { std::slice::from_raw_parts(*ptr, *len) },
+        }
+    }
+}
+
+impl DerefMut for BlitBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match &mut self.storage {
+            Storage::Heap(bytes) => bytes,
+            // SAFETY: 见 `from_raw_parts`
+            Storage::Foreign { ptr, len, .. } => unsafe { std::slice::from_raw_parts_mut(*ptr, *len) },
+        }
+    }
+}
+
+impl Drop for BlitBuffer {
+    fn drop(&mut self) {
+        if let Storage::Foreign { release, .. } = &mut self.storage {
+            if let Some(release) = release.take() {
+                release();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_blit_rotates_and_converts() {
+        // 2x1 的 RGB565 源：红、绿
+        let src: Vec<u8> = [0xf800u16, 0x07e0].iter().flat_map(|p| p.to_le_bytes()).collect();
+        let src = Surface { bytes: &src, width: 2, height: 1, format: PixelFormat::Rgb565, phys_addr: None };
+        let mut bytes = [0u8; 8];
+        let mut dst = SurfaceMut { bytes: &mut bytes, width: 1, height: 2, format: PixelFormat::Bgra8888, phys_addr: None };
+        CpuBlitter.blit(&src, &mut dst, Rotation::Rotate90).unwrap();
+        // 顺时针旋转 90 度后红色在上
+        assert_eq!(bytes, [0, 0, 0xff, 0xff, 0, 0xff, 0, 0xff]);
+
+        let mut bytes = [0u8; 4];
+        let mut dst = SurfaceMut { bytes: &mut bytes, width: 2, height: 1, format: PixelFormat::Rgb565, phys_addr: None };
+        assert!(CpuBlitter.blit(&src, &mut dst, Rotation::Rotate270).is_err());
+        CpuBlitter.blit(&src, &mut dst, Rotation::Rotate0).unwrap();
+        assert_eq!(&bytes, src.bytes);
+    }
+}
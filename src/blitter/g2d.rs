@@ -0,0 +1,261 @@
+//! NXP i.MX 的 G2D 接口 (`libg2d.so`)
+//!
+//! 库在运行时通过 `dlopen` 加载，构建时不需要 NXP 的头文件和库。i.MX8 的 GPU 2D 引擎由 imx-gpu-g2d 提供，
+//! i.MX6ULL、i.MX93 等只有 PXP 的芯片由 imx-pxp-g2d 以同样的接口提供。结构体按 g2d.h 定义，
+//! 物理地址为 32 位；超出 4 GiB 的地址和不支持的像素格式退回 CPU 处理。
+
+use super::{BlitBuffer, Blitter, CpuBlitter, Surface, SurfaceMut};
+use crate::error::Error;
+use crate::pixels::PixelFormat;
+use crate::window::Rotation;
+use i_slint_core::Color;
+use std::collections::HashMap;
+use std::ffi::{c_int, c_void, CStr};
+use std::sync::{Arc, Mutex};
+
+/// 依次尝试加载的库名
+const LIBRARIES: [&CStr; 2] = [c"libg2d.so.2", c"libg2d.so"];
+
+// enum g2d_format
+const G2D_RGB565: c_int = 0;
+const G2D_RGBA8888: c_int = 1;
+const G2D_BGRA8888: c_int = 3;
+
+// enum g2d_rotation
+const G2D_ROTATION_0: c_int = 0;
+const G2D_ROTATION_90: c_int = 1;
+const G2D_ROTATION_180: c_int = 2;
+const G2D_ROTATION_270: c_int = 3;
+
+// enum g2d_cache_mode
+const G2D_CACHE_CLEAN: c_int = 0;
+
+#[repr(C)]
+#[derive(Default)]
+struct G2dSurface {
+    format: c_int,
+    planes: [c_int; 3],
+    left: c_int,
+    top: c_int,
+    right: c_int,
+    bottom: c_int,
+    /// 行宽 (像素)
+    stride: c_int,
+    width: c_int,
+    height: c_int,
+    blendfunc: c_int,
+    global_alpha: c_int,
+    /// 0xAABBGGRR
+    clrcolor: c_int,
+    rot: c_int,
+}
+
+#[repr(C)]
+struct G2dBuf {
+    buf_handle: *mut c_void,
+    buf_vaddr: *mut c_void,
+    buf_paddr: c_int,
+    buf_size: c_int,
+}
+
+/// `libg2d` 中用到的函数
+struct Library {
+    handle: *mut c_void,
+    open: unsafe extern "C" fn(*mut *mut c_void) -> c_int,
+    close: unsafe extern "C" fn(*mut c_void) -> c_int,
+    blit: unsafe extern "C" fn(*mut c_void, *mut G2dSurface, *mut G2dSurface) -> c_int,
+    clear: unsafe extern "C" fn(*mut c_void, *mut G2dSurface) -> c_int,
+    finish: unsafe extern "C" fn(*mut c_void) -> c_int,
+    alloc: unsafe extern "C" fn(c_int, c_int) -> *mut G2dBuf,
+    free: unsafe extern "C" fn(*mut G2dBuf) -> c_int,
+    cache_op: unsafe extern "C" fn(*mut G2dBuf, c_int) -> c_int,
+}
+
+// SAFETY: 库句柄和函数指针在 dlclose 之前一直有效，可以在线程间共享
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
+impl Library {
+    // 函数指针的类型由字段决定
+    #[allow(clippy::missing_transmute_annotations)]
+    fn load() -> Result<Self, Error> {
+        // SAFETY: 库名是以 NUL 结尾的字符串
+        let handle = LIBRARIES
+            .iter()
+            .map(|name| unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) })
+            .find(|handle| !handle.is_null())
+            .ok_or_else(|| Error::Other(format!("无法加载 libg2d: {}", dl_error())))?;
+
+        macro_rules! symbol {
+            ($name:literal) => {{
+                // SAFETY: 符号的签名与 g2d.h 一致
+                let symbol = unsafe { libc::dlsym(handle, $name.as_ptr()) };
+                if symbol.is_null() {
+                    // SAFETY: 句柄由 dlopen 返回
+                    unsafe { libc::dlclose(handle) };
+                    return Err(Error::Other(format!("libg2d 缺少符号 {:?}", $name)));
+                }
+                // SAFETY: 同上
+                unsafe { std::mem::transmute::<*mut c_void, _>(symbol) }
+            }};
+        }
+
+        Ok(Self {
+            handle,
+            open: symbol!(c"g2d_open"),
+            close: symbol!(c"g2d_close"),
+            blit: symbol!(c"g2d_blit"),
+            clear: symbol!(c"g2d_clear"),
+            finish: symbol!(c"g2d_finish"),
+            alloc: symbol!(c"g2d_alloc"),
+            free: symbol!(c"g2d_free"),
+            cache_op: symbol!(c"g2d_cache_op"),
+        })
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        // SAFETY: 句柄由 dlopen 返回，所有使用库的对象都已销毁
+        unsafe { libc::dlclose(self.handle) };
+    }
+}
+
+fn dl_error() -> String {
+    // SAFETY: dlerror 返回 NUL 结尾的字符串或空指针
+    let message = unsafe { libc::dlerror() };
+    if message.is_null() {
+        return "未知错误".into();
+    }
+    unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned()
+}
+
+/// 通过 NXP G2D 接口使用 2D 引擎 (GPU 或 PXP) 的 blitter
+///
+/// 需要启用 `g2d` 特性。源和目标都有物理地址且像素格式受支持时交给硬件，否则退回 [`CpuBlitter`]。
+/// Framebuffer 驱动隐藏了物理地址 (`smem_start` 为 0，例如 DRM 的 fbdev 模拟) 时所有操作都由 CPU 完成。
+pub struct G2dBlitter {
+    library: Arc<Library>,
+    handle: *mut c_void,
+    /// 已分配的缓冲区 (物理地址 -> g2d_buf)，用于在硬件读取之前写回 CPU 缓存
+    buffers: Arc<Mutex<HashMap<u64, usize>>>,
+}
+
+// SAFETY: G2D 句柄只在 `&mut self` 的方法中使用
+unsafe impl Send for G2dBlitter {}
+
+impl G2dBlitter {
+    /// 加载 `libg2d.so` 并打开 G2D 设备
+    pub fn open() -> Result<Self, Error> {
+        let library = Arc::new(Library::load()?);
+        let mut handle = std::ptr::null_mut();
+        // SAFETY: handle 指向有效的指针变量
+        if unsafe { (library.open)(&mut handle) } != 0 {
+            return Err(Error::Other("g2d_open 失败".into()));
+        }
+        tracing::info!("已打开 G2D 设备");
+        Ok(Self { library, handle, buffers: Arc::default() })
+    }
+
+    fn run(&mut self, operation: &str, result: c_int) -> Result<(), Error> {
+        // SAFETY: 句柄由 g2d_open 返回
+        if result != 0 || unsafe { (self.library.finish)(self.handle) } != 0 {
+            return Err(Error::Other(format!("{} 失败", operation)));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for G2dBlitter {
+    fn drop(&mut self) {
+        // SAFETY: 句柄由 g2d_open 返回
+        unsafe { (self.library.close)(self.handle) };
+    }
+}
+
+/// 描述可以交给硬件的缓冲区，不满足条件时返回 `None`
+fn g2d_surface(width: u32, height: u32, format: PixelFormat, phys_addr: Option<u64>) -> Option<G2dSurface> {
+    let format = match format {
+        PixelFormat::Rgb565 => G2D_RGB565,
+        PixelFormat::Rgba8888 => G2D_RGBA8888,
+        // 两者的内存序都是 BB GG RR AA
+        PixelFormat::Bgra8888 | PixelFormat::Abgr8888 => G2D_BGRA8888,
+        PixelFormat::Unknown => return None,
+    };
+    // g2d.h 以 int 保存 32 位物理地址
+    let plane = u32::try_from(phys_addr?).ok()? as c_int;
+    Some(G2dSurface {
+        format,
+        planes: [plane, 0, 0],
+        right: width as c_int,
+        bottom: height as c_int,
+        stride: width as c_int,
+        width: width as c_int,
+        height: height as c_int,
+        global_alpha: 0xff,
+        ..Default::default()
+    })
+}
+
+impl Blitter for G2dBlitter {
+    fn blit(&mut self, src: &Surface, dst: &mut SurfaceMut, rotation: Rotation) -> Result<(), Error> {
+        let (Some(mut from), Some(mut to)) = (
+            g2d_surface(src.width, src.height, src.format, src.phys_addr),
+            g2d_surface(dst.width, dst.height, dst.format, dst.phys_addr),
+        ) else {
+            return CpuBlitter.blit(src, dst, rotation);
+        };
+        to.rot = match rotation {
+            Rotation::Rotate0 => G2D_ROTATION_0,
+            Rotation::Rotate90 => G2D_ROTATION_90,
+            Rotation::Rotate180 => G2D_ROTATION_180,
+            Rotation::Rotate270 => G2D_ROTATION_270,
+        };
+        // 影子缓冲区是可缓存的内存，CPU 写入的内容需要先写回
+        let buffer = src.phys_addr.and_then(|addr| self.buffers.lock().unwrap().get(&addr).copied());
+        if let Some(buf) = buffer {
+            // SAFETY: buf 是 g2d_alloc 返回、尚未释放的缓冲区
+            unsafe { (self.library.cache_op)(buf as *mut G2dBuf, G2D_CACHE_CLEAN) };
+        }
+        // SAFETY: 两个 surface 描述的内存在操作完成前保持有效
+        let result = unsafe { (self.library.blit)(self.handle, &mut from, &mut to) };
+        self.run("g2d_blit", result)
+    }
+
+    fn fill(&mut self, dst: &mut SurfaceMut, color: Color) -> Result<(), Error> {
+        let Some(mut surface) = g2d_surface(dst.width, dst.height, dst.format, dst.phys_addr) else {
+            return CpuBlitter.fill(dst, color);
+        };
+        surface.clrcolor = i32::from_le_bytes([color.red(), color.green(), color.blue(), 0xff]);
+        // SAFETY: surface 描述的内存在操作完成前保持有效
+        let result = unsafe { (self.library.clear)(self.handle, &mut surface) };
+        self.run("g2d_clear", result)
+    }
+
+    fn allocate(&mut self, len: usize) -> Result<BlitBuffer, Error> {
+        let size = c_int::try_from(len).map_err(|_| Error::Other(format!("缓冲区过大: {} 字节", len)))?;
+        // SAFETY: 分配可缓存的物理连续内存
+        let buf = unsafe { (self.library.alloc)(size, 1) };
+        if buf.is_null() {
+            tracing::warn!("g2d_alloc 分配 {} 字节失败，影子缓冲区改用普通内存", len);
+            return Ok(BlitBuffer::heap(len));
+        }
+        // SAFETY: g2d_alloc 返回的缓冲区有效
+        let (vaddr, paddr) = unsafe { ((*buf).buf_vaddr as *mut u8, (*buf).buf_paddr as u32 as u64) };
+        // SAFETY: vaddr 指向 len 字节的映射
+        unsafe { std::ptr::write_bytes(vaddr, 0, len) };
+        // 裸指针不是 Send，以地址保存
+        let buf = buf as usize;
+        self.buffers.lock().unwrap().insert(paddr, buf);
+
+        let library = self.library.clone();
+        let buffers = self.buffers.clone();
+        let release = move || {
+            buffers.lock().unwrap().remove(&paddr);
+            // SAFETY: buf 由 g2d_alloc 返回，只释放一次
+            unsafe { (library.free)(buf as *mut G2dBuf) };
+        };
+        // SAFETY: 映射在 release 调用之前一直有效，且只由该缓冲区访问
+        Ok(unsafe { BlitBuffer::from_raw_parts(vaddr, len, Some(paddr), release) })
+    }
+}
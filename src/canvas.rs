@@ -0,0 +1,136 @@
+//! 后端自绘内容使用的简易绘图工具
+//!
+//! 调试叠加层与虚拟键盘都在 Slint 渲染完成后直接向像素缓冲区绘制，
+//! 这里提供它们共用的矩形填充与 3x5 点阵文字绘制。
+
+use crate::window::Rotation;
+use i_slint_core::platform::software_renderer::TargetPixel;
+
+/// 字形宽度 (像素)
+pub const GLYPH_WIDTH: usize = 3;
+/// 字形高度 (像素)
+pub const GLYPH_HEIGHT: usize = 5;
+
+/// 3x5 点阵字形，每行低 3 位有效，最高位在左
+///
+/// 只包含数字、大写字母和少量符号，小写字母按大写显示。
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '^' => [0b010, 0b101, 0b000, 0b000, 0b000],
+        '<' => [0b001, 0b010, 0b100, 0b010, 0b001],
+        _ => [0; GLYPH_HEIGHT],
+    }
+}
+
+/// 以 `scale` 倍放大绘制 `len` 个字符所需的宽度
+pub fn text_width(len: usize, scale: i32) -> i32 {
+    (len as i32 * (GLYPH_WIDTH as i32 + 1) - 1).max(0) * scale
+}
+
+/// 屏幕上的一个矩形区域 (x, y, w, h)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+impl Rect {
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.w && y >= self.y && y < self.y + self.h
+    }
+}
+
+/// 绘制目标：封装像素切片及其几何信息
+///
+/// `width` 与 `height` 为像素缓冲区的尺寸；绘制坐标为窗口坐标，按 `rotation` 旋转到缓冲区。
+pub struct Canvas<'a, P> {
+    pub pixels: &'a mut [P],
+    pub stride: usize,
+    pub width: i32,
+    pub height: i32,
+    pub rotation: Rotation,
+}
+
+impl<P: TargetPixel> Canvas<'_, P> {
+    pub fn fill_rect(&mut self, rect: Rect, color: P) {
+        let (window_width, window_height) = self.rotation.rotate_size(self.width as u32, self.height as u32);
+        let rect = self.rotation.rotate_rect(rect, window_width, window_height);
+        let x0 = rect.x.clamp(0, self.width);
+        let y0 = rect.y.clamp(0, self.height);
+        let x1 = (rect.x + rect.w).clamp(x0, self.width);
+        let y1 = (rect.y + rect.h).clamp(y0, self.height);
+        for y in y0..y1 {
+            let row = y as usize * self.stride;
+            self.pixels[row + x0 as usize..row + x1 as usize].fill(color);
+        }
+    }
+
+    pub fn outline_rect(&mut self, rect: Rect, color: P) {
+        self.fill_rect(Rect { h: 1, ..rect }, color);
+        self.fill_rect(Rect { y: rect.y + rect.h - 1, h: 1, ..rect }, color);
+        self.fill_rect(Rect { w: 1, ..rect }, color);
+        self.fill_rect(Rect { x: rect.x + rect.w - 1, w: 1, ..rect }, color);
+    }
+
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str, scale: i32, color: P) {
+        for (i, c) in text.chars().enumerate() {
+            let gx = x + i as i32 * (GLYPH_WIDTH as i32 + 1) * scale;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        self.fill_rect(
+                            Rect {
+                                x: gx + col as i32 * scale,
+                                y: y + row as i32 * scale,
+                                w: scale,
+                                h: scale,
+                            },
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! 剪贴板
+//!
+//! Framebuffer 环境中没有系统剪贴板服务，因此剪贴板内容保存在进程内。
+//! 如果配置了桥接文件，默认剪贴板的内容会同时写入该文件，并在读取时以文件内容为准，
+//! 从而可以与本机上的其他进程 (脚本、另一个 UI 进程等) 交换文本。
+
+use i_slint_core::platform::Clipboard;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+/// 进程内剪贴板，可选地通过文件与其他进程共享
+pub(crate) struct ClipboardStore {
+    default: RefCell<Option<String>>,
+    selection: RefCell<Option<String>>,
+    bridge_file: Option<PathBuf>,
+}
+
+impl ClipboardStore {
+    pub fn new(bridge_file: Option<PathBuf>) -> Self {
+        Self {
+            default: RefCell::new(None),
+            selection: RefCell::new(None),
+            bridge_file,
+        }
+    }
+
+    pub fn set_text(&self, text: &str, clipboard: Clipboard) {
+        match clipboard {
+            Clipboard::DefaultClipboard => {
+                if let Some(path) = &self.bridge_file {
+                    if let Err(e) = write_atomically(path, text) {
+                        tracing::warn!("无法写入剪贴板文件 {:?}: {}", path, e);
+                    }
+                }
+                *self.default.borrow_mut() = Some(text.to_string());
+            }
+            Clipboard::SelectionClipboard => {
+                *self.selection.borrow_mut() = Some(text.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    pub fn text(&self, clipboard: Clipboard) -> Option<String> {
+        match clipboard {
+            Clipboard::DefaultClipboard => {
+                // 其他进程可能更新了桥接文件，以文件内容为准
+                if let Some(path) = &self.bridge_file {
+                    match std::fs::read_to_string(path) {
+                        Ok(text) => return Some(text),
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => tracing::warn!("无法读取剪贴板文件 {:?}: {}", path, e),
+                    }
+                }
+                self.default.borrow().clone()
+            }
+            Clipboard::SelectionClipboard => self.selection.borrow().clone(),
+            _ => None,
+        }
+    }
+}
+
+/// 先写入临时文件再重命名，避免其他进程读到写了一半的内容
+fn write_atomically(path: &Path, text: &str) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_bridge() {
+        let path = std::env::temp_dir().join(format!("linuxfb-clipboard-{}", std::process::id()));
+        let store = ClipboardStore::new(Some(path.clone()));
+        store.set_text("hello", Clipboard::DefaultClipboard);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello");
+
+        // 其他进程写入的内容可以被读取
+        std::fs::write(&path, "from outside").unwrap();
+        assert_eq!(store.text(Clipboard::DefaultClipboard).as_deref(), Some("from outside"));
+        assert_eq!(store.text(Clipboard::SelectionClipboard), None);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
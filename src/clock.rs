@@ -0,0 +1,157 @@
+//! 感知系统挂起的时钟
+//!
+//! `std::time::Instant` 基于 `CLOCK_MONOTONIC`，在系统挂起期间不会前进。
+//! 设备挂起再恢复后，Slint 的定时器会被推迟整个挂起时长，而事件循环固定的 poll 超时
+//! 也无法感知挂起。这里改用 `CLOCK_BOOTTIME` 作为 Slint 的时间源，
+//! 并使用同一时钟的 timerfd 驱动定时器唤醒；可选的 `CLOCK_BOOTTIME_ALARM`
+//! 还能在定时器到期时把系统从挂起中唤醒 (需要 `CAP_WAKE_ALARM`)。
+//!
+//! 此外提供测试用的虚拟时间：启用后输入处理 (手势识别等) 和 Slint 定时器读取的
+//! "当前时间" 只在显式推进时前进，使依赖时间的逻辑可以确定性地测试。
+
+use std::cell::Cell;
+use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant, SystemTime};
+
+thread_local! {
+    /// 虚拟时间的当前值，`None` 表示使用系统时间
+    static VIRTUAL_NOW: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// 当前时间：启用虚拟时间时返回虚拟时钟的值
+///
+/// 输入处理中与手势相关的计时都应通过该函数读取时间，而不是 `Instant::now()`。
+pub(crate) fn now() -> Instant {
+    VIRTUAL_NOW.with(Cell::get).unwrap_or_else(Instant::now)
+}
+
+/// 从 `earlier` 到 [`now`] 经过的时间
+pub(crate) fn elapsed(earlier: Instant) -> Duration {
+    now().saturating_duration_since(earlier)
+}
+
+/// 把 evdev 事件的时间戳换算为 [`now`] 所在时间轴上的时刻
+///
+/// 输入设备打开时切换为 `CLOCK_MONOTONIC` 时间戳，事件在队列中等待的时长从当前时间中扣除。
+/// 其他时间戳 (切换失败的设备使用的系统时间、程序生成的事件) 无法换算，视为刚刚发生。
+pub(crate) fn event_time(timestamp: SystemTime) -> Instant {
+    let Ok(stamp) = timestamp.duration_since(SystemTime::UNIX_EPOCH) else {
+        return now();
+    };
+    let age = clock_now(libc::CLOCK_MONOTONIC).checked_sub(stamp).unwrap_or_default();
+    now().checked_sub(age).unwrap_or_else(now)
+}
+
+/// 在当前线程启用虚拟时间，起点为当前的系统时间
+pub(crate) fn enable_virtual_time() {
+    VIRTUAL_NOW.with(|now| now.set(Some(Instant::now())));
+}
+
+/// 推进虚拟时间 (未启用时无效)
+pub(crate) fn advance_virtual_time(duration: Duration) {
+    VIRTUAL_NOW.with(|now| now.set(now.get().map(|instant| instant + duration)));
+}
+
+/// 挂起检测的阈值：两种时钟的差值增长超过该值才视为发生过挂起
+const SUSPEND_THRESHOLD: Duration = Duration::from_millis(500);
+
+fn clock_now(clock: libc::clockid_t) -> Duration {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    // SAFETY: ts 是有效的输出参数，CLOCK_BOOTTIME/CLOCK_MONOTONIC 在 Linux 上总是可用
+    unsafe { libc::clock_gettime(clock, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+/// 基于 `CLOCK_BOOTTIME` 的时钟与定时器
+pub(crate) struct SuspendAwareClock {
+    start: Duration,
+    /// 上次检查时 BOOTTIME 与 MONOTONIC 的差值 (即累计挂起时长)
+    suspended: Duration,
+    timer_fd: RawFd,
+}
+
+impl SuspendAwareClock {
+    /// 创建时钟；`wake_alarm` 为 true 时尝试使用可唤醒系统的 `CLOCK_BOOTTIME_ALARM`
+    pub fn new(wake_alarm: bool) -> std::io::Result<Self> {
+        let flags = libc::TFD_NONBLOCK | libc::TFD_CLOEXEC;
+        let mut timer_fd = -1;
+        if wake_alarm {
+            // SAFETY: 参数均为有效的常量
+            timer_fd = unsafe { libc::timerfd_create(libc::CLOCK_BOOTTIME_ALARM, flags) };
+            if timer_fd == -1 {
+                tracing::warn!(
+                    "无法创建 CLOCK_BOOTTIME_ALARM 定时器 (可能缺少 CAP_WAKE_ALARM): {}，定时器将不会唤醒系统",
+                    std::io::Error::last_os_error()
+                );
+            }
+        }
+        if timer_fd == -1 {
+            // SAFETY: 参数均为有效的常量
+            timer_fd = unsafe { libc::timerfd_create(libc::CLOCK_BOOTTIME, flags) };
+        }
+        if timer_fd == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let boot = clock_now(libc::CLOCK_BOOTTIME);
+        Ok(Self {
+            start: boot,
+            suspended: boot.saturating_sub(clock_now(libc::CLOCK_MONOTONIC)),
+            timer_fd,
+        })
+    }
+
+    /// 自创建以来经过的时间 (包含挂起时长)
+    pub fn duration_since_start(&self) -> Duration {
+        clock_now(libc::CLOCK_BOOTTIME).saturating_sub(self.start)
+    }
+
+    /// 检查自上次调用以来系统是否挂起过，返回挂起的时长
+    pub fn check_resume(&mut self) -> Option<Duration> {
+        let suspended = clock_now(libc::CLOCK_BOOTTIME).saturating_sub(clock_now(libc::CLOCK_MONOTONIC));
+        let delta = suspended.saturating_sub(self.suspended);
+        if delta > SUSPEND_THRESHOLD {
+            self.suspended = suspended;
+            Some(delta)
+        } else {
+            None
+        }
+    }
+
+    /// 定时器的文件描述符，到期时可读
+    pub fn fd(&self) -> RawFd {
+        self.timer_fd
+    }
+
+    /// 在 `timeout` 之后触发定时器 (一次性)
+    ///
+    /// 重新设置定时器会清零到期计数，因此无需读取 timerfd。
+    pub fn arm(&self, timeout: Duration) {
+        // it_value 全为 0 会解除定时器，因此至少设置 1ns
+        let timeout = timeout.max(Duration::from_nanos(1));
+        let spec = libc::itimerspec {
+            it_interval: libc::timespec { tv_sec: 0, tv_nsec: 0 },
+            it_value: libc::timespec {
+                tv_sec: timeout.as_secs() as libc::time_t,
+                tv_nsec: timeout.subsec_nanos() as libc::c_long,
+            },
+        };
+        // SAFETY: timer_fd 有效，spec 是有效的输入参数
+        unsafe { libc::timerfd_settime(self.timer_fd, 0, &spec, std::ptr::null_mut()) };
+    }
+
+    /// 解除定时器，事件循环只由其他描述符唤醒
+    pub fn disarm(&self) {
+        // SAFETY: 全为 0 的 itimerspec 是有效的输入参数
+        let spec: libc::itimerspec = unsafe { std::mem::zeroed() };
+        // SAFETY: timer_fd 有效
+        unsafe { libc::timerfd_settime(self.timer_fd, 0, &spec, std::ptr::null_mut()) };
+    }
+}
+
+impl Drop for SuspendAwareClock {
+    fn drop(&mut self) {
+        // SAFETY: timer_fd 由本结构体独占
+        unsafe { libc::close(self.timer_fd) };
+    }
+}
@@ -0,0 +1,708 @@
+//! TOML 配置文件
+//!
+//! 通过 [`LinuxFbPlatformBuilder::from_config_file`] 加载，集成方无需重新编译即可按设备调整
+//! Framebuffer、旋转方向、触摸校准、手势和输入设备过滤等选项。每个配置项对应构建器上的同名方法，
+//! 未出现的配置项保持默认值。未知的配置项和类型不符的值都会报错 (错误信息包含配置项的完整路径)，
+//! 避免拼写错误被悄悄忽略。回调、自定义设备过滤条件等无法用文本表达的选项仍需在代码中设置：
+//! 加载配置后继续链式调用构建器方法即可。
+//!
+//! ```toml
+//! framebuffer = "/dev/fb1"          # with_framebuffer
+//! tty = "/dev/tty3"                 # with_tty，false 表示 without_tty
+//! tty_keyboard_off = true           # with_tty_keyboard_off
+//! rotation = 90                     # with_rotation: 0 / 90 / 180 / 270
+//! physical_size_mm = [154, 86]      # with_physical_size_mm: 宽, 高
+//! scale_factor = 1.5                # with_scale_factor
+//! clear_color = "#202020"           # with_clear_color
+//! window_size = [640, 480]          # with_window_size: 宽, 高
+//! letterbox_color = "#000000"       # with_letterbox_color，false 表示 keep_letterbox_contents
+//! quit_on_last_window_closed = true # with_quit_on_last_window_closed
+//! signal_handler = "quit_event_loop"  # with_signal_policy: none / restore_tty_and_exit / quit_event_loop，布尔值表示 with_signal_handler
+//! vsync = true                      # with_vsync
+//! render_thread = false             # with_render_thread
+//! repaint_buffer = "new"            # with_repaint_buffer_type: new / reused / swapped
+//! debug_overlay = false             # with_debug_overlay
+//! virtual_keyboard = true           # with_virtual_keyboard
+//! backlight = "/sys/class/backlight/backlight"  # with_backlight
+//! display_hotplug = true            # with_display_hotplug，字符串表示 with_display_connector
+//! clipboard_file = "/run/slint-clipboard"       # with_clipboard_file
+//! suspend_aware_timers = true       # with_suspend_aware_timers
+//! wake_alarm = false                # with_wake_alarm
+//! watchdog_ms = 500                 # with_watchdog
+//! virtual_time = false              # with_virtual_time
+//! headless = { width = 800, height = 480, pixel_format = "bgra8888" }  # with_headless
+//! preview = { width = 800, height = 480 }  # with_preview (需要 `preview` 特性)
+//! vnc = "127.0.0.1:5900"            # with_vnc_server (需要 `vnc` 特性)
+//! mjpeg = "0.0.0.0:8080"            # with_mjpeg_server (需要 `mjpeg` 特性)
+//!
+//! [input]                           # input = false 表示 without_input
+//! autodiscovery = true              # with_input_autodiscovery
+//! threaded = true                   # with_threaded_input
+//! whitelist = ["Touchscreen"]       # with_input_whitelist
+//! blacklist = ["Power Button"]      # with_input_blacklist
+//! grab = true                       # with_grab_input_devices
+//! terminal_input = false            # with_terminal_input
+//! ime = true                        # with_ime (需要 `ime` 特性)
+//! console_keymap = "/usr/share/keymaps/de-latin1.map"  # with_console_keymap
+//! record = "/tmp/input.rec"         # with_input_recording (需要 `input-replay` 特性)
+//! replay = "/tmp/input.rec"         # with_input_replay (需要 `input-replay` 特性)
+//! move_throttle_ms = 8             # with_move_throttle，false 表示 disable_move_throttle
+//! pointer_speed = 1.5               # with_pointer_acceleration 的 speed
+//! pointer_acceleration = "adaptive" # with_pointer_acceleration 的 profile: flat / adaptive
+//! natural_scrolling = false         # with_natural_scrolling
+//! scroll_step = 20.0                # with_scroll_step
+//! invert_horizontal_scroll = false  # with_inverted_horizontal_scroll
+//! key_repeat = { delay_ms = 250, period_ms = 33 }  # with_key_repeat，false 表示 disable_key_repeat
+//! close_shortcut = "ctrl+KEY_Q"     # with_close_shortcut: 修饰键 (shift / ctrl / alt / meta) 加 evdev 键名
+//!
+//! [input.keypad_navigation]         # with_keypad_navigation: evdev 键名 = focus_next / focus_previous / activate
+//! KEY_DOWN = "focus_next"
+//! KEY_UP = "focus_previous"
+//! KEY_OK = "activate"
+//!
+//! [touch]
+//! rotation = 90                     # with_touch_rotation
+//! pointercal = "/etc/pointercal"    # with_pointercal
+//! pressure_threshold = 50           # with_pressure_threshold
+//! smoothing = { min_cutoff = 1.0, beta = 0.01 }  # with_touch_smoothing，true 表示使用默认参数
+//! pen_eraser_button = "right"       # with_pen_eraser_button: left / right / middle / none
+//!
+//! [[touch.device]]                  # with_device_transform，可重复
+//! name = "Front Panel"
+//! region = [0, 0, 800, 480]         # x, y, 宽, 高
+//! rotation = 0
+//!
+//! [gestures]                        # with_gestures
+//! long_press_right_click = true
+//! two_finger_scroll = true
+//! kinetic_scroll_friction = 4.0     # false 表示关闭惯性滚动
+//! pinch_zoom = false
+//! multi_finger = false
+//!
+//! [[encoder]]                       # with_encoder，可重复
+//! name = "rotary@"
+//! action = "focus_navigation"       # scroll / focus_navigation
+//!
+//! [beeper]                        # with_beeper
+//! device = "/dev/input/by-path/platform-pcspkr-event-spkr"  # 省略时自动查找
+//! frequency = 2000                  # Hz
+//! duration_ms = 20
+//! on_key = true
+//! on_tap = true
+//!
+//! [system_keys]                     # with_system_key_action: brightness_up / brightness_down / power / sleep
+//! power = "quit"                    # ignore / toggle_blank / quit / close，整数表示按百分比调整背光
+//! brightness_up = 5
+//!
+//! [[power]]                         # with_power_policy，每项为一个阶段，可重复
+//! after_secs = 30                   # 无输入多久后进入
+//! stage = "dim"                     # dim / backlight / blank / powerdown
+//! brightness = 0.5                  # dim 阶段的软件亮度 (0.0 ~ 1.0)
+//!
+//! [[power]]
+//! after_secs = 60
+//! stage = "backlight"
+//! percent = 10                      # backlight 阶段的背光百分比
+//! ```
+//!
+//! [`LinuxFbPlatformBuilder::from_config_file`]: crate::LinuxFbPlatformBuilder::from_config_file
+
+use crate::error::Error;
+use crate::input::{
+    AccelProfile, BeeperConfig, DeviceTransform, EncoderAction, EncoderConfig, GestureConfig, KeypadNavigation,
+    Modifiers, NavigationAction, PointerAcceleration, RawKeyCode, SmoothingConfig, SystemKey,
+};
+use crate::pixels::PixelFormat;
+use crate::platform::{LinuxFbPlatformBuilder, SystemKeyAction};
+use crate::power::{PowerPolicy, PowerStage};
+use crate::signals::SignalPolicy;
+use crate::window::Rotation;
+use i_slint_core::platform::software_renderer::RepaintBufferType;
+use i_slint_core::platform::PointerEventButton;
+use i_slint_core::Color;
+use std::time::Duration;
+use toml_edit::{Document, Item, TableLike};
+
+/// 按配置文本 `text` 依次调用 `builder` 的构建器方法
+pub(crate) fn apply(mut builder: LinuxFbPlatformBuilder, text: &str) -> Result<LinuxFbPlatformBuilder, Error> {
+    let document = Document::parse(text).map_err(|e| Error::Config(e.to_string()))?;
+    for (path, item) in document.iter() {
+        builder = match path {
+            "framebuffer" => builder.with_framebuffer(string(item, path)?),
+            "tty" => match item.as_bool() {
+                Some(false) => builder.without_tty(),
+                _ => builder.with_tty(string(item, path)?),
+            },
+            "tty_keyboard_off" => builder.with_tty_keyboard_off(boolean(item, path)?),
+            "rotation" => builder.with_rotation(rotation(item, path)?),
+            "scale_factor" => builder.with_scale_factor(float(item, path)?),
+            "clear_color" => builder.with_clear_color(color(item, path)?),
+            "window_size" => {
+                let (width, height) = physical_size(item, path)?;
+                builder.with_window_size(width, height)
+            }
+            "letterbox_color" => match item.as_bool() {
+                Some(false) => builder.keep_letterbox_contents(),
+                _ => builder.with_letterbox_color(color(item, path)?),
+            },
+            "quit_on_last_window_closed" => builder.with_quit_on_last_window_closed(boolean(item, path)?),
+            "signal_handler" => match item.as_bool() {
+                Some(enable) => builder.with_signal_handler(enable),
+                None => builder.with_signal_policy(match string(item, path)? {
+                    "none" => SignalPolicy::None,
+                    "restore_tty_and_exit" => SignalPolicy::RestoreTtyAndExit,
+                    "quit_event_loop" => SignalPolicy::QuitEventLoop,
+                    _ => return Err(invalid(path, "布尔值、\"none\"、\"restore_tty_and_exit\" 或 \"quit_event_loop\"")),
+                }),
+            },
+            "physical_size_mm" => {
+                let (width, height) = physical_size(item, path)?;
+                builder.with_physical_size_mm(width, height)
+            }
+            "vsync" => builder.with_vsync(boolean(item, path)?),
+            "render_thread" => builder.with_render_thread(boolean(item, path)?),
+            "repaint_buffer" => builder.with_repaint_buffer_type(match string(item, path)? {
+                "new" => RepaintBufferType::NewBuffer,
+                "reused" => RepaintBufferType::ReusedBuffer,
+                "swapped" => RepaintBufferType::SwappedBuffers,
+                _ => return Err(invalid(path, "\"new\"、\"reused\" 或 \"swapped\"")),
+            }),
+            "debug_overlay" => builder.with_debug_overlay(boolean(item, path)?),
+            "virtual_keyboard" => builder.with_virtual_keyboard(boolean(item, path)?),
+            "backlight" => builder.with_backlight(string(item, path)?),
+            "display_hotplug" => match item.as_bool() {
+                Some(enable) => builder.with_display_hotplug(enable),
+                None => builder.with_display_connector(string(item, path)?),
+            },
+            "clipboard_file" => builder.with_clipboard_file(string(item, path)?),
+            "suspend_aware_timers" => builder.with_suspend_aware_timers(boolean(item, path)?),
+            "wake_alarm" => builder.with_wake_alarm(boolean(item, path)?),
+            "watchdog_ms" => builder.with_watchdog(millis(item, path)?),
+            "virtual_time" => builder.with_virtual_time(boolean(item, path)?),
+            "headless" => {
+                let (width, height, format) = headless(item, path)?;
+                builder.with_headless(width, height, format)
+            }
+            #[cfg(feature = "preview")]
+            "preview" => {
+                let table = table(item, path)?;
+                check_keys(table, path, &["width", "height"])?;
+                let size = |key| required(table, path, key).and_then(|item| unsigned(item, &join(path, key)));
+                builder.with_preview(size("width")?, size("height")?)
+            }
+            #[cfg(feature = "vnc")]
+            "vnc" => builder.with_vnc_server(socket_addr(item, path)?),
+            #[cfg(feature = "mjpeg")]
+            "mjpeg" => builder.with_mjpeg_server(socket_addr(item, path)?),
+            "input" => match item.as_bool() {
+                Some(false) => builder.without_input(),
+                _ => apply_input(builder, table(item, path)?, path)?,
+            },
+            "touch" => apply_touch(builder, table(item, path)?, path)?,
+            "gestures" => builder.with_gestures(gestures(table(item, path)?, path)?),
+            "encoder" => {
+                for (i, encoder) in tables(item, path)?.into_iter().enumerate() {
+                    builder = builder.with_encoder(encoder_config(encoder, &format!("{}[{}]", path, i))?);
+                }
+                builder
+            }
+            "beeper" => builder.with_beeper(beeper(table(item, path)?, path)?),
+            "power" => {
+                let mut policy = PowerPolicy::new();
+                for (i, stage) in tables(item, path)?.into_iter().enumerate() {
+                    let (after, stage) = power_stage(stage, &format!("{}[{}]", path, i))?;
+                    policy = policy.with_stage(after, stage);
+                }
+                builder.with_power_policy(policy)
+            }
+            "system_keys" => {
+                for (key, item) in table(item, path)?.iter() {
+                    let path = join(path, key);
+                    let system_key = match key {
+                        "brightness_up" => SystemKey::BrightnessUp,
+                        "brightness_down" => SystemKey::BrightnessDown,
+                        "power" => SystemKey::Power,
+                        "sleep" => SystemKey::Sleep,
+                        _ => return Err(unknown(&path)),
+                    };
+                    builder = builder.with_system_key_action(system_key, system_key_action(item, &path)?);
+                }
+                builder
+            }
+            _ => return Err(unknown(path)),
+        };
+    }
+    Ok(builder)
+}
+
+/// `[input]` 表
+fn apply_input(
+    mut builder: LinuxFbPlatformBuilder,
+    entries: &dyn TableLike,
+    section: &str,
+) -> Result<LinuxFbPlatformBuilder, Error> {
+    // 速度与加速曲线共同组成 `PointerAcceleration`，读完整个表后再设置
+    let mut acceleration: Option<PointerAcceleration> = None;
+    for (key, item) in entries.iter() {
+        let path = &join(section, key);
+        builder = match key {
+            "autodiscovery" => builder.with_input_autodiscovery(boolean(item, path)?),
+            "threaded" => builder.with_threaded_input(boolean(item, path)?),
+            "whitelist" => builder.with_input_whitelist(string_list(item, path)?),
+            "blacklist" => builder.with_input_blacklist(string_list(item, path)?),
+            "grab" => builder.with_grab_input_devices(boolean(item, path)?),
+            "terminal_input" => builder.with_terminal_input(boolean(item, path)?),
+            #[cfg(feature = "ime")]
+            "ime" => builder.with_ime(boolean(item, path)?),
+            "console_keymap" => builder.with_console_keymap(string(item, path)?),
+            #[cfg(feature = "input-replay")]
+            "record" => builder.with_input_recording(string(item, path)?),
+            #[cfg(feature = "input-replay")]
+            "replay" => builder.with_input_replay(string(item, path)?),
+            "pointer_speed" => {
+                acceleration.get_or_insert_with(Default::default).speed = float(item, path)?;
+                builder
+            }
+            "pointer_acceleration" => {
+                acceleration.get_or_insert_with(Default::default).profile = match string(item, path)? {
+                    "flat" => AccelProfile::Flat,
+                    "adaptive" => AccelProfile::Adaptive,
+                    _ => return Err(invalid(path, "\"flat\" 或 \"adaptive\"")),
+                };
+                builder
+            }
+            "move_throttle_ms" => match item.as_bool() {
+                Some(false) => builder.disable_move_throttle(),
+                _ => builder.with_move_throttle(millis(item, path)?),
+            },
+            "natural_scrolling" => builder.with_natural_scrolling(boolean(item, path)?),
+            "scroll_step" => builder.with_scroll_step(float(item, path)?),
+            "invert_horizontal_scroll" => builder.with_inverted_horizontal_scroll(boolean(item, path)?),
+            "key_repeat" => match item.as_bool() {
+                Some(false) => builder.disable_key_repeat(),
+                _ => {
+                    let repeat = table_of(item, path, "false 或包含 delay_ms、period_ms 的表")?;
+                    let delay = required(repeat, path, "delay_ms").and_then(|i| millis(i, &join(path, "delay_ms")))?;
+                    let period = required(repeat, path, "period_ms").and_then(|i| millis(i, &join(path, "period_ms")))?;
+                    check_keys(repeat, path, &["delay_ms", "period_ms"])?;
+                    builder.with_key_repeat(delay, period)
+                }
+            },
+            "close_shortcut" => {
+                let (key, modifiers) = shortcut(item, path)?;
+                builder.with_close_shortcut(key, modifiers)
+            }
+            "keypad_navigation" => {
+                let mut navigation = KeypadNavigation::new();
+                for (key, item) in table(item, path)?.iter() {
+                    let path = join(path, key);
+                    let code = key.parse::<RawKeyCode>().map_err(|_| invalid(&path, "evdev 键名 (例如 KEY_DOWN)"))?;
+                    let action = match string(item, &path)? {
+                        "focus_next" => NavigationAction::FocusNext,
+                        "focus_previous" => NavigationAction::FocusPrevious,
+                        "activate" => NavigationAction::Activate,
+                        _ => return Err(invalid(&path, "\"focus_next\"、\"focus_previous\" 或 \"activate\"")),
+                    };
+                    navigation = navigation.with_key(code, action);
+                }
+                builder.with_keypad_navigation(navigation)
+            }
+            _ => return Err(unknown(path)),
+        };
+    }
+    if let Some(acceleration) = acceleration {
+        builder = builder.with_pointer_acceleration(acceleration);
+    }
+    Ok(builder)
+}
+
+/// `[touch]` 表
+fn apply_touch(
+    mut builder: LinuxFbPlatformBuilder,
+    entries: &dyn TableLike,
+    section: &str,
+) -> Result<LinuxFbPlatformBuilder, Error> {
+    for (key, item) in entries.iter() {
+        let path = &join(section, key);
+        builder = match key {
+            "rotation" => builder.with_touch_rotation(rotation(item, path)?),
+            "pointercal" => builder.with_pointercal(string(item, path)?),
+            "pressure_threshold" => {
+                let threshold = integer(item, path)?;
+                builder.with_pressure_threshold(i32::try_from(threshold).map_err(|_| invalid(path, "32 位整数"))?)
+            }
+            "smoothing" => match item.as_bool() {
+                Some(true) => builder.with_touch_smoothing(SmoothingConfig::default()),
+                Some(false) => builder,
+                None => {
+                    let smoothing = table_of(item, path, "布尔值或包含 min_cutoff、beta、derivative_cutoff 的表")?;
+                    let mut config = SmoothingConfig::default();
+                    for (key, item) in smoothing.iter() {
+                        let path = &join(path, key);
+                        match key {
+                            "min_cutoff" => config.min_cutoff = float(item, path)?,
+                            "beta" => config.beta = float(item, path)?,
+                            "derivative_cutoff" => config.derivative_cutoff = float(item, path)?,
+                            _ => return Err(unknown(path)),
+                        }
+                    }
+                    builder.with_touch_smoothing(config)
+                }
+            },
+            "pen_eraser_button" => builder.with_pen_eraser_button(match string(item, path)? {
+                "left" => Some(PointerEventButton::Left),
+                "right" => Some(PointerEventButton::Right),
+                "middle" => Some(PointerEventButton::Middle),
+                "none" => None,
+                _ => return Err(invalid(path, "\"left\"、\"right\"、\"middle\" 或 \"none\"")),
+            }),
+            "device" => {
+                for (i, device) in tables(item, path)?.into_iter().enumerate() {
+                    builder = builder.with_device_transform(device_transform(device, &format!("{}[{}]", path, i))?);
+                }
+                builder
+            }
+            _ => return Err(unknown(path)),
+        };
+    }
+    Ok(builder)
+}
+
+/// `[gestures]` 表，未出现的开关保持默认值
+fn gestures(entries: &dyn TableLike, section: &str) -> Result<GestureConfig, Error> {
+    let mut config = GestureConfig::default();
+    for (key, item) in entries.iter() {
+        let path = &join(section, key);
+        match key {
+            "long_press_right_click" => config.long_press_right_click = boolean(item, path)?,
+            "two_finger_scroll" => config.two_finger_scroll = boolean(item, path)?,
+            "kinetic_scroll_friction" => {
+                config.kinetic_scroll_friction = match item.as_bool() {
+                    Some(false) => None,
+                    _ => Some(float(item, path)?),
+                }
+            }
+            "pinch_zoom" => config.pinch_zoom = boolean(item, path)?,
+            "multi_finger" => config.multi_finger = boolean(item, path)?,
+            _ => return Err(unknown(path)),
+        }
+    }
+    Ok(config)
+}
+
+/// `[[touch.device]]` 的一项
+fn device_transform(table: &dyn TableLike, path: &str) -> Result<DeviceTransform, Error> {
+    check_keys(table, path, &["name", "region", "rotation"])?;
+    let mut transform = DeviceTransform::new(string(required(table, path, "name")?, &join(path, "name"))?);
+    if let Some(item) = table.get("region") {
+        let region_path = join(path, "region");
+        let values = item
+            .as_array()
+            .filter(|values| values.len() == 4)
+            .ok_or_else(|| invalid(&region_path, "[x, y, 宽, 高]"))?;
+        let values: Vec<i64> = values
+            .iter()
+            .map(|value| value.as_integer().ok_or_else(|| invalid(&region_path, "[x, y, 宽, 高]")))
+            .collect::<Result<_, _>>()?;
+        let coordinate = |value: i64| i32::try_from(value).map_err(|_| invalid(&region_path, "32 位整数坐标"));
+        let size = |value: i64| u32::try_from(value).map_err(|_| invalid(&region_path, "非负的宽高"));
+        transform = transform.with_region(coordinate(values[0])?, coordinate(values[1])?, size(values[2])?, size(values[3])?);
+    }
+    if let Some(item) = table.get("rotation") {
+        transform = transform.with_rotation(rotation(item, &join(path, "rotation"))?);
+    }
+    Ok(transform)
+}
+
+/// `[[encoder]]` 的一项
+fn encoder_config(table: &dyn TableLike, path: &str) -> Result<EncoderConfig, Error> {
+    check_keys(table, path, &["name", "action"])?;
+    let name = string(required(table, path, "name")?, &join(path, "name"))?;
+    let action = match table.get("action") {
+        None => EncoderAction::default(),
+        Some(item) => match string(item, &join(path, "action"))? {
+            "scroll" => EncoderAction::Scroll,
+            "focus_navigation" => EncoderAction::FocusNavigation,
+            _ => return Err(invalid(&join(path, "action"), "\"scroll\" 或 \"focus_navigation\"")),
+        },
+    };
+    Ok(EncoderConfig::new(name, action))
+}
+
+/// `[beeper]` 表
+fn beeper(entries: &dyn TableLike, section: &str) -> Result<BeeperConfig, Error> {
+    let mut config = BeeperConfig::default();
+    for (key, item) in entries.iter() {
+        let path = join(section, key);
+        match key {
+            "device" => config.device = Some(string(item, &path)?.into()),
+            "frequency" => config.frequency = unsigned(item, &path)?,
+            "duration_ms" => config.duration = millis(item, &path)?,
+            "on_key" => config.on_key = boolean(item, &path)?,
+            "on_tap" => config.on_tap = boolean(item, &path)?,
+            _ => return Err(unknown(&path)),
+        }
+    }
+    Ok(config)
+}
+
+/// `[[power]]` 的一项
+fn power_stage(table: &dyn TableLike, path: &str) -> Result<(Duration, PowerStage), Error> {
+    check_keys(table, path, &["after_secs", "stage", "brightness", "percent"])?;
+    let after = Duration::from_secs(unsigned(required(table, path, "after_secs")?, &join(path, "after_secs"))?.into());
+    let stage = match string(required(table, path, "stage")?, &join(path, "stage"))? {
+        "dim" => {
+            let brightness = match table.get("brightness") {
+                Some(item) => float(item, &join(path, "brightness"))?,
+                None => 0.5,
+            };
+            PowerStage::Dim { brightness }
+        }
+        "backlight" => {
+            let percent_path = join(path, "percent");
+            let percent = unsigned(required(table, path, "percent")?, &percent_path)?;
+            let percent = u8::try_from(percent).map_err(|_| invalid(&percent_path, "0 ~ 100 的百分比"))?;
+            PowerStage::Backlight { percent }
+        }
+        "blank" => PowerStage::Blank,
+        "powerdown" => PowerStage::Powerdown,
+        _ => return Err(invalid(&join(path, "stage"), "\"dim\"、\"backlight\"、\"blank\" 或 \"powerdown\"")),
+    };
+    Ok((after, stage))
+}
+
+fn system_key_action(item: &Item, path: &str) -> Result<SystemKeyAction, Error> {
+    if let Some(percent) = item.as_integer() {
+        let percent = i32::try_from(percent).map_err(|_| invalid(path, "亮度调整百分比"))?;
+        return Ok(SystemKeyAction::AdjustBacklight(percent));
+    }
+    match item.as_str() {
+        Some("ignore") => Ok(SystemKeyAction::Ignore),
+        Some("toggle_blank") => Ok(SystemKeyAction::ToggleBlank),
+        Some("quit") => Ok(SystemKeyAction::Quit),
+        Some("close") => Ok(SystemKeyAction::RequestClose),
+        _ => Err(invalid(path, "\"ignore\"、\"toggle_blank\"、\"quit\"、\"close\" 或亮度调整百分比")),
+    }
+}
+
+/// `"ctrl+KEY_Q"` 形式的快捷键：修饰键加 evdev 键名
+fn shortcut(item: &Item, path: &str) -> Result<(RawKeyCode, Modifiers), Error> {
+    let expected = "快捷键 (例如 \"ctrl+KEY_Q\")";
+    let mut parts: Vec<&str> = string(item, path)?.split('+').map(str::trim).collect();
+    let key = parts.pop().and_then(|key| key.parse::<RawKeyCode>().ok()).ok_or_else(|| invalid(path, expected))?;
+    let mut modifiers = Modifiers::default();
+    for part in parts {
+        let modifier = match part {
+            "shift" => &mut modifiers.shift,
+            "ctrl" => &mut modifiers.control,
+            "alt" => &mut modifiers.alt,
+            "meta" => &mut modifiers.meta,
+            _ => return Err(invalid(path, expected)),
+        };
+        *modifier = true;
+    }
+    Ok((key, modifiers))
+}
+
+fn headless(item: &Item, path: &str) -> Result<(u32, u32, PixelFormat), Error> {
+    let table = table(item, path)?;
+    check_keys(table, path, &["width", "height", "pixel_format"])?;
+    let width = unsigned(required(table, path, "width")?, &join(path, "width"))?;
+    let height = unsigned(required(table, path, "height")?, &join(path, "height"))?;
+    let format = match table.get("pixel_format") {
+        None => PixelFormat::Bgra8888,
+        Some(item) => match string(item, &join(path, "pixel_format"))? {
+            "rgb565" => PixelFormat::Rgb565,
+            "rgba8888" => PixelFormat::Rgba8888,
+            "bgra8888" => PixelFormat::Bgra8888,
+            "abgr8888" => PixelFormat::Abgr8888,
+            _ => {
+                return Err(invalid(
+                    &join(path, "pixel_format"),
+                    "\"rgb565\"、\"rgba8888\"、\"bgra8888\" 或 \"abgr8888\"",
+                ))
+            }
+        },
+    };
+    Ok((width, height, format))
+}
+
+#[cfg(any(feature = "vnc", feature = "mjpeg"))]
+fn socket_addr(item: &Item, path: &str) -> Result<std::net::SocketAddr, Error> {
+    string(item, path)?.parse().map_err(|_| invalid(path, "监听地址 (例如 \"127.0.0.1:5900\")"))
+}
+
+fn join(section: &str, key: &str) -> String {
+    format!("{}.{}", section, key)
+}
+
+fn unknown(path: &str) -> Error {
+    Error::Config(format!("未知的配置项 `{}`", path))
+}
+
+fn invalid(path: &str, expected: &str) -> Error {
+    Error::Config(format!("配置项 `{}` 应为{}", path, expected))
+}
+
+/// 检查内联表中没有 `allowed` 以外的键
+fn check_keys(table: &dyn TableLike, path: &str, allowed: &[&str]) -> Result<(), Error> {
+    match table.iter().find(|(key, _)| !allowed.contains(key)) {
+        Some((key, _)) => Err(unknown(&join(path, key))),
+        None => Ok(()),
+    }
+}
+
+fn required<'a>(table: &'a dyn TableLike, path: &str, key: &str) -> Result<&'a Item, Error> {
+    table.get(key).ok_or_else(|| Error::Config(format!("缺少配置项 `{}`", join(path, key))))
+}
+
+fn table<'a>(item: &'a Item, path: &str) -> Result<&'a dyn TableLike, Error> {
+    table_of(item, path, "表")
+}
+
+fn table_of<'a>(item: &'a Item, path: &str, expected: &str) -> Result<&'a dyn TableLike, Error> {
+    item.as_table_like().ok_or_else(|| invalid(path, expected))
+}
+
+/// 表数组 (`[[name]]`) 或内联表组成的数组
+fn tables<'a>(item: &'a Item, path: &str) -> Result<Vec<&'a dyn TableLike>, Error> {
+    if let Some(array) = item.as_array_of_tables() {
+        return Ok(array.iter().map(|table| table as &dyn TableLike).collect());
+    }
+    item.as_array()
+        .and_then(|array| {
+            array.iter().map(|value| value.as_inline_table().map(|table| table as &dyn TableLike)).collect()
+        })
+        .ok_or_else(|| invalid(path, "表数组"))
+}
+
+fn boolean(item: &Item, path: &str) -> Result<bool, Error> {
+    item.as_bool().ok_or_else(|| invalid(path, "布尔值"))
+}
+
+fn integer(item: &Item, path: &str) -> Result<i64, Error> {
+    item.as_integer().ok_or_else(|| invalid(path, "整数"))
+}
+
+fn unsigned(item: &Item, path: &str) -> Result<u32, Error> {
+    u32::try_from(integer(item, path)?).map_err(|_| invalid(path, "非负整数"))
+}
+
+/// 浮点数 (也接受整数写法)
+fn float(item: &Item, path: &str) -> Result<f32, Error> {
+    item.as_float()
+        .or_else(|| item.as_integer().map(|value| value as f64))
+        .map(|value| value as f32)
+        .ok_or_else(|| invalid(path, "数字"))
+}
+
+fn millis(item: &Item, path: &str) -> Result<Duration, Error> {
+    let ms = u64::try_from(integer(item, path)?).map_err(|_| invalid(path, "非负的毫秒数"))?;
+    Ok(Duration::from_millis(ms))
+}
+
+fn string<'a>(item: &'a Item, path: &str) -> Result<&'a str, Error> {
+    item.as_str().ok_or_else(|| invalid(path, "字符串"))
+}
+
+fn string_list(item: &Item, path: &str) -> Result<Vec<String>, Error> {
+    item.as_array()
+        .and_then(|array| array.iter().map(|value| value.as_str().map(String::from)).collect())
+        .ok_or_else(|| invalid(path, "字符串数组"))
+}
+
+/// `"#rrggbb"` 形式的颜色
+fn color(item: &Item, path: &str) -> Result<Color, Error> {
+    let text = string(item, path)?;
+    text.strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .map(|rgb| Color::from_argb_encoded(0xff00_0000 | rgb))
+        .ok_or_else(|| invalid(path, "\"#rrggbb\" 形式的颜色"))
+}
+
+/// `[宽, 高]` (毫米)
+fn physical_size(item: &Item, path: &str) -> Result<(u32, u32), Error> {
+    let size = |value: &toml_edit::Value| value.as_integer().and_then(|value| u32::try_from(value).ok());
+    match item.as_array().map(|array| array.iter().map(size).collect::<Vec<_>>()).as_deref() {
+        Some([Some(width), Some(height)]) => Ok((*width, *height)),
+        _ => Err(invalid(path, "[宽, 高] (非负整数)")),
+    }
+}
+
+fn rotation(item: &Item, path: &str) -> Result<Rotation, Error> {
+    match item.as_integer() {
+        Some(0) => Ok(Rotation::Rotate0),
+        Some(90) => Ok(Rotation::Rotate90),
+        Some(180) => Ok(Rotation::Rotate180),
+        Some(270) => Ok(Rotation::Rotate270),
+        _ => Err(invalid(path, "0、90、180 或 270")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_config() {
+        let text = r#"
+            tty_keyboard_off = true
+            rotation = 90
+            physical_size_mm = [154, 86]
+            scale_factor = 1.5
+            clear_color = '#202020'
+            window_size = [640, 480]
+            letterbox_color = false
+            quit_on_last_window_closed = false
+            signal_handler = "quit_event_loop"
+            vsync = true
+            repaint_buffer = "new"
+            display_hotplug = "/sys/class/drm/card0-HDMI-A-1"
+
+            [input]
+            whitelist = ["Touchscreen"]
+            pointer_speed = 2
+            move_throttle_ms = false
+            key_repeat = false
+            close_shortcut = "ctrl+KEY_Q"
+
+            [touch]
+            pressure_threshold = 50
+            smoothing = { beta = 0.5 }
+
+            [[touch.device]]
+            name = "Side Panel"
+            region = [800, 0, 224, 480]
+
+            [gestures]
+            kinetic_scroll_friction = false
+
+            [[encoder]]
+            name = "rotary@"
+            action = "focus_navigation"
+
+            [beeper]
+            frequency = 1000
+            on_key = false
+
+            [[power]]
+            after_secs = 30
+            stage = "dim"
+
+            [[power]]
+            after_secs = 120
+            stage = "blank"
+        "#;
+        assert!(apply(LinuxFbPlatformBuilder::new(), text).is_ok());
+
+        assert!(apply(LinuxFbPlatformBuilder::new(), "input = false").is_ok());
+
+        let error = apply(LinuxFbPlatformBuilder::new(), "[touch]\nrotaton = 90").err().unwrap();
+        assert!(error.to_string().contains("touch.rotaton"));
+        let error = apply(LinuxFbPlatformBuilder::new(), "rotation = 45").err().unwrap();
+        assert!(error.to_string().contains("`rotation`"));
+        let error = apply(LinuxFbPlatformBuilder::new(), "[[touch.device]]\nregion = [0, 0, 1, 1]").err().unwrap();
+        assert!(error.to_string().contains("touch.device[0].name"));
+    }
+}
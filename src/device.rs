@@ -0,0 +1,281 @@
+//! Framebuffer 设备抽象
+//!
+//! 窗口与呈现逻辑只通过 [`FramebufferDevice`] 访问显示设备：获取尺寸和像素格式、
+//! 写入后缓冲区、等待 VSync 和翻转。默认实现 [`FbdevDevice`] 基于 Linux fbdev 的双缓冲，
+//! [`MockFramebuffer`] 是纯内存实现，可以在没有 `/dev/fb0` 的开发机上测试渲染流程。
+//! 其他显示通道 (例如 SPI 屏幕驱动) 实现该 trait 后通过
+//! [`LinuxFbPlatformBuilder::with_framebuffer_device`](crate::LinuxFbPlatformBuilder::with_framebuffer_device) 接入。
+//! 该方法也接受应用已经打开并配置好 (色深、时序等) 的 [`Framebuffer`]，见 [`IntoFramebufferDevice`]。
+//!
+//! 没有可直接写入的缓冲区、只能按区域传输像素的屏幕 (例如由用户态驱动通过 SPI 刷新的小尺寸 LCD)
+//! 实现 [`DisplaySink`]，通过 [`with_display_sink`](crate::LinuxFbPlatformBuilder::with_display_sink) 接入。
+
+pub use crate::canvas::Rect;
+use crate::error::Error;
+use crate::linuxfb::{double::Buffer, Framebuffer};
+use crate::pixels::PixelFormat;
+use std::os::unix::io::AsRawFd;
+use std::path::PathBuf;
+
+/// 双缓冲显示设备
+///
+/// 呈现线程模式下设备会被移动到独立线程，因此要求 `Send`。
+pub trait FramebufferDevice: Send {
+    /// 单个缓冲区的尺寸 (像素)，即屏幕分辨率
+    fn size(&self) -> (u32, u32);
+
+    /// 缓冲区的像素格式
+    fn pixel_format(&self) -> PixelFormat;
+
+    /// 当前的后缓冲区，长度为 宽 x 高 x 每像素字节数 (行间没有填充)
+    ///
+    /// 写入的内容在调用 [`flip`](Self::flip) 之后才会显示。
+    fn back_buffer(&mut self) -> &mut [u8];
+
+    /// 交换前后缓冲区，显示后缓冲区的内容
+    fn flip(&mut self) -> Result<(), Error>;
+
+    /// 等待垂直同步，驱动不支持时返回错误 (调用方随后改用软件帧节奏)
+    fn wait_for_vsync(&mut self) -> Result<(), Error>;
+
+    /// 屏幕刷新率 (Hz)，未知时返回 `None`
+    ///
+    /// 只作为刷新间隔的初始估计，平台随后按实际呈现的间隔或 [`vblank_count`](Self::vblank_count) 实测。
+    fn refresh_rate(&self) -> Option<f32> {
+        None
+    }
+
+    /// 驱动统计的垂直消隐次数，不支持时返回 `None`
+    ///
+    /// 即使无法等待 VSync，平台也能据此测量实际的刷新间隔。
+    fn vblank_count(&mut self) -> Option<u32> {
+        None
+    }
+
+    /// 后缓冲区的物理地址，供硬件 [`Blitter`](crate::blitter::Blitter) 使用；未知时返回 `None`
+    fn back_buffer_phys_addr(&self) -> Option<u64> {
+        None
+    }
+
+    /// 关闭并重新打开设备、重新映射显存，用于在翻转失败 (例如线缆抖动后的 EIO) 后恢复
+    ///
+    /// 重新打开后缓冲区内容未定义，调用方需要重新渲染整帧。默认实现不支持重新打开。
+    fn reopen(&mut self) -> Result<(), Error> {
+        Err(Error::Other("显示设备不支持重新打开".into()))
+    }
+}
+
+/// 按区域接收像素的显示输出
+///
+/// 平台把 Slint 渲染到内存中的整帧缓冲区 (保留上一帧内容)，每帧结束后对每个重绘区域调用一次
+/// [`write_region`](Self::write_region)，最后调用 [`flush`](Self::flush)。输入、旋转、虚拟键盘等
+/// 其余功能与 Framebuffer 相同。写入在事件循环线程中进行，呈现线程设置对输出无效。
+pub trait DisplaySink: Send {
+    /// 屏幕分辨率 (像素)
+    fn size(&self) -> (u32, u32);
+
+    /// 缓冲区的像素格式
+    fn pixel_format(&self) -> PixelFormat;
+
+    /// 把 `rect` 区域的像素写入屏幕
+    ///
+    /// `frame` 为整帧缓冲区，行宽为屏幕宽度，行间没有填充。
+    fn write_region(&mut self, rect: Rect, frame: &[u8]) -> Result<(), Error>;
+
+    /// 本帧的所有区域写入完毕 (例如结束一次传输或触发屏幕刷新)
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// 可以交给 [`with_framebuffer_device`](crate::LinuxFbPlatformBuilder::with_framebuffer_device) 的显示设备
+///
+/// 包括任意 [`FramebufferDevice`] 实现，以及应用已经打开的 [`Framebuffer`]：后者在创建窗口时
+/// 才被接管，保留应用设置的 vinfo (色深、时序等)，只把虚拟高度调整为双缓冲所需的两倍屏幕高度。
+pub trait IntoFramebufferDevice: sealed::Sealed {}
+
+impl<T: sealed::Sealed> IntoFramebufferDevice for T {}
+
+pub(crate) mod sealed {
+    use super::{DisplaySink, FramebufferDevice};
+    use crate::linuxfb::Framebuffer;
+
+    /// 构建器持有的显示设备
+    pub enum DisplaySource {
+        Device(Box<dyn FramebufferDevice>),
+        /// 应用打开的 Framebuffer，创建窗口时再配置双缓冲
+        Framebuffer(Box<Framebuffer>),
+        /// 按区域接收像素的输出
+        Sink(Box<dyn DisplaySink>),
+    }
+
+    pub trait Sealed {
+        fn into_source(self) -> DisplaySource;
+    }
+
+    impl<T: FramebufferDevice + 'static> Sealed for T {
+        fn into_source(self) -> DisplaySource {
+            DisplaySource::Device(Box::new(self))
+        }
+    }
+
+    impl Sealed for Framebuffer {
+        fn into_source(self) -> DisplaySource {
+            DisplaySource::Framebuffer(Box::new(self))
+        }
+    }
+}
+
+/// 已打开的 Framebuffer 对应的设备路径 (用于错误信息)，无法确定时返回文件描述符路径
+pub(crate) fn framebuffer_path(fb: &Framebuffer) -> PathBuf {
+    let fd_path = PathBuf::from(format!("/proc/self/fd/{}", fb.file.as_raw_fd()));
+    std::fs::read_link(&fd_path).unwrap_or(fd_path)
+}
+
+/// 基于 Linux fbdev 双缓冲 (虚拟高度为两倍屏幕高度，通过偏移翻转) 的设备
+pub struct FbdevDevice {
+    buffer: Buffer,
+    pixel_format: PixelFormat,
+    /// 设备路径，重新打开时使用
+    path: PathBuf,
+    /// 驱动提供 vblank 计数 (第一次读取失败后不再尝试)
+    vblank_supported: bool,
+}
+
+impl FbdevDevice {
+    /// 接管已打开的 Framebuffer 并配置双缓冲
+    pub fn new(fb: Framebuffer) -> Result<Self, Error> {
+        let pixel_format = PixelFormat::from_fb_info(&fb.vinfo);
+        let path = framebuffer_path(&fb);
+        let buffer = Buffer::new(fb)?;
+        if buffer.is_ywrap() {
+            tracing::debug!("{:?} 支持 FB_VMODE_YWRAP，翻转时使用 ywrap", path);
+        }
+        Ok(Self { buffer, pixel_format, path, vblank_supported: true })
+    }
+}
+
+impl FramebufferDevice for FbdevDevice {
+    fn size(&self) -> (u32, u32) {
+        (self.buffer.width, self.buffer.height)
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    fn back_buffer(&mut self) -> &mut [u8] {
+        self.buffer.as_mut_slice()
+    }
+
+    fn flip(&mut self) -> Result<(), Error> {
+        Ok(self.buffer.flip()?)
+    }
+
+    fn wait_for_vsync(&mut self) -> Result<(), Error> {
+        Ok(self.buffer.wait_for_vsync()?)
+    }
+
+    fn refresh_rate(&self) -> Option<f32> {
+        self.buffer.refresh_rate()
+    }
+
+    fn vblank_count(&mut self) -> Option<u32> {
+        if !self.vblank_supported {
+            return None;
+        }
+        let count = self.buffer.vblank_count().ok().flatten();
+        self.vblank_supported = count.is_some();
+        count
+    }
+
+    fn back_buffer_phys_addr(&self) -> Option<u64> {
+        self.buffer.back_buffer_phys_addr()
+    }
+
+    fn reopen(&mut self) -> Result<(), Error> {
+        let fb = Framebuffer::new(&self.path).map_err(|e| Error::from_framebuffer(&self.path, e))?;
+        let pixel_format = PixelFormat::from_fb_info(&fb.vinfo);
+        let buffer = Buffer::new(fb).map_err(|e| Error::from_double_buffer(&self.path, e.into()))?;
+        // 渲染器按原来的尺寸和格式工作，驱动在重新打开后换了模式就无法继续
+        if (buffer.width, buffer.height) != self.size() || pixel_format != self.pixel_format {
+            return Err(Error::Other(format!(
+                "重新打开 {:?} 后显示模式改变: {}x{} {:?}",
+                self.path, buffer.width, buffer.height, pixel_format
+            )));
+        }
+        // 替换时旧的映射和文件描述符随之释放
+        self.buffer = buffer;
+        Ok(())
+    }
+}
+
+/// 纯内存的双缓冲设备，记录翻转次数
+///
+/// 与真实设备一样在两个缓冲区之间交替，渲染器的部分重绘逻辑因此得到同样的检验。
+pub struct MockFramebuffer {
+    width: u32,
+    height: u32,
+    pixel_format: PixelFormat,
+    /// 两个缓冲区，`buffers[back]` 为后缓冲区
+    buffers: [Vec<u8>; 2],
+    back: usize,
+    /// 翻转次数
+    pub flips: usize,
+}
+
+impl MockFramebuffer {
+    pub fn new(width: u32, height: u32, pixel_format: PixelFormat) -> Self {
+        let len = width as usize * height as usize * pixel_format.bytes_per_pixel();
+        Self { width, height, pixel_format, buffers: [vec![0; len], vec![0; len]], back: 0, flips: 0 }
+    }
+
+    /// 当前显示的 (前缓冲区) 内容
+    pub fn front_buffer(&self) -> &[u8] {
+        &self.buffers[1 - self.back]
+    }
+}
+
+impl FramebufferDevice for MockFramebuffer {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    fn back_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffers[self.back]
+    }
+
+    fn flip(&mut self) -> Result<(), Error> {
+        self.back = 1 - self.back;
+        self.flips += 1;
+        Ok(())
+    }
+
+    fn wait_for_vsync(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn refresh_rate(&self) -> Option<f32> {
+        Some(60.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_flip_swaps_buffers() {
+        let mut device = MockFramebuffer::new(2, 1, PixelFormat::Rgb565);
+        assert_eq!(device.back_buffer().len(), 4);
+        device.back_buffer().fill(0xff);
+        device.flip().unwrap();
+        assert_eq!(device.front_buffer(), [0xff; 4]);
+        assert_eq!(device.back_buffer(), [0; 4]);
+        assert_eq!(device.flips, 1);
+    }
+}
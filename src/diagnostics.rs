@@ -0,0 +1,541 @@
+//! 运行环境诊断
+//!
+//! 现场排查后端启动失败时，[`diagnose`] 汇总 Framebuffer、TTY 和输入设备的状态，
+//! 并列出发现的问题 (权限不足、像素格式不受支持等)。报告实现了 `Display`，
+//! 可以直接打印或写入日志：
+//!
+//! ```no_run
+//! println!("{}", slint_backend_linuxfb::diagnose());
+//! ```
+//!
+//! 安装时的验证工具则可以用 [`LinuxFbPlatformBuilder::probe`] 按实际配置试探设备，
+//! 结果为 [`ProbeReport`]。
+//!
+//! [`LinuxFbPlatformBuilder::probe`]: crate::LinuxFbPlatformBuilder::probe
+
+use crate::error::Error;
+use crate::input::{self, InputConfig, InputDeviceInfo};
+use crate::linuxfb::{self, fbio, Framebuffer, PixelLayout};
+use crate::pixels::PixelFormat;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 诊断报告
+#[derive(Debug, Clone)]
+pub struct DiagnosticsReport {
+    /// 默认使用的 Framebuffer (环境变量 `SLINT_FRAMEBUFFER`，或按 [`select_framebuffer`] 自动选择的设备)
+    pub default_framebuffer: PathBuf,
+    pub framebuffers: Vec<FramebufferReport>,
+    pub ttys: Vec<TtyReport>,
+    pub input_devices: Vec<InputDeviceReport>,
+    /// 发现的问题及建议
+    pub problems: Vec<String>,
+}
+
+/// 单个 Framebuffer 设备
+#[derive(Debug, Clone)]
+pub struct FramebufferReport {
+    pub path: PathBuf,
+    /// 设备信息，打开或查询失败时为错误描述
+    pub info: Result<FramebufferInfo, String>,
+}
+
+/// Framebuffer 的固定信息 (finfo) 与可变信息 (vinfo)
+#[derive(Debug, Clone)]
+pub struct FramebufferInfo {
+    /// 驱动标识
+    pub id: String,
+    pub size: (u32, u32),
+    pub virtual_size: (u32, u32),
+    /// 物理尺寸 (毫米)，驱动未报告时为 0
+    pub physical_size_mm: (u32, u32),
+    pub bits_per_pixel: u32,
+    pub pixel_layout: PixelLayout,
+    /// 根据像素布局识别的格式，`Unknown` 表示后端不支持
+    pub pixel_format: PixelFormat,
+    /// 每行的字节数 (包括填充)
+    pub line_length: u32,
+    /// 显存大小 (字节)
+    pub memory_size: u32,
+    pub refresh_rate: Option<f32>,
+}
+
+/// 单个 TTY 设备
+#[derive(Debug, Clone)]
+pub struct TtyReport {
+    pub path: PathBuf,
+    /// 无法以读写方式打开时的错误描述
+    pub error: Option<String>,
+}
+
+/// 单个输入设备
+#[derive(Debug, Clone)]
+pub struct InputDeviceReport {
+    pub path: PathBuf,
+    /// 设备名称与功能，打开失败时为错误描述
+    pub info: Result<InputDeviceInfo, String>,
+}
+
+/// 按构建器配置试探设备的结果，见 [`LinuxFbPlatformBuilder::probe`](crate::LinuxFbPlatformBuilder::probe)
+#[derive(Debug)]
+pub struct ProbeReport {
+    /// 打开的 Framebuffer，使用自定义显示设备或无头模式时为 `None`
+    pub framebuffer: Option<PathBuf>,
+    /// 屏幕分辨率
+    pub size: (u32, u32),
+    /// 识别的像素格式，`Unknown` 表示后端不支持
+    pub pixel_format: PixelFormat,
+    pub refresh_rate: Option<f32>,
+    /// 物理尺寸 (毫米)，构建器的 `with_physical_size_mm` 优先于驱动报告的值，未知时为 `None`
+    pub physical_size_mm: Option<(u32, u32)>,
+    /// 双缓冲平移 (panning) 试探的结果，失败时的错误带有建议 (见 [`Error::hint`])
+    pub panning: Result<(), Error>,
+    /// 按配置会使用的输入设备
+    pub input_devices: Vec<InputDeviceReport>,
+}
+
+impl ProbeReport {
+    /// 显示设备能否被后端使用 (像素格式受支持且双缓冲平移可用)
+    pub fn is_usable(&self) -> bool {
+        self.pixel_format != PixelFormat::Unknown && self.panning.is_ok()
+    }
+}
+
+/// 识别已打开的 Framebuffer 的分辨率与像素格式并试探双缓冲平移
+///
+/// 试探使用复制的文件描述符，`fb` 本身 (包括其中缓存的 vinfo) 不变。
+pub(crate) fn probe_display(fb: &Framebuffer) -> Result<ProbeReport, Error> {
+    let path = crate::device::framebuffer_path(fb);
+    let file = fb.file.try_clone().map_err(|e| Error::from_framebuffer(&path, e.into()))?;
+    let mut probe = Framebuffer { file, finfo: fb.finfo.clone(), vinfo: fb.vinfo.clone() };
+    let panning = check_panning(&mut probe).map_err(|e| Error::from_double_buffer(&path, e.into()));
+    Ok(ProbeReport {
+        framebuffer: Some(path),
+        size: fb.get_size(),
+        pixel_format: PixelFormat::from_fb_info(&fb.vinfo),
+        refresh_rate: fb.get_refresh_rate(),
+        physical_size_mm: Some(fb.get_physical_size()).filter(|&(width, height)| width != 0 && height != 0),
+        panning,
+        input_devices: Vec::new(),
+    })
+}
+
+/// 按配置识别输入设备
+pub(crate) fn probe_input_devices(config: &InputConfig) -> Vec<InputDeviceReport> {
+    input::probe_configured_devices(config)
+        .into_iter()
+        .map(|(path, info)| InputDeviceReport { path, info: info.map_err(|e| e.to_string()) })
+        .collect()
+}
+
+/// 按双缓冲的方式配置虚拟分辨率并翻到另一页再翻回，最后恢复原来的设置
+///
+/// 先按 `ypanstep` 和显存大小检查，不满足时不修改设备。
+/// 翻页前把当前画面复制到另一页，试探过程中屏幕内容不变。
+fn check_panning(fb: &mut Framebuffer) -> Result<(), linuxfb::Error> {
+    let original = fb.vinfo.clone();
+    let (width, height) = fb.get_size();
+    linuxfb::double::check_panning(&fb.finfo, height)?;
+    let mut pan = || -> Result<(), linuxfb::Error> {
+        fb.set_virtual_size(width, height * 2)?;
+        let visible = if fb.get_offset().1 == height { height } else { 0 };
+        let hidden = height - visible;
+        let page = (width * height * fb.get_bytes_per_pixel()) as usize;
+        let mut map = fb.map()?;
+        let (src, dst) = if visible == 0 { (0, page) } else { (page, 0) };
+        map.copy_within(src..src + page, dst);
+        drop(map);
+        fb.set_offset(0, hidden)?;
+        fb.set_offset(0, visible)
+    };
+    let result = pan();
+    let mut vinfo = original;
+    vinfo.activate_now();
+    let restored = fbio::put_vscreeninfo(&fb.file, &mut vinfo);
+    result?;
+    Ok(restored?)
+}
+
+/// 检查运行环境，返回诊断报告
+///
+/// 只读取设备信息，不修改 Framebuffer 和 TTY 的状态，可以在后端运行时调用。
+pub fn diagnose() -> DiagnosticsReport {
+    let mut problems = Vec::new();
+
+    let default_framebuffer = std::env::var("SLINT_FRAMEBUFFER")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(select_framebuffer)
+        .unwrap_or_else(|| PathBuf::from("/dev/fb0"));
+    let mut fb_paths = list_framebuffers();
+    if default_framebuffer.exists() && !fb_paths.contains(&default_framebuffer) {
+        fb_paths.insert(0, default_framebuffer.clone());
+    }
+    if fb_paths.is_empty() {
+        problems.push("未找到 Framebuffer 设备 (/dev/fb*)：内核可能未启用 fbdev 驱动或 DRM 的 fbdev 模拟".into());
+    } else if !default_framebuffer.exists() {
+        problems.push(format!(
+            "默认的 Framebuffer {:?} 不存在，请通过 SLINT_FRAMEBUFFER 或 with_framebuffer 指定",
+            default_framebuffer
+        ));
+    }
+    let framebuffers: Vec<_> = fb_paths.into_iter().map(|path| probe_framebuffer(path, &mut problems)).collect();
+
+    let mut tty_paths: Vec<PathBuf> = std::env::var("SLINT_TTY_DEVICE").map(PathBuf::from).into_iter().collect();
+    tty_paths.extend([PathBuf::from("/dev/tty1"), PathBuf::from("/dev/tty0")]);
+    let ttys: Vec<_> = tty_paths
+        .into_iter()
+        .map(|path| {
+            let error = OpenOptions::new().read(true).write(true).open(&path).err();
+            if error.as_ref().is_some_and(is_permission_denied) {
+                problems.push(format!("没有 {:?} 的读写权限：请以 root 运行或把用户加入 tty 组", path));
+            }
+            TtyReport { path, error: error.map(|e| e.to_string()) }
+        })
+        .collect();
+    if ttys.iter().all(|tty| tty.error.is_some()) {
+        problems.push("无法打开任何 TTY：fbcon 的光标和控制台输出可能覆盖界面".into());
+    }
+
+    let input_devices: Vec<_> = input::probe_devices()
+        .into_iter()
+        .map(|(path, info)| {
+            if info.as_ref().is_err_and(is_permission_denied) {
+                problems.push(format!("没有 {:?} 的读取权限：请以 root 运行或把用户加入 input 组", path));
+            }
+            InputDeviceReport { path, info: info.map_err(|e| e.to_string()) }
+        })
+        .collect();
+    let recognized = input_devices.iter().any(|device| {
+        device.info.as_ref().is_ok_and(|info| info.capabilities != input::DeviceCapabilities::default())
+    });
+    if !recognized {
+        problems.push("没有识别到可用的输入设备 (触摸屏、鼠标、键盘等)".into());
+    }
+
+    DiagnosticsReport { default_framebuffer, framebuffers, ttys, input_devices, problems }
+}
+
+/// 由 fb 驱动管理的设备节点；`/proc/devices` 不可用时按名称查找 `/dev/fb*`
+fn list_framebuffers() -> Vec<PathBuf> {
+    let mut paths = Framebuffer::list().unwrap_or_default();
+    if paths.is_empty() {
+        if let Ok(entries) = fs::read_dir("/dev") {
+            paths = entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("fb")))
+                .collect();
+        }
+    }
+    paths.sort();
+    paths
+}
+
+/// 在所有 Framebuffer 中选择最适合显示界面的一个 (没有指定设备时使用)
+///
+/// 例如 fb0 是 vfb 等虚拟设备、真正的屏幕是 fb1 时，选择 fb1。按以下条件依次降低得分：
+/// 虚拟设备 (驱动标识包含 "Virtual"、"vfb" 或 "dummy")、像素格式不受支持、处于挂起状态，
+/// 显存能容纳两帧、驱动报告了物理尺寸时提高得分。得分相同时选择编号较小的设备，
+/// 无法打开或分辨率为 0 的设备不参与选择。没有可用设备时返回 `None`。
+pub fn select_framebuffer() -> Option<PathBuf> {
+    let mut best: Option<(i32, PathBuf, FramebufferInfo)> = None;
+    for path in list_framebuffers() {
+        let info = match Framebuffer::new(&path) {
+            Ok(fb) => framebuffer_info(&fb),
+            Err(e) => {
+                tracing::debug!("Framebuffer 候选 {:?} 无法打开: {}", path, e);
+                continue;
+            }
+        };
+        let Some(score) = score_framebuffer(&info, is_suspended(&path)) else {
+            continue;
+        };
+        tracing::debug!(
+            "Framebuffer 候选 {:?} \"{}\" {}x{} {:?}: 得分 {}",
+            path, info.id, info.size.0, info.size.1, info.pixel_format, score
+        );
+        if best.as_ref().is_none_or(|(best_score, ..)| score > *best_score) {
+            best = Some((score, path, info));
+        }
+    }
+    let (_, path, info) = best?;
+    tracing::info!(
+        "自动选择 Framebuffer {:?} (\"{}\", {}x{}, {:?})",
+        path, info.id, info.size.0, info.size.1, info.pixel_format
+    );
+    Some(path)
+}
+
+/// Framebuffer 作为界面显示设备的得分，分辨率为 0 时返回 `None`
+fn score_framebuffer(info: &FramebufferInfo, suspended: bool) -> Option<i32> {
+    if info.size.0 == 0 || info.size.1 == 0 {
+        return None;
+    }
+    let id = info.id.to_lowercase();
+    let mut score = 0;
+    if ["virtual", "vfb", "dummy"].iter().any(|name| id.contains(name)) {
+        score -= 100;
+    }
+    if info.pixel_format == PixelFormat::Unknown {
+        score -= 50;
+    }
+    if suspended {
+        score -= 20;
+    }
+    if u64::from(info.memory_size) >= u64::from(info.line_length) * u64::from(info.size.1) * 2 {
+        score += 10;
+    }
+    if info.physical_size_mm != (0, 0) {
+        score += 5;
+    }
+    Some(score)
+}
+
+/// sysfs 中的 `state` 为 1 表示设备处于挂起状态
+fn is_suspended(path: &Path) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+    let state = Path::new("/sys/class/graphics").join(name).join("state");
+    fs::read_to_string(state).is_ok_and(|state| state.trim() == "1")
+}
+
+fn framebuffer_info(fb: &Framebuffer) -> FramebufferInfo {
+    let (width, height) = fb.vinfo.size_in_pixels();
+    FramebufferInfo {
+        id: fb.finfo.id(),
+        size: (width, height),
+        virtual_size: fb.vinfo.virtual_size(),
+        physical_size_mm: fb.vinfo.size_in_mm(),
+        bits_per_pixel: fb.vinfo.internal.bits_per_pixel,
+        pixel_layout: fb.vinfo.pixel_layout(),
+        pixel_format: PixelFormat::from_fb_info(&fb.vinfo),
+        line_length: fb.finfo.line_length(),
+        memory_size: fb.finfo.memory_size(),
+        refresh_rate: fb.vinfo.refresh_rate(),
+    }
+}
+
+fn probe_framebuffer(path: PathBuf, problems: &mut Vec<String>) -> FramebufferReport {
+    let fb = match Framebuffer::new(&path) {
+        Ok(fb) => fb,
+        Err(e) => {
+            if matches!(&e, linuxfb::Error::Io(io) if is_permission_denied(io)) {
+                problems.push(format!("没有 {:?} 的读写权限：请以 root 运行或把用户加入 video 组", path));
+            }
+            return FramebufferReport { path, info: Err(e.to_string()) };
+        }
+    };
+    let info = framebuffer_info(&fb);
+    problems.extend(check_framebuffer(&path, &info));
+    FramebufferReport { path, info: Ok(info) }
+}
+
+/// 检查后端对 Framebuffer 的要求：支持的像素格式、行间无填充、显存可容纳两帧
+fn check_framebuffer(path: &Path, info: &FramebufferInfo) -> Vec<String> {
+    let mut problems = Vec::new();
+    if info.pixel_format == PixelFormat::Unknown {
+        problems.push(format!(
+            "{:?} 的像素格式不受支持 ({} 位, {:?})：后端支持 RGB565 和 32 位 RGBA/BGRA/ABGR",
+            path, info.bits_per_pixel, info.pixel_layout
+        ));
+    }
+    let row_bytes = info.size.0 * info.bits_per_pixel / 8;
+    if info.line_length != row_bytes {
+        problems.push(format!(
+            "{:?} 的行跨度为 {} 字节，与宽度对应的 {} 字节不一致：画面会错位",
+            path, info.line_length, row_bytes
+        ));
+    }
+    let double_buffer = u64::from(info.line_length) * u64::from(info.size.1) * 2;
+    if u64::from(info.memory_size) < double_buffer {
+        problems.push(format!(
+            "{:?} 的显存 ({} 字节) 不足以容纳两帧 ({} 字节)：双缓冲翻转会失败，可尝试增大驱动的显存或降低分辨率",
+            path, info.memory_size, double_buffer
+        ));
+    }
+    problems
+}
+
+fn is_permission_denied(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::PermissionDenied
+}
+
+/// 设备类型的描述，例如 "触摸屏, 键盘"
+fn device_kinds(info: &InputDeviceInfo) -> String {
+    let caps = info.capabilities;
+    let kinds: Vec<_> = [
+        (caps.touchscreen, "触摸屏"),
+        (caps.touchpad, "触摸板"),
+        (caps.pen, "手写笔"),
+        (caps.mouse, "鼠标"),
+        (caps.keyboard, "键盘"),
+        (caps.encoder, "旋转编码器"),
+        (caps.switches, "开关"),
+    ]
+    .into_iter()
+    .filter_map(|(present, kind)| present.then_some(kind))
+    .collect();
+    if kinds.is_empty() { "不支持".to_string() } else { kinds.join(", ") }
+}
+
+impl fmt::Display for DiagnosticsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Framebuffer (默认 {:?}):", self.default_framebuffer)?;
+        if self.framebuffers.is_empty() {
+            writeln!(f, "  (无)")?;
+        }
+        for fb in &self.framebuffers {
+            match &fb.info {
+                Ok(info) => {
+                    writeln!(
+                        f,
+                        "  {:?} \"{}\": {}x{} (虚拟 {}x{}), {} 位 {:?}, 行跨度 {}, 显存 {}, 物理尺寸 {}x{}mm, 刷新率 {}",
+                        fb.path,
+                        info.id,
+                        info.size.0,
+                        info.size.1,
+                        info.virtual_size.0,
+                        info.virtual_size.1,
+                        info.bits_per_pixel,
+                        info.pixel_format,
+                        info.line_length,
+                        info.memory_size,
+                        info.physical_size_mm.0,
+                        info.physical_size_mm.1,
+                        info.refresh_rate.map_or("未知".to_string(), |hz| format!("{:.1}Hz", hz)),
+                    )?;
+                }
+                Err(e) => writeln!(f, "  {:?}: {}", fb.path, e)?,
+            }
+        }
+
+        writeln!(f, "TTY:")?;
+        for tty in &self.ttys {
+            match &tty.error {
+                None => writeln!(f, "  {:?}: 可读写", tty.path)?,
+                Some(e) => writeln!(f, "  {:?}: {}", tty.path, e)?,
+            }
+        }
+
+        writeln!(f, "输入设备:")?;
+        if self.input_devices.is_empty() {
+            writeln!(f, "  (无)")?;
+        }
+        for device in &self.input_devices {
+            match &device.info {
+                Ok(info) => writeln!(f, "  {:?} \"{}\": {}", device.path, info.name, device_kinds(info))?,
+                Err(e) => writeln!(f, "  {:?}: {}", device.path, e)?,
+            }
+        }
+
+        if self.problems.is_empty() {
+            writeln!(f, "未发现问题")
+        } else {
+            writeln!(f, "问题:")?;
+            for problem in &self.problems {
+                writeln!(f, "  - {}", problem)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for ProbeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.framebuffer {
+            Some(path) => write!(f, "Framebuffer {:?}", path)?,
+            None => write!(f, "显示设备")?,
+        }
+        writeln!(
+            f,
+            ": {}x{}, {:?}, 刷新率 {}",
+            self.size.0,
+            self.size.1,
+            self.pixel_format,
+            self.refresh_rate.map_or("未知".to_string(), |hz| format!("{:.1}Hz", hz)),
+        )?;
+        if let Some((width, height)) = self.physical_size_mm {
+            writeln!(f, "物理尺寸: {}x{} mm", width, height)?;
+        }
+        match &self.panning {
+            Ok(()) => writeln!(f, "双缓冲平移: 可用")?,
+            Err(e) => writeln!(f, "双缓冲平移: {}", e)?,
+        }
+        writeln!(f, "输入设备:")?;
+        if self.input_devices.is_empty() {
+            writeln!(f, "  (无)")?;
+        }
+        for device in &self.input_devices {
+            match &device.info {
+                Ok(info) => writeln!(f, "  {:?} \"{}\": {}", device.path, info.name, device_kinds(info))?,
+                Err(e) => writeln!(f, "  {:?}: {}", device.path, e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linuxfb::PixelLayoutChannel;
+
+    #[test]
+    fn test_check_framebuffer() {
+        let channel = |offset| PixelLayoutChannel { offset, length: 8, msb_right: false };
+        let mut info = FramebufferInfo {
+            id: "test".into(),
+            size: (800, 480),
+            virtual_size: (800, 480),
+            physical_size_mm: (0, 0),
+            bits_per_pixel: 32,
+            pixel_layout: PixelLayout { red: channel(16), green: channel(8), blue: channel(0), alpha: channel(24) },
+            pixel_format: PixelFormat::Bgra8888,
+            line_length: 800 * 4,
+            memory_size: 800 * 4 * 480 * 2,
+            refresh_rate: None,
+        };
+        assert!(check_framebuffer(Path::new("/dev/fb0"), &info).is_empty());
+
+        // 行尾有填充，且显存只够一帧
+        info.line_length = 832 * 4;
+        info.memory_size = 832 * 4 * 480;
+        assert_eq!(check_framebuffer(Path::new("/dev/fb0"), &info).len(), 2);
+    }
+
+    #[test]
+    fn test_score_framebuffer() {
+        let channel = |offset| PixelLayoutChannel { offset, length: 8, msb_right: false };
+        let lcd = FramebufferInfo {
+            id: "mxsfb-drm".into(),
+            size: (800, 480),
+            virtual_size: (800, 960),
+            physical_size_mm: (154, 86),
+            bits_per_pixel: 32,
+            pixel_layout: PixelLayout { red: channel(16), green: channel(8), blue: channel(0), alpha: channel(24) },
+            pixel_format: PixelFormat::Bgra8888,
+            line_length: 800 * 4,
+            memory_size: 800 * 4 * 480 * 2,
+            refresh_rate: Some(60.0),
+        };
+        let vfb = FramebufferInfo { id: "Virtual FB".into(), physical_size_mm: (0, 0), ..lcd.clone() };
+        let lcd_score = score_framebuffer(&lcd, false).unwrap();
+        assert!(lcd_score > score_framebuffer(&vfb, false).unwrap());
+        assert!(lcd_score > score_framebuffer(&lcd, true).unwrap());
+        assert_eq!(score_framebuffer(&FramebufferInfo { size: (0, 0), ..lcd }, false), None);
+    }
+
+    #[test]
+    fn test_probe_headless() {
+        let builder = crate::LinuxFbPlatformBuilder::new().with_headless(320, 240, PixelFormat::Rgb565);
+        let report = builder.probe().unwrap();
+        assert_eq!(report.size, (320, 240));
+        assert!(report.is_usable());
+        // 无头模式关闭了输入设备自动发现
+        assert!(report.input_devices.is_empty());
+    }
+}
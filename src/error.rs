@@ -1,9 +1,23 @@
 //! 定义库的统一错误类型。
 
 use i_slint_core::api::PlatformError;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use thiserror::Error;
 
+const PERMISSION_HINT: &str =
+    "以 root 运行，或把用户加入设备所属的组 (Framebuffer 通常为 video，TTY 为 tty，输入设备为 input)";
+const BUSY_HINT: &str = "检查是否有其他图形程序 (X11、Weston 或另一个 Slint 实例) 正在占用显示设备";
+const NO_FRAMEBUFFER_HINT: &str =
+    "确认内核启用了 fbdev 驱动或 DRM 的 fbdev 模拟 (CONFIG_DRM_FBDEV_EMULATION)，或通过 SLINT_FRAMEBUFFER 指定其他设备";
+const PANNING_HINT: &str =
+    "驱动的显存需要容纳两帧，DRM fbdev 模拟可通过内核参数 drm_kms_helper.drm_fbdev_overalloc=200 分配";
+const PIXEL_FORMAT_HINT: &str = "后端支持 RGB565 和 32 位 RGBA/BGRA/ABGR，可用 fbset 切换色深";
+
 /// `slint-linuxfb` 后端的主错误类型。
+///
+/// 通过 Slint 返回时封装在 [`PlatformError::OtherError`] 中，可以用 `downcast_ref` 取回。
 #[derive(Debug, Error)]
 pub enum Error {
     /// 封装了来自 `rust-linuxfb` 库的 I/O 或 ioctl 错误。
@@ -17,16 +31,187 @@ pub enum Error {
     SetPlatformError(#[from] i_slint_core::platform::SetPlatformError),
 
     /// 当 framebuffer 的像素格式不是我们支持的格式之一时返回。
-    #[error("不支持的 Framebuffer 像素格式")]
+    #[error("不支持的 Framebuffer 像素格式 (提示: {})", PIXEL_FORMAT_HINT)]
     UnsupportedPixelFormat,
 
+    /// 没有打开设备的权限 (EACCES / EPERM)。
+    #[error("没有访问 {path:?} 的权限 (提示: {})", PERMISSION_HINT)]
+    PermissionDenied { path: PathBuf },
+
+    /// 设备正被其他程序占用 (EBUSY)。
+    #[error("设备 {path:?} 正被占用 (提示: {})", BUSY_HINT)]
+    DeviceBusy { path: PathBuf },
+
+    /// Framebuffer 设备不存在 (ENOENT / ENODEV / ENXIO)。
+    #[error("未找到 Framebuffer 设备 {path:?} (提示: {})", NO_FRAMEBUFFER_HINT)]
+    NoFramebufferFound { path: PathBuf },
+
+    /// 驱动不支持双缓冲所需的虚拟分辨率或平移 (panning)。
+    #[error("Framebuffer {path:?} 不支持双缓冲平移: {source} (提示: {})", PANNING_HINT)]
+    PanningUnsupported {
+        path: PathBuf,
+        #[source]
+        source: crate::linuxfb::Error,
+    },
+
+    /// 配置文件无法读取、语法错误或包含无效的配置项。
+    #[error("配置文件错误: {0}")]
+    Config(String),
+
     /// 兜底的其他错误。
     #[error("后端错误: {0}")]
     Other(String),
 }
 
-impl Into<PlatformError> for Error {
-    fn into(self) -> PlatformError {
-        PlatformError::Other(self.to_string())
+impl Error {
+    /// 建议的解决办法 (没有明确建议的错误返回 `None`)
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Error::UnsupportedPixelFormat => Some(PIXEL_FORMAT_HINT),
+            Error::PermissionDenied { .. } => Some(PERMISSION_HINT),
+            Error::DeviceBusy { .. } => Some(BUSY_HINT),
+            Error::NoFramebufferFound { .. } => Some(NO_FRAMEBUFFER_HINT),
+            Error::PanningUnsupported { .. } => Some(PANNING_HINT),
+            _ => None,
+        }
+    }
+
+    /// 把打开或查询 Framebuffer `path` 时的错误按 errno 映射为具体的错误
+    pub(crate) fn from_framebuffer(path: &Path, error: crate::linuxfb::Error) -> Self {
+        let errno = match &error {
+            crate::linuxfb::Error::Io(e) => e.raw_os_error(),
+            crate::linuxfb::Error::Fb(e) => Some(e.errno),
+        };
+        let path = path.to_path_buf();
+        match errno {
+            Some(libc::EACCES | libc::EPERM) => Error::PermissionDenied { path },
+            Some(libc::EBUSY) => Error::DeviceBusy { path },
+            Some(libc::ENOENT | libc::ENODEV | libc::ENXIO) => Error::NoFramebufferFound { path },
+            _ => Error::LinuxFb(error),
+        }
+    }
+
+    /// 显示设备是否暂时失联 (EIO / ENODEV)，例如 USB 显示适配器或 SPI 屏幕的线缆抖动，
+    /// 重新打开设备有望恢复
+    pub(crate) fn is_device_lost(&self) -> bool {
+        let errno = match self {
+            Error::LinuxFb(crate::linuxfb::Error::Io(e)) => e.raw_os_error(),
+            Error::LinuxFb(crate::linuxfb::Error::Fb(e)) => Some(e.errno),
+            _ => None,
+        };
+        matches!(errno, Some(libc::EIO | libc::ENODEV))
+    }
+
+    /// 把配置双缓冲 (虚拟分辨率、偏移、映射显存) 时的错误映射为具体的错误
+    pub(crate) fn from_double_buffer(path: &Path, error: Error) -> Self {
+        match error {
+            Error::LinuxFb(error) => match Error::from_framebuffer(path, error) {
+                Error::LinuxFb(source) => Error::PanningUnsupported { path: path.to_path_buf(), source },
+                other => other,
+            },
+            other => other,
+        }
+    }
+}
+
+impl From<Error> for PlatformError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::SlintPlatform(error) => error,
+            error => PlatformError::OtherError(Box::new(error)),
+        }
     }
-}
\ No newline at end of file
+}
+
+/// 运行时问题报告：通过构建器的 `with_backend_event_callback` 交给应用
+///
+/// 这些问题不会中断事件循环 (翻转失败且重新打开设备也无法恢复时除外，它随后作为错误从事件循环返回)，
+/// 应用可以据此在界面上提示或上报遥测数据。
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendEvent {
+    /// 后端已自动降级或将自行恢复
+    Warning(BackendIssue),
+    /// 显示或输入功能失效
+    Error(BackendIssue),
+}
+
+/// 运行时问题
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum BackendIssue {
+    /// 硬件 VSync 不可用，已改用软件帧节奏
+    VsyncUnsupported { reason: String },
+    /// Framebuffer 翻转失败
+    FlipFailed { reason: String },
+    /// 翻转失败后重新打开了 Framebuffer，第 `attempts` 次尝试成功，整帧已重新渲染
+    DisplayRecovered { reason: String, attempts: u32 },
+    /// 分辨率被外部 (例如 fbset、HDMI 重新协商) 改变，已重新配置缓冲区并通知界面重新布局
+    DisplayModeChanged { from: (u32, u32), to: (u32, u32) },
+    /// 显示器接入或断开 (见构建器的 `with_display_hotplug`)，接入后已重新设置模式
+    DisplayHotplug { connected: bool },
+    /// 帧渲染失败
+    RenderFailed { reason: String },
+    /// 输入设备读取失败，将在 `retry_in` 后重新打开
+    DeviceReadFailed { path: PathBuf, reason: String, retry_in: Duration },
+    /// 输入设备已断开，不再读取
+    DeviceDropped { path: PathBuf, reason: String },
+    /// 事件循环迭代超出看门狗预算
+    LoopOverBudget { elapsed: Duration, budget: Duration },
+    /// 辅助服务 (VNC、MJPEG 推流等) 无法启动
+    ServiceUnavailable { service: &'static str, reason: String },
+}
+
+impl fmt::Display for BackendIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackendIssue::VsyncUnsupported { reason } => write!(f, "VSync 不可用，改用软件帧节奏: {}", reason),
+            BackendIssue::FlipFailed { reason } => write!(f, "Framebuffer 翻转(Flip)失败: {}", reason),
+            BackendIssue::DisplayRecovered { reason, attempts } => {
+                write!(f, "Framebuffer 翻转失败 ({})，第 {} 次重新打开设备后恢复", reason, attempts)
+            }
+            BackendIssue::DisplayModeChanged { from, to } => {
+                write!(f, "显示模式被外部改变: {}x{} -> {}x{}，已重新配置缓冲区", from.0, from.1, to.0, to.1)
+            }
+            BackendIssue::DisplayHotplug { connected: true } => write!(f, "显示器已接入，已重新设置显示模式"),
+            BackendIssue::DisplayHotplug { connected: false } => write!(f, "显示器已断开"),
+            BackendIssue::RenderFailed { reason } => write!(f, "帧渲染错误: {}", reason),
+            BackendIssue::DeviceReadFailed { path, reason, retry_in } => {
+                write!(f, "设备读取失败 {:?}: {}，{:?} 后重新打开", path, reason, retry_in)
+            }
+            BackendIssue::DeviceDropped { path, reason } => write!(f, "输入设备已移除 {:?}: {}", path, reason),
+            BackendIssue::LoopOverBudget { elapsed, budget } => {
+                write!(f, "事件循环迭代耗时 {:?}，超出看门狗预算 {:?}", elapsed, budget)
+            }
+            BackendIssue::ServiceUnavailable { service, reason } => write!(f, "无法启动 {}: {}", service, reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linuxfb::ErrnoError;
+
+    #[test]
+    fn test_errno_mapping() {
+        let path = Path::new("/dev/fb0");
+        let io = |errno| crate::linuxfb::Error::Io(std::io::Error::from_raw_os_error(errno));
+        assert!(matches!(Error::from_framebuffer(path, io(libc::EACCES)), Error::PermissionDenied { .. }));
+        assert!(matches!(Error::from_framebuffer(path, io(libc::ENOENT)), Error::NoFramebufferFound { .. }));
+
+        let ioctl = |errno| Error::LinuxFb(crate::linuxfb::Error::Fb(ErrnoError { errno, message: String::new() }));
+        assert!(matches!(Error::from_double_buffer(path, ioctl(libc::EBUSY)), Error::DeviceBusy { .. }));
+        let error = Error::from_double_buffer(path, ioctl(libc::EINVAL));
+        assert!(matches!(error, Error::PanningUnsupported { .. }));
+        assert!(error.hint().is_some());
+    }
+
+    #[test]
+    fn test_device_lost() {
+        let ioctl = |errno| Error::LinuxFb(crate::linuxfb::Error::Fb(ErrnoError { errno, message: String::new() }));
+        assert!(ioctl(libc::EIO).is_device_lost());
+        assert!(ioctl(libc::ENODEV).is_device_lost());
+        assert!(!ioctl(libc::EINVAL).is_device_lost());
+        assert!(!Error::Other(String::new()).is_device_lost());
+    }
+}
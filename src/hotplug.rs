@@ -0,0 +1,123 @@
+//! 显示接口热插拔检测
+//!
+//! Framebuffer 背后是 HDMI 接口的板卡上，应用启动后才插入线缆时屏幕保持黑屏：
+//! 驱动不会自动为新接入的显示器重新设置模式。这里定期读取接口状态 ——
+//! DRM 连接器的 `/sys/class/drm/<卡>-<接口>/status` (`connected` / `disconnected`)，
+//! 或 extcon 设备的 `/sys/class/extcon/<设备>/state` (包含 `HDMI=1` 之类的行) ——
+//! 由事件循环在显示器接入时重新设置模式并重新配置缓冲区。
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const DRM_CLASS_DIR: &str = "/sys/class/drm";
+const EXTCON_CLASS_DIR: &str = "/sys/class/extcon";
+
+/// 接口状态的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectorKind {
+    /// DRM 连接器的 `status` 文件
+    Drm,
+    /// extcon 设备的 `state` 文件
+    Extcon,
+}
+
+/// 显示接口的连接状态监视
+#[derive(Debug)]
+pub(crate) struct ConnectorMonitor {
+    path: PathBuf,
+    kind: ConnectorKind,
+    connected: bool,
+}
+
+impl ConnectorMonitor {
+    /// 打开接口状态文件；`path` 为 `None` 时查找第一个 HDMI 连接器，其次是报告 HDMI 的 extcon 设备
+    ///
+    /// `path` 可以是 DRM 连接器目录、其中的 `status` 文件或 extcon 的 `state` 文件。
+    pub fn open(path: Option<&Path>) -> io::Result<Self> {
+        let (path, kind) = match path {
+            Some(path) if path.is_dir() => (path.join("status"), ConnectorKind::Drm),
+            Some(path) if path.file_name().is_some_and(|name| name == "state") => {
+                (path.to_path_buf(), ConnectorKind::Extcon)
+            }
+            Some(path) => (path.to_path_buf(), ConnectorKind::Drm),
+            None => find_connector()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "未找到 HDMI 连接器或 extcon 设备"))?,
+        };
+        let text = fs::read_to_string(&path)?;
+        let connected = parse_connected(kind, &text).unwrap_or(false);
+        tracing::info!("监视显示接口: {:?} (当前{})", path, if connected { "已连接" } else { "未连接" });
+        Ok(Self { path, kind, connected })
+    }
+
+    /// 重新读取状态，连接状态改变时返回新的状态
+    ///
+    /// 读取失败或状态未知时保持原来的状态。
+    pub fn poll(&mut self) -> Option<bool> {
+        let text = fs::read_to_string(&self.path)
+            .map_err(|e| tracing::debug!("无法读取显示接口状态 {:?}: {}", self.path, e))
+            .ok()?;
+        let connected = parse_connected(self.kind, &text)?;
+        if connected == self.connected {
+            return None;
+        }
+        self.connected = connected;
+        Some(connected)
+    }
+}
+
+/// 解析状态文件的内容，无法判断时返回 `None`
+fn parse_connected(kind: ConnectorKind, text: &str) -> Option<bool> {
+    match kind {
+        ConnectorKind::Drm => match text.trim() {
+            "connected" => Some(true),
+            "disconnected" => Some(false),
+            _ => None,
+        },
+        // 每行为 "名称=0/1"，取第一个 HDMI 线路
+        ConnectorKind::Extcon => text
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .find(|(name, _)| name.trim().starts_with("HDMI"))
+            .map(|(_, value)| value.trim() != "0"),
+    }
+}
+
+/// 查找 HDMI 接口的状态文件
+fn find_connector() -> Option<(PathBuf, ConnectorKind)> {
+    let sorted_entries = |dir| -> Vec<PathBuf> {
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        entries.sort();
+        entries
+    };
+    let drm = sorted_entries(DRM_CLASS_DIR).into_iter().find(|path| {
+        path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.contains("-HDMI-"))
+            && path.join("status").exists()
+    });
+    if let Some(path) = drm {
+        return Some((path.join("status"), ConnectorKind::Drm));
+    }
+    sorted_entries(EXTCON_CLASS_DIR).into_iter().map(|path| path.join("state")).find_map(|path| {
+        let text = fs::read_to_string(&path).ok()?;
+        parse_connected(ConnectorKind::Extcon, &text).map(|_| (path, ConnectorKind::Extcon))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_connected() {
+        assert_eq!(parse_connected(ConnectorKind::Drm, "connected\n"), Some(true));
+        assert_eq!(parse_connected(ConnectorKind::Drm, "disconnected\n"), Some(false));
+        assert_eq!(parse_connected(ConnectorKind::Drm, "unknown\n"), None);
+        assert_eq!(parse_connected(ConnectorKind::Extcon, "USB=0\nHDMI=1\n"), Some(true));
+        assert_eq!(parse_connected(ConnectorKind::Extcon, "HDMI=0\n"), Some(false));
+        assert_eq!(parse_connected(ConnectorKind::Extcon, "USB=1\n"), None);
+    }
+}
@@ -2,30 +2,320 @@
 //!
 //! 负责协调键盘、鼠标和触摸设备。
 
+mod acceleration;
+mod beeper;
+mod calibration;
+#[cfg(not(feature = "xkb"))]
+mod compose;
+mod encoder;
+#[cfg(feature = "ime")]
+mod ime;
 mod keyboard;
+#[cfg(not(feature = "xkb"))]
+mod keymap;
+mod pen;
+#[cfg(feature = "input-replay")]
+mod replay;
+mod smoothing;
+mod terminal;
 mod touch;
+mod touchpad;
+#[cfg(feature = "uinput")]
+mod uinput;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::os::unix::io::{AsRawFd, RawFd};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use evdev::{AbsInfo, AbsoluteAxisCode, Device, EventSummary, InputEvent, KeyCode, RelativeAxisCode, SynchronizationCode};
+use evdev::{AbsInfo, AbsoluteAxisCode, Device, EventSummary, EventType, InputEvent, KeyCode, LedCode, RelativeAxisCode, SwitchCode, SynchronizationCode};
 use i_slint_core::api::PhysicalPosition;
+use i_slint_core::input::key_codes;
 use i_slint_core::platform::{PointerEventButton, WindowEvent};
+use i_slint_core::SharedString;
 
-use crate::error::Error;
-use self::keyboard::KeyboardHandler;
-use self::touch::{TouchState, analyze_touch_gesture};
+use crate::clock;
+use crate::error::{BackendEvent, BackendIssue, Error};
+use crate::window::Rotation;
+use self::acceleration::PointerAccelerator;
+pub use self::acceleration::{AccelProfile, PointerAcceleration};
+pub(crate) use self::beeper::Beeper;
+pub use self::beeper::BeeperConfig;
+use self::keyboard::{KeyboardHandler, LockState};
+pub(crate) use self::calibration::TouchCalibration;
+use self::encoder::EncoderState;
+pub use self::encoder::{EncoderAction, EncoderConfig};
+#[cfg(feature = "ime")]
+pub use self::ime::ImeEvent;
+#[cfg(feature = "ime")]
+use self::ime::ImeBridge;
+use self::pen::PenState;
+#[cfg(feature = "input-replay")]
+use self::replay::{InputRecorder, InputReplay};
+use self::smoothing::PointFilter;
+pub use self::smoothing::SmoothingConfig;
+pub(crate) use self::terminal::restore_terminal;
+use self::terminal::TerminalInput;
+use self::touch::{TouchState, analyze_raw_touch, analyze_touch_gesture};
+use self::touchpad::TouchpadState;
+#[cfg(feature = "uinput")]
+pub use self::uinput::{VirtualInput, VIRTUAL_KEYBOARD_NAME, VIRTUAL_TOUCHSCREEN_NAME};
+pub use self::touch::{GestureConfig, SwipeDirection, TouchContact, TouchGesture, TouchPhase};
 
 /// 重新扫描输入设备的时间间隔
 const RESCAN_INTERVAL: Duration = Duration::from_secs(3);
-/// 移动事件节流阈值 (约 120Hz)
+/// 默认的移动事件节流间隔 (约 120Hz)
 const MOVE_THROTTLE_DURATION: Duration = Duration::from_millis(8);
+/// 读取失败后第一次重新打开设备的等待时间，之后每次失败加倍
+const REOPEN_BACKOFF: Duration = Duration::from_millis(100);
+/// 重新打开设备的最长等待时间
+const REOPEN_MAX_BACKOFF: Duration = Duration::from_secs(5);
+/// 每个设备原始事件缓冲区的初始容量 (多点触控的一帧通常不超过几十个事件)
+const EVENT_BUFFER_CAPACITY: usize = 64;
+
+pub use evdev::KeyCode as RawKeyCode;
+
+/// 当前按下的修饰键 (左右两侧不作区分)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    /// 根据所有设备上按住的修饰键计算状态
+    fn from_held(held: &HashMap<KeyCode, u32>) -> Self {
+        let any = |left, right| held.contains_key(&left) || held.contains_key(&right);
+        Self {
+            shift: any(KeyCode::KEY_LEFTSHIFT, KeyCode::KEY_RIGHTSHIFT),
+            control: any(KeyCode::KEY_LEFTCTRL, KeyCode::KEY_RIGHTCTRL),
+            alt: any(KeyCode::KEY_LEFTALT, KeyCode::KEY_RIGHTALT),
+            meta: any(KeyCode::KEY_LEFTMETA, KeyCode::KEY_RIGHTMETA),
+        }
+    }
+}
+
+/// 指针与触摸的当前状态
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PointerState {
+    /// 指针位置 (物理像素，窗口坐标)
+    pub position: PhysicalPosition,
+    /// 当前按下的指针按键 (触摸按下时包含左键)
+    pub pressed_buttons: Vec<PointerEventButton>,
+    /// 所有触摸设备上的活跃触点数量
+    pub touch_count: usize,
+}
+
+impl PointerState {
+    /// 用户是否正在操作 (按住指针按键或有手指接触屏幕)
+    pub fn is_interacting(&self) -> bool {
+        !self.pressed_buttons.is_empty() || self.touch_count > 0
+    }
+}
+
+/// 原始按键拦截器：参数为 evdev 键码、值 (0 释放 / 1 按下 / 2 重复) 和修饰键状态，
+/// 返回 `true` 表示事件已被消费，不再转发给 Slint
+pub type KeyInterceptor = Box<dyn FnMut(RawKeyCode, i32, Modifiers) -> bool>;
+
+/// 系统按键：由平台处理 (见构建器的 `with_system_key_action`)，不作为普通按键转发给 Slint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SystemKey {
+    BrightnessUp,
+    BrightnessDown,
+    Power,
+    Sleep,
+}
+
+impl SystemKey {
+    fn from_key_code(code: KeyCode) -> Option<Self> {
+        match code {
+            KeyCode::KEY_BRIGHTNESSUP => Some(SystemKey::BrightnessUp),
+            KeyCode::KEY_BRIGHTNESSDOWN => Some(SystemKey::BrightnessDown),
+            KeyCode::KEY_POWER => Some(SystemKey::Power),
+            KeyCode::KEY_SLEEP => Some(SystemKey::Sleep),
+            _ => None,
+        }
+    }
+}
+
+/// 自定义设备过滤条件：返回 `false` 的设备不会被打开
+///
+/// 在名称白名单/黑名单之后调用，可按厂商/产品 ID 或设备能力过滤。
+/// 热插拔扫描在后台线程中进行，因此需要 `Send + Sync`。
+#[derive(Clone)]
+pub struct DeviceFilter(Arc<dyn Fn(&Device) -> bool + Send + Sync>);
+
+impl DeviceFilter {
+    pub fn new(filter: impl Fn(&Device) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(filter))
+    }
+
+    fn accepts(&self, device: &Device) -> bool {
+        (self.0)(device)
+    }
+}
+
+impl std::fmt::Debug for DeviceFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DeviceFilter")
+    }
+}
+
+/// 输入设备支持的功能
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeviceCapabilities {
+    pub touchscreen: bool,
+    pub touchpad: bool,
+    pub pen: bool,
+    pub mouse: bool,
+    pub keyboard: bool,
+    /// 旋转编码器 (按配置匹配)
+    pub encoder: bool,
+    /// 报告盖子、平板模式或扩展坞开关
+    pub switches: bool,
+}
+
+/// 输入设备信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputDeviceInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub capabilities: DeviceCapabilities,
+}
+
+/// 设备添加或移除：通过构建器的 `with_device_callback` 交给应用
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceEvent {
+    Added(InputDeviceInfo),
+    Removed(InputDeviceInfo),
+}
+
+/// 开关 (EV_SW)：状态变化通过构建器的 `with_switch_callback` 交给应用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Switch {
+    /// 笔记本盖子，激活表示合上
+    Lid,
+    /// 平板模式
+    TabletMode,
+    /// 扩展坞，激活表示已连接
+    Dock,
+}
+
+impl Switch {
+    const ALL: [Switch; 3] = [Switch::Lid, Switch::TabletMode, Switch::Dock];
+
+    fn code(self) -> SwitchCode {
+        match self {
+            Switch::Lid => SwitchCode::SW_LID,
+            Switch::TabletMode => SwitchCode::SW_TABLET_MODE,
+            Switch::Dock => SwitchCode::SW_DOCK,
+        }
+    }
+
+    fn from_switch_code(code: SwitchCode) -> Option<Self> {
+        Self::ALL.into_iter().find(|switch| switch.code() == code)
+    }
+}
+
+/// 按键导航动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NavigationAction {
+    /// 焦点移到下一个元素 (Tab)
+    FocusNext,
+    /// 焦点移到上一个元素 (Shift+Tab)
+    FocusPrevious,
+    /// 激活当前焦点元素 (回车)
+    Activate,
+}
+
+impl NavigationAction {
+    fn key_text(self) -> SharedString {
+        match self {
+            NavigationAction::FocusNext => key_codes::Tab,
+            NavigationAction::FocusPrevious => key_codes::Backtab,
+            NavigationAction::Activate => key_codes::Return,
+        }
+        .into()
+    }
+}
+
+/// 纯按键导航模式
+///
+/// 适用于只有几个硬件按键、没有指针设备的面板：不再产生任何指针事件，
+/// 界面始终保持一个焦点元素，并可把任意按键映射为焦点切换与激活。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeypadNavigation {
+    /// 映射为导航动作的按键，未映射的按键照常转发
+    pub keys: Vec<(RawKeyCode, NavigationAction)>,
+}
+
+impl KeypadNavigation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 把按键映射为导航动作
+    pub fn with_key(mut self, key: RawKeyCode, action: NavigationAction) -> Self {
+        self.keys.push((key, action));
+        self
+    }
+}
+
+/// 键盘自动重复参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRepeat {
+    /// 按住按键到开始重复的延迟
+    pub delay: Duration,
+    /// 重复的间隔
+    pub period: Duration,
+}
+
+impl Default for KeyRepeat {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(250),
+            period: Duration::from_millis(33),
+        }
+    }
+}
+
+/// 按设备名称匹配的触摸坐标变换
+///
+/// 用于多个触摸设备分别对应屏幕不同区域的场合，例如前面板与侧面板各有一块触摸层。
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceTransform {
+    /// 设备名称中包含的字符串 (匹配方式与白名单相同)
+    pub name_pattern: String,
+    /// 设备映射到的窗口区域 `(x, y, 宽, 高)`，`None` 表示整个窗口
+    pub region: Option<(i32, i32, u32, u32)>,
+    /// 该设备的触摸旋转方向，`None` 表示使用 [`InputConfig::touch_rotation`]
+    pub rotation: Option<Rotation>,
+}
+
+impl DeviceTransform {
+    pub fn new(name_pattern: impl Into<String>) -> Self {
+        Self { name_pattern: name_pattern.into(), region: None, rotation: None }
+    }
+
+    /// 将设备映射到窗口中的指定区域
+    pub fn with_region(mut self, x: i32, y: i32, width: u32, height: u32) -> Self {
+        self.region = Some((x, y, width, height));
+        self
+    }
+
+    /// 设置该设备的触摸旋转方向
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+}
 
 /// 输入设备配置选项
 #[derive(Debug, Clone)]
@@ -34,6 +324,61 @@ pub struct InputConfig {
     pub threaded_input: bool,
     pub whitelist: Vec<String>,
     pub blacklist: Vec<String>,
+    /// 自定义设备过滤条件
+    pub device_filter: Option<DeviceFilter>,
+    /// 独占 (EVIOCGRAB) 打开的输入设备，防止 getty / gpm 等其他进程同时读取
+    pub grab_devices: bool,
+    /// 键盘自动重复参数，`None` 表示禁用自动重复
+    pub key_repeat: Option<KeyRepeat>,
+    /// 控制台键盘映射文件 (仅在未启用 `xkb` 特性时使用)
+    pub console_keymap: Option<PathBuf>,
+    /// 是否从控制终端 (stdin) 读取键盘输入
+    pub terminal_input: bool,
+    /// tslib 格式的触摸校准文件 (pointercal)，未设置时使用环境变量 `TSLIB_CALIBFILE`
+    pub pointercal: Option<PathBuf>,
+    /// 触摸坐标的旋转方向，`None` 表示与显示旋转方向一致
+    ///
+    /// 仅当触摸层与显示面板的安装方向不同时才需要单独设置。
+    pub touch_rotation: Option<Rotation>,
+    /// 触摸压力阈值 (设备原始单位)，压力低于该值的触点不视为按下
+    ///
+    /// 用于过滤电阻屏上的轻触误报；设备不报告压力时无效。
+    pub pressure_threshold: Option<i32>,
+    /// 触摸手势开关
+    pub gestures: GestureConfig,
+    /// 指针移动事件的最小间隔，`None` 表示不节流，也不合并同一次轮询中的移动 (高采样率的手写板、签名板)
+    pub move_throttle: Option<Duration>,
+    /// 鼠标指针加速参数 (默认不加速)，其中的速度倍数同样作用于触摸板
+    pub pointer_acceleration: PointerAcceleration,
+    /// 自然滚动：鼠标滚轮与触摸板滚动方向反转，内容跟随手指移动
+    pub natural_scrolling: bool,
+    /// 鼠标滚轮每一格的滚动距离 (像素)
+    pub scroll_step: f32,
+    /// 反转水平滚轮 (REL_HWHEEL) 的方向，与自然滚动叠加
+    pub invert_horizontal_scroll: bool,
+    /// 触摸坐标平滑参数，`None` 表示不平滑 (只使用固定的去抖阈值)
+    pub touch_smoothing: Option<SmoothingConfig>,
+    /// 原始多点触控模式：不做手势识别，逐个报告触点 (见 [`InputManager::take_touch_contacts`])
+    pub raw_touch: bool,
+    /// 手写笔橡皮擦端接触屏幕时按下的按钮，`None` 表示忽略橡皮擦
+    pub pen_eraser_button: Option<PointerEventButton>,
+    /// 按设备名称匹配的坐标变换，按顺序使用第一个匹配项
+    pub device_transforms: Vec<DeviceTransform>,
+    /// 按设备名称匹配的旋转编码器，按顺序使用第一个匹配项
+    pub encoders: Vec<EncoderConfig>,
+    /// 纯按键导航模式，`None` 表示关闭
+    pub keypad_navigation: Option<KeypadNavigation>,
+    /// 按下后向窗口分发 `CloseRequested` 的快捷键 (键码, 修饰键)
+    pub close_shortcut: Option<(RawKeyCode, Modifiers)>,
+    /// 是否连接输入法 (ibus / fcitx5)
+    #[cfg(feature = "ime")]
+    pub ime: bool,
+    /// 将所有设备的原始事件录制到该文件
+    #[cfg(feature = "input-replay")]
+    pub record_path: Option<PathBuf>,
+    /// 回放该录制文件中的事件
+    #[cfg(feature = "input-replay")]
+    pub replay_path: Option<PathBuf>,
 }
 
 impl Default for InputConfig {
@@ -43,6 +388,33 @@ impl Default for InputConfig {
             threaded_input: true,
             whitelist: Vec::new(),
             blacklist: Vec::new(),
+            device_filter: None,
+            grab_devices: false,
+            key_repeat: Some(KeyRepeat::default()),
+            console_keymap: None,
+            terminal_input: false,
+            pointercal: None,
+            touch_rotation: None,
+            pressure_threshold: None,
+            gestures: GestureConfig::default(),
+            move_throttle: Some(MOVE_THROTTLE_DURATION),
+            pointer_acceleration: PointerAcceleration::default(),
+            natural_scrolling: false,
+            scroll_step: 20.0,
+            invert_horizontal_scroll: false,
+            touch_smoothing: None,
+            raw_touch: false,
+            pen_eraser_button: Some(PointerEventButton::Left),
+            device_transforms: Vec::new(),
+            encoders: Vec::new(),
+            keypad_navigation: None,
+            close_shortcut: None,
+            #[cfg(feature = "ime")]
+            ime: false,
+            #[cfg(feature = "input-replay")]
+            record_path: None,
+            #[cfg(feature = "input-replay")]
+            replay_path: None,
         }
     }
 }
@@ -59,26 +431,209 @@ struct ManagedDevice {
 
     // 触摸状态
     touch: TouchState,
+
+    // 手写笔状态 (仅手写笔设备)
+    pen: Option<PenState>,
+
+    // 触摸板状态 (仅触摸板设备，按相对设备处理)
+    touchpad: Option<TouchpadState>,
+
+    // 旋转编码器状态 (仅配置为编码器的设备)
+    encoder: Option<EncoderState>,
+
+    // 该设备上按住的修饰键
+    held_modifiers: HashSet<KeyCode>,
+
+    // 本次读取到的原始事件 (在轮询之间复用，避免每次分配)
+    events: Vec<InputEvent>,
+
+    // 是否已独占该设备
+    grabbed: bool,
+
+    // 是否处理该设备的事件 (禁用时读取并丢弃)
+    enabled: bool,
+
+    // 连续读取失败的次数
+    read_failures: u32,
+    // 读取失败后下一次尝试重新打开设备的时间
+    retry_at: Option<Instant>,
+}
+
+impl ManagedDevice {
+    /// 设备名称包含 `name_or_path`，或设备路径与其相同
+    fn matches(&self, name_or_path: &str) -> bool {
+        self.path == Path::new(name_or_path) || self.device.name().is_some_and(|name| name.contains(name_or_path))
+    }
+
+    fn info(&self) -> InputDeviceInfo {
+        InputDeviceInfo {
+            name: self.device.name().unwrap_or_default().to_string(),
+            path: self.path.clone(),
+            capabilities: DeviceCapabilities {
+                touchscreen: self.abs_x_info.is_some() && self.pen.is_none(),
+                touchpad: self.touchpad.is_some(),
+                pen: self.pen.is_some(),
+                mouse: is_mouse(&self.device),
+                keyboard: is_keyboard(&self.device),
+                encoder: self.encoder.is_some(),
+                switches: has_switches(&self.device),
+            },
+        }
+    }
+
+    /// 把设备事件读取到 [`events`](Self::events) (先清空)，返回 `Err` 表示设备已被移除
+    ///
+    /// 其他读取错误视为暂时性故障：按退避时间重新打开设备，期间不读取该设备，并记录到 `issues`。
+    fn read_events(&mut self, grab: bool, issues: &mut Vec<BackendEvent>) -> io::Result<()> {
+        self.events.clear();
+        if let Some(retry_at) = self.retry_at {
+            if Instant::now() < retry_at {
+                return Ok(());
+            }
+            if let Err(e) = self.reopen(grab) {
+                if is_device_removed(&e) {
+                    return Err(e);
+                }
+                issues.push(self.schedule_retry(&e));
+                return Ok(());
+            }
+            tracing::info!("已重新打开输入设备 {:?}", self.path);
+            self.retry_at = None;
+        }
+        let result = self.device.fetch_events().map(|events| self.events.extend(events));
+        match result {
+            Ok(()) => {
+                if !self.events.is_empty() {
+                    self.read_failures = 0;
+                }
+                Ok(())
+            }
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted) => Ok(()),
+            Err(e) if is_device_removed(&e) => Err(e),
+            Err(e) => {
+                issues.push(self.schedule_retry(&e));
+                Ok(())
+            }
+        }
+    }
+
+    fn schedule_retry(&mut self, error: &io::Error) -> BackendEvent {
+        let delay = REOPEN_BACKOFF
+            .saturating_mul(1 << self.read_failures.min(6))
+            .min(REOPEN_MAX_BACKOFF);
+        self.read_failures += 1;
+        self.retry_at = Some(Instant::now() + delay);
+        BackendEvent::Warning(BackendIssue::DeviceReadFailed {
+            path: self.path.clone(),
+            reason: error.to_string(),
+            retry_in: delay,
+        })
+    }
+
+    /// 重新打开设备节点，替换失效的文件描述符
+    fn reopen(&mut self, grab: bool) -> io::Result<()> {
+        let device = Device::open(&self.path)?;
+        device.set_nonblocking(true)?;
+        use_monotonic_timestamps(&device);
+        self.device = device;
+        // 旧的文件描述符关闭时独占随之释放
+        self.grabbed = false;
+        self.set_grab(grab);
+        Ok(())
+    }
+
+    fn set_grab(&mut self, grab: bool) {
+        if grab == self.grabbed {
+            return;
+        }
+        let result = if grab { self.device.grab() } else { self.device.ungrab() };
+        match result {
+            Ok(()) => self.grabbed = grab,
+            Err(e) => tracing::warn!("无法{}输入设备 {:?}: {}", if grab { "独占" } else { "释放" }, self.path, e),
+        }
+    }
+}
+
+impl Drop for ManagedDevice {
+    fn drop(&mut self) {
+        // 设备可能已被拔出，忽略错误
+        if self.grabbed {
+            let _ = self.device.ungrab();
+        }
+    }
+}
+
+/// 没有对应 evdev 设备的合成输入源 (桌面预览窗口、测试脚本)
+struct SyntheticDevice {
+    touch: TouchState,
+    held_modifiers: HashSet<KeyCode>,
+    /// 等待在下一次轮询中处理的事件
+    pending: Vec<InputEvent>,
 }
 
 /// 全局输入状态
 struct GlobalInputState {
     pointer_pos: PhysicalPosition,
     is_left_pressed: bool,
+    // 已发出按下事件、尚未释放的指针按键
+    pressed_buttons: Vec<PointerEventButton>,
     screen_width: u32,
     screen_height: u32,
     
+    // 鼠标指针加速
+    accelerator: PointerAccelerator,
+    // 滚轮每一格在两个方向上的滚动距离 (已包含方向反转)
+    scroll_step: (f32, f32),
+
     // 键盘处理逻辑 (抽象层)
     keyboard: KeyboardHandler,
+    // 是否转发按键重复事件
+    key_repeat_enabled: bool,
+    // 最近一次写入键盘指示灯的锁定键状态
+    leds: LockState,
+    // 等待平台处理的系统按键
+    system_keys: Vec<SystemKey>,
+    // 尚未交给平台的开关状态变化 (开关, 是否激活)
+    switch_events: Vec<(Switch, bool)>,
+    // 尚未交给平台的设备添加与移除
+    device_events: Vec<DeviceEvent>,
+    // 尚未交给平台的运行时问题 (设备读取失败、断开)
+    backend_events: Vec<BackendEvent>,
+    // 映射为导航动作的按键
+    navigation_keys: Vec<(KeyCode, NavigationAction)>,
+    // 原始多点触控模式
+    raw_touch: bool,
+    // 等待交给应用的触点变化 (仅原始多点触控模式)
+    touch_contacts: Vec<TouchContact>,
+    // 等待交给应用的触摸手势
+    touch_gestures: Vec<TouchGesture>,
+    // 修饰键状态 (供拦截器使用，与键盘布局实现无关)
+    modifiers: Modifiers,
+    // 每个修饰键被多少个设备按住
+    held_modifiers: HashMap<KeyCode, u32>,
+    // 原始按键拦截器
+    key_interceptor: Option<KeyInterceptor>,
+    // 请求关闭窗口的快捷键
+    close_shortcut: Option<(KeyCode, Modifiers)>,
+    // 输入法连接
+    #[cfg(feature = "ime")]
+    ime: Option<ImeBridge>,
+    // 按下时被输入法消费的按键，其重复和释放同样不转发给 Slint
+    #[cfg(feature = "ime")]
+    ime_keys: HashSet<KeyCode>,
     
     // 节流控制
+    move_throttle: Option<Duration>,
     last_move_time: Instant,
 }
 
 impl GlobalInputState {
     fn should_emit_move(&mut self) -> bool {
-        let now = Instant::now();
-        if now.duration_since(self.last_move_time) >= MOVE_THROTTLE_DURATION {
+        let Some(throttle) = self.move_throttle else {
+            return true;
+        };
+        let now = clock::now();
+        if now.duration_since(self.last_move_time) >= throttle {
             self.last_move_time = now;
             true
         } else {
@@ -86,27 +641,261 @@ impl GlobalInputState {
         }
     }
 
-    fn process_device_events(&mut self, dev: &mut ManagedDevice, events: Vec<InputEvent>) -> Vec<WindowEvent> {
+    /// 按节流间隔丢弃 `output[start..]` 中过于频繁的移动事件
+    fn throttle_moves(&mut self, output: &mut Vec<WindowEvent>, start: usize) {
+        let mut kept = start;
+        for index in start..output.len() {
+            if !matches!(output[index], WindowEvent::PointerMoved { .. }) || self.should_emit_move() {
+                output.swap(kept, index);
+                kept += 1;
+            }
+        }
+        output.truncate(kept);
+    }
+
+    /// 文本输入框获得焦点时将按键交给输入法，返回是否不再转发给 Slint
+    ///
+    /// 重复和释放跟随按下时输入法的决定，Slint 因此总是收到成对的按下和释放；
+    /// 释放总是告知输入法，以免它认为按键仍被按住。
+    #[cfg(feature = "ime")]
+    fn forward_to_ime(&mut self, key: KeyCode, event: &WindowEvent) -> bool {
+        let ime = self.ime.as_ref().filter(|ime| ime.is_focused());
+        match event {
+            WindowEvent::KeyPressed { text } => {
+                let consumed = ime.is_some_and(|ime| ime.process_key(text, key.code(), true, self.modifiers));
+                if consumed {
+                    self.ime_keys.insert(key);
+                } else {
+                    self.ime_keys.remove(&key);
+                }
+                consumed
+            }
+            WindowEvent::KeyPressRepeated { text } => {
+                let consumed = self.ime_keys.contains(&key);
+                if let Some(ime) = ime.filter(|_| consumed) {
+                    ime.process_key(text, key.code(), true, self.modifiers);
+                }
+                consumed
+            }
+            WindowEvent::KeyReleased { text } => {
+                if let Some(ime) = ime {
+                    ime.process_key(text, key.code(), false, self.modifiers);
+                }
+                self.ime_keys.remove(&key)
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg(not(feature = "ime"))]
+    fn forward_to_ime(&mut self, _key: KeyCode, _event: &WindowEvent) -> bool {
+        false
+    }
+
+    /// 记录修饰键在各设备上的按住状态，返回是否应继续处理该事件
+    ///
+    /// 所有键盘共享同一份修饰键状态：同一修饰键被多个设备按住时，
+    /// 只有第一次按下和最后一次释放会被处理，这样在一个键盘上按住 Shift、
+    /// 在另一个键盘上输入也能得到正确的结果。
+    fn track_modifier(&mut self, device_modifiers: &mut HashSet<KeyCode>, key: KeyCode, value: i32) -> bool {
+        match value {
+            1 => {
+                if !device_modifiers.insert(key) {
+                    return false;
+                }
+                let count = self.held_modifiers.entry(key).or_insert(0);
+                *count += 1;
+                *count == 1
+            }
+            0 => {
+                if !device_modifiers.remove(&key) {
+                    return false;
+                }
+                self.release_modifier(key)
+            }
+            _ => true,
+        }
+    }
+
+    /// 处理一个按键事件，`device_modifiers` 为该设备上按住的修饰键
+    ///
+    /// `relative_pointer` 表示设备的鼠标按键作用于相对指针 (触摸屏的 BTN_TOUCH 由手势识别处理)。
+    fn process_key(
+        &mut self,
+        device_modifiers: &mut HashSet<KeyCode>,
+        relative_pointer: bool,
+        key: KeyCode,
+        value: i32,
+        output: &mut Vec<WindowEvent>,
+    ) {
+        if is_modifier_key(key) {
+            if !self.track_modifier(device_modifiers, key, value) {
+                return;
+            }
+            self.modifiers = Modifiers::from_held(&self.held_modifiers);
+        }
+        let intercepted = map_key_to_pointer_button(key).is_none()
+            && self
+                .key_interceptor
+                .as_mut()
+                .is_some_and(|interceptor| interceptor(key, value, self.modifiers));
+
+        if intercepted {
+            // 已被拦截器消费
+        } else if self.close_shortcut == Some((key, self.modifiers)) {
+            // 关闭快捷键：由窗口的 close-requested 回调决定是否关闭
+            if value == 1 {
+                output.push(WindowEvent::CloseRequested);
+            }
+        } else if let Some(system_key) = SystemKey::from_key_code(key) {
+            // 系统按键 (亮度、电源、睡眠) 交给平台处理
+            if value == 1 {
+                self.system_keys.push(system_key);
+            }
+        } else if let Some(&(_, action)) = self.navigation_keys.iter().find(|(k, _)| *k == key) {
+            // 导航按键转换为 Tab / Shift+Tab / 回车
+            let text = action.key_text();
+            output.push(if value == 0 {
+                WindowEvent::KeyReleased { text }
+            } else {
+                WindowEvent::KeyPressed { text }
+            });
+        } else if let Some(btn) = map_key_to_pointer_button(key) {
+            // 鼠标/触摸按键
+            if relative_pointer {
+                let pressed = value == 1;
+                if pressed {
+                    output.push(WindowEvent::PointerPressed {
+                        position: self.pointer_pos.to_logical(1.0),
+                        button: btn,
+                    });
+                } else {
+                    output.push(WindowEvent::PointerReleased {
+                        position: self.pointer_pos.to_logical(1.0),
+                        button: btn,
+                    });
+                }
+            }
+        } else if value == 2 && !self.key_repeat_enabled {
+            // 已禁用自动重复：忽略设备自身产生的重复事件
+        } else {
+            // 键盘按键 (委托给 KeyboardHandler)
+            if let Some(e) = self.keyboard.handle_key_event(key, value) {
+                if !self.forward_to_ime(key, &e) {
+                    output.push(e);
+                }
+            }
+        }
+    }
+
+    /// 减少修饰键的按住计数，返回是否已没有设备按住它
+    fn release_modifier(&mut self, key: KeyCode) -> bool {
+        match self.held_modifiers.get_mut(&key) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                false
+            }
+            _ => {
+                self.held_modifiers.remove(&key);
+                true
+            }
+        }
+    }
+
+    /// 记录设备移除，并释放它按住的修饰键，避免修饰键卡在按下状态
+    fn remove_device(&mut self, dev: &ManagedDevice) -> Vec<WindowEvent> {
+        self.device_events.push(DeviceEvent::Removed(dev.info()));
         let mut output = Vec::new();
+        for &key in &dev.held_modifiers {
+            if self.release_modifier(key) {
+                self.modifiers = Modifiers::from_held(&self.held_modifiers);
+                output.extend(self.keyboard.handle_key_event(key, 0));
+            }
+        }
+        output
+    }
+
+    fn process_synthetic_events(
+        &mut self,
+        dev: &mut SyntheticDevice,
+        events: &[InputEvent],
+        output: &mut Vec<WindowEvent>,
+    ) {
+        for ev in events {
+            match ev.destructure() {
+                EventSummary::AbsoluteAxis(_, code, value) => dev.touch.process_axis(code, value, true),
+                EventSummary::Key(_, key, value) => {
+                    self.process_key(&mut dev.held_modifiers, false, key, value, output);
+                }
+                EventSummary::RelativeAxis(_, RelativeAxisCode::REL_WHEEL, value) => {
+                    output.push(WindowEvent::PointerScrolled {
+                        position: self.pointer_pos.to_logical(1.0),
+                        delta_x: 0.0,
+                        delta_y: (value as f32) * self.scroll_step.1,
+                    });
+                }
+                EventSummary::Synchronization(_, SynchronizationCode::SYN_REPORT, _) => {
+                    dev.touch.frame_time = clock::event_time(ev.timestamp());
+                    // 坐标已是窗口像素：不需要坐标范围，也不再旋转
+                    let start = output.len();
+                    analyze_touch_gesture(
+                        &mut dev.touch,
+                        &mut self.pointer_pos,
+                        &mut self.is_left_pressed,
+                        self.screen_width,
+                        self.screen_height,
+                        &None,
+                        &None,
+                        output,
+                    );
+                    self.throttle_moves(output, start);
+                    self.touch_gestures.append(&mut dev.touch.pending_gestures);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 处理一个设备的原始事件，生成的窗口事件追加到 `output`
+    fn process_device_events(&mut self, dev: &mut ManagedDevice, events: &[InputEvent], output: &mut Vec<WindowEvent>) {
         let mut sync_needed = false;
         
         let mut wheel_dx = 0;
         let mut wheel_dy = 0;
+        let mut rel_dx = 0;
+        let mut rel_dy = 0;
 
         for ev in events {
             match ev.destructure() {
-                // --- MT Protocol B / Touch Handling ---
-                EventSummary::AbsoluteAxis(_, code, value) => {
-                    dev.touch.process_axis(code, value, dev.is_protocol_b);
+                // --- 旋转编码器 ---
+                EventSummary::RelativeAxis(_, _, value) if dev.encoder.is_some() => {
+                    dev.encoder.as_mut().unwrap().process_relative(value);
+                }
+                EventSummary::AbsoluteAxis(_, _, value) if dev.encoder.is_some() => {
+                    dev.encoder.as_mut().unwrap().process_absolute(value);
+                }
+                EventSummary::Key(_, _, value) if dev.encoder.is_some() => {
+                    output.extend(dev.encoder.as_mut().unwrap().process_key(value));
                 }
 
+                // --- MT Protocol B / Touch Handling ---
+                EventSummary::AbsoluteAxis(_, code, value) => match dev.pen.as_mut() {
+                    Some(pen) => pen.process_axis(code, value),
+                    // 多点触控触摸板的 ABS_X/Y 只是第一个手指的副本，以各 Slot 的坐标为准
+                    None if dev.touchpad.is_some()
+                        && dev.is_protocol_b
+                        && matches!(code, AbsoluteAxisCode::ABS_X | AbsoluteAxisCode::ABS_Y) => {}
+                    None => dev.touch.process_axis(code, value, dev.is_protocol_b),
+                },
+
                 // --- 相对移动 (鼠标) ---
+                // 位移在帧结束时整体加速
                 EventSummary::RelativeAxis(_, RelativeAxisCode::REL_X, value) => {
-                    self.pointer_pos.x = (self.pointer_pos.x + value).clamp(0, self.screen_width as i32 - 1);
+                    rel_dx += value;
                     sync_needed = true;
                 }
                 EventSummary::RelativeAxis(_, RelativeAxisCode::REL_Y, value) => {
-                    self.pointer_pos.y = (self.pointer_pos.y + value).clamp(0, self.screen_height as i32 - 1);
+                    rel_dy += value;
                     sync_needed = true;
                 }
                 EventSummary::RelativeAxis(_, RelativeAxisCode::REL_WHEEL, value) => {
@@ -118,27 +907,20 @@ impl GlobalInputState {
 
                 // --- 按键 ---
                 EventSummary::Key(_, key, value) => {
-                    if let Some(btn) = map_key_to_pointer_button(key) {
-                        // 鼠标/触摸按键
-                        if dev.abs_x_info.is_none() { 
-                            let pressed = value == 1;
-                            if pressed {
-                                output.push(WindowEvent::PointerPressed {
-                                    position: self.pointer_pos.to_logical(1.0),
-                                    button: btn,
-                                });
-                            } else {
-                                output.push(WindowEvent::PointerReleased {
-                                    position: self.pointer_pos.to_logical(1.0),
-                                    button: btn,
-                                });
-                            }
-                        }
-                    } else {
-                        // 键盘按键 (委托给 KeyboardHandler)
-                        if let Some(e) = self.keyboard.handle_key_event(key, value) {
-                            output.push(e);
-                        }
+                    if dev.pen.as_mut().is_some_and(|pen| pen.process_key(key, value)) {
+                        continue;
+                    }
+                    if dev.touchpad.is_some() && TouchpadState::consumes_key(key) {
+                        continue;
+                    }
+                    let relative_pointer = dev.abs_x_info.is_none();
+                    self.process_key(&mut dev.held_modifiers, relative_pointer, key, value, output);
+                }
+
+                // --- 开关 ---
+                EventSummary::Switch(_, code, value) => {
+                    if let Some(switch) = Switch::from_switch_code(code) {
+                        self.switch_events.push((switch, value != 0));
                     }
                 }
 
@@ -151,36 +933,70 @@ impl GlobalInputState {
 
                 // --- 帧同步 ---
                 EventSummary::Synchronization(_, SynchronizationCode::SYN_REPORT, _) => {
+                    dev.touch.frame_time = clock::event_time(ev.timestamp());
                     if !dev.is_protocol_b {
                         dev.touch.finish_frame_protocol_a();
                     }
 
-                    if dev.abs_x_info.is_some() {
+                    let start = output.len();
+                    if let Some(encoder) = dev.encoder.as_mut() {
+                        encoder.finish_frame(self.pointer_pos, output);
+                    } else if let Some(pen) = dev.pen.as_mut() {
+                        // 手写笔：悬停移动、笔尖接触与笔杆按钮
+                        pen.finish_frame(
+                            &dev.touch,
+                            &mut self.pointer_pos,
+                            self.screen_width,
+                            self.screen_height,
+                            &dev.abs_x_info,
+                            &dev.abs_y_info,
+                            output,
+                        );
+                        self.throttle_moves(output, start);
+                    } else if let Some(touchpad) = dev.touchpad.as_mut() {
+                        // 触摸板：相对移动、轻点与双指滚动
+                        touchpad.finish_frame(
+                            &dev.touch,
+                            &mut self.pointer_pos,
+                            self.screen_width,
+                            self.screen_height,
+                            output,
+                        );
+                        self.throttle_moves(output, start);
+                    } else if dev.abs_x_info.is_some() && self.raw_touch {
+                        // 原始多点触控：逐个报告触点，主触点作为左键指针
+                        analyze_raw_touch(
+                            &mut dev.touch,
+                            &mut self.pointer_pos,
+                            &mut self.is_left_pressed,
+                            self.screen_width,
+                            self.screen_height,
+                            &dev.abs_x_info,
+                            &dev.abs_y_info,
+                            output,
+                        );
+                        self.touch_contacts.append(&mut dev.touch.pending_contacts);
+                        self.throttle_moves(output, start);
+                    } else if dev.abs_x_info.is_some() {
                         // 触摸手势分析
-                        if let Some(gesture_events) = analyze_touch_gesture(
-                            &mut dev.touch, 
-                            &mut self.pointer_pos, 
+                        analyze_touch_gesture(
+                            &mut dev.touch,
+                            &mut self.pointer_pos,
                             &mut self.is_left_pressed,
                             self.screen_width,
                             self.screen_height,
                             &dev.abs_x_info,
-                            &dev.abs_y_info
-                        ) {
-                            // 检查移动事件节流
-                            let mut filtered_events = Vec::new();
-                            for evt in gesture_events {
-                                match evt {
-                                    WindowEvent::PointerMoved { .. } => {
-                                        if self.should_emit_move() {
-                                            filtered_events.push(evt);
-                                        }
-                                    }
-                                    _ => filtered_events.push(evt),
-                                }
-                            }
-                            output.extend(filtered_events);
-                        }
+                            &dev.abs_y_info,
+                            output,
+                        );
+                        self.throttle_moves(output, start);
+                        self.touch_gestures.append(&mut dev.touch.pending_gestures);
                     } else if sync_needed {
+                        let (dx, dy) = self.accelerator.apply(rel_dx, rel_dy, dev.touch.frame_time);
+                        rel_dx = 0;
+                        rel_dy = 0;
+                        self.pointer_pos.x = (self.pointer_pos.x + dx).clamp(0, self.screen_width as i32 - 1);
+                        self.pointer_pos.y = (self.pointer_pos.y + dy).clamp(0, self.screen_height as i32 - 1);
                         if self.should_emit_move() {
                             output.push(WindowEvent::PointerMoved {
                                 position: self.pointer_pos.to_logical(1.0),
@@ -190,11 +1006,10 @@ impl GlobalInputState {
                     }
 
                     if wheel_dx != 0 || wheel_dy != 0 {
-                        let scroll_step = 20.0; 
                         output.push(WindowEvent::PointerScrolled {
                             position: self.pointer_pos.to_logical(1.0),
-                            delta_x: (wheel_dx as f32) * scroll_step,
-                            delta_y: (wheel_dy as f32) * scroll_step,
+                            delta_x: (wheel_dx as f32) * self.scroll_step.0,
+                            delta_y: (wheel_dy as f32) * self.scroll_step.1,
                         });
                         wheel_dx = 0;
                         wheel_dy = 0;
@@ -203,7 +1018,6 @@ impl GlobalInputState {
                 _ => {}
             }
         }
-        output
     }
 }
 
@@ -213,22 +1027,89 @@ pub struct InputManager {
     config: InputConfig,
     state: GlobalInputState,
     hotplug_receiver: Option<Receiver<ManagedDevice>>,
+    /// 复用的文件描述符列表，见 [`get_poll_fds`](Self::get_poll_fds)
+    poll_fds: Vec<RawFd>,
+    /// 复用的触点列表，见 [`touch_points`](Self::touch_points)
+    touch_points: Vec<PhysicalPosition>,
+    /// 终端 (stdin) 输入源
+    terminal: Option<TerminalInput>,
+    /// 应用于所有触摸设备的校准参数
+    touch_calibration: Option<TouchCalibration>,
+    /// 被禁用的设备 (名称或路径)，对之后添加的设备同样生效
+    disabled_devices: HashSet<String>,
+    /// 原始事件录制
+    #[cfg(feature = "input-replay")]
+    recorder: Option<InputRecorder>,
+    /// 录制事件回放
+    #[cfg(feature = "input-replay")]
+    replay: Option<InputReplay>,
+    /// 合成输入源
+    synthetic: SyntheticDevice,
 }
 
 impl InputManager {
-    pub fn new(screen_width: u32, screen_height: u32, config: InputConfig) -> Result<Self, Error> {
+    /// `screen_width` 与 `screen_height` 为窗口尺寸 (即旋转后的屏幕尺寸)
+    ///
+    /// `wake` 在热插拔线程发现新设备时调用，用于唤醒事件循环。
+    pub fn new(
+        screen_width: u32,
+        screen_height: u32,
+        config: InputConfig,
+        wake: impl Fn() + Send + 'static,
+    ) -> Result<Self, Error> {
         tracing::info!("InputManager 初始化: 屏幕 {}x{}, 自动发现: {}, 多线程: {}, XKB支持: {}", 
             screen_width, screen_height, config.autodiscovery, config.threaded_input, cfg!(feature = "xkb"));
 
-        let keyboard = KeyboardHandler::new()?;
+        let keyboard = KeyboardHandler::new(&config)?;
+
+        let touch_calibration = match &config.pointercal {
+            Some(path) => Some(
+                TouchCalibration::load(path)
+                    .map_err(|e| Error::Other(format!("无法加载触摸校准文件 {:?}: {}", path, e)))?,
+            ),
+            // 环境变量指向的文件可能尚未生成 (例如还未运行 ts_calibrate)，此时不视为错误
+            None => std::env::var_os("TSLIB_CALIBFILE").and_then(|path| {
+                TouchCalibration::load(Path::new(&path))
+                    .map_err(|e| tracing::warn!("无法加载触摸校准文件 {:?}: {}", path, e))
+                    .ok()
+            }),
+        };
+        if touch_calibration.is_some() {
+            tracing::info!("已启用 tslib 触摸校准");
+        }
+
+        let step_y = if config.natural_scrolling { -config.scroll_step } else { config.scroll_step };
+        let step_x = if config.invert_horizontal_scroll { -step_y } else { step_y };
 
         let state = GlobalInputState {
             pointer_pos: PhysicalPosition::new((screen_width / 2) as i32, (screen_height / 2) as i32),
             is_left_pressed: false,
+            pressed_buttons: Vec::new(),
             screen_width,
             screen_height,
+            accelerator: PointerAccelerator::new(config.pointer_acceleration),
+            scroll_step: (step_x, step_y),
             keyboard,
-            last_move_time: Instant::now(),
+            key_repeat_enabled: config.key_repeat.is_some(),
+            leds: LockState::default(),
+            system_keys: Vec::new(),
+            switch_events: Vec::new(),
+            device_events: Vec::new(),
+            backend_events: Vec::new(),
+            navigation_keys: config.keypad_navigation.as_ref().map(|nav| nav.keys.clone()).unwrap_or_default(),
+            raw_touch: config.raw_touch,
+            touch_contacts: Vec::new(),
+            touch_gestures: Vec::new(),
+            modifiers: Modifiers::default(),
+            held_modifiers: HashMap::new(),
+            key_interceptor: None,
+            close_shortcut: config.close_shortcut,
+            #[cfg(feature = "ime")]
+            ime: None,
+            #[cfg(feature = "ime")]
+            ime_keys: HashSet::new(),
+            move_throttle: config.move_throttle.filter(|throttle| !throttle.is_zero()),
+            last_move_time: clock::now(),
         };
 
         let mut manager = Self {
@@ -237,85 +1118,477 @@ impl InputManager {
             config: config.clone(),
             state,
             hotplug_receiver: None,
+            poll_fds: Vec::new(),
+            touch_points: Vec::new(),
+            terminal: None,
+            touch_calibration,
+            disabled_devices: HashSet::new(),
+            #[cfg(feature = "input-replay")]
+            recorder: None,
+            #[cfg(feature = "input-replay")]
+            replay: None,
+            synthetic: SyntheticDevice { touch: TouchState::new(), held_modifiers: HashSet::new(), pending: Vec::new() },
         };
+        manager.synthetic.touch.gestures = config.gestures;
+
+        if config.terminal_input {
+            match TerminalInput::new() {
+                Ok(terminal) => manager.terminal = Some(terminal),
+                Err(e) => tracing::warn!("无法启用终端输入: {}", e),
+            }
+        }
+
+        #[cfg(feature = "ime")]
+        if config.ime {
+            match ImeBridge::connect() {
+                Ok(ime) => manager.state.ime = Some(ime),
+                Err(e) => tracing::warn!("无法连接输入法: {}", e),
+            }
+        }
+
+        #[cfg(feature = "input-replay")]
+        {
+            if let Some(path) = &config.record_path {
+                let recorder = InputRecorder::create(path)
+                    .map_err(|e| Error::Other(format!("无法创建输入录制文件 {:?}: {}", path, e)))?;
+                tracing::info!("开始录制输入事件到 {:?}", path);
+                manager.recorder = Some(recorder);
+            }
+            if let Some(path) = &config.replay_path {
+                let replay = InputReplay::load(path)
+                    .map_err(|e| Error::Other(format!("无法加载输入录制文件 {:?}: {}", path, e)))?;
+                tracing::info!("开始回放输入事件 {:?}", path);
+                manager.replay = Some(replay);
+            }
+        }
 
         if config.autodiscovery {
             if config.threaded_input {
                 let (tx, rx) = channel();
                 manager.hotplug_receiver = Some(rx);
-                spawn_hotplug_thread(tx, config);
+                spawn_hotplug_thread(tx, config, wake);
             } else {
                 manager.rescan_devices_blocking();
             }
         }
+        manager.rebuild_poll_fds();
 
         Ok(manager)
     }
 
-    pub fn get_poll_fds(&self) -> Vec<RawFd> {
-        self.devices.iter().map(|dev| dev.device.as_raw_fd()).collect()
+    /// 所有触摸设备上当前活跃触点的屏幕坐标 (复用内部缓冲区，不在每次调用时分配)
+    pub fn touch_points(&mut self) -> &[PhysicalPosition] {
+        let (width, height) = (self.state.screen_width, self.state.screen_height);
+        self.touch_points.clear();
+        self.touch_points.extend(
+            self.devices
+                .iter()
+                .filter(|dev| dev.abs_x_info.is_some())
+                .flat_map(|dev| dev.touch.active_points(width, height, &dev.abs_x_info, &dev.abs_y_info)),
+        );
+        &self.touch_points
+    }
+
+    /// 返回指针位置、按下的按键和活跃触点数量
+    pub fn pointer_state(&self) -> PointerState {
+        let (width, height) = (self.state.screen_width, self.state.screen_height);
+        let touch_count = self
+            .devices
+            .iter()
+            .filter(|dev| dev.abs_x_info.is_some())
+            .map(|dev| dev.touch.active_points(width, height, &dev.abs_x_info, &dev.abs_y_info).count())
+            .sum();
+        PointerState { position: self.state.pointer_pos, pressed_buttons: self.state.pressed_buttons.clone(), touch_count }
+    }
+
+    /// 需要等待可读的文件描述符，只在输入源变化时重建
+    pub fn get_poll_fds(&self) -> &[RawFd] {
+        &self.poll_fds
     }
 
-    pub fn poll(&mut self) -> Vec<WindowEvent> {
+    /// 输入设备增减、开始或结束等待重新打开后重建 [`get_poll_fds`](Self::get_poll_fds) 的列表
+    fn rebuild_poll_fds(&mut self) {
+        self.poll_fds.clear();
+        // 等待重新打开的设备的描述符可能一直处于错误状态，不参与等待
+        self.poll_fds.extend(
+            self.devices
+                .iter()
+                .filter(|dev| dev.retry_at.is_none())
+                .map(|dev| dev.device.as_raw_fd())
+                .chain(self.terminal.as_ref().map(|terminal| terminal.fd()))
+                .chain(self.ime_fd()),
+        );
+    }
+
+    #[cfg(feature = "ime")]
+    fn ime_fd(&self) -> Option<RawFd> {
+        self.state.ime.as_ref().map(|ime| ime.fd())
+    }
+
+    #[cfg(not(feature = "ime"))]
+    fn ime_fd(&self) -> Option<RawFd> {
+        None
+    }
+
+    /// 读取并处理所有输入源，把生成的窗口事件写入 `slint_events` (先清空)
+    ///
+    /// 原始事件和窗口事件都写入复用的缓冲区，各设备逐帧生成的事件直接追加到 `slint_events`，
+    /// 高报告率的触摸屏、手写笔和触摸板在稳定状态下轮询不分配内存。
+    pub fn poll(&mut self, slint_events: &mut Vec<WindowEvent>) {
+        slint_events.clear();
+
         if self.config.autodiscovery {
             if self.config.threaded_input {
-                if let Some(rx) = &self.hotplug_receiver {
-                    while let Ok(device) = rx.try_recv() {
-                        tracing::info!("热插拔: 添加新设备 {:?}", device.path);
-                        self.devices.push(device);
-                    }
+                while let Some(device) = self.hotplug_receiver.as_ref().and_then(|rx| rx.try_recv().ok()) {
+                    tracing::info!("热插拔: 添加新设备 {:?}", device.path);
+                    self.add_device(device);
                 }
             } else {
                 if self.last_rescan.elapsed() > RESCAN_INTERVAL {
-                    self.rescan_devices_blocking();
+                    slint_events.extend(self.rescan_devices_blocking());
                 }
             }
         }
 
-        let mut slint_events = Vec::new();
         let mut indices_to_remove = Vec::new();
+        let mut retry_changed = false;
 
         for (i, managed_dev) in self.devices.iter_mut().enumerate() {
-            let events: Vec<_> = match managed_dev.device.fetch_events() {
-                Ok(iter) => iter.collect(),
-                Err(e) if e.kind() == io::ErrorKind::WouldBlock => Vec::new(),
-                Err(e) => {
-                    tracing::error!("设备读取失败 {:?}: {}", managed_dev.path, e);
-                    indices_to_remove.push(i);
-                    Vec::new()
-                }
-            };
+            let retrying = managed_dev.retry_at.is_some();
+            let result = managed_dev.read_events(self.config.grab_devices, &mut self.state.backend_events);
+            // 重新打开后描述符也会改变
+            retry_changed |= managed_dev.retry_at.is_some() != retrying;
+            if let Err(e) = result {
+                self.state.backend_events.push(BackendEvent::Warning(BackendIssue::DeviceDropped {
+                    path: managed_dev.path.clone(),
+                    reason: e.to_string(),
+                }));
+                indices_to_remove.push(i);
+                continue;
+            }
+            if !managed_dev.enabled || managed_dev.events.is_empty() {
+                continue;
+            }
 
-            if !events.is_empty() {
-                let new_events = self.state.process_device_events(managed_dev, events);
-                slint_events.extend(new_events);
+            #[cfg(feature = "input-replay")]
+            if let Some(recorder) = self.recorder.as_mut() {
+                let name = managed_dev.device.name().unwrap_or_default();
+                if let Err(e) = recorder.record(&managed_dev.path, name, &managed_dev.events) {
+                    tracing::error!("输入录制失败，停止录制: {}", e);
+                    self.recorder = None;
+                }
             }
+
+            // 暂时取出缓冲区 (保留容量)，处理时设备本身也需要可变借用
+            let events = std::mem::take(&mut managed_dev.events);
+            self.state.process_device_events(managed_dev, &events, slint_events);
+            managed_dev.events = events;
         }
 
         for &i in indices_to_remove.iter().rev() {
-            self.devices.remove(i);
+            let device = self.devices.remove(i);
+            slint_events.extend(self.state.remove_device(&device));
         }
+        if retry_changed || !indices_to_remove.is_empty() {
+            self.rebuild_poll_fds();
+        }
+
+        #[cfg(feature = "input-replay")]
+        self.poll_replay(slint_events);
+
+        if !self.synthetic.pending.is_empty() {
+            let mut synthetic = std::mem::take(&mut self.synthetic.pending);
+            self.state.process_synthetic_events(&mut self.synthetic, &synthetic, slint_events);
+            synthetic.clear();
+            self.synthetic.pending = synthetic;
+        }
+
+        // 双指滚动抬起后的惯性滚动
+        for managed_dev in &mut self.devices {
+            slint_events.extend(managed_dev.touch.kinetic_scroll());
+        }
+        slint_events.extend(self.synthetic.touch.kinetic_scroll());
+
+        if let Some(terminal) = &mut self.terminal {
+            terminal.read_events(slint_events);
+        }
+
+        // 纯按键导航模式不产生指针事件
+        if self.config.keypad_navigation.is_some() {
+            slint_events.retain(|event| {
+                !matches!(
+                    event,
+                    WindowEvent::PointerPressed { .. }
+                        | WindowEvent::PointerReleased { .. }
+                        | WindowEvent::PointerMoved { .. }
+                        | WindowEvent::PointerScrolled { .. }
+                        | WindowEvent::PointerExited
+                )
+            });
+        }
+
+        // 关闭节流时保留每一个采样点 (手写笔迹)
+        if self.state.move_throttle.is_some() {
+            coalesce_pointer_moves(slint_events);
+        }
+
+        for event in slint_events.iter() {
+            match *event {
+                WindowEvent::PointerPressed { button, .. } if !self.state.pressed_buttons.contains(&button) => {
+                    self.state.pressed_buttons.push(button);
+                }
+                WindowEvent::PointerReleased { button, .. } => self.state.pressed_buttons.retain(|&b| b != button),
+                _ => {}
+            }
+        }
+
+        // 锁定键状态变化时，更新所有键盘上的指示灯
+        let locks = self.state.keyboard.lock_state();
+        if locks != self.state.leds {
+            self.state.leds = locks;
+            for managed_dev in &mut self.devices {
+                sync_leds(&mut managed_dev.device, locks);
+            }
+        }
+    }
+
+    /// 距离下一次需要调用 [`poll`](Self::poll) 的时间 (例如惯性滚动)，`None` 表示只需等待输入事件
+    pub fn next_timeout(&self) -> Option<Duration> {
+        let now = Instant::now();
+        let kinetic = self
+            .devices
+            .iter()
+            .map(|dev| &dev.touch)
+            .chain([&self.synthetic.touch])
+            .any(TouchState::is_kinetic_scrolling)
+            .then_some(touch::KINETIC_INTERVAL);
+        // 等待重新打开的设备
+        let retry = self
+            .devices
+            .iter()
+            .filter_map(|dev| dev.retry_at)
+            .map(|retry_at| retry_at.saturating_duration_since(now))
+            .min();
+        #[cfg(feature = "input-replay")]
+        let replay = self.replay.as_ref().and_then(|replay| replay.next_timeout());
+        #[cfg(not(feature = "input-replay"))]
+        let replay = None;
+        // 不使用热插拔线程时由事件循环定期重新扫描
+        let rescan = (self.config.autodiscovery && !self.config.threaded_input)
+            .then(|| RESCAN_INTERVAL.saturating_sub(self.last_rescan.elapsed()));
+        [kinetic, retry, replay, rescan].into_iter().flatten().min()
+    }
+
+    /// 把已到时间的录制事件交给同名设备处理
+    #[cfg(feature = "input-replay")]
+    fn poll_replay(&mut self, output: &mut Vec<WindowEvent>) {
+        let Some(replay) = self.replay.as_mut() else { return };
+        for (name, events) in replay.take_due() {
+            let device = self
+                .devices
+                .iter_mut()
+                .find(|dev| dev.device.name().unwrap_or_default() == name);
+            match device {
+                Some(device) => self.state.process_device_events(device, &events, output),
+                None => tracing::warn!("回放: 未找到设备 {:?}，跳过 {} 个事件", name, events.len()),
+            }
+        }
+        if replay.is_finished() {
+            tracing::info!("输入事件回放结束");
+            self.replay = None;
+        }
+    }
 
-        slint_events
+    /// 把 evdev 事件交给合成输入源，在下一次 [`poll`](Self::poll) 中处理
+    ///
+    /// 多点触控 (Protocol B) 坐标为窗口像素，与真实触摸屏一样经过手势识别；按键经过相同的键盘映射。
+    pub(crate) fn push_synthetic_events(&mut self, events: impl IntoIterator<Item = InputEvent>) {
+        self.synthetic.pending.extend(events);
     }
 
-    fn rescan_devices_blocking(&mut self) {
+    /// 是否启用了纯按键导航模式
+    pub fn keypad_navigation(&self) -> bool {
+        self.config.keypad_navigation.is_some()
+    }
+
+    /// 独占或释放所有输入设备 (例如在交互式触摸校准期间临时释放)
+    pub(crate) fn set_grab(&mut self, grab: bool) {
+        for dev in &mut self.devices {
+            dev.set_grab(grab);
+        }
+    }
+
+    /// 设置原始按键拦截器，在按键转换为 Slint 事件之前调用
+    pub fn set_key_interceptor(&mut self, interceptor: Option<KeyInterceptor>) {
+        self.state.key_interceptor = interceptor;
+    }
+
+    /// 同步文本输入框的焦点状态，只有获得焦点时按键才会交给输入法
+    #[cfg(feature = "ime")]
+    pub fn set_text_input_focused(&mut self, focused: bool) {
+        if let Some(ime) = &mut self.state.ime {
+            ime.set_focused(focused);
+        }
+    }
+
+    /// 取出输入法产生的预编辑与上屏事件
+    #[cfg(feature = "ime")]
+    pub fn take_ime_events(&mut self) -> Vec<ImeEvent> {
+        self.state.ime.as_mut().map(|ime| ime.take_events()).unwrap_or_default()
+    }
+
+    /// 取出自上次调用以来的触点变化 (仅在原始多点触控模式下产生)
+    pub fn take_touch_contacts(&mut self) -> Vec<TouchContact> {
+        std::mem::take(&mut self.state.touch_contacts)
+    }
+
+    /// 取出自上次调用以来识别出的触摸手势
+    pub fn take_touch_gestures(&mut self) -> Vec<TouchGesture> {
+        std::mem::take(&mut self.state.touch_gestures)
+    }
+
+    /// 取出自上次调用以来的开关状态变化 (开关, 是否激活)
+    pub fn take_switch_events(&mut self) -> Vec<(Switch, bool)> {
+        std::mem::take(&mut self.state.switch_events)
+    }
+
+    /// 取出自上次调用以来添加和移除的设备
+    pub fn take_device_events(&mut self) -> Vec<DeviceEvent> {
+        std::mem::take(&mut self.state.device_events)
+    }
+
+    /// 取出自上次调用以来的运行时问题
+    pub fn take_backend_events(&mut self) -> Vec<BackendEvent> {
+        std::mem::take(&mut self.state.backend_events)
+    }
+
+    /// 是否有尚未取出的系统按键
+    pub fn has_system_keys(&self) -> bool {
+        !self.state.system_keys.is_empty()
+    }
+
+    /// 取出自上次调用以来按下的系统按键
+    pub fn take_system_keys(&mut self) -> Vec<SystemKey> {
+        std::mem::take(&mut self.state.system_keys)
+    }
+
+    /// 启用或禁用名称包含 `name_or_path` (或路径与其相同) 的设备
+    ///
+    /// 禁用期间设备的事件被读取后直接丢弃；设置同样作用于之后插入的匹配设备。
+    pub fn set_device_enabled(&mut self, name_or_path: &str, enabled: bool) {
+        if enabled {
+            self.disabled_devices.remove(name_or_path);
+        } else {
+            self.disabled_devices.insert(name_or_path.to_string());
+        }
+        for dev in &mut self.devices {
+            if dev.matches(name_or_path) {
+                tracing::info!("{}输入设备 {:?}", if enabled { "启用" } else { "禁用" }, dev.path);
+                dev.enabled = !self.disabled_devices.iter().any(|pattern| dev.matches(pattern));
+            }
+        }
+    }
+
+    /// 窗口尺寸改变 (例如显示模式被外部改变) 后更新坐标映射范围，指针限制在新的范围内
+    pub(crate) fn set_screen_size(&mut self, screen_width: u32, screen_height: u32) {
+        self.state.screen_width = screen_width;
+        self.state.screen_height = screen_height;
+        let pos = &mut self.state.pointer_pos;
+        pos.x = pos.x.clamp(0, (screen_width as i32 - 1).max(0));
+        pos.y = pos.y.clamp(0, (screen_height as i32 - 1).max(0));
+    }
+
+    /// 替换所有触摸设备的校准参数 (例如交互式校准完成后)
+    pub(crate) fn set_touch_calibration(&mut self, calibration: Option<TouchCalibration>) {
+        self.touch_calibration = calibration;
+        for dev in &mut self.devices {
+            configure_touch(dev, &self.config, calibration);
+        }
+    }
+
+    fn add_device(&mut self, mut device: ManagedDevice) {
+        sync_leds(&mut device.device, self.state.leds);
+        self.state.device_events.push(DeviceEvent::Added(device.info()));
+        device.enabled = !self.disabled_devices.iter().any(|pattern| device.matches(pattern));
+        // 报告已激活的开关 (例如启动时盖子已合上)
+        if has_switches(&device.device) {
+            if let Ok(active) = device.device.get_switch_state() {
+                for switch in Switch::ALL {
+                    if active.contains(switch.code()) {
+                        self.state.switch_events.push((switch, true));
+                    }
+                }
+            }
+        }
+        configure_touch(&mut device, &self.config, self.touch_calibration);
+        self.devices.push(device);
+        self.rebuild_poll_fds();
+    }
+
+    /// 重新扫描设备，返回因设备移除而释放修饰键产生的事件
+    fn rescan_devices_blocking(&mut self) -> Vec<WindowEvent> {
         let found_paths = scan_input_dir();
-        self.devices.retain(|dev| found_paths.contains(&dev.path));
-        
+        let mut released = Vec::new();
+        let (kept, removed): (Vec<_>, Vec<_>) = std::mem::take(&mut self.devices)
+            .into_iter()
+            .partition(|dev| found_paths.contains(&dev.path));
+        self.devices = kept;
+        if !removed.is_empty() {
+            self.rebuild_poll_fds();
+        }
+        for device in removed {
+            released.extend(self.state.remove_device(&device));
+        }
+
         for path in found_paths {
             if !self.devices.iter().any(|dev| dev.path == path) {
                 if let Ok(Some(managed_device)) = open_device_if_compatible(&path, &self.config) {
-                    self.devices.push(managed_device);
+                    self.add_device(managed_device);
                 }
             }
         }
         self.last_rescan = Instant::now();
+        released
     }
 }
 
 // --- 独立函数与线程逻辑 ---
 
+/// 让设备以 `CLOCK_MONOTONIC` 报告事件时间戳 (EVIOCSCLOCKID)，使其可以换算为 [`Instant`]
+///
+/// 默认的 `CLOCK_REALTIME` 会随系统时间调整跳变；设置失败时手势计时退回到处理事件时的时间。
+fn use_monotonic_timestamps(device: &Device) {
+    use rustix::ioctl::{ioctl, opcode, Setter};
+    const SET_CLOCK_ID: rustix::ioctl::Opcode = opcode::write::<libc::c_int>(b'E', 0xa0);
+    // SAFETY: EVIOCSCLOCKID 读取一个 int 类型的时钟编号
+    if let Err(e) = unsafe { ioctl(device, Setter::<SET_CLOCK_ID, libc::c_int>::new(libc::CLOCK_MONOTONIC)) } {
+        tracing::debug!("无法切换输入设备的时间戳时钟: {}", e);
+    }
+}
+
+/// 将锁定键状态写入设备的指示灯 (EV_LED)，不支持的指示灯会被跳过
+fn sync_leds(device: &mut Device, locks: LockState) {
+    let Some(supported) = device.supported_leds() else {
+        return;
+    };
+    let mut events: Vec<InputEvent> = [
+        (LedCode::LED_CAPSL, locks.caps_lock),
+        (LedCode::LED_NUML, locks.num_lock),
+        (LedCode::LED_SCROLLL, locks.scroll_lock),
+    ]
+    .into_iter()
+    .filter(|(led, _)| supported.contains(*led))
+    .map(|(led, on)| InputEvent::new(EventType::LED.0, led.0, on as i32))
+    .collect();
+    if events.is_empty() {
+        return;
+    }
+    events.push(InputEvent::new(EventType::SYNCHRONIZATION.0, SynchronizationCode::SYN_REPORT.0, 0));
+    if let Err(e) = device.send_events(&events) {
+        tracing::debug!("无法更新键盘指示灯: {}", e);
+    }
+}
+
 fn scan_input_dir() -> HashSet<PathBuf> {
     let mut found = HashSet::new();
     if let Ok(entries) = fs::read_dir("/dev/input") {
@@ -329,7 +1602,114 @@ fn scan_input_dir() -> HashSet<PathBuf> {
     found
 }
 
-fn spawn_hotplug_thread(sender: Sender<ManagedDevice>, config: InputConfig) {
+/// 打开所有触摸屏设备 (供交互式校准读取原始坐标)
+pub(crate) fn open_touchscreens() -> Vec<Device> {
+    scan_input_dir()
+        .into_iter()
+        .filter_map(|path| Device::open(path).ok())
+        .filter(|device| is_touchscreen(device) && !TouchpadState::is_touchpad(device))
+        .collect()
+}
+
+/// 识别 `/dev/input` 下所有事件设备的功能 (供诊断报告使用)
+///
+/// 旋转编码器需要按配置匹配名称，因此不会被识别。
+pub(crate) fn probe_devices() -> Vec<(PathBuf, io::Result<InputDeviceInfo>)> {
+    let mut paths: Vec<_> = scan_input_dir().into_iter().collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let info = Device::open(&path).map(|device| device_info(&path, &device, false));
+            (path, info)
+        })
+        .collect()
+}
+
+/// 按配置 (自动发现、名称白名单/黑名单、自定义过滤条件) 识别事件循环会使用的输入设备 (供 `probe` 使用)
+///
+/// 打开失败的设备也会返回，以便报告权限问题。配置了独占时会试探 EVIOCGRAB 并立即释放，
+/// 设备已被其他程序独占时返回错误。
+pub(crate) fn probe_configured_devices(config: &InputConfig) -> Vec<(PathBuf, io::Result<InputDeviceInfo>)> {
+    if !config.autodiscovery {
+        return Vec::new();
+    }
+    let mut paths: Vec<_> = scan_input_dir().into_iter().collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let mut device = match Device::open(&path) {
+                Ok(device) => device,
+                Err(e) => return Some((path, Err(e))),
+            };
+            let name = device.name().unwrap_or("Unknown Device").to_string();
+            if !accepts_device(&device, &name, config) {
+                return None;
+            }
+            let encoder = config.encoders.iter().any(|encoder| name.contains(&encoder.name_pattern));
+            let info = device_info(&path, &device, encoder);
+            if info.capabilities == DeviceCapabilities::default() {
+                return None;
+            }
+            if config.grab_devices {
+                if let Err(e) = device.grab() {
+                    return Some((path, Err(e)));
+                }
+                let _ = device.ungrab();
+            }
+            Some((path, Ok(info)))
+        })
+        .collect()
+}
+
+/// 按设备报告的功能识别设备类型，`encoder` 表示名称与编码器配置匹配
+fn device_info(path: &Path, device: &Device, encoder: bool) -> InputDeviceInfo {
+    let touchpad = TouchpadState::is_touchpad(device);
+    let pen = PenState::is_pen(device);
+    InputDeviceInfo {
+        name: device.name().unwrap_or("Unknown Device").to_string(),
+        path: path.to_path_buf(),
+        capabilities: DeviceCapabilities {
+            touchscreen: is_touchscreen(device) && !touchpad && !pen && !encoder,
+            touchpad: touchpad && !encoder,
+            pen,
+            mouse: is_mouse(device),
+            keyboard: is_keyboard(device),
+            encoder,
+            switches: has_switches(device),
+        },
+    }
+}
+
+/// 合并连续的指针移动，每段只保留最后一个 `PointerMoved`
+///
+/// 多个设备或触点在同一次轮询中报告时，中间位置只会带来多余的命中测试。
+/// 按下、释放等其他事件自身带有坐标，前后的移动分别合并，事件顺序保持不变。
+fn coalesce_pointer_moves(events: &mut Vec<WindowEvent>) {
+    // `dedup_by` 删除的是后一个元素，先交换使保留下来的是最新的位置
+    events.dedup_by(|next, previous| {
+        let both_moves =
+            matches!((&*previous, &*next), (WindowEvent::PointerMoved { .. }, WindowEvent::PointerMoved { .. }));
+        if both_moves {
+            std::mem::swap(next, previous);
+        }
+        both_moves
+    });
+}
+
+/// 设备是否通过名称白名单/黑名单和自定义过滤条件
+fn accepts_device(device: &Device, name: &str, config: &InputConfig) -> bool {
+    if config.blacklist.iter().any(|block| name.contains(block)) {
+        return false;
+    }
+    if !config.whitelist.is_empty() && !config.whitelist.iter().any(|allow| name.contains(allow)) {
+        return false;
+    }
+    config.device_filter.as_ref().is_none_or(|filter| filter.accepts(device))
+}
+
+fn spawn_hotplug_thread(sender: Sender<ManagedDevice>, config: InputConfig, wake: impl Fn() + Send + 'static) {
     thread::spawn(move || {
         let mut known_paths = HashSet::new();
         loop {
@@ -340,6 +1720,7 @@ fn spawn_hotplug_thread(sender: Sender<ManagedDevice>, config: InputConfig) {
                         if sender.send(device).is_err() {
                             return;
                         }
+                        wake();
                         known_paths.insert(path.clone());
                     }
                 }
@@ -353,20 +1734,13 @@ fn spawn_hotplug_thread(sender: Sender<ManagedDevice>, config: InputConfig) {
 fn open_device_if_compatible(path: &Path, config: &InputConfig) -> io::Result<Option<ManagedDevice>> {
     let mut device = Device::open(path)?;
     let name = device.name().unwrap_or("Unknown Device");
-
-    for block in &config.blacklist {
-        if name.contains(block) { return Ok(None); }
-    }
-    if !config.whitelist.is_empty() {
-        let mut found = false;
-        for allow in &config.whitelist {
-            if name.contains(allow) { found = true; break; }
-        }
-        if !found { return Ok(None); }
+    if !accepts_device(&device, name, config) {
+        return Ok(None);
     }
 
     device.set_nonblocking(true)
         .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+    use_monotonic_timestamps(&device);
 
     let mut abs_x_info = None;
     let mut abs_y_info = None;
@@ -375,33 +1749,116 @@ fn open_device_if_compatible(path: &Path, config: &InputConfig) -> io::Result<Op
         axes.contains(AbsoluteAxisCode::ABS_MT_SLOT)
     });
 
-    if is_touchscreen(&device) {
-        if let Ok(axes) = device.get_absinfo() {
-            for (code, info) in axes {
-                match code {
-                    AbsoluteAxisCode::ABS_X | AbsoluteAxisCode::ABS_MT_POSITION_X => abs_x_info = Some(info),
-                    AbsoluteAxisCode::ABS_Y | AbsoluteAxisCode::ABS_MT_POSITION_Y => abs_y_info = Some(info),
-                    _ => {}
-                }
-            }
-        }
+    let encoder = config
+        .encoders
+        .iter()
+        .find(|encoder| name.contains(&encoder.name_pattern))
+        .map(|encoder| EncoderState::new(&device, encoder.action, config.scroll_step));
+    let touchpad = TouchpadState::is_touchpad(&device).then(|| {
+        let mut touchpad = TouchpadState::new(&device);
+        touchpad.speed = config.pointer_acceleration.speed;
+        touchpad.natural_scrolling = config.natural_scrolling;
+        touchpad
+    });
+    if encoder.is_some() {
+        tracing::info!("旋转编码器: {:?}", name);
+    } else if touchpad.is_some() {
+        // 触摸板按相对设备处理，不记录绝对坐标范围
+    } else if is_touchscreen(&device) {
+        // 多点触控坐标的范围可能与模拟单点的 ABS_X/Y 不同，以 MT 坐标轴为准
+        let axes: HashMap<_, _> = device.get_absinfo().map(Iterator::collect).unwrap_or_default();
+        let range = |mt, legacy| axes.get(&mt).or_else(|| axes.get(&legacy)).copied();
+        abs_x_info = range(AbsoluteAxisCode::ABS_MT_POSITION_X, AbsoluteAxisCode::ABS_X);
+        abs_y_info = range(AbsoluteAxisCode::ABS_MT_POSITION_Y, AbsoluteAxisCode::ABS_Y);
     } else if is_mouse(&device) {
         // Just log
     } else if is_keyboard(&device) {
-        let repeat_config = evdev::AutoRepeat { delay: 250, period: 33 };
+        // 周期为 0 时内核不会产生重复事件
+        let repeat_config = match config.key_repeat {
+            Some(repeat) => evdev::AutoRepeat {
+                delay: repeat.delay.as_millis() as u32,
+                period: repeat.period.as_millis() as u32,
+            },
+            None => evdev::AutoRepeat { delay: 0, period: 0 },
+        };
         let _ = device.update_auto_repeat(&repeat_config);
+    } else if has_switches(&device) {
+        // 只报告开关状态的设备 (盖子、扩展坞等)
     } else {
         return Ok(None);
     }
 
-    Ok(Some(ManagedDevice {
+    let pen = PenState::is_pen(&device).then(PenState::new);
+    let touch = TouchState::for_device(&device);
+    let mut managed_device = ManagedDevice {
         path: path.to_path_buf(),
         device,
         abs_x_info,
         abs_y_info,
         is_protocol_b,
-        touch: TouchState::new(),
-    }))
+        touch,
+        pen,
+        touchpad,
+        encoder,
+        held_modifiers: HashSet::new(),
+        events: Vec::with_capacity(EVENT_BUFFER_CAPACITY),
+        grabbed: false,
+        enabled: true,
+        read_failures: 0,
+        retry_at: None,
+    };
+    managed_device.set_grab(config.grab_devices);
+    Ok(Some(managed_device))
+}
+
+/// 为触摸设备 (包括手写笔) 设置校准参数及其名称匹配的坐标变换
+///
+/// 全局校准参数针对整个屏幕，因此不用于映射到部分区域的设备。
+fn configure_touch(dev: &mut ManagedDevice, config: &InputConfig, calibration: Option<TouchCalibration>) {
+    if dev.abs_x_info.is_none() {
+        return;
+    }
+    let name = dev.device.name().unwrap_or_default();
+    let transform = config
+        .device_transforms
+        .iter()
+        .find(|transform| name.contains(&transform.name_pattern));
+    let region = transform.and_then(|transform| transform.region);
+    if let Some(transform) = transform {
+        tracing::debug!("触摸设备 {:?} 使用坐标变换 {:?}", name, transform);
+    }
+    dev.touch.calibration = if region.is_some() { None } else { calibration };
+    dev.touch.rotation = transform
+        .and_then(|transform| transform.rotation)
+        .or(config.touch_rotation)
+        .unwrap_or_default();
+    dev.touch.region = region;
+    dev.touch.pressure_threshold = config.pressure_threshold;
+    dev.touch.gestures = config.gestures;
+    dev.touch.smoothing = config.touch_smoothing.map(PointFilter::new);
+    if let Some(pen) = dev.pen.as_mut() {
+        pen.eraser_button = config.pen_eraser_button;
+    }
+}
+
+/// 读取或打开设备时的错误是否表示设备已被拔出
+fn is_device_removed(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::NotFound || error.raw_os_error() == Some(libc::ENODEV)
+}
+
+/// 在多个键盘之间共享按住状态的修饰键
+fn is_modifier_key(key: KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::KEY_LEFTSHIFT
+            | KeyCode::KEY_RIGHTSHIFT
+            | KeyCode::KEY_LEFTCTRL
+            | KeyCode::KEY_RIGHTCTRL
+            | KeyCode::KEY_LEFTALT
+            | KeyCode::KEY_RIGHTALT
+            | KeyCode::KEY_LEFTMETA
+            | KeyCode::KEY_RIGHTMETA
+    )
 }
 
 fn map_key_to_pointer_button(key: KeyCode) -> Option<PointerEventButton> {
@@ -429,8 +1886,54 @@ fn is_mouse(dev: &Device) -> bool {
     has_rel && has_btn
 }
 
+fn has_switches(dev: &Device) -> bool {
+    dev.supported_switches()
+        .is_some_and(|switches| Switch::ALL.iter().any(|switch| switches.contains(switch.code())))
+}
+
 fn is_keyboard(dev: &Device) -> bool {
     dev.supported_keys().map_or(false, |keys| {
         keys.contains(KeyCode::KEY_A) && keys.contains(KeyCode::KEY_ENTER)
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i_slint_core::api::LogicalPosition;
+
+    #[test]
+    fn test_coalesce_pointer_moves() {
+        let moved = |x| WindowEvent::PointerMoved { position: LogicalPosition::new(x, 0.0) };
+        let pressed = WindowEvent::PointerPressed {
+            position: LogicalPosition::new(3.0, 0.0),
+            button: PointerEventButton::Left,
+        };
+        let mut events = vec![moved(1.0), moved(2.0), pressed.clone(), moved(4.0), moved(5.0), moved(6.0)];
+        coalesce_pointer_moves(&mut events);
+        assert_eq!(events, [moved(2.0), pressed, moved(6.0)]);
+    }
+
+    #[test]
+    fn test_disabled_throttle_keeps_every_move() {
+        let config = InputConfig { autodiscovery: false, move_throttle: None, ..InputConfig::default() };
+        let mut manager = InputManager::new(100, 100, config, || {}).unwrap();
+        let abs = |code: AbsoluteAxisCode, value| InputEvent::new(EventType::ABSOLUTE.0, code.0, value);
+        let sync = InputEvent::new(EventType::SYNCHRONIZATION.0, SynchronizationCode::SYN_REPORT.0, 0);
+        manager.push_synthetic_events([abs(AbsoluteAxisCode::ABS_MT_TRACKING_ID, 1)]);
+        for x in [10, 20, 30, 40] {
+            manager.push_synthetic_events([abs(AbsoluteAxisCode::ABS_MT_POSITION_X, x), sync]);
+        }
+
+        let mut events = Vec::new();
+        manager.poll(&mut events);
+        let moves: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                WindowEvent::PointerMoved { position } => Some(position.x),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(moves, [10.0, 20.0, 30.0, 40.0]);
+    }
+}
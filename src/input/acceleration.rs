@@ -0,0 +1,107 @@
+//! 鼠标指针加速
+//!
+//! 鼠标报告的是设备计数 (REL_X / REL_Y)，与屏幕像素没有固定比例：
+//! 原样使用时在高分辨率屏幕上移动迟缓，在低分辨率屏幕上又过于灵敏。
+//! 这里提供两种曲线：
+//! - 平坦 (Flat)：位移乘以固定系数
+//! - 自适应 (Adaptive)：慢速时保持精确，移动越快放大倍数越高 (类似 libinput 的默认曲线)
+
+use std::time::{Duration, Instant};
+
+/// 自适应曲线开始加速的速度 (计数/毫秒)
+const ADAPTIVE_THRESHOLD: f32 = 0.4;
+/// 超过阈值后每 (计数/毫秒) 增加的倍数
+const ADAPTIVE_SLOPE: f32 = 1.0;
+/// 自适应曲线的最大倍数
+const ADAPTIVE_MAX_FACTOR: f32 = 3.5;
+/// 两帧间隔超过此值时视为重新开始移动，按一帧的典型间隔估计速度
+const MOTION_TIMEOUT: Duration = Duration::from_millis(100);
+const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(8);
+
+/// 加速曲线
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccelProfile {
+    /// 固定倍数，不随速度变化
+    #[default]
+    Flat,
+    /// 随移动速度增加倍数
+    Adaptive,
+}
+
+/// 鼠标指针加速参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerAcceleration {
+    pub profile: AccelProfile,
+    /// 基础速度倍数，`1.0` 表示每个设备计数移动一个像素
+    pub speed: f32,
+}
+
+impl Default for PointerAcceleration {
+    /// 默认不加速，与设备计数一一对应
+    fn default() -> Self {
+        Self { profile: AccelProfile::Flat, speed: 1.0 }
+    }
+}
+
+/// 按加速参数把设备计数转换为像素位移
+#[derive(Debug)]
+pub struct PointerAccelerator {
+    config: PointerAcceleration,
+    /// 累积的不足一像素的位移
+    remainder: (f32, f32),
+    last_motion: Option<Instant>,
+}
+
+impl PointerAccelerator {
+    pub fn new(config: PointerAcceleration) -> Self {
+        Self { config, remainder: (0.0, 0.0), last_motion: None }
+    }
+
+    /// 转换一帧内的相对位移，`now` 用于估计移动速度
+    pub fn apply(&mut self, dx: i32, dy: i32, now: Instant) -> (i32, i32) {
+        let dt = self
+            .last_motion
+            .map(|last| now.duration_since(last))
+            .filter(|dt| *dt < MOTION_TIMEOUT)
+            .unwrap_or(DEFAULT_FRAME_INTERVAL);
+        self.last_motion = Some(now);
+
+        let factor = match self.config.profile {
+            AccelProfile::Flat => 1.0,
+            AccelProfile::Adaptive => {
+                let millis = (dt.as_secs_f32() * 1000.0).max(1.0);
+                let velocity = (dx as f32).hypot(dy as f32) / millis;
+                (1.0 + (velocity - ADAPTIVE_THRESHOLD).max(0.0) * ADAPTIVE_SLOPE).min(ADAPTIVE_MAX_FACTOR)
+            }
+        } * self.config.speed;
+
+        self.remainder.0 += dx as f32 * factor;
+        self.remainder.1 += dy as f32 * factor;
+        let (x, y) = (self.remainder.0.trunc(), self.remainder.1.trunc());
+        self.remainder.0 -= x;
+        self.remainder.1 -= y;
+        (x as i32, y as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        let start = Instant::now();
+        let frame = |i: u32| start + DEFAULT_FRAME_INTERVAL * i;
+
+        // 平坦曲线：半速时两帧合计移动一个像素
+        let mut flat = PointerAccelerator::new(PointerAcceleration { profile: AccelProfile::Flat, speed: 0.5 });
+        assert_eq!(flat.apply(1, 0, frame(0)), (0, 0));
+        assert_eq!(flat.apply(1, 0, frame(1)), (1, 0));
+
+        // 自适应曲线：慢速不放大，快速放大
+        let mut adaptive = PointerAccelerator::new(PointerAcceleration { profile: AccelProfile::Adaptive, speed: 1.0 });
+        assert_eq!(adaptive.apply(2, 0, frame(0)), (2, 0));
+        let (fast, _) = adaptive.apply(20, 0, frame(1));
+        assert!(fast > 40 && fast <= 70);
+    }
+}
@@ -0,0 +1,94 @@
+//! 蜂鸣器 (EV_SND) 操作反馈
+//!
+//! 工业面板常带有类似 PC 扬声器的蜂鸣器，内核通过支持 `EV_SND` 的输入设备 (例如 `pcspkr`) 暴露。
+//! 按键或轻点时写入 `SND_TONE` (指定频率) 或 `SND_BELL`，持续一段时间后再写入 0 停止。
+//! 停止需要等待，因此由独立线程发声，事件循环只投递请求；上一次鸣响尚未结束时新的请求被丢弃。
+
+use evdev::{Device, EventType, InputEvent, SoundCode};
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::thread;
+use std::time::Duration;
+
+/// 蜂鸣器反馈配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeeperConfig {
+    /// 蜂鸣器设备路径，`None` 时自动查找支持 `EV_SND` 的设备
+    pub device: Option<PathBuf>,
+    /// 音调频率 (Hz)，设备只支持 `SND_BELL` 时忽略
+    pub frequency: u32,
+    /// 每次鸣响的时长
+    pub duration: Duration,
+    /// 按键按下时鸣响
+    pub on_key: bool,
+    /// 触摸轻点或鼠标按下时鸣响
+    pub on_tap: bool,
+}
+
+impl Default for BeeperConfig {
+    fn default() -> Self {
+        Self { device: None, frequency: 2000, duration: Duration::from_millis(20), on_key: true, on_tap: true }
+    }
+}
+
+/// 发声线程的句柄
+pub(crate) struct Beeper {
+    sender: SyncSender<()>,
+}
+
+impl Beeper {
+    /// 打开蜂鸣器设备并启动发声线程
+    pub fn open(config: &BeeperConfig) -> io::Result<Self> {
+        let (path, mut device) = match &config.device {
+            Some(path) => (path.clone(), Device::open(path)?),
+            None => find_beeper()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "未找到支持 EV_SND 的蜂鸣器设备"))?,
+        };
+        let tone = device.supported_sounds().is_some_and(|sounds| sounds.contains(SoundCode::SND_TONE));
+        if !tone && !device.supported_sounds().is_some_and(|sounds| sounds.contains(SoundCode::SND_BELL)) {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, format!("{:?} 不支持 SND_TONE 或 SND_BELL", path)));
+        }
+        tracing::info!("使用蜂鸣器: {:?} ({})", path, if tone { "SND_TONE" } else { "SND_BELL" });
+
+        let (code, value) = if tone {
+            (SoundCode::SND_TONE, config.frequency as i32)
+        } else {
+            (SoundCode::SND_BELL, 1)
+        };
+        let duration = config.duration;
+        let (sender, receiver) = sync_channel::<()>(1);
+        thread::Builder::new().name("linuxfb-beeper".into()).spawn(move || {
+            for () in receiver {
+                let sound = |value| InputEvent::new(EventType::SOUND.0, code.0, value);
+                if let Err(e) = device.send_events(&[sound(value)]) {
+                    tracing::warn!("蜂鸣器发声失败: {}", e);
+                    continue;
+                }
+                thread::sleep(duration);
+                let _ = device.send_events(&[sound(0)]);
+            }
+        })?;
+        Ok(Self { sender })
+    }
+
+    /// 请求鸣响一次，不阻塞
+    pub fn beep(&self) {
+        let _ = self.sender.try_send(());
+    }
+}
+
+/// 查找支持 `SND_TONE` 的设备，其次是支持 `SND_BELL` 的设备
+fn find_beeper() -> Option<(PathBuf, Device)> {
+    let mut bell = None;
+    for (path, device) in evdev::enumerate() {
+        let Some(sounds) = device.supported_sounds() else { continue };
+        if sounds.contains(SoundCode::SND_TONE) {
+            return Some((path, device));
+        }
+        if bell.is_none() && sounds.contains(SoundCode::SND_BELL) {
+            bell = Some((path, device));
+        }
+    }
+    bell
+}
@@ -0,0 +1,164 @@
+//! 触摸屏校准
+//!
+//! 兼容 tslib 的 `pointercal` 文件 (由 `ts_calibrate` 生成)，文件内容为一行整数：
+//!
+//! ```text
+//! a b c d e f s [xres yres]
+//! ```
+//!
+//! 原始坐标 (x, y) 按下式转换为屏幕坐标：
+//! `x' = (a*x + b*y + c) / s`，`y' = (d*x + e*y + f) / s`。
+//! 较新的 tslib 会在末尾记录校准时的屏幕分辨率，分辨率与当前屏幕不同时按比例缩放。
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// 定点系数的除数 (与 ts_calibrate 一致)
+const DIVIDER: i64 = 65536;
+
+/// 校准采样：(原始触摸坐标, 对应的屏幕坐标)
+pub type CalibrationSample = ((i32, i32), (i32, i32));
+
+/// tslib 格式的仿射校准参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchCalibration {
+    coefficients: [i64; 6],
+    divider: i64,
+    /// 校准时的屏幕分辨率
+    resolution: Option<(u32, u32)>,
+}
+
+impl TouchCalibration {
+    /// 读取 pointercal 文件
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("无效的 pointercal 文件: {:?}", path))
+        })
+    }
+
+    /// 解析 pointercal 内容
+    pub fn parse(text: &str) -> Option<Self> {
+        let values: Vec<i64> = text
+            .split_whitespace()
+            .map(|v| v.parse().ok())
+            .collect::<Option<_>>()?;
+        if values.len() < 7 || values[6] == 0 {
+            return None;
+        }
+        let resolution = match values.get(7..9) {
+            Some(&[w, h]) if w > 0 && h > 0 => Some((w as u32, h as u32)),
+            _ => None,
+        };
+        Some(Self {
+            coefficients: values[..6].try_into().ok()?,
+            divider: values[6],
+            resolution,
+        })
+    }
+
+    /// 由采样点以最小二乘法计算校准参数，至少需要 3 个不共线的点
+    pub fn from_samples(samples: &[CalibrationSample], screen_width: u32, screen_height: u32) -> Option<Self> {
+        if samples.len() < 3 {
+            return None;
+        }
+        // 法方程 M * [a, b, c] = v，M 对 X 与 Y 两个方向相同
+        let mut m = [[0.0f64; 3]; 3];
+        let mut vx = [0.0f64; 3];
+        let mut vy = [0.0f64; 3];
+        for &((x, y), (sx, sy)) in samples {
+            let row = [x as f64, y as f64, 1.0];
+            for (i, m_row) in m.iter_mut().enumerate() {
+                for (j, cell) in m_row.iter_mut().enumerate() {
+                    *cell += row[i] * row[j];
+                }
+                vx[i] += row[i] * sx as f64;
+                vy[i] += row[i] * sy as f64;
+            }
+        }
+        let [a, b, c] = solve3(m, vx)?;
+        let [d, e, f] = solve3(m, vy)?;
+        let scale = |v: f64| (v * DIVIDER as f64).round() as i64;
+        Some(Self {
+            coefficients: [scale(a), scale(b), scale(c), scale(d), scale(e), scale(f)],
+            divider: DIVIDER,
+            resolution: Some((screen_width, screen_height)),
+        })
+    }
+
+    /// 以 pointercal 格式写入文件
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let [a, b, c, d, e, f] = self.coefficients;
+        let mut text = format!("{} {} {} {} {} {} {}", a, b, c, d, e, f, self.divider);
+        if let Some((width, height)) = self.resolution {
+            text.push_str(&format!(" {} {}", width, height));
+        }
+        text.push('\n');
+        fs::write(path, text)
+    }
+
+    /// 将原始触摸坐标转换为屏幕坐标
+    pub fn apply(&self, x: i32, y: i32, screen_width: u32, screen_height: u32) -> (i32, i32) {
+        let [a, b, c, d, e, f] = self.coefficients;
+        let (x, y) = (x as i64, y as i64);
+        let mut sx = (a * x + b * y + c) / self.divider;
+        let mut sy = (d * x + e * y + f) / self.divider;
+        if let Some((width, height)) = self.resolution {
+            sx = sx * screen_width as i64 / width as i64;
+            sy = sy * screen_height as i64 / height as i64;
+        }
+        (sx as i32, sy as i32)
+    }
+}
+
+/// 用克莱姆法则求解 3x3 线性方程组，矩阵奇异 (采样点共线) 时返回 `None`
+fn solve3(m: [[f64; 3]; 3], v: [f64; 3]) -> Option<[f64; 3]> {
+    let det = |m: &[[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1]) - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let denominator = det(&m);
+    if denominator.abs() < f64::EPSILON {
+        return None;
+    }
+    let mut result = [0.0; 3];
+    for (column, value) in result.iter_mut().enumerate() {
+        let mut replaced = m;
+        for (replaced_row, value) in replaced.iter_mut().zip(v) {
+            replaced_row[column] = value;
+        }
+        *value = det(&replaced) / denominator;
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_apply() {
+        // 交换 X/Y 并缩小一半，校准分辨率为 400x300
+        let calibration = TouchCalibration::parse("0 32768 0 32768 0 0 65536 400 300\n").unwrap();
+        assert_eq!(calibration.apply(100, 200, 400, 300), (100, 50));
+        assert_eq!(calibration.apply(100, 200, 800, 600), (200, 100));
+
+        assert!(TouchCalibration::parse("1 0 0 0 1 0").is_none());
+        assert!(TouchCalibration::parse("1 0 0 0 1 0 0").is_none());
+    }
+
+    #[test]
+    fn test_from_samples() {
+        // 原始坐标范围 0..4096，X 轴反向
+        let to_raw = |(x, y): (i32, i32)| (4096 - x * 4096 / 800, y * 4096 / 480);
+        let targets = [(80, 48), (720, 48), (720, 432), (80, 432), (400, 240)];
+        let samples: Vec<_> = targets.iter().map(|&t| (to_raw(t), t)).collect();
+        let calibration = TouchCalibration::from_samples(&samples, 800, 480).unwrap();
+        let (x, y) = calibration.apply(to_raw((200, 300)).0, to_raw((200, 300)).1, 800, 480);
+        assert!((x - 200).abs() <= 1 && (y - 300).abs() <= 1);
+
+        let collinear = [((0, 0), (0, 0)), ((1, 1), (1, 1)), ((2, 2), (2, 2))];
+        assert!(TouchCalibration::from_samples(&collinear, 800, 480).is_none());
+    }
+}
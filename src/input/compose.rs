@@ -0,0 +1,203 @@
+//! 死键 (dead key) 与 Compose 组合输入
+//!
+//! 为简易键盘实现提供常用拉丁字母重音的输入方式：
+//! - 键盘映射中的死键 (例如 `dead_acute`) 后接字母，如 `´` + `e` → `é`
+//! - Compose 键后接两个字符，如 `Compose` + `'` + `e` → `é`，`Compose` + `"` + `u` → `ü`
+//!
+//! 组合规则由下面的数据表驱动，控制台键盘映射中的 `compose` 定义可以补充额外规则。
+
+use std::collections::HashMap;
+
+/// 死键类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadKey {
+    Grave,
+    Acute,
+    Circumflex,
+    Tilde,
+    Diaeresis,
+    Cedilla,
+}
+
+/// 组合表：(死键, 基础字符, 组合结果)，基础字符与结果按位置一一对应
+const COMPOSE_TABLE: &[(DeadKey, &str, &str)] = &[
+    (DeadKey::Grave, "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+    (DeadKey::Acute, "aeiouycAEIOUYC", "áéíóúýćÁÉÍÓÚÝĆ"),
+    (DeadKey::Circumflex, "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+    (DeadKey::Tilde, "anoANO", "ãñõÃÑÕ"),
+    (DeadKey::Diaeresis, "aeiouyAEIOUY", "äëïöüÿÄËÏÖÜŸ"),
+    (DeadKey::Cedilla, "cC", "çÇ"),
+];
+
+impl DeadKey {
+    /// Compose 序列中代表该重音的 ASCII 字符
+    pub fn from_accent(c: char) -> Option<Self> {
+        Some(match c {
+            '`' => DeadKey::Grave,
+            '\'' => DeadKey::Acute,
+            '^' => DeadKey::Circumflex,
+            '~' => DeadKey::Tilde,
+            '"' => DeadKey::Diaeresis,
+            ',' => DeadKey::Cedilla,
+            _ => return None,
+        })
+    }
+
+    /// 控制台键盘映射中的符号名
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "dead_grave" => DeadKey::Grave,
+            "dead_acute" => DeadKey::Acute,
+            "dead_circumflex" => DeadKey::Circumflex,
+            "dead_tilde" => DeadKey::Tilde,
+            "dead_diaeresis" => DeadKey::Diaeresis,
+            "dead_cedilla" => DeadKey::Cedilla,
+            _ => return None,
+        })
+    }
+
+    /// 内核 KT_DEAD keysym 的值
+    pub fn from_kernel_value(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => DeadKey::Grave,
+            1 => DeadKey::Acute,
+            2 => DeadKey::Circumflex,
+            3 => DeadKey::Tilde,
+            4 => DeadKey::Diaeresis,
+            5 => DeadKey::Cedilla,
+            _ => return None,
+        })
+    }
+
+    /// 单独输入该重音时产生的字符 (死键后接空格)
+    pub fn spacing_char(self) -> char {
+        match self {
+            DeadKey::Grave => '`',
+            DeadKey::Acute => '´',
+            DeadKey::Circumflex => '^',
+            DeadKey::Tilde => '~',
+            DeadKey::Diaeresis => '¨',
+            DeadKey::Cedilla => '¸',
+        }
+    }
+
+    /// 将重音与基础字符组合
+    pub fn compose(self, base: char) -> Option<char> {
+        let (_, bases, results) = COMPOSE_TABLE.iter().find(|(dead, _, _)| *dead == self)?;
+        let index = bases.chars().position(|c| c == base)?;
+        results.chars().nth(index)
+    }
+}
+
+/// 组合输入的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    /// 已按下死键
+    Dead(DeadKey),
+    /// 已按下 Compose 键
+    Compose,
+    /// Compose 序列已输入第一个字符
+    ComposeFirst(char),
+}
+
+/// 组合输入的处理结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Composed {
+    /// 按键被组合序列吸收，不产生文本
+    Pending,
+    /// 产生的文本 (组合结果或原样透传)
+    Text(String),
+}
+
+/// 死键与 Compose 序列的状态机
+#[derive(Debug)]
+pub struct Composer {
+    state: State,
+    /// 键盘映射中额外定义的 Compose 规则
+    extra: HashMap<(char, char), char>,
+}
+
+impl Composer {
+    pub fn new(extra: HashMap<(char, char), char>) -> Self {
+        Self { state: State::Idle, extra }
+    }
+
+    /// 按下死键
+    pub fn dead_key(&mut self, dead: DeadKey) -> Composed {
+        match self.state {
+            // 连续两次同一死键：输出重音本身
+            State::Dead(previous) if previous == dead => {
+                self.state = State::Idle;
+                Composed::Text(dead.spacing_char().to_string())
+            }
+            _ => {
+                self.state = State::Dead(dead);
+                Composed::Pending
+            }
+        }
+    }
+
+    /// 按下 Compose 键
+    pub fn compose_key(&mut self) {
+        self.state = State::Compose;
+    }
+
+    /// 输入一个可打印字符
+    pub fn feed(&mut self, c: char) -> Composed {
+        let state = std::mem::replace(&mut self.state, State::Idle);
+        match state {
+            State::Idle => Composed::Text(c.to_string()),
+            State::Dead(dead) => Composed::Text(match dead.compose(c) {
+                Some(result) => result.to_string(),
+                None if c == ' ' => dead.spacing_char().to_string(),
+                // 无法组合：重音与字符都保留
+                None => format!("{}{}", dead.spacing_char(), c),
+            }),
+            State::Compose => {
+                self.state = State::ComposeFirst(c);
+                Composed::Pending
+            }
+            State::ComposeFirst(first) => match self.lookup(first, c) {
+                Some(result) => Composed::Text(result.to_string()),
+                // 无效的 Compose 序列被丢弃
+                None => Composed::Pending,
+            },
+        }
+    }
+
+    /// 取消进行中的组合序列 (例如按下 Escape 或功能键)
+    pub fn reset(&mut self) {
+        self.state = State::Idle;
+    }
+
+    fn lookup(&self, first: char, second: char) -> Option<char> {
+        if let Some(result) = self.extra.get(&(first, second)) {
+            return Some(*result);
+        }
+        // 重音字符在前或在后均可
+        DeadKey::from_accent(first)
+            .and_then(|dead| dead.compose(second))
+            .or_else(|| DeadKey::from_accent(second).and_then(|dead| dead.compose(first)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dead_and_compose_sequences() {
+        let mut composer = Composer::new(HashMap::new());
+        assert_eq!(composer.dead_key(DeadKey::Acute), Composed::Pending);
+        assert_eq!(composer.feed('e'), Composed::Text("é".into()));
+
+        composer.dead_key(DeadKey::Diaeresis);
+        assert_eq!(composer.feed('x'), Composed::Text("¨x".into()));
+
+        composer.compose_key();
+        assert_eq!(composer.feed('n'), Composed::Pending);
+        assert_eq!(composer.feed('~'), Composed::Text("ñ".into()));
+        assert_eq!(composer.feed('a'), Composed::Text("a".into()));
+    }
+}
@@ -0,0 +1,136 @@
+//! 旋转编码器 (旋钮) 处理
+//!
+//! 内核的 `rotary-encoder` 等驱动把旋转报告为相对轴 (`REL_DIAL` / `REL_X` 等)
+//! 或在范围内循环的绝对轴，按压则报告为普通按键。
+//! 编码器设备按名称匹配配置，旋转转换为滚动或焦点切换 (Tab / Shift+Tab)，按压转换为回车。
+
+use evdev::Device;
+use i_slint_core::api::PhysicalPosition;
+use i_slint_core::input::key_codes;
+use i_slint_core::platform::WindowEvent;
+use i_slint_core::SharedString;
+
+/// 编码器旋转的用途
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EncoderAction {
+    /// 每一格滚动一次 (与鼠标滚轮相同)
+    #[default]
+    Scroll,
+    /// 顺时针切换到下一个焦点 (Tab)，逆时针切换到上一个 (Shift+Tab)
+    FocusNavigation,
+}
+
+/// 按设备名称匹配的旋转编码器配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncoderConfig {
+    /// 设备名称包含该字符串时匹配
+    pub name_pattern: String,
+    pub action: EncoderAction,
+}
+
+impl EncoderConfig {
+    pub fn new(name_pattern: impl Into<String>, action: EncoderAction) -> Self {
+        Self { name_pattern: name_pattern.into(), action }
+    }
+}
+
+/// 编码器状态
+#[derive(Debug)]
+pub struct EncoderState {
+    action: EncoderAction,
+    /// 每一格的滚动距离 (像素)
+    scroll_step: f32,
+    /// 本帧累计的旋转格数，顺时针为正
+    steps: i32,
+    /// 绝对轴编码器上一次的值
+    last_abs: Option<i32>,
+    /// 绝对轴的取值范围，用于处理循环回绕
+    abs_range: i32,
+}
+
+impl EncoderState {
+    pub fn new(device: &Device, action: EncoderAction, scroll_step: f32) -> Self {
+        let abs_range = device
+            .get_absinfo()
+            .ok()
+            .and_then(|mut axes| axes.next())
+            .map_or(0, |(_, info)| info.maximum() - info.minimum() + 1);
+        Self { action, scroll_step, steps: 0, last_abs: None, abs_range }
+    }
+
+    pub fn process_relative(&mut self, value: i32) {
+        self.steps += value;
+    }
+
+    /// 绝对轴编码器：按与上一次的差值计算旋转格数，跨越范围边界时按回绕处理
+    pub fn process_absolute(&mut self, value: i32) {
+        if let Some(last) = self.last_abs.replace(value) {
+            let mut delta = value - last;
+            if self.abs_range > 0 && delta.abs() > self.abs_range / 2 {
+                delta -= delta.signum() * self.abs_range;
+            }
+            self.steps += delta;
+        }
+    }
+
+    /// 编码器上的任意按键 (按压旋钮) 作为回车
+    pub fn process_key(&mut self, value: i32) -> Option<WindowEvent> {
+        let text: SharedString = key_codes::Return.into();
+        match value {
+            0 => Some(WindowEvent::KeyReleased { text }),
+            1 => Some(WindowEvent::KeyPressed { text }),
+            _ => None,
+        }
+    }
+
+    /// 帧结束时把累计的旋转转换为事件，追加到 `events`
+    pub fn finish_frame(&mut self, pointer_pos: PhysicalPosition, events: &mut Vec<WindowEvent>) {
+        let steps = std::mem::take(&mut self.steps);
+        if steps == 0 {
+            return;
+        }
+        match self.action {
+            // 顺时针对应向下滚动 (与滚轮向后滚动相同)
+            EncoderAction::Scroll => events.push(WindowEvent::PointerScrolled {
+                position: pointer_pos.to_logical(1.0),
+                delta_x: 0.0,
+                delta_y: -(steps as f32) * self.scroll_step,
+            }),
+            EncoderAction::FocusNavigation => {
+                let key: SharedString = if steps > 0 { key_codes::Tab } else { key_codes::Backtab }.into();
+                for _ in 0..steps.abs() {
+                    events.push(WindowEvent::KeyPressed { text: key.clone() });
+                    events.push(WindowEvent::KeyReleased { text: key.clone() });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_wraparound() {
+        let mut encoder = EncoderState {
+            action: EncoderAction::FocusNavigation,
+            scroll_step: 20.0,
+            steps: 0,
+            last_abs: None,
+            abs_range: 24,
+        };
+        encoder.process_absolute(23);
+        encoder.process_absolute(0);
+        let mut events = Vec::new();
+        encoder.finish_frame(PhysicalPosition::default(), &mut events);
+        assert_eq!(events[0], WindowEvent::KeyPressed { text: key_codes::Tab.into() });
+        assert_eq!(events.len(), 2);
+
+        encoder.process_absolute(22);
+        events.clear();
+        encoder.finish_frame(PhysicalPosition::default(), &mut events);
+        assert_eq!(events[0], WindowEvent::KeyPressed { text: key_codes::Backtab.into() });
+        assert_eq!(events.len(), 4);
+    }
+}
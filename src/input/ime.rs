@@ -0,0 +1,291 @@
+//! 输入法 (IME) 桥接
+//!
+//! 通过会话总线上的 `org.freedesktop.portal.IBus` 接口与输入法框架通信。
+//! ibus (ibus-portal) 和 fcitx5 (ibus 前端插件) 都提供该接口，因此两者均可使用。
+//!
+//! 文本输入框获得焦点时，键盘事件先交给输入法处理；输入法未消费的按键照常转发给 Slint。
+//! 输入法的上屏文本与预编辑 (pre-edit) 文本通过 D-Bus 信号到达，由后台线程接收，
+//! 并通过 eventfd 唤醒事件循环。
+//!
+//! 候选词列表由输入法自身的面板负责显示，在纯 Framebuffer 环境中通常不可见，
+//! 此时仍可使用空格或数字键选择候选词。
+
+use i_slint_core::input::key_codes;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use zbus::blocking::{connection, Connection, MessageIterator};
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+const PORTAL_SERVICE: &str = "org.freedesktop.portal.IBus";
+const PORTAL_PATH: &str = "/org/freedesktop/IBus";
+const PORTAL_INTERFACE: &str = "org.freedesktop.IBus.Portal";
+const CONTEXT_INTERFACE: &str = "org.freedesktop.IBus.InputContext";
+
+/// D-Bus 方法调用的超时：按键在事件循环线程中同步交给输入法，输入法无响应时不能长时间冻结界面
+const CALL_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// IBusCapabilite: PreeditText | Focus
+const CAPABILITIES: u32 = 1 | 8;
+
+// X11 修饰键掩码 (IBus 沿用)
+const SHIFT_MASK: u32 = 1 << 0;
+const CONTROL_MASK: u32 = 1 << 2;
+const MOD1_MASK: u32 = 1 << 3;
+const MOD4_MASK: u32 = 1 << 6;
+const RELEASE_MASK: u32 = 1 << 30;
+
+/// 输入法产生的事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// 更新预编辑文本，`cursor` 为光标在文本中的字节偏移
+    Preedit { text: String, cursor: usize },
+    /// 提交 (上屏) 文本
+    Commit(String),
+}
+
+/// 与输入法的 D-Bus 连接
+pub struct ImeBridge {
+    connection: Connection,
+    context: OwnedObjectPath,
+    receiver: Receiver<ImeEvent>,
+    wake_fd: Arc<OwnedFd>,
+    focused: bool,
+}
+
+impl ImeBridge {
+    /// 连接会话总线并创建输入上下文
+    pub fn connect() -> zbus::Result<Self> {
+        let connection = connection::Builder::session()?.method_timeout(CALL_TIMEOUT).build()?;
+        let reply = connection.call_method(
+            Some(PORTAL_SERVICE),
+            PORTAL_PATH,
+            Some(PORTAL_INTERFACE),
+            "CreateInputContext",
+            &("slint-backend-linuxfb",),
+        )?;
+        let context: OwnedObjectPath = reply.body().deserialize()?;
+
+        let rule = zbus::MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .interface(CONTEXT_INTERFACE)?
+            .path(context.clone())?
+            .build();
+        let signals = MessageIterator::for_match_rule(rule, &connection, Some(64))?;
+
+        // SAFETY: 参数均为有效的常量
+        let raw_fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        if raw_fd == -1 {
+            return Err(zbus::Error::InputOutput(std::io::Error::last_os_error().into()));
+        }
+        // SAFETY: raw_fd 是刚创建的有效描述符，所有权转移给 OwnedFd
+        let wake_fd = Arc::new(unsafe { OwnedFd::from_raw_fd(raw_fd) });
+
+        let (sender, receiver) = channel();
+        let thread_wake_fd = wake_fd.clone();
+        thread::Builder::new()
+            .name("linuxfb-ime".into())
+            .spawn(move || {
+                for message in signals {
+                    let Ok(message) = message else { continue };
+                    let Some(event) = parse_signal(&message) else { continue };
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                    // SAFETY: 向 eventfd 写入 8 字节计数
+                    unsafe { libc::write(thread_wake_fd.as_raw_fd(), &1u64 as *const u64 as *const _, 8) };
+                }
+            })
+            .map_err(|e| zbus::Error::InputOutput(e.into()))?;
+
+        let bridge = Self { connection, context, receiver, wake_fd, focused: false };
+        bridge.call("SetCapabilities", &(CAPABILITIES,))?;
+        tracing::info!("已连接输入法: {}", bridge.context.as_str());
+        Ok(bridge)
+    }
+
+    fn call<B>(&self, method: &str, body: &B) -> zbus::Result<zbus::message::Message>
+    where
+        B: zbus::export::serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        self.connection.call_method(
+            Some(PORTAL_SERVICE),
+            self.context.as_str(),
+            Some(CONTEXT_INTERFACE),
+            method,
+            body,
+        )
+    }
+
+    /// 信号到达时可读的文件描述符
+    pub fn fd(&self) -> RawFd {
+        self.wake_fd.as_raw_fd()
+    }
+
+    /// 同步文本输入框的焦点状态
+    pub fn set_focused(&mut self, focused: bool) {
+        if focused == self.focused {
+            return;
+        }
+        self.focused = focused;
+        let result = if focused {
+            self.call("FocusIn", &())
+        } else {
+            self.call("Reset", &()).and_then(|_| self.call("FocusOut", &()))
+        };
+        if let Err(e) = result {
+            tracing::warn!("输入法焦点切换失败: {}", e);
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// 将按键交给输入法，返回输入法是否消费了该按键
+    ///
+    /// `text` 为 Slint 按键文本，`code` 为 evdev 键码，`pressed` 为 false 表示释放。
+    pub fn process_key(&self, text: &str, code: u16, pressed: bool, modifiers: super::Modifiers) -> bool {
+        let Some(keysym) = keysym_from_text(text) else {
+            return false;
+        };
+        let mut state = 0;
+        if modifiers.shift {
+            state |= SHIFT_MASK;
+        }
+        if modifiers.control {
+            state |= CONTROL_MASK;
+        }
+        if modifiers.alt {
+            state |= MOD1_MASK;
+        }
+        if modifiers.meta {
+            state |= MOD4_MASK;
+        }
+        if !pressed {
+            state |= RELEASE_MASK;
+        }
+        match self
+            .call("ProcessKeyEvent", &(keysym, code as u32, state))
+            .and_then(|reply| reply.body().deserialize::<bool>())
+        {
+            // 修饰键 (例如用于切换中英文的 Shift) 始终转发给 Slint，以免其修饰键状态失去同步
+            Ok(handled) => handled && !is_modifier_keysym(keysym),
+            Err(e) => {
+                tracing::warn!("输入法处理按键失败: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 取出已到达的输入法事件
+    pub fn take_events(&mut self) -> Vec<ImeEvent> {
+        let mut counter = 0u64;
+        // SAFETY: 读取 8 字节计数以清除可读状态
+        unsafe { libc::read(self.wake_fd.as_raw_fd(), &mut counter as *mut u64 as *mut _, 8) };
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for ImeBridge {
+    fn drop(&mut self) {
+        let _ = self.connection.call_method(
+            Some(PORTAL_SERVICE),
+            self.context.as_str(),
+            Some("org.freedesktop.IBus.Service"),
+            "Destroy",
+            &(),
+        );
+    }
+}
+
+/// 解析输入上下文发出的信号
+fn parse_signal(message: &zbus::message::Message) -> Option<ImeEvent> {
+    let header = message.header();
+    let body = message.body();
+    match header.member()?.as_str() {
+        "CommitText" => {
+            let text: Value = body.deserialize().ok()?;
+            Some(ImeEvent::Commit(ibus_text(&text)?))
+        }
+        "UpdatePreeditText" => {
+            let (text, cursor, visible): (Value, u32, bool) = body.deserialize().ok()?;
+            let text = if visible { ibus_text(&text)? } else { String::new() };
+            let cursor = text.char_indices().nth(cursor as usize).map_or(text.len(), |(i, _)| i);
+            Some(ImeEvent::Preedit { text, cursor })
+        }
+        "HidePreeditText" => Some(ImeEvent::Preedit { text: String::new(), cursor: 0 }),
+        _ => None,
+    }
+}
+
+/// 从序列化的 IBusText (`(sa{sv}sv)`) 中取出文本
+fn ibus_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Value(inner) => ibus_text(inner),
+        Value::Structure(fields) => match fields.fields().get(2)? {
+            Value::Str(text) => Some(text.to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_modifier_keysym(keysym: u32) -> bool {
+    matches!(keysym, 0xffe1..=0xffee | 0xfe03)
+}
+
+/// 将 Slint 按键文本转换为 X11 keysym
+fn keysym_from_text(text: &str) -> Option<u32> {
+    let mut chars = text.chars();
+    let c = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+    Some(match c {
+        key_codes::Backspace => 0xff08,
+        key_codes::Tab => 0xff09,
+        key_codes::Return => 0xff0d,
+        key_codes::Escape => 0xff1b,
+        key_codes::Delete => 0xffff,
+        key_codes::Home => 0xff50,
+        key_codes::LeftArrow => 0xff51,
+        key_codes::UpArrow => 0xff52,
+        key_codes::RightArrow => 0xff53,
+        key_codes::DownArrow => 0xff54,
+        key_codes::PageUp => 0xff55,
+        key_codes::PageDown => 0xff56,
+        key_codes::End => 0xff57,
+        key_codes::Shift => 0xffe1,
+        key_codes::ShiftR => 0xffe2,
+        key_codes::Control => 0xffe3,
+        key_codes::ControlR => 0xffe4,
+        key_codes::Alt => 0xffe9,
+        key_codes::AltGr => 0xfe03,
+        key_codes::Meta => 0xffeb,
+        key_codes::MetaR => 0xffec,
+        // Slint 在私有区中定义的其他功能键不交给输入法
+        '\u{f700}'..='\u{f8ff}' => return None,
+        c if c.is_control() => return None,
+        // Latin-1 字符的 keysym 与码点相同，其余 Unicode 字符使用 0x01000000 + 码点
+        c if (c as u32) < 0x100 => c as u32,
+        c => 0x0100_0000 + c as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keysym_from_text() {
+        assert_eq!(keysym_from_text("a"), Some(0x61));
+        assert_eq!(keysym_from_text("é"), Some(0xe9));
+        assert_eq!(keysym_from_text("中"), Some(0x0100_4e2d));
+        assert_eq!(keysym_from_text(&key_codes::Backspace.to_string()), Some(0xff08));
+        assert_eq!(keysym_from_text(&key_codes::F5.to_string()), None);
+        assert_eq!(keysym_from_text("ab"), None);
+    }
+}
@@ -7,12 +7,23 @@
 //!    支持通过环境变量配置布局（如 `XKB_DEFAULT_LAYOUT=de`）。
 //! 2. **简易实现** (`feature != "xkb"`): 内置一个简单的 US QWERTY 静态映射表。
 //!    仅支持基本的字母、数字、Shift 组合符号和常用功能键，适用于资源受限或无需多语言输入的嵌入式环境。
+//!    可以额外加载 Linux 控制台键盘映射文件 (`loadkeys` 格式或 `bkeymap`) 以支持非 US 布局，
+//!    并通过死键或 Compose 键输入常用的拉丁字母重音 (é/ü/ñ 等)。
 
 use crate::error::Error;
+use super::InputConfig;
 use evdev::KeyCode;
 use i_slint_core::platform::WindowEvent;
 use i_slint_core::SharedString;
 
+/// 锁定键状态，用于同步键盘上的指示灯
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LockState {
+    pub caps_lock: bool,
+    pub num_lock: bool,
+    pub scroll_lock: bool,
+}
+
 // -----------------------------------------------------------------------------
 // 实现 1: 使用 xkbcommon (feature = "xkb")
 // -----------------------------------------------------------------------------
@@ -34,7 +45,11 @@ mod impl_xkb {
         /// 初始化 xkb 上下文、键映射和状态机
         ///
         /// 优先读取 `XKB_DEFAULT_*` 环境变量配置，否则使用系统默认值。
-        pub fn new() -> Result<Self, Error> {
+        pub fn new(config: &InputConfig) -> Result<Self, Error> {
+            if config.console_keymap.is_some() {
+                tracing::warn!("已启用 xkb 特性，控制台键盘映射文件将被忽略");
+            }
+
             // 创建上下文 (无特殊标志)
             let context = xkb::Context::new(xkb_context::ContextFlags::NO_FLAGS)
                 .map_err(|_| Error::Other("Failed to create xkb context".into()))?;
@@ -91,6 +106,16 @@ mod impl_xkb {
                 _ => None,
             }
         }
+
+        /// 当前锁定键状态 (来自 xkb 的 LED 状态)
+        pub fn lock_state(&self) -> LockState {
+            let led = |name: &str| self.state.led_name_is_active(name).unwrap_or(false);
+            LockState {
+                caps_lock: led("Caps Lock"),
+                num_lock: led("Num Lock"),
+                scroll_lock: led("Scroll Lock"),
+            }
+        }
     }
 
     /// 将 X11 Keysym 映射为 Slint 使用的字符或功能键代码
@@ -171,44 +196,139 @@ mod impl_xkb {
 #[cfg(not(feature = "xkb"))]
 mod impl_simple {
     use super::*;
+    use crate::input::compose::{Composed, Composer, DeadKey};
+    use crate::input::keymap::{ConsoleKeymap, Keysym, MOD_ALTGR, MOD_SHIFT};
     use i_slint_core::input::key_codes;
+    use std::collections::HashMap;
 
-    /// 简易键盘处理器 (静态 US QWERTY 布局)
+    /// 简易键盘处理器 (静态 US QWERTY 布局，可选控制台键盘映射)
     pub struct KeyboardHandler {
         /// 简单的 Shift 状态跟踪
         shift_pressed: bool,
+        /// AltGr (右 Alt) 状态，用于选择键盘映射中的第三层字符
+        altgr_pressed: bool,
+        /// CapsLock 锁定状态 (每次按下切换)
+        caps_lock: bool,
+        /// NumLock / ScrollLock 锁定状态 (仅用于同步指示灯)
+        num_lock: bool,
+        scroll_lock: bool,
+        /// 从文件加载的控制台键盘映射
+        keymap: Option<ConsoleKeymap>,
+        /// 死键与 Compose 序列状态
+        composer: Composer,
+        /// 按下时被组合序列吸收 (`None`) 或产生了组合结果的键，释放时与按下保持一致
+        composed_keys: HashMap<KeyCode, Option<SharedString>>,
     }
 
     impl KeyboardHandler {
-        pub fn new() -> Result<Self, Error> {
+        pub fn new(config: &InputConfig) -> Result<Self, Error> {
             tracing::info!("Keyboard: Using simple static mapping (No XKB)");
+            let keymap = config
+                .console_keymap
+                .as_deref()
+                .map(ConsoleKeymap::load)
+                .transpose()?;
+            let compose_rules = keymap
+                .as_ref()
+                .map(|keymap| keymap.compose_rules().clone())
+                .unwrap_or_default();
             Ok(Self {
                 shift_pressed: false,
+                altgr_pressed: false,
+                caps_lock: false,
+                num_lock: false,
+                scroll_lock: false,
+                keymap,
+                composer: Composer::new(compose_rules),
+                composed_keys: HashMap::new(),
             })
         }
 
         pub fn handle_key_event(&mut self, key_code: KeyCode, value: i32) -> Option<WindowEvent> {
-            // 1. 更新修饰符状态 (仅跟踪 Shift)
+            // 1. 更新修饰符状态 (跟踪 Shift、AltGr 与 CapsLock)
             match value {
                 1 => {
                     // Press
                     if matches!(key_code, KeyCode::KEY_LEFTSHIFT | KeyCode::KEY_RIGHTSHIFT) {
                         self.shift_pressed = true;
                     }
+                    if key_code == KeyCode::KEY_RIGHTALT {
+                        self.altgr_pressed = true;
+                    }
+                    match key_code {
+                        KeyCode::KEY_CAPSLOCK => self.caps_lock = !self.caps_lock,
+                        KeyCode::KEY_NUMLOCK => self.num_lock = !self.num_lock,
+                        KeyCode::KEY_SCROLLLOCK => self.scroll_lock = !self.scroll_lock,
+                        _ => {}
+                    }
                 }
                 0 => {
                     // Release
                     if matches!(key_code, KeyCode::KEY_LEFTSHIFT | KeyCode::KEY_RIGHTSHIFT) {
                         self.shift_pressed = false;
                     }
+                    if key_code == KeyCode::KEY_RIGHTALT {
+                        self.altgr_pressed = false;
+                    }
                 }
                 _ => {} // Repeat
             }
 
-            // 2. 获取按键对应的字符或功能码
-            let text = self.map_key_code(key_code).unwrap_or_default();
+            // 组合序列中按下的键：被吸收的按下不产生释放，组合结果的释放带相同的文本
+            if value != 1 {
+                if let Some(text) = self.composed_keys.get(&key_code).cloned() {
+                    if value != 0 {
+                        return None;
+                    }
+                    self.composed_keys.remove(&key_code);
+                    return text.map(|text| WindowEvent::KeyReleased { text });
+                }
+            }
+
+            // 2. 死键与 Compose 键只推进组合状态，本身不产生按键事件
+            if key_code == KeyCode::KEY_COMPOSE {
+                if value == 1 {
+                    self.composer.compose_key();
+                }
+                return None;
+            }
+            if let Some(dead) = self.dead_key(key_code) {
+                if value != 1 {
+                    return None;
+                }
+                return match self.composer.dead_key(dead) {
+                    Composed::Pending => None,
+                    Composed::Text(text) => {
+                        let text: SharedString = text.into();
+                        self.composed_keys.insert(key_code, Some(text.clone()));
+                        Some(WindowEvent::KeyPressed { text })
+                    }
+                };
+            }
 
-            // 3. 生成事件
+            // 3. 获取按键对应的字符或功能码
+            let mut text = self.lookup_text(key_code).unwrap_or_default();
+
+            // 4. 按下可打印字符时交给组合状态机；其他非修饰键会中断组合序列
+            if value == 1 {
+                match printable_char(&text) {
+                    Some(c) => match self.composer.feed(c) {
+                        Composed::Pending => {
+                            self.composed_keys.insert(key_code, None);
+                            return None;
+                        }
+                        Composed::Text(composed) if composed != text.as_str() => {
+                            text = composed.into();
+                            self.composed_keys.insert(key_code, Some(text.clone()));
+                        }
+                        Composed::Text(_) => {}
+                    },
+                    None if !is_modifier(key_code) => self.composer.reset(),
+                    None => {}
+                }
+            }
+
+            // 5. 生成事件
             match value {
                 0 => Some(WindowEvent::KeyReleased { text }),
                 1 => Some(WindowEvent::KeyPressed { text }),
@@ -217,9 +337,55 @@ mod impl_simple {
             }
         }
 
+        /// 当前锁定键状态
+        pub fn lock_state(&self) -> LockState {
+            LockState {
+                caps_lock: self.caps_lock,
+                num_lock: self.num_lock,
+                scroll_lock: self.scroll_lock,
+            }
+        }
+
+        /// 当前修饰键状态下，该键是否为键盘映射中的死键
+        fn dead_key(&self, code: KeyCode) -> Option<DeadKey> {
+            match self.keymap.as_ref()?.lookup(code.code(), self.modifiers()) {
+                Some(Keysym::Dead(dead)) => Some(dead),
+                _ => None,
+            }
+        }
+
+        fn modifiers(&self) -> u8 {
+            let mut modifiers = 0;
+            if self.shift_pressed {
+                modifiers |= MOD_SHIFT;
+            }
+            if self.altgr_pressed {
+                modifiers |= MOD_ALTGR;
+            }
+            modifiers
+        }
+
+        /// 优先使用控制台键盘映射，未定义的键位退回静态映射
+        fn lookup_text(&self, code: KeyCode) -> Option<SharedString> {
+            if let Some(keymap) = &self.keymap {
+                if keymap.defines(code.code()) {
+                    return match keymap.lookup(code.code(), self.modifiers()) {
+                        Some(Keysym::Char(c)) => Some(c.into()),
+                        // Shift 已经选择了大小写，CapsLock 再将其反转
+                        Some(Keysym::Letter(c)) if self.caps_lock => Some(toggle_case(c).into()),
+                        Some(Keysym::Letter(c)) => Some(c.into()),
+                        Some(Keysym::Dead(_)) | None => None,
+                    };
+                }
+            }
+            self.map_key_code(code)
+        }
+
               /// 静态映射逻辑：evdev KeyCode -> Slint SharedString
         /// 实现了标准的 US 键盘 Shift 组合逻辑
         fn map_key_code(&self, code: KeyCode) -> Option<SharedString> {
+            // 字母的大小写由 Shift 与 CapsLock 共同决定 (异或)，其他符号只受 Shift 影响
+            let upper = self.shift_pressed != self.caps_lock;
             let s = match code {
                 // 修饰键 (Modifiers)
                 KeyCode::KEY_LEFTSHIFT => return Some(key_codes::Shift.into()),
@@ -233,32 +399,32 @@ mod impl_simple {
                 KeyCode::KEY_CAPSLOCK => return Some(key_codes::CapsLock.into()),
 
                 // 字母 (A-Z)
-                KeyCode::KEY_Q => if self.shift_pressed { "Q" } else { "q" },
-                KeyCode::KEY_W => if self.shift_pressed { "W" } else { "w" },
-                KeyCode::KEY_E => if self.shift_pressed { "E" } else { "e" },
-                KeyCode::KEY_R => if self.shift_pressed { "R" } else { "r" },
-                KeyCode::KEY_T => if self.shift_pressed { "T" } else { "t" },
-                KeyCode::KEY_Y => if self.shift_pressed { "Y" } else { "y" },
-                KeyCode::KEY_U => if self.shift_pressed { "U" } else { "u" },
-                KeyCode::KEY_I => if self.shift_pressed { "I" } else { "i" },
-                KeyCode::KEY_O => if self.shift_pressed { "O" } else { "o" },
-                KeyCode::KEY_P => if self.shift_pressed { "P" } else { "p" },
-                KeyCode::KEY_A => if self.shift_pressed { "A" } else { "a" },
-                KeyCode::KEY_S => if self.shift_pressed { "S" } else { "s" },
-                KeyCode::KEY_D => if self.shift_pressed { "D" } else { "d" },
-                KeyCode::KEY_F => if self.shift_pressed { "F" } else { "f" },
-                KeyCode::KEY_G => if self.shift_pressed { "G" } else { "g" },
-                KeyCode::KEY_H => if self.shift_pressed { "H" } else { "h" },
-                KeyCode::KEY_J => if self.shift_pressed { "J" } else { "j" },
-                KeyCode::KEY_K => if self.shift_pressed { "K" } else { "k" },
-                KeyCode::KEY_L => if self.shift_pressed { "L" } else { "l" },
-                KeyCode::KEY_Z => if self.shift_pressed { "Z" } else { "z" },
-                KeyCode::KEY_X => if self.shift_pressed { "X" } else { "x" },
-                KeyCode::KEY_C => if self.shift_pressed { "C" } else { "c" },
-                KeyCode::KEY_V => if self.shift_pressed { "V" } else { "v" },
-                KeyCode::KEY_B => if self.shift_pressed { "B" } else { "b" },
-                KeyCode::KEY_N => if self.shift_pressed { "N" } else { "n" },
-                KeyCode::KEY_M => if self.shift_pressed { "M" } else { "m" },
+                KeyCode::KEY_Q => if upper { "Q" } else { "q" },
+                KeyCode::KEY_W => if upper { "W" } else { "w" },
+                KeyCode::KEY_E => if upper { "E" } else { "e" },
+                KeyCode::KEY_R => if upper { "R" } else { "r" },
+                KeyCode::KEY_T => if upper { "T" } else { "t" },
+                KeyCode::KEY_Y => if upper { "Y" } else { "y" },
+                KeyCode::KEY_U => if upper { "U" } else { "u" },
+                KeyCode::KEY_I => if upper { "I" } else { "i" },
+                KeyCode::KEY_O => if upper { "O" } else { "o" },
+                KeyCode::KEY_P => if upper { "P" } else { "p" },
+                KeyCode::KEY_A => if upper { "A" } else { "a" },
+                KeyCode::KEY_S => if upper { "S" } else { "s" },
+                KeyCode::KEY_D => if upper { "D" } else { "d" },
+                KeyCode::KEY_F => if upper { "F" } else { "f" },
+                KeyCode::KEY_G => if upper { "G" } else { "g" },
+                KeyCode::KEY_H => if upper { "H" } else { "h" },
+                KeyCode::KEY_J => if upper { "J" } else { "j" },
+                KeyCode::KEY_K => if upper { "K" } else { "k" },
+                KeyCode::KEY_L => if upper { "L" } else { "l" },
+                KeyCode::KEY_Z => if upper { "Z" } else { "z" },
+                KeyCode::KEY_X => if upper { "X" } else { "x" },
+                KeyCode::KEY_C => if upper { "C" } else { "c" },
+                KeyCode::KEY_V => if upper { "V" } else { "v" },
+                KeyCode::KEY_B => if upper { "B" } else { "b" },
+                KeyCode::KEY_N => if upper { "N" } else { "n" },
+                KeyCode::KEY_M => if upper { "M" } else { "m" },
 
                 // 数字行 (Shift 符号映射)
                 KeyCode::KEY_1 => if self.shift_pressed { "!" } else { "1" },
@@ -347,6 +513,117 @@ mod impl_simple {
             Some(s.into())
         }
       }
+
+    /// 反转字母大小写；没有单字符对应形式的字母 (如 ß) 保持不变
+    fn toggle_case(c: char) -> char {
+        let mut mapped: Vec<char> = if c.is_uppercase() {
+            c.to_lowercase().collect()
+        } else {
+            c.to_uppercase().collect()
+        };
+        match mapped.len() {
+            1 => mapped.pop().unwrap_or(c),
+            _ => c,
+        }
+    }
+
+    /// 文本为单个可打印字符时返回该字符 (Slint 的功能键码位于私用区，不算可打印)
+    fn printable_char(text: &str) -> Option<char> {
+        let mut chars = text.chars();
+        let c = chars.next()?;
+        let private_use = ('\u{e000}'..='\u{f8ff}').contains(&c);
+        (chars.next().is_none() && !c.is_control() && !private_use).then_some(c)
+    }
+
+    fn is_modifier(code: KeyCode) -> bool {
+        matches!(
+            code,
+            KeyCode::KEY_LEFTSHIFT
+                | KeyCode::KEY_RIGHTSHIFT
+                | KeyCode::KEY_LEFTCTRL
+                | KeyCode::KEY_RIGHTCTRL
+                | KeyCode::KEY_LEFTALT
+                | KeyCode::KEY_RIGHTALT
+                | KeyCode::KEY_LEFTMETA
+                | KeyCode::KEY_RIGHTMETA
+                | KeyCode::KEY_CAPSLOCK
+        )
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_compose_sequence_releases() {
+            let mut keyboard = KeyboardHandler::new(&InputConfig::default()).unwrap();
+            let text = |text: &str| SharedString::from(text);
+
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_COMPOSE, 1), None);
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_COMPOSE, 0), None);
+            // 被组合序列吸收的按下没有对应的释放
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_E, 1), None);
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_E, 0), None);
+            // 组合结果的释放与按下的文本相同
+            assert_eq!(
+                keyboard.handle_key_event(KeyCode::KEY_APOSTROPHE, 1),
+                Some(WindowEvent::KeyPressed { text: text("é") })
+            );
+            assert_eq!(
+                keyboard.handle_key_event(KeyCode::KEY_APOSTROPHE, 0),
+                Some(WindowEvent::KeyReleased { text: text("é") })
+            );
+
+            // 组合结束后恢复正常
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_E, 1), Some(WindowEvent::KeyPressed { text: text("e") }));
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_E, 0), Some(WindowEvent::KeyReleased { text: text("e") }));
+        }
+
+        #[test]
+        fn test_caps_lock() {
+            let mut keyboard = KeyboardHandler::new(&InputConfig::default()).unwrap();
+            let press = |text: &str| Some(WindowEvent::KeyPressed { text: text.into() });
+            let release = |text: &str| Some(WindowEvent::KeyReleased { text: text.into() });
+
+            // 只有按下切换锁定状态，重复和释放不切换
+            let caps_lock = SharedString::from(key_codes::CapsLock);
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_CAPSLOCK, 1), press(&caps_lock));
+            keyboard.handle_key_event(KeyCode::KEY_CAPSLOCK, 2);
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_CAPSLOCK, 0), release(&caps_lock));
+            assert!(keyboard.lock_state().caps_lock);
+
+            // CapsLock + 字母为大写，释放与按下的文本相同
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_A, 1), press("A"));
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_A, 0), release("A"));
+            // 数字不受 CapsLock 影响
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_1, 1), press("1"));
+            keyboard.handle_key_event(KeyCode::KEY_1, 0);
+
+            // CapsLock + Shift + 字母为小写
+            keyboard.handle_key_event(KeyCode::KEY_LEFTSHIFT, 1);
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_A, 1), press("a"));
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_A, 0), release("a"));
+            keyboard.handle_key_event(KeyCode::KEY_LEFTSHIFT, 0);
+
+            // 再次按下解除锁定
+            keyboard.handle_key_event(KeyCode::KEY_CAPSLOCK, 1);
+            keyboard.handle_key_event(KeyCode::KEY_CAPSLOCK, 0);
+            assert!(!keyboard.lock_state().caps_lock);
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_A, 1), press("a"));
+        }
+
+        #[test]
+        fn test_swallowed_press_released_after_sequence() {
+            let mut keyboard = KeyboardHandler::new(&InputConfig::default()).unwrap();
+
+            // 第一个字符的键在序列完成后才松开，仍不产生释放
+            keyboard.handle_key_event(KeyCode::KEY_COMPOSE, 1);
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_E, 1), None);
+            assert!(matches!(keyboard.handle_key_event(KeyCode::KEY_APOSTROPHE, 1), Some(WindowEvent::KeyPressed { .. })));
+            assert_eq!(keyboard.handle_key_event(KeyCode::KEY_E, 0), None);
+            assert!(matches!(keyboard.handle_key_event(KeyCode::KEY_APOSTROPHE, 0), Some(WindowEvent::KeyReleased { .. })));
+        }
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -0,0 +1,385 @@
+//! Linux 控制台键盘映射 (keymap) 加载
+//!
+//! 在未启用 `xkb` 特性时，为简易键盘实现提供非 US 布局支持。支持两种格式：
+//! - `loadkeys` 使用的文本格式 (例如 `de-latin1.map`，需先解压)
+//! - `loadkeys -b` / busybox `dumpkmap` 生成的二进制 `bkeymap` 格式
+//!
+//! 只解析产生字符的键位 (字母、数字、符号和 Latin-1/Unicode 字符)、死键
+//! 以及 `compose` 组合规则，功能键、修饰键等仍由内置的静态映射处理。
+
+use super::compose::DeadKey;
+use crate::error::Error;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 修饰键掩码：Shift
+pub const MOD_SHIFT: u8 = 1;
+/// 修饰键掩码：AltGr
+pub const MOD_ALTGR: u8 = 2;
+
+/// bkeymap 文件头
+const BKEYMAP_MAGIC: &[u8] = b"bkeymap";
+/// 内核支持的 keymap 数量 (MAX_NR_KEYMAPS)
+const MAX_NR_KEYMAPS: usize = 256;
+/// 每个 keymap 中的键数 (NR_KEYS)
+const NR_KEYS: usize = 128;
+
+/// 内核 keysym 类型：Latin-1 字符
+const KT_LATIN: u16 = 0;
+/// 内核 keysym 类型：死键
+const KT_DEAD: u16 = 8;
+/// 内核 keysym 类型：受 CapsLock 影响的字母
+const KT_LETTER: u16 = 11;
+
+/// ASCII 可打印字符 (0x20..=0x7e) 的符号名
+const ASCII_NAMES: [&str; 95] = [
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "apostrophe",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "minus", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "colon", "semicolon", "less", "equal", "greater", "question", "at",
+    "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M",
+    "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+    "bracketleft", "backslash", "bracketright", "asciicircum", "underscore", "grave",
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m",
+    "n", "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z",
+    "braceleft", "bar", "braceright", "asciitilde",
+];
+
+/// Latin-1 字符 (0xa0..=0xff) 的符号名
+const LATIN1_NAMES: [&str; 96] = [
+    "nobreakspace", "exclamdown", "cent", "sterling", "currency", "yen", "brokenbar", "section",
+    "diaeresis", "copyright", "ordfeminine", "guillemotleft", "notsign", "hyphen", "registered", "macron",
+    "degree", "plusminus", "twosuperior", "threesuperior", "acute", "mu", "paragraph", "periodcentered",
+    "cedilla", "onesuperior", "masculine", "guillemotright", "onequarter", "onehalf", "threequarters", "questiondown",
+    "Agrave", "Aacute", "Acircumflex", "Atilde", "Adiaeresis", "Aring", "AE", "Ccedilla",
+    "Egrave", "Eacute", "Ecircumflex", "Ediaeresis", "Igrave", "Iacute", "Icircumflex", "Idiaeresis",
+    "ETH", "Ntilde", "Ograve", "Oacute", "Ocircumflex", "Otilde", "Odiaeresis", "multiply",
+    "Ooblique", "Ugrave", "Uacute", "Ucircumflex", "Udiaeresis", "Yacute", "THORN", "ssharp",
+    "agrave", "aacute", "acircumflex", "atilde", "adiaeresis", "aring", "ae", "ccedilla",
+    "egrave", "eacute", "ecircumflex", "ediaeresis", "igrave", "iacute", "icircumflex", "idiaeresis",
+    "eth", "ntilde", "ograve", "oacute", "ocircumflex", "otilde", "odiaeresis", "division",
+    "oslash", "ugrave", "uacute", "ucircumflex", "udiaeresis", "yacute", "thorn", "ydiaeresis",
+];
+
+/// keymap 中一个键位产生的符号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keysym {
+    /// 普通字符
+    Char(char),
+    /// 字母 (受 CapsLock 影响)
+    Letter(char),
+    /// 死键，与下一个字符组合
+    Dead(DeadKey),
+}
+
+/// 解析后的控制台键盘映射
+#[derive(Debug, Default)]
+pub struct ConsoleKeymap {
+    /// (evdev 键码, 修饰键掩码) -> 符号
+    entries: HashMap<(u16, u8), Keysym>,
+    /// `compose 'a' 'b' to 'c'` 定义的组合规则
+    compose: HashMap<(char, char), char>,
+}
+
+impl ConsoleKeymap {
+    /// 从文件加载，根据文件头自动识别文本或二进制格式
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let data = std::fs::read(path)
+            .map_err(|e| Error::Other(format!("无法读取键盘映射 {:?}: {}", path, e)))?;
+        let keymap = if data.starts_with(BKEYMAP_MAGIC) {
+            Self::parse_binary(&data)?
+        } else {
+            Self::parse(&String::from_utf8_lossy(&data))
+        };
+        tracing::info!("已加载键盘映射 {:?} ({} 个键位)", path, keymap.entries.len());
+        Ok(keymap)
+    }
+
+    /// 解析 `loadkeys` 文本格式
+    pub fn parse(text: &str) -> Self {
+        let mut keymap = Self::default();
+        // 每一列对应的修饰键组合，默认依次为 0, 1, 2, ...
+        let mut columns: Vec<u8> = (0..=u8::MAX).collect();
+
+        for line in text.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(spec) = line.strip_prefix("keymaps") {
+                columns = parse_keymaps_spec(spec);
+                continue;
+            }
+
+            if let Some(rule) = line.strip_prefix("compose") {
+                if let Some((first, second, result)) = parse_compose_rule(rule) {
+                    keymap.compose.insert((first, second), result);
+                }
+                continue;
+            }
+
+            let Some((lhs, rhs)) = line.split_once('=') else {
+                continue;
+            };
+            let mut words = lhs.split_whitespace().peekable();
+
+            // 可选的修饰键前缀，例如 "altgr keycode 16 = at"
+            let mut modifiers = 0u8;
+            let mut has_prefix = false;
+            while let Some(word) = words.peek() {
+                let bit = match *word {
+                    "plain" => 0,
+                    "shift" => MOD_SHIFT,
+                    "altgr" => MOD_ALTGR,
+                    "control" => 4,
+                    "alt" => 8,
+                    _ => break,
+                };
+                modifiers |= bit;
+                has_prefix = true;
+                words.next();
+            }
+            if words.next() != Some("keycode") {
+                // string / include 等定义暂不支持
+                continue;
+            }
+            let Some(code) = words.next().and_then(parse_number) else {
+                continue;
+            };
+            let code = code as u16;
+
+            let symbols: Vec<&str> = rhs.split_whitespace().collect();
+            if has_prefix {
+                if let Some(sym) = symbols.first().and_then(|s| parse_symbol(s)) {
+                    keymap.entries.insert((code, modifiers), sym);
+                }
+                continue;
+            }
+
+            // 只有一个字母时，Shift 列自动取大写 (与 loadkeys 行为一致)
+            if let [single] = symbols.as_slice() {
+                if let Some(Keysym::Char(c) | Keysym::Letter(c)) = parse_symbol(single) {
+                    if c.is_alphabetic() {
+                        keymap.entries.insert((code, 0), Keysym::Letter(c.to_lowercase().next().unwrap_or(c)));
+                        keymap.entries.insert((code, MOD_SHIFT), Keysym::Letter(c.to_uppercase().next().unwrap_or(c)));
+                        continue;
+                    }
+                }
+            }
+
+            for (column, symbol) in columns.iter().zip(symbols) {
+                if let Some(sym) = parse_symbol(symbol) {
+                    keymap.entries.insert((code, *column), sym);
+                }
+            }
+        }
+        keymap
+    }
+
+    /// 解析二进制 `bkeymap` 格式
+    pub fn parse_binary(data: &[u8]) -> Result<Self, Error> {
+        let invalid = || Error::Other("无效的 bkeymap 文件".into());
+        let flags = data
+            .get(BKEYMAP_MAGIC.len()..BKEYMAP_MAGIC.len() + MAX_NR_KEYMAPS)
+            .ok_or_else(invalid)?;
+        let mut rest = &data[BKEYMAP_MAGIC.len() + MAX_NR_KEYMAPS..];
+
+        let mut keymap = Self::default();
+        for (index, _) in flags.iter().enumerate().filter(|(_, flag)| **flag != 0) {
+            let table = rest.get(..NR_KEYS * 2).ok_or_else(invalid)?;
+            rest = &rest[NR_KEYS * 2..];
+            for (code, value) in table.chunks_exact(2).enumerate() {
+                let value = u16::from_ne_bytes([value[0], value[1]]);
+                if let Some(sym) = decode_kernel_keysym(value) {
+                    keymap.entries.insert((code as u16, index as u8), sym);
+                }
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// 查询键位在给定修饰键下产生的符号
+    pub fn lookup(&self, code: u16, modifiers: u8) -> Option<Keysym> {
+        self.entries.get(&(code, modifiers)).copied()
+    }
+
+    /// keymap 中定义的组合规则
+    pub fn compose_rules(&self) -> &HashMap<(char, char), char> {
+        &self.compose
+    }
+
+    /// keymap 是否为该键定义了任何字符
+    pub fn defines(&self, code: u16) -> bool {
+        (0..=MOD_SHIFT | MOD_ALTGR).any(|modifiers| self.entries.contains_key(&(code, modifiers)))
+    }
+}
+
+/// 去掉行尾的 `#` 或 `!` 注释 (引号内的字符除外，例如 `compose '!' '!' to '¡'`)
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            '#' | '!' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// 解析 "keymaps 0-2,4-6,8" 形式的列定义
+fn parse_keymaps_spec(spec: &str) -> Vec<u8> {
+    let mut columns = Vec::new();
+    for part in spec.trim().split(',') {
+        let (start, end) = match part.split_once('-') {
+            Some((a, b)) => (parse_number(a), parse_number(b)),
+            None => (parse_number(part), parse_number(part)),
+        };
+        if let (Some(start), Some(end)) = (start, end) {
+            columns.extend((start..=end.min(u8::MAX as u32)).map(|i| i as u8));
+        }
+    }
+    columns
+}
+
+/// 解析 "'`' 'a' to 'à'" 形式的组合规则
+fn parse_compose_rule(rule: &str) -> Option<(char, char, char)> {
+    let (sequence, result) = rule.split_once(" to ")?;
+    let mut chars = sequence.split_whitespace().map(parse_compose_char);
+    let first = chars.next()??;
+    let second = chars.next()??;
+    Some((first, second, parse_compose_char(result.trim())?))
+}
+
+/// 组合规则中的字符：'x' 形式的字面量或符号名
+fn parse_compose_char(s: &str) -> Option<char> {
+    match s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(quoted) => {
+            let quoted = quoted.strip_prefix('\\').unwrap_or(quoted);
+            let mut chars = quoted.chars();
+            let c = chars.next()?;
+            chars.next().is_none().then_some(c)
+        }
+        None => match parse_symbol(s)? {
+            Keysym::Char(c) | Keysym::Letter(c) => Some(c),
+            Keysym::Dead(_) => None,
+        },
+    }
+}
+
+fn parse_number(s: &str) -> Option<u32> {
+    let s = s.trim();
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+/// 解析单个符号：字符、`U+XXXX`、数值形式的内核 keysym 或符号名
+fn parse_symbol(symbol: &str) -> Option<Keysym> {
+    let (letter, symbol) = match symbol.strip_prefix('+') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, symbol),
+    };
+
+    let sym = if let Some(hex) = symbol.strip_prefix("U+") {
+        Keysym::Char(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?)
+    } else if symbol.chars().count() == 1 {
+        let c = symbol.chars().next()?;
+        if c.is_ascii_alphabetic() { Keysym::Letter(c) } else { Keysym::Char(c) }
+    } else if symbol.starts_with(|c: char| c.is_ascii_digit()) {
+        decode_kernel_keysym(parse_number(symbol)? as u16)?
+    } else if let Some(dead) = DeadKey::from_name(symbol) {
+        Keysym::Dead(dead)
+    } else {
+        Keysym::Char(char_from_name(symbol)?)
+    };
+
+    Some(match sym {
+        Keysym::Char(c) if letter => Keysym::Letter(c),
+        sym => sym,
+    })
+}
+
+fn char_from_name(name: &str) -> Option<char> {
+    if let Some(i) = ASCII_NAMES.iter().position(|n| *n == name) {
+        return char::from_u32(0x20 + i as u32);
+    }
+    if let Some(i) = LATIN1_NAMES.iter().position(|n| *n == name) {
+        return char::from_u32(0xa0 + i as u32);
+    }
+    match name {
+        "quoteright" => Some('\''),
+        "quoteleft" => Some('`'),
+        "Oslash" => Some('Ø'),
+        "euro" | "EuroSign" => Some('€'),
+        _ => None,
+    }
+}
+
+/// 解码内核格式的 keysym (KTYP << 8 | KVAL)，只处理字符类型
+///
+/// 控制字符 (例如 Backspace 的 0x7f、Tab 的 0x09) 不作为文本，交给按键码映射处理。
+fn decode_kernel_keysym(value: u16) -> Option<Keysym> {
+    let c = char::from(value as u8);
+    match value >> 8 {
+        KT_LATIN if !c.is_control() => Some(Keysym::Char(c)),
+        KT_LETTER => Some(Keysym::Letter(c)),
+        KT_DEAD => DeadKey::from_kernel_value(value as u8).map(Keysym::Dead),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_keymap() {
+        let keymap = ConsoleKeymap::parse(
+            "keymaps 0-2\n\
+             keycode 16 = +q Q at\n\
+             keycode 21 = z\n\
+             keycode 12 = ssharp question backslash # comment\n\
+             altgr keycode 18 = U+20AC\n\
+             keycode 13 = dead_acute dead_grave\n\
+             compose 'o' 'e' to U+0153\n",
+        );
+        assert_eq!(keymap.lookup(16, MOD_ALTGR), Some(Keysym::Char('@')));
+        assert_eq!(keymap.lookup(16, 0), Some(Keysym::Letter('q')));
+        assert_eq!(keymap.lookup(21, MOD_SHIFT), Some(Keysym::Letter('Z')));
+        assert_eq!(keymap.lookup(12, 0), Some(Keysym::Char('ß')));
+        assert_eq!(keymap.lookup(18, MOD_ALTGR), Some(Keysym::Char('€')));
+        assert_eq!(keymap.lookup(13, MOD_SHIFT), Some(Keysym::Dead(DeadKey::Grave)));
+        assert_eq!(keymap.compose_rules().get(&('o', 'e')), Some(&'œ'));
+        assert!(!keymap.defines(1));
+    }
+
+    #[test]
+    fn test_parse_binary_keymap() {
+        let mut data = BKEYMAP_MAGIC.to_vec();
+        let mut flags = [0u8; MAX_NR_KEYMAPS];
+        flags[0] = 1;
+        flags[MOD_SHIFT as usize] = 1;
+        data.extend_from_slice(&flags);
+        for (tab, backspace, q) in [(0x0009, 0x007f, 0x0b71), (0x0009, 0x007f, 0x0b51)] {
+            let mut table = [0u16; NR_KEYS];
+            table[14] = backspace;
+            table[15] = tab;
+            table[16] = q;
+            table[17] = 0x00e9;
+            data.extend(table.iter().flat_map(|value| value.to_ne_bytes()));
+        }
+
+        let keymap = ConsoleKeymap::parse_binary(&data).unwrap();
+        assert_eq!(keymap.lookup(16, 0), Some(Keysym::Letter('q')));
+        assert_eq!(keymap.lookup(16, MOD_SHIFT), Some(Keysym::Letter('Q')));
+        assert_eq!(keymap.lookup(17, 0), Some(Keysym::Char('é')));
+        // Backspace 与 Tab 不产生文本，回退到按键码映射
+        assert!(!keymap.defines(14));
+        assert!(!keymap.defines(15));
+
+        assert!(ConsoleKeymap::parse_binary(&data[..data.len() - 1]).is_err());
+    }
+}
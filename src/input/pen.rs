@@ -0,0 +1,194 @@
+//! 手写笔 (数位笔) 处理
+//!
+//! Wacom 一类的手写笔通过 `BTN_TOOL_PEN` / `BTN_TOOL_RUBBER` 报告笔进入感应范围，
+//! 笔尖接触屏幕时报告 `BTN_TOUCH`，笔杆按钮为 `BTN_STYLUS` / `BTN_STYLUS2`。
+//! 与触摸屏不同，笔在悬停时也会报告坐标，因此不能走触摸手势的路径：
+//! - 悬停：只移动指针，不按下
+//! - 笔尖接触：左键 (橡皮擦端按配置的按钮)
+//! - 笔杆按钮：右键 / 中键
+//! - 离开感应范围：释放按钮并发送 `PointerExited`
+
+use super::touch::TouchState;
+use evdev::{AbsInfo, AbsoluteAxisCode, Device, KeyCode};
+use i_slint_core::api::PhysicalPosition;
+use i_slint_core::platform::{PointerEventButton, WindowEvent};
+
+/// 当前使用的笔端
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PenTool {
+    Pen,
+    Eraser,
+}
+
+/// 手写笔状态
+#[derive(Debug, Default)]
+pub struct PenState {
+    x: i32,
+    y: i32,
+    pressure: Option<i32>,
+    /// 在感应范围内的笔端，`None` 表示笔已离开
+    tool: Option<PenTool>,
+    /// 本帧离开了感应范围
+    left_proximity: bool,
+    tip: bool,
+    moved: bool,
+    /// 笔尖接触时按下的按钮，释放时发送同一个按钮
+    tip_button: Option<PointerEventButton>,
+    /// 等待发送的笔杆按钮变化 (按钮, 是否按下)
+    barrel_buttons: Vec<(PointerEventButton, bool)>,
+    /// 橡皮擦端接触时按下的按钮，`None` 表示忽略橡皮擦
+    pub eraser_button: Option<PointerEventButton>,
+}
+
+impl PenState {
+    pub fn new() -> Self {
+        Self { eraser_button: Some(PointerEventButton::Left), ..Default::default() }
+    }
+
+    /// 设备是否为手写笔
+    pub fn is_pen(device: &Device) -> bool {
+        device
+            .supported_keys()
+            .is_some_and(|keys| keys.contains(KeyCode::BTN_TOOL_PEN))
+    }
+
+    pub fn process_axis(&mut self, code: AbsoluteAxisCode, value: i32) {
+        match code {
+            AbsoluteAxisCode::ABS_X => {
+                self.x = value;
+                self.moved = true;
+            }
+            AbsoluteAxisCode::ABS_Y => {
+                self.y = value;
+                self.moved = true;
+            }
+            AbsoluteAxisCode::ABS_PRESSURE => self.pressure = Some(value),
+            _ => {}
+        }
+    }
+
+    /// 处理按键事件，返回该按键是否属于手写笔
+    pub fn process_key(&mut self, key: KeyCode, value: i32) -> bool {
+        let pressed = value != 0;
+        match key {
+            KeyCode::BTN_TOOL_PEN | KeyCode::BTN_TOOL_RUBBER => {
+                let tool = if key == KeyCode::BTN_TOOL_PEN { PenTool::Pen } else { PenTool::Eraser };
+                if pressed {
+                    self.tool = Some(tool);
+                    self.left_proximity = false;
+                } else if self.tool == Some(tool) {
+                    self.tool = None;
+                    self.left_proximity = true;
+                }
+            }
+            KeyCode::BTN_TOUCH => self.tip = pressed,
+            KeyCode::BTN_STYLUS if value != 2 => self.barrel_buttons.push((PointerEventButton::Right, pressed)),
+            KeyCode::BTN_STYLUS2 if value != 2 => self.barrel_buttons.push((PointerEventButton::Middle, pressed)),
+            KeyCode::BTN_STYLUS | KeyCode::BTN_STYLUS2 => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// 帧结束时生成指针事件，追加到 `events`
+    ///
+    /// 坐标映射 (校准、旋转、区域) 与压力阈值沿用设备的触摸配置。
+    #[allow(clippy::too_many_arguments)]
+    pub fn finish_frame(
+        &mut self,
+        touch: &TouchState,
+        pointer_pos: &mut PhysicalPosition,
+        screen_width: u32,
+        screen_height: u32,
+        abs_x: &Option<AbsInfo>,
+        abs_y: &Option<AbsInfo>,
+        events: &mut Vec<WindowEvent>,
+    ) {
+        let position = |pos: &PhysicalPosition| pos.to_logical(1.0);
+
+        let Some(tool) = self.tool else {
+            if let Some(button) = self.tip_button.take() {
+                events.push(WindowEvent::PointerReleased { position: position(pointer_pos), button });
+            }
+            self.barrel_buttons.clear();
+            if std::mem::take(&mut self.left_proximity) {
+                events.push(WindowEvent::PointerExited);
+            }
+            self.moved = false;
+            return;
+        };
+
+        if std::mem::take(&mut self.moved) {
+            *pointer_pos = touch.map_point(self.x, self.y, screen_width, screen_height, abs_x, abs_y);
+            events.push(WindowEvent::PointerMoved { position: position(pointer_pos) });
+        }
+
+        let contact = self.tip
+            && match (touch.pressure_threshold, self.pressure) {
+                (Some(threshold), Some(pressure)) => pressure >= threshold,
+                _ => true,
+            };
+        match (contact, self.tip_button) {
+            (true, None) => {
+                let button = match tool {
+                    PenTool::Pen => Some(PointerEventButton::Left),
+                    PenTool::Eraser => self.eraser_button,
+                };
+                if let Some(button) = button {
+                    events.push(WindowEvent::PointerPressed { position: position(pointer_pos), button });
+                    self.tip_button = Some(button);
+                }
+            }
+            (false, Some(button)) => {
+                events.push(WindowEvent::PointerReleased { position: position(pointer_pos), button });
+                self.tip_button = None;
+            }
+            _ => {}
+        }
+
+        for (button, pressed) in self.barrel_buttons.drain(..) {
+            let position = position(pointer_pos);
+            events.push(if pressed {
+                WindowEvent::PointerPressed { position, button }
+            } else {
+                WindowEvent::PointerReleased { position, button }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hover_then_contact() {
+        let touch = TouchState::new();
+        let axis = Some(AbsInfo::new(0, 0, 1000, 0, 0, 0));
+        let mut pointer = PhysicalPosition::default();
+        let mut pen = PenState::new();
+        let mut frame = |pen: &mut PenState| {
+            let mut events = Vec::new();
+            pen.finish_frame(&touch, &mut pointer, 100, 100, &axis, &axis, &mut events);
+            events
+        };
+
+        // 悬停只产生移动事件
+        pen.process_key(KeyCode::BTN_TOOL_PEN, 1);
+        pen.process_axis(AbsoluteAxisCode::ABS_X, 500);
+        let events = frame(&mut pen);
+        assert!(matches!(events.as_slice(), [WindowEvent::PointerMoved { .. }]));
+
+        pen.process_key(KeyCode::BTN_TOUCH, 1);
+        let events = frame(&mut pen);
+        assert!(matches!(events.as_slice(), [WindowEvent::PointerPressed { button: PointerEventButton::Left, .. }]));
+
+        // 离开感应范围时释放按钮
+        pen.process_key(KeyCode::BTN_TOOL_PEN, 0);
+        let events = frame(&mut pen);
+        assert!(matches!(
+            events.as_slice(),
+            [WindowEvent::PointerReleased { .. }, WindowEvent::PointerExited]
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+//! 输入事件录制与回放
+//!
+//! 录制文件为纯文本，便于查看和手工裁剪：
+//!
+//! ```text
+//! device <编号> <设备路径> <设备名称>
+//! <微秒> <设备编号> <事件类型> <事件代码> <值>
+//! ```
+//!
+//! 时间取自内核事件时间戳，相对于录制的第一个事件。
+//! 回放时按设备名称把事件交给当前打开的同名设备，经过与真实输入相同的处理流程 (包括手势识别)。
+
+use evdev::InputEvent;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// 把原始 evdev 事件写入录制文件
+pub struct InputRecorder {
+    writer: BufWriter<File>,
+    /// 第一个事件的时间戳
+    start: Option<SystemTime>,
+    devices: HashMap<PathBuf, usize>,
+}
+
+impl InputRecorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "# slint-backend-linuxfb input recording")?;
+        Ok(Self { writer, start: None, devices: HashMap::new() })
+    }
+
+    pub fn record(&mut self, path: &Path, name: &str, events: &[InputEvent]) -> io::Result<()> {
+        let next_id = self.devices.len();
+        let id = match self.devices.get(path) {
+            Some(&id) => id,
+            None => {
+                writeln!(self.writer, "device {} {} {}", next_id, path.display(), name)?;
+                self.devices.insert(path.to_path_buf(), next_id);
+                next_id
+            }
+        };
+        for event in events {
+            let start = *self.start.get_or_insert(event.timestamp());
+            let time = event.timestamp().duration_since(start).unwrap_or_default();
+            writeln!(
+                self.writer,
+                "{} {} {} {} {}",
+                time.as_micros(),
+                id,
+                event.event_type().0,
+                event.code(),
+                event.value()
+            )?;
+        }
+        // 及时写入，程序异常退出时也能保留录制内容
+        self.writer.flush()
+    }
+}
+
+/// 待回放的事件
+struct RecordedEvent {
+    time: Duration,
+    device: usize,
+    event: InputEvent,
+}
+
+/// 按录制时的节奏回放事件
+pub struct InputReplay {
+    events: VecDeque<RecordedEvent>,
+    /// 设备编号对应的设备名称
+    devices: HashMap<usize, String>,
+    /// 回放开始的时间 (第一次取事件时开始计时，等待设备打开)
+    start: Option<Instant>,
+}
+
+impl InputReplay {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::parse(BufReader::new(File::open(path)?))
+    }
+
+    fn parse(reader: impl BufRead) -> io::Result<Self> {
+        let invalid = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("无效的录制内容: {}", line));
+        let mut events = VecDeque::new();
+        let mut devices = HashMap::new();
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("device ") {
+                // device <编号> <路径> <名称>，名称中可能包含空格
+                let mut parts = rest.splitn(3, ' ');
+                let id = parts.next().and_then(|id| id.parse().ok()).ok_or_else(|| invalid(line))?;
+                let name = parts.nth(1).unwrap_or_default();
+                devices.insert(id, name.to_string());
+                continue;
+            }
+            let fields: Vec<i64> = line
+                .split_whitespace()
+                .map(|field| field.parse().ok())
+                .collect::<Option<_>>()
+                .ok_or_else(|| invalid(line))?;
+            let &[time, device, type_, code, value] = fields.as_slice() else {
+                return Err(invalid(line));
+            };
+            events.push_back(RecordedEvent {
+                time: Duration::from_micros(time as u64),
+                device: device as usize,
+                event: InputEvent::new(type_ as u16, code as u16, value as i32),
+            });
+        }
+        Ok(Self { events, devices, start: None })
+    }
+
+    /// 取出已到回放时间的事件，返回 (设备名称, 事件)，同一设备的连续事件合并在一起
+    pub fn take_due(&mut self) -> Vec<(String, Vec<InputEvent>)> {
+        let elapsed = self.start.get_or_insert_with(Instant::now).elapsed();
+        let mut due: Vec<(usize, Vec<InputEvent>)> = Vec::new();
+        while let Some(recorded) = self.events.front().filter(|recorded| recorded.time <= elapsed) {
+            match due.last_mut() {
+                Some((device, events)) if *device == recorded.device => events.push(recorded.event),
+                _ => due.push((recorded.device, vec![recorded.event])),
+            }
+            self.events.pop_front();
+        }
+        due.into_iter()
+            .map(|(device, events)| (self.devices.get(&device).cloned().unwrap_or_default(), events))
+            .collect()
+    }
+
+    /// 距离下一个事件的时间，回放结束时返回 `None`
+    pub fn next_timeout(&self) -> Option<Duration> {
+        self.events
+            .front()
+            .map(|recorded| recorded.time.saturating_sub(self.start.map_or(Duration::ZERO, |start| start.elapsed())))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_take_due() {
+        let text = "# comment\n\
+                    device 0 /dev/input/event2 Goodix Capacitive TouchScreen\n\
+                    0 0 3 57 12\n\
+                    0 0 0 0 0\n\
+                    60000000 0 3 57 -1\n";
+        let mut replay = InputReplay::parse(text.as_bytes()).unwrap();
+        let due = replay.take_due();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].0, "Goodix Capacitive TouchScreen");
+        assert_eq!(due[0].1.len(), 2);
+        assert_eq!(due[0].1[0].value(), 12);
+        assert!(!replay.is_finished());
+        assert!(replay.next_timeout().unwrap() > Duration::from_secs(50));
+
+        assert!(InputReplay::parse("0 0 3".as_bytes()).is_err());
+    }
+}
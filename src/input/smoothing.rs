@@ -0,0 +1,116 @@
+//! 触摸坐标平滑
+//!
+//! 采用 1€ 滤波器 (One Euro Filter)：一个截止频率随移动速度变化的低通滤波器。
+//! 慢速移动时截止频率低，抖动被充分抑制；快速移动时截止频率升高，减少跟手延迟。
+//! 参见 Casiez 等人的论文 "1€ Filter: A Simple Speed-based Low-pass Filter for Noisy Input"。
+
+use i_slint_core::api::PhysicalPosition;
+use std::f32::consts::PI;
+use std::time::Instant;
+
+/// 1€ 滤波器参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothingConfig {
+    /// 静止时的截止频率 (Hz)，越小越平滑但延迟越大
+    pub min_cutoff: f32,
+    /// 截止频率随速度 (像素/秒) 增加的系数，越大快速移动时越跟手
+    pub beta: f32,
+    /// 速度估计的截止频率 (Hz)
+    pub derivative_cutoff: f32,
+}
+
+impl Default for SmoothingConfig {
+    fn default() -> Self {
+        Self { min_cutoff: 1.0, beta: 0.01, derivative_cutoff: 1.0 }
+    }
+}
+
+/// 时间间隔为 `dt` 秒时，截止频率 `cutoff` 对应的平滑系数
+fn alpha(cutoff: f32, dt: f32) -> f32 {
+    let tau = 1.0 / (2.0 * PI * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+/// 单个坐标轴的滤波状态 (滤波后的值, 滤波后的速度)
+#[derive(Debug, Clone, Copy)]
+struct AxisFilter {
+    value: f32,
+    velocity: f32,
+}
+
+impl AxisFilter {
+    fn filter(&mut self, config: &SmoothingConfig, value: f32, dt: f32) -> f32 {
+        let velocity = (value - self.value) / dt;
+        let a = alpha(config.derivative_cutoff, dt);
+        self.velocity += a * (velocity - self.velocity);
+        let cutoff = config.min_cutoff + config.beta * self.velocity.abs();
+        let a = alpha(cutoff, dt);
+        self.value += a * (value - self.value);
+        self.value
+    }
+}
+
+/// 二维坐标的 1€ 滤波器
+#[derive(Debug, Clone)]
+pub struct PointFilter {
+    config: SmoothingConfig,
+    /// 各轴状态与上一次采样的时间，`None` 表示尚未开始
+    state: Option<([AxisFilter; 2], Instant)>,
+}
+
+impl PointFilter {
+    pub fn new(config: SmoothingConfig) -> Self {
+        Self { config, state: None }
+    }
+
+    /// 清除历史，下一个采样点原样输出 (手指抬起或手指数量变化时调用)
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+
+    /// 输入 `now` 时刻的采样点，返回平滑后的坐标
+    pub fn filter(&mut self, point: PhysicalPosition, now: Instant) -> PhysicalPosition {
+        let (x, y) = (point.x as f32, point.y as f32);
+        let Some((axes, last)) = &mut self.state else {
+            let axes = [AxisFilter { value: x, velocity: 0.0 }, AxisFilter { value: y, velocity: 0.0 }];
+            self.state = Some((axes, now));
+            return point;
+        };
+        let dt = now.duration_since(*last).as_secs_f32();
+        if dt <= 0.0 {
+            return PhysicalPosition::new(axes[0].value.round() as i32, axes[1].value.round() as i32);
+        }
+        *last = now;
+        let x = axes[0].filter(&self.config, x, dt);
+        let y = axes[1].filter(&self.config, y, dt);
+        PhysicalPosition::new(x.round() as i32, y.round() as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_filter_jitter() {
+        let mut filter = PointFilter::new(SmoothingConfig::default());
+        let start = Instant::now();
+        let frame = |i: u64| start + Duration::from_millis(i * 10);
+        assert_eq!(filter.filter(PhysicalPosition::new(100, 100), frame(0)), PhysicalPosition::new(100, 100));
+
+        // 静止时 ±3 像素的抖动被抑制
+        for i in 1..20 {
+            let offset = if i % 2 == 0 { 3 } else { -3 };
+            let point = filter.filter(PhysicalPosition::new(100 + offset, 100), frame(i));
+            assert!((point.x - 100).abs() <= 1);
+        }
+
+        // 快速移动时跟随输入
+        let mut point = PhysicalPosition::default();
+        for i in 20..40 {
+            point = filter.filter(PhysicalPosition::new(100 + (i as i32 - 19) * 20, 100), frame(i));
+        }
+        assert!(point.x > 450);
+    }
+}
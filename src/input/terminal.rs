@@ -0,0 +1,267 @@
+//! 终端 (stdin) 键盘输入
+//!
+//! 通过 SSH 或串口调试时没有 evdev 键盘可用。启用后，控制终端被切换到原始模式 (raw mode)，
+//! 读取到的字符和转义序列被转换为 Slint 按键事件，作为 evdev 之外的补充输入源。
+//!
+//! 注意：如果 stdin 就是显示 UI 的本地控制台，同一次按键会经 evdev 和终端各输入一次，
+//! 因此该模式只应在远程终端中启用。
+
+use i_slint_core::input::key_codes;
+use i_slint_core::platform::WindowEvent;
+use i_slint_core::SharedString;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::sync::Mutex;
+
+const STDIN_FD: RawFd = 0;
+const ESC: u8 = 0x1b;
+
+/// 切换到原始模式之前的终端属性，供 [`restore_terminal`] 在信号处理函数中恢复
+static ORIGINAL_TERMIOS: Mutex<Option<libc::termios>> = Mutex::new(None);
+
+/// 把 stdin 恢复为切换到原始模式之前的属性
+///
+/// 由 [`restore_tty`](crate::restore_tty) 调用，只执行一次 `tcsetattr`，不阻塞 (正在更新时直接返回)。
+/// 终端输入未启用时什么也不做。
+pub(crate) fn restore_terminal() {
+    if let Ok(guard) = ORIGINAL_TERMIOS.try_lock() {
+        if let Some(termios) = guard.as_ref() {
+            // SAFETY: termios 是 tcgetattr 得到的有效属性
+            unsafe { libc::tcsetattr(STDIN_FD, libc::TCSANOW, termios) };
+        }
+    }
+}
+
+/// 原始模式下的终端输入源
+pub struct TerminalInput {
+    /// 尚未构成完整 UTF-8 字符或转义序列的字节
+    pending: Vec<u8>,
+}
+
+impl TerminalInput {
+    /// 将 stdin 切换到原始模式；stdin 不是终端时返回错误
+    pub fn new() -> io::Result<Self> {
+        // SAFETY: 以下调用只操作 stdin，termios 为有效的输出参数
+        unsafe {
+            if libc::isatty(STDIN_FD) != 1 {
+                return Err(io::Error::new(io::ErrorKind::Unsupported, "stdin 不是终端"));
+            }
+            let mut termios: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(STDIN_FD, &mut termios) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let original_termios = termios;
+
+            // 关闭行缓冲与回显，保留 ISIG 以便 Ctrl+C 仍能触发退出信号。
+            // VMIN=0/VTIME=0 使没有输入时 read 立即返回，而不必给与 shell 共享的 stdin 设置 O_NONBLOCK
+            termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::IEXTEN);
+            termios.c_iflag &= !(libc::IXON | libc::ICRNL);
+            termios.c_cc[libc::VMIN] = 0;
+            termios.c_cc[libc::VTIME] = 0;
+            *ORIGINAL_TERMIOS.lock().unwrap() = Some(original_termios);
+            if libc::tcsetattr(STDIN_FD, libc::TCSANOW, &termios) != 0 {
+                let error = io::Error::last_os_error();
+                *ORIGINAL_TERMIOS.lock().unwrap() = None;
+                return Err(error);
+            }
+
+            tracing::info!("终端输入已启用 (stdin 原始模式)");
+            Ok(Self { pending: Vec::new() })
+        }
+    }
+
+    pub fn fd(&self) -> RawFd {
+        STDIN_FD
+    }
+
+    /// 读取所有可用的输入并转换为按键事件，追加到 `events`
+    pub fn read_events(&mut self, events: &mut Vec<WindowEvent>) {
+        let mut buf = [0u8; 256];
+        let mut pollfd = libc::pollfd { fd: STDIN_FD, events: libc::POLLIN, revents: 0 };
+        loop {
+            // SAFETY: pollfd 和 buf 都是有效的缓冲区；poll 超时为 0，只在有输入时读取
+            let n = unsafe {
+                if libc::poll(&mut pollfd, 1, 0) <= 0 || pollfd.revents & libc::POLLIN == 0 {
+                    break;
+                }
+                libc::read(STDIN_FD, buf.as_mut_ptr() as *mut _, buf.len())
+            };
+            if n <= 0 {
+                break;
+            }
+            self.pending.extend_from_slice(&buf[..n as usize]);
+            parse_input(&mut self.pending, events);
+        }
+    }
+}
+
+impl Drop for TerminalInput {
+    fn drop(&mut self) {
+        restore_terminal();
+        *ORIGINAL_TERMIOS.lock().unwrap() = None;
+    }
+}
+
+/// 生成一次完整的按键 (按下 + 释放)
+fn key_stroke(events: &mut Vec<WindowEvent>, text: impl Into<SharedString>) {
+    let text = text.into();
+    events.push(WindowEvent::KeyPressed { text: text.clone() });
+    events.push(WindowEvent::KeyReleased { text });
+}
+
+/// 转义序列 (ESC 之后的部分) 对应的按键
+fn escape_sequence(seq: &[u8]) -> Option<char> {
+    Some(match seq {
+        b"[A" | b"OA" => key_codes::UpArrow,
+        b"[B" | b"OB" => key_codes::DownArrow,
+        b"[C" | b"OC" => key_codes::RightArrow,
+        b"[D" | b"OD" => key_codes::LeftArrow,
+        b"[H" | b"OH" | b"[1~" | b"[7~" => key_codes::Home,
+        b"[F" | b"OF" | b"[4~" | b"[8~" => key_codes::End,
+        b"[2~" => key_codes::Insert,
+        b"[3~" => key_codes::Delete,
+        b"[5~" => key_codes::PageUp,
+        b"[6~" => key_codes::PageDown,
+        b"[Z" => key_codes::Backtab,
+        b"OP" => key_codes::F1,
+        b"OQ" => key_codes::F2,
+        b"OR" => key_codes::F3,
+        b"OS" => key_codes::F4,
+        _ => return None,
+    })
+}
+
+/// 解析终端输入，生成的按键事件追加到 `events`，已处理的字节从 `buf` 中移除 (不完整的 UTF-8 字符和转义序列会被保留)
+fn parse_input(buf: &mut Vec<u8>, events: &mut Vec<WindowEvent>) {
+    let mut i = 0;
+    while i < buf.len() {
+        let byte = buf[i];
+        match byte {
+            ESC => {
+                // CSI / SS3 序列：ESC [ ... 终止字符，或 ESC O x；
+                // 序列可能被拆到两次读取中，不完整的序列保留到下一次读取
+                let rest = &buf[i + 1..];
+                let len = match rest.first() {
+                    Some(b'[') => match rest[1..].iter().position(|b| (0x40..=0x7e).contains(b)) {
+                        Some(end) => Some(end + 2),
+                        None => break,
+                    },
+                    Some(b'O') if rest.len() >= 2 => Some(2),
+                    Some(b'O') => break,
+                    _ => None,
+                };
+                match len {
+                    Some(len) => {
+                        if let Some(key) = escape_sequence(&rest[..len]) {
+                            key_stroke(events, key);
+                        }
+                        i += 1 + len;
+                    }
+                    None => {
+                        // 单独的 ESC
+                        key_stroke(events, key_codes::Escape);
+                        i += 1;
+                    }
+                }
+            }
+            b'\r' | b'\n' => {
+                key_stroke(events, key_codes::Return);
+                i += 1;
+            }
+            b'\t' => {
+                key_stroke(events, key_codes::Tab);
+                i += 1;
+            }
+            0x7f | 0x08 => {
+                key_stroke(events, key_codes::Backspace);
+                i += 1;
+            }
+            // Ctrl+A .. Ctrl+Z：模拟按住 Control 再按字母，以便触发 Slint 的快捷键
+            0x01..=0x1a => {
+                let letter = char::from(b'a' + byte - 1);
+                events.push(WindowEvent::KeyPressed { text: key_codes::Control.into() });
+                key_stroke(events, letter);
+                events.push(WindowEvent::KeyReleased { text: key_codes::Control.into() });
+                i += 1;
+            }
+            0x00..=0x1f => i += 1,
+            _ => {
+                let len = match byte {
+                    0xc0..=0xdf => 2,
+                    0xe0..=0xef => 3,
+                    0xf0..=0xf7 => 4,
+                    _ => 1,
+                };
+                if i + len > buf.len() {
+                    // 不完整的 UTF-8 字符，等待后续字节
+                    break;
+                }
+                if let Ok(s) = std::str::from_utf8(&buf[i..i + len]) {
+                    key_stroke(events, s);
+                }
+                i += len;
+            }
+        }
+    }
+    buf.drain(..i);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(events: &[WindowEvent]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                WindowEvent::KeyPressed { text } => Some(text.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let mut buf = b"a\x1b[A\x1b[3~\r\xc3".to_vec();
+        let mut events = Vec::new();
+        parse_input(&mut buf, &mut events);
+        assert_eq!(
+            texts(&events),
+            vec![
+                "a".to_string(),
+                key_codes::UpArrow.to_string(),
+                key_codes::Delete.to_string(),
+                key_codes::Return.to_string(),
+            ]
+        );
+        // 不完整的 "é" 保留到下一次读取
+        assert_eq!(buf, b"\xc3");
+        buf.push(0xa9);
+        events.clear();
+        parse_input(&mut buf, &mut events);
+        assert_eq!(texts(&events), vec!["é".to_string()]);
+
+        // 拆到两次读取中的转义序列不会被当作 Escape 加普通字符
+        for (first, second, key) in [
+            (&b"\x1b["[..], &b"3~"[..], key_codes::Delete),
+            (b"\x1b[1", b"~", key_codes::Home),
+            (b"\x1bO", b"A", key_codes::UpArrow),
+        ] {
+            let mut buf = first.to_vec();
+            events.clear();
+            parse_input(&mut buf, &mut events);
+            assert!(events.is_empty());
+            assert_eq!(buf, first);
+            buf.extend_from_slice(second);
+            parse_input(&mut buf, &mut events);
+            assert_eq!(texts(&events), vec![key.to_string()]);
+            assert!(buf.is_empty());
+        }
+
+        // 末尾单独的 ESC 仍是 Escape 键
+        let mut buf = b"\x1b".to_vec();
+        events.clear();
+        parse_input(&mut buf, &mut events);
+        assert_eq!(texts(&events), vec![key_codes::Escape.to_string()]);
+    }
+}
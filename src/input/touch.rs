@@ -3,9 +3,16 @@
 //! 本模块负责处理来自 `evdev` 的触摸屏事件，包括：
 //! - 多点触控协议解析 (支持 Protocol A 和 Protocol B)。
 //! - 坐标映射与校准。
-//! - 手势识别：单指点击、单指拖拽、长按右键、双指滚动。
+//! - 按压力阈值过滤轻触 (电阻屏的虚假触点)。
+//! - 可选的坐标平滑 (1€ 滤波器)，抑制噪声较大的面板上的抖动。
+//! - 手势识别：单指点击、单指拖拽、长按右键、双指滚动 (抬起后惯性滚动)。
+//! - 原始多点触控模式：不做手势识别，逐个报告触点。
 
-use evdev::{AbsInfo, AbsoluteAxisCode};
+use super::calibration::TouchCalibration;
+use super::smoothing::PointFilter;
+use crate::clock;
+use crate::window::Rotation;
+use evdev::{AbsInfo, AbsoluteAxisCode, Device};
 use i_slint_core::api::PhysicalPosition;
 use i_slint_core::platform::{PointerEventButton, WindowEvent};
 use std::time::{Duration, Instant};
@@ -22,8 +29,26 @@ const LONG_PRESS_DURATION: Duration = Duration::from_millis(600);
 /// 滚动速度缩放因子：将触摸移动距离转换为滚动距离的倍率。
 const SCROLL_SCALE: f32 = 2.0;
 
-/// 支持的最大硬件触控点数量 (Slot)。虽然通常只需要处理前两个点，但保留余量以防万一。
-const MAX_SLOTS: usize = 10;
+/// 多指滑动所需的最小重心位移（像素）
+const SWIPE_THRESHOLD: i32 = 50;
+
+/// 惯性滚动的更新间隔
+pub const KINETIC_INTERVAL: Duration = Duration::from_millis(16);
+
+/// 惯性滚动的最小速度（滚动像素/秒），低于此值时停止
+const KINETIC_MIN_VELOCITY: f32 = 30.0;
+
+/// 手指抬起前停顿超过此时间则不产生惯性滚动
+const KINETIC_MAX_IDLE: Duration = Duration::from_millis(100);
+
+/// 识别为捏合所需的双指距离相对变化
+const PINCH_THRESHOLD: f32 = 0.15;
+
+/// 设备未报告 Slot 数量时 (Protocol A、单点触摸) 跟踪的触控点数量。
+const DEFAULT_SLOTS: usize = 10;
+
+/// 跟踪的最大触控点数量，防止异常的 ABS_MT_SLOT 范围分配过多状态。
+const MAX_SLOTS: usize = 64;
 
 /// 单个触控点 (Slot) 的内部状态
 #[derive(Debug, Clone, Copy, Default)]
@@ -36,6 +61,94 @@ pub struct SlotState {
     pub x: i32,
     /// 原始 Y 坐标
     pub y: i32,
+    /// 原始压力值 (设备未报告压力时为 `None`)
+    pub pressure: Option<i32>,
+}
+
+/// 触摸手势开关
+///
+/// 关闭的手势退化为普通的按下/移动/释放。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureConfig {
+    /// 单指长按触发右键
+    pub long_press_right_click: bool,
+    /// 双指滚动
+    pub two_finger_scroll: bool,
+    /// 双指滚动抬起后的惯性滚动摩擦系数，`None` 表示关闭惯性滚动
+    ///
+    /// 滚动速度按 `e^(-摩擦系数 * 秒)` 衰减，数值越大停得越快，默认 `4.0`。
+    pub kinetic_scroll_friction: Option<f32>,
+    /// 双指捏合缩放 (通过手势回调报告为 [`TouchGesture::Pinch`])
+    ///
+    /// 默认关闭：开启后双指距离明显变化时不再滚动。多指手势全部关闭时只跟踪第一个手指。
+    pub pinch_zoom: bool,
+    /// 三指及以上的点击与滑动 (通过手势回调报告为 [`TouchGesture::Tap`] / [`TouchGesture::Swipe`])
+    ///
+    /// 默认关闭：开启后三指及以上的触摸不再滚动。
+    pub multi_finger: bool,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            long_press_right_click: true,
+            two_finger_scroll: true,
+            kinetic_scroll_friction: Some(4.0),
+            pinch_zoom: false,
+            multi_finger: false,
+        }
+    }
+}
+
+/// 交给应用处理的触摸手势
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TouchGesture {
+    /// 双指捏合，`scale` 为相对捏合开始时的缩放比例，`center` 为双指中心 (窗口坐标)
+    Pinch {
+        scale: f32,
+        center: PhysicalPosition,
+        phase: TouchPhase,
+    },
+    /// 多指点击，`position` 为手指的中心 (窗口坐标)
+    Tap {
+        fingers: usize,
+        position: PhysicalPosition,
+    },
+    /// 多指滑动
+    Swipe {
+        fingers: usize,
+        direction: SwipeDirection,
+    },
+}
+
+/// 滑动方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// 触点所处的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchPhase {
+    /// 手指按下
+    Started,
+    /// 手指移动
+    Moved,
+    /// 手指抬起
+    Ended,
+}
+
+/// 原始多点触控模式下报告给应用的单个触点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchContact {
+    /// 触点标识，在手指抬起前保持不变
+    pub id: i32,
+    /// 窗口坐标
+    pub position: PhysicalPosition,
+    pub phase: TouchPhase,
 }
 
 /// 手势识别状态机模式
@@ -49,16 +162,35 @@ enum GestureMode {
     RightDrag,
     /// 滚动：双指移动触发，模拟鼠标滚轮
     Scroll,
+    /// 捏合：双指距离变化触发，报告给应用的手势回调
+    Pinch,
+    /// 三指及以上：抬起后识别为点击或滑动，报告给应用的手势回调
+    MultiFinger,
     /// 等待释放：手势结束或无效状态，等待所有手指抬起
     WaitRelease,
 }
 
+/// 手指抬起后仍在进行的惯性滚动
+#[derive(Debug, Clone, Copy)]
+struct KineticScroll {
+    /// 滚动速度 (滚动像素/秒)
+    velocity: (f32, f32),
+    /// 上一次生成滚动事件的时间
+    last_tick: Instant,
+    /// 滚动事件的指针位置
+    position: PhysicalPosition,
+}
+
 /// 触摸屏全局状态管理器
 pub struct TouchState {
-    /// 所有触控点的状态数组
-    pub slots: [SlotState; MAX_SLOTS],
+    /// 所有触控点的状态，数量由设备报告的 Slot 范围决定
+    pub slots: Vec<SlotState>,
+    /// 本帧按下的 Slot 索引 (每帧复用，避免分配)
+    active_slots: Vec<usize>,
     /// 当前正在处理的 Slot 索引 (用于 Protocol B)
     pub current_slot: usize,
+    /// 当前帧 (SYN_REPORT) 的内核时间戳，手势计时都以它为准而不是处理时的时间
+    pub frame_time: Instant,
 
     // --- 手势相关状态 ---
     gesture_mode: GestureMode,
@@ -75,13 +207,69 @@ pub struct TouchState {
     max_fingers_down: usize,
     /// 标记长按是否已失效 (例如已经发生了移动)
     long_press_invalidated: bool,
+
+    /// 触摸校准参数 (未设置时按设备坐标范围线性映射)
+    pub calibration: Option<TouchCalibration>,
+    /// 映射到屏幕后再施加的旋转
+    pub rotation: Rotation,
+    /// 设备对应的窗口区域 `(x, y, 宽, 高)`，`None` 表示整个窗口
+    pub region: Option<(i32, i32, u32, u32)>,
+    /// 压力阈值：压力低于该值的触点视为悬停而非按下
+    pub pressure_threshold: Option<i32>,
+    /// 设备是否报告了逐触点压力 (ABS_MT_PRESSURE)
+    has_mt_pressure: bool,
+    /// 设备是否报告逐触点坐标 (ABS_MT_POSITION_X/Y)，此时 ABS_X/Y 只是第一个手指的副本
+    has_mt_position: bool,
+    /// 启用的手势
+    pub gestures: GestureConfig,
+    /// 手势模式下的重心平滑滤波器，`None` 表示不平滑
+    pub smoothing: Option<PointFilter>,
+    /// 滤波器当前跟踪的手指数量 (数量变化时重心跳变，需要重新开始滤波)
+    smoothed_fingers: usize,
+    /// 捏合开始时的双指距离
+    pinch_distance: f32,
+    /// 最近一次报告的捏合缩放比例
+    last_pinch_scale: f32,
+    /// 等待交给应用的手势
+    pub pending_gestures: Vec<TouchGesture>,
+    /// 等待交给应用的触点变化 (仅原始多点触控模式)
+    pub pending_contacts: Vec<TouchContact>,
+    /// 双指滚动的速度 (滚动像素/秒) 与最近一次滚动的时间
+    scroll_velocity: (f32, f32),
+    last_scroll_time: Option<Instant>,
+    /// 进行中的惯性滚动
+    kinetic: Option<KineticScroll>,
+
+    // --- 原始多点触控模式 ---
+    /// 上一帧报告的各 Slot 触点 (标识, 窗口坐标)
+    reported: Vec<Option<(i32, PhysicalPosition)>>,
+    /// 作为左键指针的主触点 (Slot 索引, 标识)
+    primary: Option<(usize, i32)>,
 }
 
 impl TouchState {
     pub fn new() -> Self {
+        Self::with_slots(DEFAULT_SLOTS)
+    }
+
+    /// 按设备的坐标轴创建：Slot 数量取 ABS_MT_SLOT 的最大值加一，Protocol A 与单点设备使用默认数量
+    pub fn for_device(device: &Device) -> Self {
+        let axes: Vec<_> = device.get_absinfo().map(Iterator::collect).unwrap_or_default();
+        let slots = axes
+            .iter()
+            .find(|(code, _)| *code == AbsoluteAxisCode::ABS_MT_SLOT)
+            .map_or(DEFAULT_SLOTS, |(_, info)| info.maximum().saturating_add(1).clamp(1, MAX_SLOTS as i32) as usize);
+        let mut state = Self::with_slots(slots);
+        state.has_mt_position = axes.iter().any(|(code, _)| *code == AbsoluteAxisCode::ABS_MT_POSITION_X);
+        state
+    }
+
+    fn with_slots(count: usize) -> Self {
         Self {
-            slots: [SlotState::default(); MAX_SLOTS],
+            slots: vec![SlotState::default(); count],
+            active_slots: Vec::with_capacity(count),
             current_slot: 0,
+            frame_time: clock::now(),
             gesture_mode: GestureMode::None,
             gesture_start_time: None,
             initial_centroid: None,
@@ -89,7 +277,123 @@ impl TouchState {
             last_reported_pos: None,
             max_fingers_down: 0,
             long_press_invalidated: false,
+            calibration: None,
+            rotation: Rotation::default(),
+            region: None,
+            pressure_threshold: None,
+            has_mt_pressure: false,
+            has_mt_position: false,
+            gestures: GestureConfig::default(),
+            smoothing: None,
+            smoothed_fingers: 0,
+            pinch_distance: 0.0,
+            last_pinch_scale: 1.0,
+            pending_gestures: Vec::new(),
+            pending_contacts: Vec::new(),
+            scroll_velocity: (0.0, 0.0),
+            last_scroll_time: None,
+            kinetic: None,
+            reported: vec![None; count],
+            primary: None,
+        }
+    }
+
+    /// 触点是否处于按下状态 (活跃且压力达到阈值)
+    ///
+    /// 设备不报告压力时不做过滤。
+    pub(super) fn is_pressed(&self, slot: &SlotState) -> bool {
+        slot.active
+            && match (self.pressure_threshold, slot.pressure) {
+                (Some(threshold), Some(pressure)) => pressure >= threshold,
+                _ => true,
+            }
+    }
+
+    /// 是否有进行中的惯性滚动
+    pub fn is_kinetic_scrolling(&self) -> bool {
+        self.kinetic.is_some()
+    }
+
+    /// 推进惯性滚动，返回自上次调用以来的滚动事件
+    ///
+    /// 速度衰减到最小值以下时结束。
+    pub fn kinetic_scroll(&mut self) -> Option<WindowEvent> {
+        let friction = self.gestures.kinetic_scroll_friction?;
+        let kinetic = self.kinetic.as_mut()?;
+        let now = clock::now();
+        let dt = now.duration_since(kinetic.last_tick).as_secs_f32();
+        if dt <= 0.0 {
+            return None;
         }
+        kinetic.last_tick = now;
+
+        // 对衰减的速度积分，得到这段时间内的滚动距离
+        let decay = (-friction * dt).exp();
+        let travel = if friction > 0.0 { (1.0 - decay) / friction } else { dt };
+        let (vx, vy) = kinetic.velocity;
+        let event = WindowEvent::PointerScrolled {
+            position: kinetic.position.to_logical(1.0),
+            delta_x: vx * travel,
+            delta_y: vy * travel,
+        };
+        kinetic.velocity = (vx * decay, vy * decay);
+        if kinetic.velocity.0.hypot(kinetic.velocity.1) < KINETIC_MIN_VELOCITY {
+            self.kinetic = None;
+        }
+        Some(event)
+    }
+
+    /// 双指滚动结束 (手指抬起) 时，按抬起前的速度开始惯性滚动
+    fn start_kinetic_scroll(&mut self, position: PhysicalPosition) {
+        let frame_time = self.frame_time;
+        let recent = self
+            .last_scroll_time
+            .take()
+            .is_some_and(|t| frame_time.saturating_duration_since(t) < KINETIC_MAX_IDLE);
+        let (vx, vy) = std::mem::take(&mut self.scroll_velocity);
+        if self.gestures.kinetic_scroll_friction.is_some() && recent && vx.hypot(vy) >= KINETIC_MIN_VELOCITY {
+            self.kinetic = Some(KineticScroll { velocity: (vx, vy), last_tick: clock::now(), position });
+        }
+    }
+
+    /// 将原始触摸坐标映射到窗口像素坐标
+    ///
+    /// 先 (经校准或按坐标范围) 映射到未旋转的屏幕，再按 `rotation` 旋转到窗口；
+    /// 设置了 `region` 时整个映射都在该区域内进行。
+    pub(super) fn map_point(
+        &self,
+        x: i32,
+        y: i32,
+        screen_width: u32,
+        screen_height: u32,
+        abs_x: &Option<AbsInfo>,
+        abs_y: &Option<AbsInfo>,
+    ) -> PhysicalPosition {
+        let (offset_x, offset_y, window_width, window_height) =
+            self.region.unwrap_or((0, 0, screen_width, screen_height));
+        let (screen_width, screen_height) = self.rotation.rotate_size(window_width, window_height);
+        let (sx, sy) = match &self.calibration {
+            Some(calibration) => calibration.apply(x, y, screen_width, screen_height),
+            None => (map_coord(x, abs_x, screen_width), map_coord(y, abs_y, screen_height)),
+        };
+        let (max_x, max_y) = ((screen_width as i32 - 1).max(0), (screen_height as i32 - 1).max(0));
+        let (sx, sy) = (sx.clamp(0, max_x), sy.clamp(0, max_y));
+        let (wx, wy) = self.rotation.inverse().rotate_point(sx, sy, screen_width, screen_height);
+        PhysicalPosition::new(offset_x + wx, offset_y + wy)
+    }
+
+    /// 返回所有活跃触点映射到屏幕后的坐标
+    pub fn active_points<'a>(
+        &'a self,
+        screen_width: u32,
+        screen_height: u32,
+        abs_x: &'a Option<AbsInfo>,
+        abs_y: &'a Option<AbsInfo>,
+    ) -> impl Iterator<Item = PhysicalPosition> + 'a {
+        self.slots
+            .iter()
+            .filter(|slot| self.is_pressed(slot))
+            .map(move |slot| self.map_point(slot.x, slot.y, screen_width, screen_height, abs_x, abs_y))
     }
 
     /// 处理 evdev 的绝对坐标 (ABS) 事件
@@ -100,13 +404,13 @@ impl TouchState {
         match code {
             // --- MT Protocol B: Slot 切换 ---
             AbsoluteAxisCode::ABS_MT_SLOT => {
-                if (value as usize) < MAX_SLOTS {
+                if (0..self.slots.len() as i32).contains(&value) {
                     self.current_slot = value as usize;
                 }
             }
             // --- MT Protocol B: 追踪 ID ---
             AbsoluteAxisCode::ABS_MT_TRACKING_ID => {
-                if self.current_slot < MAX_SLOTS {
+                if self.current_slot < self.slots.len() {
                     if value == -1 {
                         // ID 为 -1 表示手指抬起
                         self.slots[self.current_slot].active = false;
@@ -119,26 +423,38 @@ impl TouchState {
             }
             // --- MT 坐标数据 ---
             AbsoluteAxisCode::ABS_MT_POSITION_X => {
-                if self.current_slot < MAX_SLOTS {
+                if self.current_slot < self.slots.len() {
                     self.slots[self.current_slot].x = value;
-                    // Protocol A 兼容：如果不是 B 协议，收到坐标即视为活跃
+                    // Protocol A 兼容：如果不是 B 协议，收到坐标即视为活跃 (以 Slot 索引作为标识)
                     if !is_protocol_b && !self.slots[self.current_slot].active {
                         self.slots[self.current_slot].active = true;
+                        self.slots[self.current_slot].id = self.current_slot as i32;
                     }
                 }
             }
             AbsoluteAxisCode::ABS_MT_POSITION_Y => {
-                if self.current_slot < MAX_SLOTS {
+                if self.current_slot < self.slots.len() {
                     self.slots[self.current_slot].y = value;
                     if !is_protocol_b && !self.slots[self.current_slot].active {
                         self.slots[self.current_slot].active = true;
+                        self.slots[self.current_slot].id = self.current_slot as i32;
                     }
                 }
             }
+            // --- 压力 ---
+            AbsoluteAxisCode::ABS_MT_PRESSURE if self.current_slot < self.slots.len() => {
+                self.slots[self.current_slot].pressure = Some(value);
+                self.has_mt_pressure = true;
+            }
+            // 多点触控设备的 ABS_PRESSURE 只是模拟单点的副本，以逐触点压力为准
+            AbsoluteAxisCode::ABS_PRESSURE if !self.has_mt_pressure => {
+                self.slots[0].pressure = Some(value);
+            }
             // --- 单点触摸兼容 (Legacy) ---
-            // 某些驱动在发送 MT 事件的同时也会发送传统的 ABS_X/Y，
-            // 或者对于不支持 MT 的老设备，只发送这两个事件。
+            // 某些驱动在发送 MT 事件的同时也会发送传统的 ABS_X/Y，其坐标范围可能与 MT 坐标不同，
+            // 此时以逐触点坐标为准；对于不支持 MT 的老设备，只发送这两个事件。
             // 我们将其映射到 Slot 0 以保证兼容性。
+            AbsoluteAxisCode::ABS_X | AbsoluteAxisCode::ABS_Y if self.has_mt_position => {}
             AbsoluteAxisCode::ABS_X => {
                 self.slots[0].x = value;
                 if !self.slots[0].active {
@@ -160,10 +476,7 @@ impl TouchState {
     /// 在 Protocol A 中，每个触点数据包以 SYN_MT_REPORT 结束。
     /// 我们需要手动递增 Slot 索引来为下一个触点做准备。
     pub fn sync_mt_report(&mut self) {
-        self.current_slot += 1;
-        if self.current_slot >= MAX_SLOTS {
-            self.current_slot = MAX_SLOTS - 1;
-        }
+        self.current_slot = (self.current_slot + 1).min(self.slots.len() - 1);
     }
 
     /// 处理 Protocol A 的帧结束
@@ -171,17 +484,30 @@ impl TouchState {
     /// Protocol A 不显式发送“抬起”事件，而是通过不再报告该触点来表示。
     /// 因此在帧结束时，未被更新的后续 Slot 应被标记为非活跃。
     pub fn finish_frame_protocol_a(&mut self) {
-        for i in self.current_slot..MAX_SLOTS {
-            self.slots[i].active = false;
+        for slot in &mut self.slots[self.current_slot..] {
+            slot.active = false;
         }
         self.current_slot = 0;
     }
 }
 
+/// 坐标映射：将原始设备坐标映射到屏幕像素坐标
+pub fn map_coord(val: i32, info: &Option<AbsInfo>, screen_max: u32) -> i32 {
+    if let Some(info) = info {
+        let range = (info.maximum() - info.minimum()) as f32;
+        if range > 0.0 {
+            return ((val - info.minimum()) as f32 / range * screen_max as f32).round() as i32;
+        }
+    }
+    // 兜底：如果没有获取到 abs info，直接返回原始值
+    val
+}
+
 /// 分析触摸数据并生成 Slint 事件
 ///
 /// 该函数在每帧同步 (SYN_REPORT) 时调用。它计算所有活跃触点的几何重心，
-/// 并根据手指数量和持续时间维护手势状态机。
+/// 并根据手指数量和持续时间维护手势状态机，生成的事件追加到 `events`。
+#[allow(clippy::too_many_arguments)]
 pub fn analyze_touch_gesture(
     state: &mut TouchState,
     pointer_pos: &mut PhysicalPosition,
@@ -190,33 +516,26 @@ pub fn analyze_touch_gesture(
     screen_height: u32,
     abs_x: &Option<AbsInfo>,
     abs_y: &Option<AbsInfo>,
-) -> Option<Vec<WindowEvent>> {
+    events: &mut Vec<WindowEvent>,
+) {
     // 1. 统计活跃手指
-    let mut active_slots = Vec::new();
-    for (i, slot) in state.slots.iter().enumerate() {
-        if slot.active {
-            active_slots.push(i);
+    state.active_slots.clear();
+    for i in 0..state.slots.len() {
+        if state.is_pressed(&state.slots[i]) {
+            state.active_slots.push(i);
         }
     }
-    let finger_count = active_slots.len();
-    let mut events = Vec::new();
-
-    // 坐标映射闭包：将原始设备坐标映射到屏幕像素坐标
-    let map_coord = |val: i32, info: &Option<AbsInfo>, screen_max: u32| -> i32 {
-        if let Some(info) = info {
-            let range = (info.maximum() - info.minimum()) as f32;
-            if range > 0.0 {
-                return ((val - info.minimum()) as f32 / range * screen_max as f32).round() as i32;
-            }
-        }
-        // 兜底：如果没有获取到 abs info，直接返回原始值
-        val
-    };
+    // 多指手势全部关闭时只跟踪第一个手指，其余手指不影响指针
+    let gestures = state.gestures;
+    if !gestures.two_finger_scroll && !gestures.pinch_zoom && !gestures.multi_finger {
+        state.active_slots.truncate(1);
+    }
+    let finger_count = state.active_slots.len();
 
     // 2. 计算重心 (Centroid)
     // 多指操作时，我们使用所有手指的中心点作为光标位置
     let (cx, cy) = if finger_count > 0 {
-        let (sum_x, sum_y) = active_slots.iter().fold((0, 0), |acc, &idx| {
+        let (sum_x, sum_y) = state.active_slots.iter().fold((0, 0), |acc, &idx| {
             (acc.0 + state.slots[idx].x, acc.1 + state.slots[idx].y)
         });
         (sum_x / finger_count as i32, sum_y / finger_count as i32)
@@ -224,13 +543,26 @@ pub fn analyze_touch_gesture(
         (0, 0)
     };
 
-    let screen_cx = map_coord(cx, abs_x, screen_width);
-    let screen_cy = map_coord(cy, abs_y, screen_height);
-    let current_centroid = PhysicalPosition::new(screen_cx, screen_cy);
+    let mut current_centroid = state.map_point(cx, cy, screen_width, screen_height, abs_x, abs_y);
+
+    // 平滑后的坐标已足够稳定，不再需要去抖阈值 (否则慢速拖动会出现阶梯)
+    let mut jitter_threshold = JITTER_THRESHOLD;
+    if let Some(filter) = state.smoothing.as_mut() {
+        if finger_count != state.smoothed_fingers {
+            filter.reset();
+            state.smoothed_fingers = finger_count;
+        }
+        if finger_count > 0 {
+            current_centroid = filter.filter(current_centroid, state.frame_time);
+        }
+        jitter_threshold = 0;
+    }
 
     // 3. 初始化新手势
     if finger_count > 0 && state.gesture_start_time.is_none() {
-        state.gesture_start_time = Some(Instant::now());
+        // 新的触摸会停止惯性滚动
+        state.kinetic = None;
+        state.gesture_start_time = Some(state.frame_time);
         state.initial_centroid = Some(current_centroid);
         state.max_fingers_down = finger_count;
         state.long_press_invalidated = false;
@@ -239,51 +571,123 @@ pub fn analyze_touch_gesture(
         state.max_fingers_down = state.max_fingers_down.max(finger_count);
     }
 
+    // 捏合在手指数量不再是两个时结束
+    if finger_count != 2 && state.gesture_mode == GestureMode::Pinch {
+        state.gesture_mode = GestureMode::WaitRelease;
+        state.pending_gestures.push(TouchGesture::Pinch {
+            scale: state.last_pinch_scale,
+            center: state.last_centroid.unwrap_or(current_centroid),
+            phase: TouchPhase::Ended,
+        });
+    }
+
+    // 三指及以上：手势周期内不再产生指针事件，全部抬起后识别为点击或滑动
+    if state.gestures.multi_finger && finger_count >= 3 && state.gesture_mode != GestureMode::MultiFinger {
+        release_buttons(state, pointer_pos, is_left_pressed, events);
+        state.gesture_mode = GestureMode::MultiFinger;
+        state.initial_centroid = Some(current_centroid);
+        state.last_centroid = Some(current_centroid);
+    }
+    if state.gesture_mode == GestureMode::MultiFinger {
+        if finger_count > 0 {
+            // 手指陆续抬起时重心会跳变，只记录全部手指都在时的位置
+            if finger_count == state.max_fingers_down {
+                state.last_centroid = Some(current_centroid);
+            }
+            return;
+        }
+        if let Some(gesture) = recognize_multi_finger(state) {
+            state.pending_gestures.push(gesture);
+        }
+    }
+
     // 4. 状态机分支处理
-    // 只要检测到两指或更多，优先进入滚动模式，提高误触容忍度
+    // 只要检测到两指或更多，优先进入滚动 (或捏合) 模式，提高误触容忍度
     if finger_count >= 2 {
         // --- 双指 (及以上) 滚动模式 ---
 
         // 状态清理：如果之前处于按压状态，先释放
-        if *is_left_pressed {
-            *is_left_pressed = false;
-            events.push(WindowEvent::PointerReleased {
-                position: pointer_pos.to_logical(1.0),
-                button: PointerEventButton::Left,
-            });
-        }
-        if state.gesture_mode == GestureMode::RightDrag {
-            events.push(WindowEvent::PointerReleased {
-                position: pointer_pos.to_logical(1.0),
-                button: PointerEventButton::Right,
-            });
-        }
+        release_buttons(state, pointer_pos, is_left_pressed, events);
 
-        let just_entered = state.gesture_mode != GestureMode::Scroll;
-        state.gesture_mode = GestureMode::Scroll;
+        let just_entered = !matches!(state.gesture_mode, GestureMode::Scroll | GestureMode::Pinch);
+        if just_entered {
+            state.gesture_mode = GestureMode::Scroll;
+        }
 
         // 滚动时更新指针位置到重心，保持视觉连贯性
         *pointer_pos = current_centroid;
 
+        // 前两个手指之间的距离 (用于识别捏合)
+        let first = state.slots[state.active_slots[0]];
+        let second = state.slots[state.active_slots[1]];
+        let a = state.map_point(first.x, first.y, screen_width, screen_height, abs_x, abs_y);
+        let b = state.map_point(second.x, second.y, screen_width, screen_height, abs_x, abs_y);
+        let distance = ((a.x - b.x) as f32).hypot((a.y - b.y) as f32);
+
         if just_entered {
             state.last_centroid = Some(current_centroid);
+            state.pinch_distance = distance;
+            state.scroll_velocity = (0.0, 0.0);
+            state.last_scroll_time = None;
+        } else if state.gesture_mode == GestureMode::Pinch {
+            let scale = distance / state.pinch_distance;
+            state.last_centroid = Some(current_centroid);
+            if state.last_pinch_scale != scale {
+                state.last_pinch_scale = scale;
+                state.pending_gestures.push(TouchGesture::Pinch {
+                    scale,
+                    center: current_centroid,
+                    phase: TouchPhase::Moved,
+                });
+            }
+        } else if state.gestures.pinch_zoom
+            && state.pinch_distance > 0.0
+            && (distance / state.pinch_distance - 1.0).abs() > PINCH_THRESHOLD
+        {
+            // 距离变化足够大：从滚动切换为捏合，之后的缩放比例以此刻为基准
+            state.gesture_mode = GestureMode::Pinch;
+            state.pinch_distance = distance;
+            state.last_pinch_scale = 1.0;
+            state.pending_gestures.push(TouchGesture::Pinch {
+                scale: 1.0,
+                center: current_centroid,
+                phase: TouchPhase::Started,
+            });
         } else {
-            if let Some(last) = state.last_centroid {
+            if let Some(last) = state.last_centroid.filter(|_| state.gestures.two_finger_scroll) {
                 let dx = (current_centroid.x - last.x) as f32;
                 let dy = (current_centroid.y - last.y) as f32;
 
                 // 滚动去抖：只有移动量超过阈值才生成事件
                 if dx.abs() > 0.5 || dy.abs() > 0.5 {
+                    let (delta_x, delta_y) = (dx * SCROLL_SCALE, dy * SCROLL_SCALE);
                     events.push(WindowEvent::PointerScrolled {
                         position: pointer_pos.to_logical(1.0),
-                        delta_x: dx * SCROLL_SCALE,
-                        delta_y: dy * SCROLL_SCALE,
+                        delta_x,
+                        delta_y,
                     });
+
+                    // 平滑估计滚动速度，供抬起后的惯性滚动使用
+                    let now = state.frame_time;
+                    if let Some(dt) = state.last_scroll_time.map(|t| now.saturating_duration_since(t).as_secs_f32()) {
+                        if dt > 0.0 {
+                            let (vx, vy) = state.scroll_velocity;
+                            let weight = if dt < KINETIC_MAX_IDLE.as_secs_f32() { 0.6 } else { 1.0 };
+                            state.scroll_velocity = (
+                                vx + (delta_x / dt - vx) * weight,
+                                vy + (delta_y / dt - vy) * weight,
+                            );
+                        }
+                    }
+                    state.last_scroll_time = Some(now);
                 }
             }
             state.last_centroid = Some(current_centroid);
         }
     } else {
+        if state.gesture_mode == GestureMode::Scroll {
+            state.start_kinetic_scroll(*pointer_pos);
+        }
         match finger_count {
             0 => {
                 // --- 0 指：释放/结束 ---
@@ -322,8 +726,8 @@ pub fn analyze_touch_gesture(
                     // 保持右键拖拽状态
                     let moved = match state.last_reported_pos {
                         Some(last) => {
-                            (current_centroid.x - last.x).abs() > JITTER_THRESHOLD
-                                || (current_centroid.y - last.y).abs() > JITTER_THRESHOLD
+                            (current_centroid.x - last.x).abs() > jitter_threshold
+                                || (current_centroid.y - last.y).abs() > jitter_threshold
                         }
                         None => true,
                     };
@@ -352,8 +756,8 @@ pub fn analyze_touch_gesture(
                     // 移动去抖
                     let moved = match state.last_reported_pos {
                         Some(last) => {
-                            (current_centroid.x - last.x).abs() > JITTER_THRESHOLD
-                                || (current_centroid.y - last.y).abs() > JITTER_THRESHOLD
+                            (current_centroid.x - last.x).abs() > jitter_threshold
+                                || (current_centroid.y - last.y).abs() > jitter_threshold
                         }
                         None => true,
                     };
@@ -378,8 +782,9 @@ pub fn analyze_touch_gesture(
                     // 长按检测逻辑 (触发右键)
                     // 条件：手指未抬起 + 没有发生大幅位移 + 时间超过阈值
                     if let Some(start_time) = state.gesture_start_time {
-                        if !state.long_press_invalidated
-                            && start_time.elapsed() > LONG_PRESS_DURATION
+                        if state.gestures.long_press_right_click
+                            && !state.long_press_invalidated
+                            && state.frame_time.saturating_duration_since(start_time) > LONG_PRESS_DURATION
                         {
                             state.gesture_mode = GestureMode::RightDrag;
                             // 状态切换：释放左键 -> 按下右键
@@ -400,5 +805,304 @@ pub fn analyze_touch_gesture(
         }
     }
 
-    Some(events)
+}
+
+/// 释放单指手势按下的左键或右键
+fn release_buttons(
+    state: &TouchState,
+    pointer_pos: &PhysicalPosition,
+    is_left_pressed: &mut bool,
+    events: &mut Vec<WindowEvent>,
+) {
+    if *is_left_pressed {
+        *is_left_pressed = false;
+        events.push(WindowEvent::PointerReleased {
+            position: pointer_pos.to_logical(1.0),
+            button: PointerEventButton::Left,
+        });
+    }
+    if state.gesture_mode == GestureMode::RightDrag {
+        events.push(WindowEvent::PointerReleased {
+            position: pointer_pos.to_logical(1.0),
+            button: PointerEventButton::Right,
+        });
+    }
+}
+
+/// 多指手势结束时，根据持续时间和重心位移识别为点击或滑动
+fn recognize_multi_finger(state: &TouchState) -> Option<TouchGesture> {
+    let fingers = state.max_fingers_down;
+    let start = state.initial_centroid?;
+    let end = state.last_centroid?;
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let quick = state
+        .gesture_start_time
+        .is_some_and(|time| state.frame_time.saturating_duration_since(time) < LONG_PRESS_DURATION);
+
+    if dx.abs() <= TAP_DRIFT_THRESHOLD && dy.abs() <= TAP_DRIFT_THRESHOLD {
+        return quick.then_some(TouchGesture::Tap { fingers, position: start });
+    }
+    if dx.abs().max(dy.abs()) < SWIPE_THRESHOLD {
+        return None;
+    }
+    let direction = if dx.abs() > dy.abs() {
+        if dx > 0 { SwipeDirection::Right } else { SwipeDirection::Left }
+    } else if dy > 0 {
+        SwipeDirection::Down
+    } else {
+        SwipeDirection::Up
+    };
+    Some(TouchGesture::Swipe { fingers, direction })
+}
+
+/// 原始多点触控模式：不做手势识别，逐个报告触点的变化
+///
+/// 第一个按下的手指同时作为左键指针 (直到它抬起)，使普通控件仍然可以点击；
+/// 其余手指只通过 `pending_contacts` 中的触点变化报告给应用。
+#[allow(clippy::too_many_arguments)]
+pub fn analyze_raw_touch(
+    state: &mut TouchState,
+    pointer_pos: &mut PhysicalPosition,
+    is_left_pressed: &mut bool,
+    screen_width: u32,
+    screen_height: u32,
+    abs_x: &Option<AbsInfo>,
+    abs_y: &Option<AbsInfo>,
+    events: &mut Vec<WindowEvent>,
+) {
+    // 1. 逐个 Slot 比较，生成触点变化
+    let mut first_started = None;
+    for index in 0..state.slots.len() {
+        let slot = state.slots[index];
+        let current = state.is_pressed(&slot).then(|| {
+            (slot.id, state.map_point(slot.x, slot.y, screen_width, screen_height, abs_x, abs_y))
+        });
+        match (state.reported[index], current) {
+            (Some((old_id, old_position)), Some((id, position))) if old_id == id => {
+                if position != old_position {
+                    state.pending_contacts.push(TouchContact { id, position, phase: TouchPhase::Moved });
+                }
+            }
+            (previous, current) => {
+                if let Some((id, position)) = previous {
+                    state.pending_contacts.push(TouchContact { id, position, phase: TouchPhase::Ended });
+                }
+                if let Some((id, position)) = current {
+                    state.pending_contacts.push(TouchContact { id, position, phase: TouchPhase::Started });
+                    first_started.get_or_insert((index, id, position));
+                }
+            }
+        }
+        state.reported[index] = current;
+    }
+
+    // 2. 主触点驱动左键指针
+    match state.primary {
+        Some((index, id)) => match state.reported[index] {
+            Some((current_id, position)) if current_id == id => {
+                if position != *pointer_pos {
+                    *pointer_pos = position;
+                    events.push(WindowEvent::PointerMoved { position: pointer_pos.to_logical(1.0) });
+                }
+            }
+            _ => {
+                state.primary = None;
+                if *is_left_pressed {
+                    *is_left_pressed = false;
+                    events.push(WindowEvent::PointerReleased {
+                        position: pointer_pos.to_logical(1.0),
+                        button: PointerEventButton::Left,
+                    });
+                }
+            }
+        },
+        None => {
+            if let Some((index, id, position)) = first_started {
+                state.primary = Some((index, id));
+                *pointer_pos = position;
+                *is_left_pressed = true;
+                events.push(WindowEvent::PointerMoved { position: pointer_pos.to_logical(1.0) });
+                events.push(WindowEvent::PointerPressed {
+                    position: pointer_pos.to_logical(1.0),
+                    button: PointerEventButton::Left,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_point_region() {
+        // 侧面板触摸层旋转 90 度，映射到 1000x480 窗口右侧 200 像素宽的区域
+        let mut state = TouchState::new();
+        state.region = Some((800, 0, 200, 480));
+        state.rotation = Rotation::Rotate90;
+        let axis = Some(AbsInfo::new(0, 0, 1000, 0, 0, 0));
+        assert_eq!(state.map_point(0, 0, 1000, 480, &axis, &axis), PhysicalPosition::new(800, 479));
+        assert_eq!(state.map_point(1000, 1000, 1000, 480, &axis, &axis), PhysicalPosition::new(999, 0));
+
+        state.region = None;
+        state.rotation = Rotation::Rotate0;
+        assert_eq!(state.map_point(500, 500, 1000, 480, &axis, &axis), PhysicalPosition::new(500, 240));
+    }
+
+    #[test]
+    fn test_pressure_threshold() {
+        let mut state = TouchState::new();
+        state.pressure_threshold = Some(50);
+        let axis = Some(AbsInfo::new(0, 0, 1000, 0, 0, 0));
+        state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, 1, true);
+        state.process_axis(AbsoluteAxisCode::ABS_MT_PRESSURE, 10, true);
+        assert_eq!(state.active_points(100, 100, &axis, &axis).count(), 0);
+
+        state.process_axis(AbsoluteAxisCode::ABS_MT_PRESSURE, 80, true);
+        // 多点触控设备的 ABS_PRESSURE 被忽略
+        state.process_axis(AbsoluteAxisCode::ABS_PRESSURE, 0, true);
+        assert_eq!(state.active_points(100, 100, &axis, &axis).count(), 1);
+    }
+
+    #[test]
+    fn test_slots_sized_per_device() {
+        let mut state = TouchState::with_slots(16);
+        state.has_mt_position = true;
+        state.process_axis(AbsoluteAxisCode::ABS_MT_SLOT, 12, true);
+        state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, 5, true);
+        state.process_axis(AbsoluteAxisCode::ABS_MT_POSITION_X, 300, true);
+        // 超出范围的 Slot 被忽略，模拟单点的 ABS_X 不会激活 Slot 0
+        state.process_axis(AbsoluteAxisCode::ABS_MT_SLOT, 16, true);
+        state.process_axis(AbsoluteAxisCode::ABS_X, 3000, true);
+
+        let active: Vec<_> = state.slots.iter().enumerate().filter(|(_, slot)| slot.active).collect();
+        assert!(matches!(active[..], [(12, slot)] if slot.id == 5 && slot.x == 300));
+    }
+
+    #[test]
+    fn test_raw_touch_contacts() {
+        let mut state = TouchState::new();
+        let axis = Some(AbsInfo::new(0, 0, 100, 0, 0, 0));
+        let mut pointer = PhysicalPosition::default();
+        let mut pressed = false;
+        let mut frame = |state: &mut TouchState| {
+            let mut events = Vec::new();
+            analyze_raw_touch(state, &mut pointer, &mut pressed, 100, 100, &axis, &axis, &mut events);
+            (events, std::mem::take(&mut state.pending_contacts))
+        };
+
+        for (slot, id, x) in [(0, 7, 10), (1, 8, 90)] {
+            state.process_axis(AbsoluteAxisCode::ABS_MT_SLOT, slot, true);
+            state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, id, true);
+            state.process_axis(AbsoluteAxisCode::ABS_MT_POSITION_X, x, true);
+        }
+        let (events, contacts) = frame(&mut state);
+        assert_eq!(contacts.len(), 2);
+        assert!(contacts.iter().all(|contact| contact.phase == TouchPhase::Started));
+        assert!(matches!(events.last(), Some(WindowEvent::PointerPressed { .. })));
+
+        // 第二个手指抬起不影响指针
+        state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, -1, true);
+        let (events, contacts) = frame(&mut state);
+        assert!(events.is_empty());
+        assert_eq!(contacts, [TouchContact { id: 8, position: PhysicalPosition::new(90, 0), phase: TouchPhase::Ended }]);
+    }
+
+    #[test]
+    fn test_long_press_uses_frame_time() {
+        let mut state = TouchState::new();
+        let axis = Some(AbsInfo::new(0, 0, 100, 0, 0, 0));
+        let mut pointer = PhysicalPosition::default();
+        let mut pressed = false;
+        state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, 1, true);
+        state.process_axis(AbsoluteAxisCode::ABS_MT_POSITION_X, 50, true);
+        let start = state.frame_time;
+        analyze_touch_gesture(&mut state, &mut pointer, &mut pressed, 100, 100, &axis, &axis, &mut Vec::new());
+        assert!(pressed);
+
+        // 事件批量到达时按时间戳判断，与处理时的时间无关
+        state.frame_time = start + LONG_PRESS_DURATION / 2;
+        let mut events = Vec::new();
+        analyze_touch_gesture(&mut state, &mut pointer, &mut pressed, 100, 100, &axis, &axis, &mut events);
+        assert!(events.is_empty());
+        state.frame_time = start + LONG_PRESS_DURATION * 2;
+        let mut events = Vec::new();
+        analyze_touch_gesture(&mut state, &mut pointer, &mut pressed, 100, 100, &axis, &axis, &mut events);
+        assert!(matches!(events.last(), Some(WindowEvent::PointerPressed { button: PointerEventButton::Right, .. })));
+    }
+
+    #[test]
+    fn test_pinch_gesture() {
+        let mut state = TouchState::new();
+        state.gestures.pinch_zoom = true;
+        let axis = Some(AbsInfo::new(0, 0, 100, 0, 0, 0));
+        let mut pointer = PhysicalPosition::default();
+        let mut pressed = false;
+        let mut frame = |state: &mut TouchState, xs: &[i32]| {
+            for (slot, &x) in xs.iter().enumerate() {
+                state.process_axis(AbsoluteAxisCode::ABS_MT_SLOT, slot as i32, true);
+                state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, slot as i32, true);
+                state.process_axis(AbsoluteAxisCode::ABS_MT_POSITION_X, x, true);
+            }
+            analyze_touch_gesture(state, &mut pointer, &mut pressed, 100, 100, &axis, &axis, &mut Vec::new());
+            std::mem::take(&mut state.pending_gestures)
+        };
+
+        assert!(frame(&mut state, &[40, 60]).is_empty());
+        let gestures = frame(&mut state, &[30, 70]);
+        assert!(matches!(gestures[..], [TouchGesture::Pinch { phase: TouchPhase::Started, .. }]));
+        let gestures = frame(&mut state, &[20, 80]);
+        assert!(matches!(gestures[..], [TouchGesture::Pinch { scale, phase: TouchPhase::Moved, .. }] if scale == 1.5));
+
+        state.process_axis(AbsoluteAxisCode::ABS_MT_SLOT, 1, true);
+        state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, -1, true);
+        let gestures = frame(&mut state, &[20]);
+        assert!(matches!(gestures[..], [TouchGesture::Pinch { phase: TouchPhase::Ended, .. }]));
+    }
+
+    #[test]
+    fn test_four_finger_tap() {
+        let mut state = TouchState::new();
+        state.gestures.multi_finger = true;
+        let axis = Some(AbsInfo::new(0, 0, 100, 0, 0, 0));
+        let mut pointer = PhysicalPosition::default();
+        let mut pressed = false;
+
+        for slot in 0..4 {
+            state.process_axis(AbsoluteAxisCode::ABS_MT_SLOT, slot, true);
+            state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, slot, true);
+            state.process_axis(AbsoluteAxisCode::ABS_MT_POSITION_X, 20 + slot * 10, true);
+            analyze_touch_gesture(&mut state, &mut pointer, &mut pressed, 100, 100, &axis, &axis, &mut Vec::new());
+        }
+        // 手指陆续抬起，期间不产生手势
+        for slot in 0..4 {
+            assert!(state.pending_gestures.is_empty());
+            state.process_axis(AbsoluteAxisCode::ABS_MT_SLOT, slot, true);
+            state.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, -1, true);
+            analyze_touch_gesture(&mut state, &mut pointer, &mut pressed, 100, 100, &axis, &axis, &mut Vec::new());
+        }
+        assert!(matches!(state.pending_gestures[..], [TouchGesture::Tap { fingers: 4, .. }]));
+        assert!(!pressed);
+    }
+
+    #[test]
+    fn test_kinetic_scroll() {
+        let mut state = TouchState::new();
+        state.scroll_velocity = (0.0, 1000.0);
+        state.last_scroll_time = Some(clock::now());
+        state.start_kinetic_scroll(PhysicalPosition::new(10, 10));
+        assert!(state.is_kinetic_scrolling());
+
+        state.kinetic.as_mut().unwrap().last_tick -= Duration::from_millis(100);
+        let Some(WindowEvent::PointerScrolled { delta_x, delta_y, .. }) = state.kinetic_scroll() else { panic!() };
+        // 以 4.0 的摩擦系数衰减 100ms，滚动距离小于匀速的 100 像素
+        assert_eq!(delta_x, 0.0);
+        assert!(delta_y > 80.0 && delta_y < 100.0);
+
+        // 很久之后速度已衰减到最小值以下，结束惯性滚动
+        state.kinetic.as_mut().unwrap().last_tick -= Duration::from_secs(5);
+        assert!(state.kinetic_scroll().is_some());
+        assert!(!state.is_kinetic_scrolling());
+    }
 }
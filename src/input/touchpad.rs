@@ -0,0 +1,233 @@
+//! 触摸板处理
+//!
+//! 笔记本式触摸板同样报告 `ABS_X` / `ABS_Y`，但坐标对应的是触摸板表面而不是屏幕，
+//! 若按触摸屏处理，指针会在屏幕上绝对跳动。触摸板按相对设备处理：
+//! - 单指移动：按位移移动指针
+//! - 轻点：单指为左键，双指为右键，三指为中键
+//! - 双指移动：滚动
+//!
+//! 物理按键 (`BTN_LEFT` 等) 与鼠标相同处理。
+
+use super::touch::TouchState;
+use evdev::{AbsoluteAxisCode, Device, KeyCode, PropType};
+use i_slint_core::api::PhysicalPosition;
+use i_slint_core::platform::{PointerEventButton, WindowEvent};
+use std::time::{Duration, Instant};
+
+/// 轻点的最长持续时间
+const TAP_DURATION: Duration = Duration::from_millis(180);
+
+/// 轻点期间允许的最大移动距离 (像素)
+const TAP_TRAVEL: f32 = 10.0;
+
+/// 滚动速度缩放因子 (与触摸屏双指滚动一致)
+const SCROLL_SCALE: f32 = 2.0;
+
+/// 一次触摸周期内的轻点候选
+#[derive(Debug, Clone, Copy)]
+struct TapCandidate {
+    start: Instant,
+    /// 周期内出现过的最大手指数量
+    fingers: usize,
+    /// 累积移动距离 (像素)
+    travel: f32,
+}
+
+/// 触摸板状态
+#[derive(Debug)]
+pub struct TouchpadState {
+    /// X 轴的坐标范围 (设备单位)
+    range_x: i32,
+    /// 两个轴的物理分辨率 (单位/毫米)，未知时为 0
+    resolution: (i32, i32),
+    /// 上一帧的 (手指数量, 第一个手指的标识, 设备坐标重心)
+    last: Option<(usize, i32, (f32, f32))>,
+    /// 累积的不足一像素的指针位移
+    remainder: (f32, f32),
+    tap: Option<TapCandidate>,
+    /// 速度倍数
+    pub speed: f32,
+    /// 自然滚动：内容跟随手指移动
+    pub natural_scrolling: bool,
+}
+
+impl TouchpadState {
+    /// 设备是否为触摸板 (间接指点设备，且报告手指)
+    pub fn is_touchpad(device: &Device) -> bool {
+        let props = device.properties();
+        props.contains(PropType::POINTER)
+            && !props.contains(PropType::DIRECT)
+            && device
+                .supported_keys()
+                .is_some_and(|keys| keys.contains(KeyCode::BTN_TOOL_FINGER) && !keys.contains(KeyCode::BTN_TOOL_PEN))
+    }
+
+    /// 从设备的坐标轴信息创建
+    pub fn new(device: &Device) -> Self {
+        let mut range_x = 0;
+        let mut resolution = (0, 0);
+        if let Ok(axes) = device.get_absinfo() {
+            for (code, info) in axes {
+                match code {
+                    AbsoluteAxisCode::ABS_X => {
+                        range_x = info.maximum() - info.minimum();
+                        resolution.0 = info.resolution();
+                    }
+                    AbsoluteAxisCode::ABS_Y => resolution.1 = info.resolution(),
+                    _ => {}
+                }
+            }
+        }
+        Self::from_axes(range_x, resolution)
+    }
+
+    fn from_axes(range_x: i32, resolution: (i32, i32)) -> Self {
+        Self {
+            range_x,
+            resolution,
+            last: None,
+            remainder: (0.0, 0.0),
+            tap: None,
+            speed: 1.0,
+            natural_scrolling: false,
+        }
+    }
+
+    /// 设备单位到屏幕像素的缩放 (x, y)
+    ///
+    /// 速度倍数为 1 时触摸板宽度对应屏幕宽度，纵向按物理分辨率保持相同的比例。
+    fn scale(&self, screen_width: u32) -> (f32, f32) {
+        let scale_x = if self.range_x > 0 { screen_width as f32 / self.range_x as f32 } else { 1.0 } * self.speed;
+        let scale_y = match self.resolution {
+            (x, y) if x > 0 && y > 0 => scale_x * x as f32 / y as f32,
+            _ => scale_x,
+        };
+        (scale_x, scale_y)
+    }
+
+    /// 触摸板自身处理的按键 (手指数量与接触状态)，不应作为指针按键发送
+    pub fn consumes_key(key: KeyCode) -> bool {
+        matches!(
+            key,
+            KeyCode::BTN_TOUCH
+                | KeyCode::BTN_TOOL_FINGER
+                | KeyCode::BTN_TOOL_DOUBLETAP
+                | KeyCode::BTN_TOOL_TRIPLETAP
+                | KeyCode::BTN_TOOL_QUADTAP
+                | KeyCode::BTN_TOOL_QUINTTAP
+        )
+    }
+
+    /// 帧结束时根据触点变化生成指针事件，追加到 `events`
+    pub fn finish_frame(
+        &mut self,
+        touch: &TouchState,
+        pointer_pos: &mut PhysicalPosition,
+        screen_width: u32,
+        screen_height: u32,
+        events: &mut Vec<WindowEvent>,
+    ) {
+        let mut active = touch.slots.iter().filter(|slot| touch.is_pressed(slot));
+        let Some(first) = active.next() else {
+            self.finish_touch(pointer_pos, touch.frame_time, events);
+            return;
+        };
+        let (fingers, sum_x, sum_y) = active.fold((1, first.x as f32, first.y as f32), |(n, x, y), slot| {
+            (n + 1, x + slot.x as f32, y + slot.y as f32)
+        });
+
+        let scale = self.scale(screen_width);
+        let tap = self.tap.get_or_insert(TapCandidate { start: touch.frame_time, fingers, travel: 0.0 });
+        tap.fingers = tap.fingers.max(fingers);
+
+        let centroid = (sum_x / fingers as f32, sum_y / fingers as f32);
+        let current = (fingers, first.id, centroid);
+
+        // 手指数量或第一个手指变化时重心会跳变，从这一帧重新开始计算位移
+        if let Some((_, _, last)) = self.last.filter(|&(n, id, _)| n == fingers && id == current.1) {
+            let dx = (centroid.0 - last.0) * scale.0;
+            let dy = (centroid.1 - last.1) * scale.1;
+            tap.travel += dx.hypot(dy);
+
+            if fingers == 1 {
+                self.remainder.0 += dx;
+                self.remainder.1 += dy;
+                let (step_x, step_y) = (self.remainder.0.trunc(), self.remainder.1.trunc());
+                self.remainder.0 -= step_x;
+                self.remainder.1 -= step_y;
+                if step_x != 0.0 || step_y != 0.0 {
+                    pointer_pos.x = (pointer_pos.x + step_x as i32).clamp(0, screen_width as i32 - 1);
+                    pointer_pos.y = (pointer_pos.y + step_y as i32).clamp(0, screen_height as i32 - 1);
+                    events.push(WindowEvent::PointerMoved { position: pointer_pos.to_logical(1.0) });
+                }
+            } else if fingers == 2 && (dx.abs() > 0.5 || dy.abs() > 0.5) {
+                // 传统方向与滚轮一致：手指向下移动时内容向上滚动
+                let scroll_scale = if self.natural_scrolling { SCROLL_SCALE } else { -SCROLL_SCALE };
+                events.push(WindowEvent::PointerScrolled {
+                    position: pointer_pos.to_logical(1.0),
+                    delta_x: dx * scroll_scale,
+                    delta_y: dy * scroll_scale,
+                });
+            }
+        }
+        self.last = Some(current);
+    }
+
+    /// 所有手指抬起：短暂且几乎没有移动的触摸按手指数量点击左键、右键或中键
+    fn finish_touch(&mut self, pointer_pos: &PhysicalPosition, frame_time: Instant, events: &mut Vec<WindowEvent>) {
+        self.last = None;
+        self.remainder = (0.0, 0.0);
+        let Some(tap) = self.tap.take() else { return };
+        if frame_time.saturating_duration_since(tap.start) > TAP_DURATION || tap.travel > TAP_TRAVEL {
+            return;
+        }
+        let button = match tap.fingers {
+            1 => PointerEventButton::Left,
+            2 => PointerEventButton::Right,
+            3 => PointerEventButton::Middle,
+            _ => return,
+        };
+        let position = pointer_pos.to_logical(1.0);
+        events.push(WindowEvent::PointerPressed { position, button });
+        events.push(WindowEvent::PointerReleased { position, button });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_motion_and_tap() {
+        // 触摸板宽 1000 单位，对应 500 像素宽的屏幕
+        let mut touchpad = TouchpadState::from_axes(1000, (0, 0));
+        let mut touch = TouchState::new();
+        let mut pointer = PhysicalPosition::new(100, 100);
+        let mut frame = |touch: &TouchState, touchpad: &mut TouchpadState| {
+            let mut events = Vec::new();
+            touchpad.finish_frame(touch, &mut pointer, 500, 500, &mut events);
+            events
+        };
+
+        touch.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, 1, true);
+        touch.process_axis(AbsoluteAxisCode::ABS_MT_POSITION_X, 800, true);
+        touch.process_axis(AbsoluteAxisCode::ABS_MT_POSITION_Y, 800, true);
+        // 手指落下的位置不影响指针
+        assert!(frame(&touch, &mut touchpad).is_empty());
+
+        touch.process_axis(AbsoluteAxisCode::ABS_MT_POSITION_X, 810, true);
+        let events = frame(&touch, &mut touchpad);
+        assert!(matches!(events[..], [WindowEvent::PointerMoved { position }] if position.x == 105.0 && position.y == 100.0));
+
+        // 轻微移动后抬起，视为单指轻点
+        touch.process_axis(AbsoluteAxisCode::ABS_MT_TRACKING_ID, -1, true);
+        let events = frame(&touch, &mut touchpad);
+        assert!(matches!(
+            events[..],
+            [
+                WindowEvent::PointerPressed { button: PointerEventButton::Left, .. },
+                WindowEvent::PointerReleased { button: PointerEventButton::Left, .. }
+            ]
+        ));
+    }
+}
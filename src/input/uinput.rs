@@ -0,0 +1,157 @@
+//! 通过 uinput 注入合成输入事件
+//!
+//! 创建的虚拟设备由内核注册为普通的 `/dev/input/event*` 节点，
+//! 因此注入的事件与真实硬件经过完全相同的处理流程 (设备识别、校准、手势识别等)。
+//! 适用于自动化测试和远程控制工具，需要 `/dev/uinput` 的写权限。
+
+use evdev::uinput::VirtualDevice;
+use evdev::{AbsInfo, AbsoluteAxisCode, AttributeSet, EventType, InputEvent, KeyCode, PropType, UinputAbsSetup};
+use std::io;
+use std::path::PathBuf;
+
+/// 虚拟触摸屏支持的最大触点数量
+const MAX_SLOTS: i32 = 10;
+
+/// 虚拟键盘的设备名称
+pub const VIRTUAL_KEYBOARD_NAME: &str = "slint-backend-linuxfb virtual keyboard";
+/// 虚拟触摸屏的设备名称
+pub const VIRTUAL_TOUCHSCREEN_NAME: &str = "slint-backend-linuxfb virtual touchscreen";
+
+/// 合成输入设备：一个多点触控触摸屏 (Protocol B) 和一个键盘
+///
+/// 触摸屏的坐标范围与创建时给定的屏幕尺寸一致，未设置旋转或校准时坐标即为屏幕像素。
+/// 设备由输入自动发现找到，通过轮询扫描时最长需要等待一个扫描周期。
+pub struct VirtualInput {
+    touchscreen: VirtualDevice,
+    keyboard: VirtualDevice,
+    /// 下一个触点的跟踪 ID
+    next_tracking_id: i32,
+    /// 按下的触点 (按位)
+    active_slots: u16,
+}
+
+impl VirtualInput {
+    /// 创建坐标范围为 `width` x `height` 的虚拟触摸屏和虚拟键盘
+    pub fn new(width: u32, height: u32) -> io::Result<Self> {
+        let axis = |code, max: u32| UinputAbsSetup::new(code, AbsInfo::new(0, 0, max.saturating_sub(1) as i32, 0, 0, 0));
+        let touchscreen = VirtualDevice::builder()?
+            .name(VIRTUAL_TOUCHSCREEN_NAME)
+            .with_properties(&[PropType::DIRECT].into_iter().collect::<AttributeSet<_>>())?
+            .with_keys(&[KeyCode::BTN_TOUCH].into_iter().collect::<AttributeSet<_>>())?
+            .with_absolute_axis(&axis(AbsoluteAxisCode::ABS_MT_POSITION_X, width))?
+            .with_absolute_axis(&axis(AbsoluteAxisCode::ABS_MT_POSITION_Y, height))?
+            .with_absolute_axis(&UinputAbsSetup::new(
+                AbsoluteAxisCode::ABS_MT_SLOT,
+                AbsInfo::new(0, 0, MAX_SLOTS - 1, 0, 0, 0),
+            ))?
+            .with_absolute_axis(&UinputAbsSetup::new(
+                AbsoluteAxisCode::ABS_MT_TRACKING_ID,
+                AbsInfo::new(0, -1, i32::from(u16::MAX), 0, 0, 0),
+            ))?
+            .build()?;
+
+        // KEY_ESC 到 KEY_MICMUTE：完整的键盘按键范围
+        let keys: AttributeSet<KeyCode> = (KeyCode::KEY_ESC.code()..=KeyCode::KEY_MICMUTE.code()).map(KeyCode::new).collect();
+        let keyboard = VirtualDevice::builder()?.name(VIRTUAL_KEYBOARD_NAME).with_keys(&keys)?.build()?;
+
+        Ok(Self { touchscreen, keyboard, next_tracking_id: 0, active_slots: 0 })
+    }
+
+    /// 虚拟设备的设备节点路径 (例如用于白名单或手动打开)
+    pub fn device_paths(&mut self) -> io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for device in [&mut self.touchscreen, &mut self.keyboard] {
+            for path in device.enumerate_dev_nodes_blocking()? {
+                paths.push(path?);
+            }
+        }
+        Ok(paths)
+    }
+
+    /// 在触点 `slot` (0 到 9) 处按下手指
+    pub fn touch_down(&mut self, slot: u8, x: i32, y: i32) -> io::Result<()> {
+        check_slot(slot)?;
+        let tracking_id = self.next_tracking_id;
+        self.next_tracking_id = (self.next_tracking_id + 1) % i32::from(u16::MAX);
+        let mut events = touch_events(
+            slot,
+            &[
+                (AbsoluteAxisCode::ABS_MT_TRACKING_ID, tracking_id),
+                (AbsoluteAxisCode::ABS_MT_POSITION_X, x),
+                (AbsoluteAxisCode::ABS_MT_POSITION_Y, y),
+            ],
+        );
+        if self.active_slots == 0 {
+            events.push(InputEvent::new(EventType::KEY.0, KeyCode::BTN_TOUCH.code(), 1));
+        }
+        self.active_slots |= 1 << slot;
+        self.touchscreen.emit(&events)
+    }
+
+    /// 移动触点 `slot` 上的手指
+    pub fn touch_move(&mut self, slot: u8, x: i32, y: i32) -> io::Result<()> {
+        check_slot(slot)?;
+        let events = touch_events(
+            slot,
+            &[(AbsoluteAxisCode::ABS_MT_POSITION_X, x), (AbsoluteAxisCode::ABS_MT_POSITION_Y, y)],
+        );
+        self.touchscreen.emit(&events)
+    }
+
+    /// 抬起触点 `slot` 上的手指
+    pub fn touch_up(&mut self, slot: u8) -> io::Result<()> {
+        check_slot(slot)?;
+        let mut events = touch_events(slot, &[(AbsoluteAxisCode::ABS_MT_TRACKING_ID, -1)]);
+        self.active_slots &= !(1 << slot);
+        if self.active_slots == 0 {
+            events.push(InputEvent::new(EventType::KEY.0, KeyCode::BTN_TOUCH.code(), 0));
+        }
+        self.touchscreen.emit(&events)
+    }
+
+    /// 在 (`x`, `y`) 处单指轻点
+    pub fn tap(&mut self, x: i32, y: i32) -> io::Result<()> {
+        self.touch_down(0, x, y)?;
+        self.touch_up(0)
+    }
+
+    /// 按下 (`pressed` 为 `true`) 或释放按键
+    pub fn key(&mut self, key: KeyCode, pressed: bool) -> io::Result<()> {
+        self.keyboard.emit(&[InputEvent::new(EventType::KEY.0, key.code(), pressed as i32)])
+    }
+
+    /// 按下并释放按键
+    pub fn press_key(&mut self, key: KeyCode) -> io::Result<()> {
+        self.key(key, true)?;
+        self.key(key, false)
+    }
+}
+
+fn check_slot(slot: u8) -> io::Result<()> {
+    if i32::from(slot) < MAX_SLOTS {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidInput, format!("触点编号超出范围: {}", slot)))
+    }
+}
+
+/// 选择触点 `slot` 并设置其坐标轴
+fn touch_events(slot: u8, axes: &[(AbsoluteAxisCode, i32)]) -> Vec<InputEvent> {
+    std::iter::once((AbsoluteAxisCode::ABS_MT_SLOT, i32::from(slot)))
+        .chain(axes.iter().copied())
+        .map(|(code, value)| InputEvent::new(EventType::ABSOLUTE.0, code.0, value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_touch_events() {
+        let events = touch_events(2, &[(AbsoluteAxisCode::ABS_MT_POSITION_X, 100)]);
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].code(), events[0].value()), (AbsoluteAxisCode::ABS_MT_SLOT.0, 2));
+        assert_eq!((events[1].code(), events[1].value()), (AbsoluteAxisCode::ABS_MT_POSITION_X.0, 100));
+    }
+}
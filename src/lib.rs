@@ -1,15 +1,88 @@
 //! Slint 平台的 Linux Framebuffer (linuxfb) 后端
 //!
-//! 
+//! [`linuxfb`] 模块是独立的 fbdev 封装，不依赖 Slint：关闭默认的 `slint` 特性
+//! (`default-features = false`) 后只编译该模块，依赖仅有 libc、memmap2、rustix 和 thiserror。
+
+#[cfg(feature = "slint")]
+mod backlight;
+#[cfg(feature = "slint")]
+pub mod blitter;
+#[cfg(feature = "slint")]
+mod canvas;
+#[cfg(feature = "slint")]
+mod clipboard;
+#[cfg(feature = "slint")]
+mod clock;
+#[cfg(feature = "slint")]
+mod hotplug;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "slint")]
+pub mod device;
+#[cfg(feature = "slint")]
+pub mod diagnostics;
+#[cfg(feature = "slint")]
 pub mod error;
+#[cfg(feature = "slint")]
 pub mod input;
+#[cfg(feature = "slint")]
+mod mirror;
+#[cfg(feature = "mjpeg")]
+mod mjpeg;
+#[cfg(feature = "slint")]
+pub mod osd;
+#[cfg(feature = "slint")]
+mod overlay;
+#[cfg(feature = "slint")]
+mod pacing;
+#[cfg(feature = "slint")]
 pub mod pixels;
+#[cfg(feature = "slint")]
 pub mod platform;
+#[cfg(feature = "slint")]
+pub mod power;
+#[cfg(feature = "slint")]
+mod presenter;
+#[cfg(feature = "preview")]
+mod preview;
+#[cfg(feature = "slint")]
+mod recorder;
+#[cfg(feature = "slint")]
+mod signals;
+#[cfg(feature = "slint")]
+pub mod stats;
+#[cfg(feature = "slint")]
+mod touch_calibration;
+#[cfg(feature = "slint")]
+mod virtual_keyboard;
+#[cfg(feature = "vnc")]
+mod vnc;
+#[cfg(feature = "slint")]
+mod watchdog;
+#[cfg(feature = "slint")]
 pub mod window;
 pub mod linuxfb;
 
-pub use error::Error;
-pub use platform::{LinuxFbPlatform, LinuxFbPlatformBuilder};
+#[cfg(feature = "slint")]
+pub use diagnostics::{diagnose, DiagnosticsReport};
+#[cfg(feature = "slint")]
+pub use error::{BackendEvent, BackendIssue, Error};
+#[cfg(feature = "slint")]
+pub use recorder::RecordingFormat;
+#[cfg(feature = "slint")]
+pub use signals::{Signal, SignalPolicy};
+#[cfg(feature = "slint")]
+pub use input::SystemKey;
+#[cfg(feature = "slint")]
+pub use platform::{restore_tty, LinuxFbPlatform, LinuxFbPlatformBuilder, SystemKeyAction};
+#[cfg(feature = "slint")]
+pub use stats::FrameStats;
+#[cfg(feature = "slint")]
+pub use window::Rotation;
+
+/// 重新导出 `tracing-tracy`，应用可将 `TracyLayer` 注册到自己的 subscriber 中
+#[cfg(feature = "tracy")]
+pub use tracing_tracy;
 
 /// 初始化 Slint 的 Linux Framebuffer 后端 (使用默认配置)。
 ///
@@ -19,8 +92,26 @@ pub use platform::{LinuxFbPlatform, LinuxFbPlatformBuilder};
 /// # 返回
 /// 成功时返回 `Ok(())`，如果 framebuffer 无法打开或
 /// 像素格式不受支持，则返回 `Err(Error)`。
+#[cfg(feature = "slint")]
 pub fn init() -> Result<(), Error> {
     let platform = LinuxFbPlatform::new()?;
     i_slint_core::platform::set_platform(Box::new(platform))?;
     Ok(())
+}
+
+/// 在当前线程初始化后端，用于嵌入到更大的 (例如 C 编写的) 宿主应用中
+///
+/// 调用线程不必是主线程，但它从此成为界面线程：Slint 的平台和窗口都是线程局部的，
+/// 创建组件、`run_event_loop` 等所有 Slint 调用都必须在该线程中进行，其他线程通过
+/// `slint::invoke_from_event_loop` 和 `slint::quit_event_loop` 与界面交互。
+/// 每个进程只能初始化一次，已在其他线程初始化过时返回错误。
+///
+/// 与 [`init`] 相比不安装信号处理函数 ([`SignalPolicy::None`])：信号属于整个进程，应由宿主处理。
+/// 宿主退出前应让事件循环返回并销毁平台，TTY 在此时恢复为文本模式。
+/// 平台自身的资源 (eventfd、TTY、后台线程) 都属于平台实例，不依赖调用线程。
+#[cfg(feature = "slint")]
+pub fn init_from_thread(builder: LinuxFbPlatformBuilder) -> Result<(), Error> {
+    let platform = builder.with_signal_policy(SignalPolicy::None).build()?;
+    i_slint_core::platform::set_platform(Box::new(platform))?;
+    Ok(())
 }
\ No newline at end of file
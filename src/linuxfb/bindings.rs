@@ -0,0 +1,112 @@
+//! Hand-written definitions from `<linux/fb.h>` and `<linux/kd.h>`.
+//!
+//! These are used unless the `generate-bindings` feature is enabled, so that
+//! building does not require libclang for the target. The structures only use
+//! fixed-size fields plus `unsigned long`, and the ioctl numbers are plain
+//! numbers, so the same definitions are valid on every Linux architecture.
+//! Names follow what bindgen generates from `bindings.h`.
+
+pub const FBIOGET_VSCREENINFO: u32 = 0x4600;
+pub const FBIOPUT_VSCREENINFO: u32 = 0x4601;
+pub const FBIOGET_FSCREENINFO: u32 = 0x4602;
+pub const FBIOPAN_DISPLAY: u32 = 0x4606;
+pub const FBIOBLANK: u32 = 0x4611;
+
+pub const FB_ACTIVATE_NOW: u32 = 0;
+pub const FB_ACTIVATE_FORCE: u32 = 128;
+pub const FB_VMODE_YWRAP: u32 = 256;
+
+pub const FB_BLANK_UNBLANK: u32 = 0;
+pub const FB_BLANK_NORMAL: u32 = 1;
+pub const FB_BLANK_VSYNC_SUSPEND: u32 = 2;
+pub const FB_BLANK_HSYNC_SUSPEND: u32 = 3;
+pub const FB_BLANK_POWERDOWN: u32 = 4;
+
+pub const KDSETMODE: u32 = 0x4B3A;
+pub const KD_TEXT: u32 = 0;
+pub const KD_GRAPHICS: u32 = 1;
+pub const KDGKBMODE: u32 = 0x4B44;
+pub const KDSKBMODE: u32 = 0x4B45;
+pub const K_RAW: u32 = 0;
+pub const K_XLATE: u32 = 1;
+pub const K_MEDIUMRAW: u32 = 2;
+pub const K_UNICODE: u32 = 3;
+pub const K_OFF: u32 = 4;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct fb_fix_screeninfo {
+    pub id: [::core::ffi::c_char; 16],
+    pub smem_start: ::core::ffi::c_ulong,
+    pub smem_len: u32,
+    pub type_: u32,
+    pub type_aux: u32,
+    pub visual: u32,
+    pub xpanstep: u16,
+    pub ypanstep: u16,
+    pub ywrapstep: u16,
+    pub line_length: u32,
+    pub mmio_start: ::core::ffi::c_ulong,
+    pub mmio_len: u32,
+    pub accel: u32,
+    pub capabilities: u16,
+    pub reserved: [u16; 2],
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct fb_bitfield {
+    pub offset: u32,
+    pub length: u32,
+    pub msb_right: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct fb_var_screeninfo {
+    pub xres: u32,
+    pub yres: u32,
+    pub xres_virtual: u32,
+    pub yres_virtual: u32,
+    pub xoffset: u32,
+    pub yoffset: u32,
+    pub bits_per_pixel: u32,
+    pub grayscale: u32,
+    pub red: fb_bitfield,
+    pub green: fb_bitfield,
+    pub blue: fb_bitfield,
+    pub transp: fb_bitfield,
+    pub nonstd: u32,
+    pub activate: u32,
+    pub height: u32,
+    pub width: u32,
+    pub accel_flags: u32,
+    pub pixclock: u32,
+    pub left_margin: u32,
+    pub right_margin: u32,
+    pub upper_margin: u32,
+    pub lower_margin: u32,
+    pub hsync_len: u32,
+    pub vsync_len: u32,
+    pub sync: u32,
+    pub vmode: u32,
+    pub rotate: u32,
+    pub colorspace: u32,
+    pub reserved: [u32; 4],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::{offset_of, size_of};
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(size_of::<fb_var_screeninfo>(), 160);
+        // smem_start and mmio_start are `unsigned long`, 4 or 8 bytes
+        let long = size_of::<::core::ffi::c_ulong>();
+        assert_eq!(offset_of!(fb_fix_screeninfo, smem_len), 16 + long);
+        assert_eq!(offset_of!(fb_fix_screeninfo, mmio_len), if long == 8 { 64 } else { 52 });
+        assert_eq!(size_of::<fb_fix_screeninfo>(), if long == 8 { 80 } else { 68 });
+    }
+}
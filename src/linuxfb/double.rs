@@ -2,7 +2,7 @@
 //!
 //! See [`Buffer`] for an example.
 
-use super::{Framebuffer, Error, BlankingLevel};
+use super::{fbio::FixScreeninfo, BlankingLevel, ErrnoError, Error, Framebuffer};
 use memmap2::MmapMut;
 
 #[derive(Debug)]
@@ -24,6 +24,7 @@ impl State {
 /// Double-buffered interface to a framebuffer
 ///
 /// ```no_run
+/// # use slint_backend_linuxfb::linuxfb;
 /// let mut fb = linuxfb::Framebuffer::new("/dev/fb0").unwrap();
 /// // Do any custom setup on the framebuffer here, such as
 /// // setting bytes_per_pixel.
@@ -82,6 +83,8 @@ pub struct Buffer {
     fb: Framebuffer,
     map: MmapMut,
     state: State,
+    /// Flip with `FB_VMODE_YWRAP` instead of panning
+    ywrap: bool,
 }
 
 impl Buffer {
@@ -98,11 +101,28 @@ impl Buffer {
     /// it is left like that and the initial backbuffer is at `(0, 0)`.
     /// This behavior prevents the display from showing an old, retained image
     /// between the call to `new` and the first call to [`flip`].
+    ///
+    /// When the driver supports `FB_VMODE_YWRAP` (see [`ywrap_supported`]),
+    /// flips wrap the display instead of panning it, which is cheaper on such drivers.
+    ///
+    /// Fails with `EINVAL` if the driver cannot pan between the two buffers
+    /// (see [`check_panning`]), or if it does not accept the doubled virtual height.
     pub fn new(mut fb: Framebuffer) -> Result<Self, Error> {
         let (width, height) = fb.get_size();
+        check_panning(&fb.finfo, height)?;
+        let ywrap = ywrap_supported(&fb.finfo, height);
         let (virtual_width, virtual_height) = fb.get_virtual_size();
         if virtual_width != width || virtual_height != (height * 2) {
             fb.set_virtual_size(width, height * 2)?;
+            // Some drivers silently clamp the virtual size instead of rejecting it
+            let (_, virtual_height) = fb.get_virtual_size();
+            if virtual_height < height * 2 {
+                return Err(invalid(format!(
+                    "driver clamped the virtual height to {} (requested {})",
+                    virtual_height,
+                    height * 2
+                )));
+            }
         }
         let (offset_x, mut offset_y) = fb.get_offset();
         if offset_x != 0 || (offset_y != 0 && offset_y != height) {
@@ -115,7 +135,7 @@ impl Buffer {
         } else {
             State::DrawToSecond
         };
-        Ok(Self { width, height, fb, map, state })
+        Ok(Self { width, height, fb, map, state, ywrap })
     }
 
     /// Returns a mutable slice to the current backbuffer.
@@ -135,12 +155,45 @@ impl Buffer {
         &mut self.map[start..end]
     }
 
+    /// Physical address of the current backbuffer, for hardware blitters.
+    ///
+    /// Returns `None` when the driver hides the address (`smem_start` is zero),
+    /// which DRM fbdev emulation does by default.
+    pub fn back_buffer_phys_addr(&self) -> Option<u64> {
+        // `unsigned long` is 32 bits on 32-bit targets
+        #[allow(clippy::unnecessary_cast)]
+        let start = self.fb.finfo.internal.smem_start as u64;
+        if start == 0 {
+            return None;
+        }
+        let page_size = (self.fb.get_bytes_per_pixel() * self.height * self.width) as u64;
+        Some(match self.state {
+            State::DrawToFirst => start,
+            State::DrawToSecond => start + page_size,
+        })
+    }
+
     /// Flips the display, by exchanging 
+    ///
+    /// If a wrap-mode flip is rejected with `EINVAL`, the buffer falls back to
+    /// panning for this and all following flips.
     pub fn flip(&mut self) -> Result<(), Error> {
-        match self.state.flip() {
-            State::DrawToFirst => self.fb.set_offset(0, self.height),
-            State::DrawToSecond => self.fb.set_offset(0, 0),
+        let y = match self.state.flip() {
+            State::DrawToFirst => self.height,
+            State::DrawToSecond => 0,
+        };
+        if self.ywrap {
+            match self.fb.set_offset_ywrap(y) {
+                Err(Error::Fb(error)) if error.errno == libc::EINVAL => self.ywrap = false,
+                result => return result,
+            }
         }
+        self.fb.set_offset(0, y)
+    }
+
+    /// Whether flips currently use `FB_VMODE_YWRAP` instead of panning
+    pub fn is_ywrap(&self) -> bool {
+        self.ywrap
     }
 
     /// Calls [`blank`](Framebuffer::blank) on the underlying Framebuffer
@@ -148,8 +201,96 @@ impl Buffer {
         self.fb.blank(level)
     }
 
+    /// Calls [`get_refresh_rate`](Framebuffer::get_refresh_rate) on the underlying Framebuffer
+    pub fn refresh_rate(&self) -> Option<f32> {
+        self.fb.get_refresh_rate()
+    }
+
     /// Calls [`wait_for_vsync`](Framebuffer::blank) on the underlying Framebuffer
     pub fn wait_for_vsync(&self) -> Result<(), Error> {
         self.fb.wait_for_vsync()
     }
+
+    /// Calls [`vblank_count`](Framebuffer::vblank_count) on the underlying Framebuffer
+    pub fn vblank_count(&self) -> Result<Option<u32>, Error> {
+        self.fb.vblank_count()
+    }
+}
+
+/// Checks up front whether the driver can pan between two buffers of `height` lines.
+///
+/// Uses the fixed screen info only, so nothing on the device is modified:
+/// the driver must support vertical panning (`ypanstep` not zero) with `height`
+/// a multiple of `ypanstep`, or wrapping (see [`ywrap_supported`]),
+/// and the framebuffer memory must hold two frames.
+/// Failures are reported as `EINVAL` with a descriptive message, instead of the
+/// opaque `EINVAL` that [`Framebuffer::set_offset`] would return on the first flip.
+pub fn check_panning(finfo: &FixScreeninfo, height: u32) -> Result<(), Error> {
+    let (_, ypanstep) = finfo.pan_steps();
+    if !ywrap_supported(finfo, height) {
+        if ypanstep == 0 {
+            return Err(invalid("driver supports neither vertical panning nor ywrap (ypanstep = 0)".into()));
+        }
+        if !height.is_multiple_of(ypanstep as u32) {
+            return Err(invalid(format!("height {} is not a multiple of ypanstep {}", height, ypanstep)));
+        }
+    }
+    let required = finfo.line_length() as u64 * height as u64 * 2;
+    if (finfo.memory_size() as u64) < required {
+        return Err(invalid(format!(
+            "framebuffer memory holds {} bytes, two frames need {}",
+            finfo.memory_size(),
+            required
+        )));
+    }
+    Ok(())
+}
+
+/// Whether the driver can flip between two buffers of `height` lines with `FB_VMODE_YWRAP`
+pub fn ywrap_supported(finfo: &FixScreeninfo, height: u32) -> bool {
+    let ywrapstep = finfo.ywrap_step();
+    ywrapstep != 0 && height.is_multiple_of(ywrapstep as u32)
+}
+
+fn invalid(message: String) -> Error {
+    Error::Fb(ErrnoError { errno: libc::EINVAL, message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_panning() {
+        let mut finfo = FixScreeninfo::default();
+        finfo.internal.line_length = 800 * 4;
+        finfo.internal.smem_len = 800 * 4 * 480 * 2;
+        let errno = |result: Result<(), Error>| match result {
+            Err(Error::Fb(error)) => Some(error.errno),
+            _ => None,
+        };
+
+        assert_eq!(errno(check_panning(&finfo, 480)), Some(libc::EINVAL));
+        finfo.internal.ypanstep = 1;
+        assert!(check_panning(&finfo, 480).is_ok());
+        assert_eq!(errno(check_panning(&finfo, 481)), Some(libc::EINVAL));
+        finfo.internal.ypanstep = 16;
+        assert!(check_panning(&finfo, 480).is_ok());
+        assert_eq!(errno(check_panning(&finfo, 472)), Some(libc::EINVAL));
+    }
+
+    #[test]
+    fn test_ywrap_supported() {
+        let mut finfo = FixScreeninfo::default();
+        finfo.internal.line_length = 800 * 4;
+        finfo.internal.smem_len = 800 * 4 * 480 * 2;
+        assert!(!ywrap_supported(&finfo, 480));
+
+        finfo.internal.ywrapstep = 1;
+        assert!(ywrap_supported(&finfo, 480));
+        // Wrapping alone is enough to flip, even without panning
+        assert!(check_panning(&finfo, 480).is_ok());
+        finfo.internal.ywrapstep = 64;
+        assert!(!ywrap_supported(&finfo, 480));
+    }
 }
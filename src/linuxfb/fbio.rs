@@ -1,19 +1,54 @@
 //! This module wraps the framebuffer API's `ioctl` calls.
-//! It uses a generated binding, based on the `<linux/fb.h>` header.
+//! The definitions from the `<linux/fb.h>` header are hand-written by default,
+//! and generated with bindgen when the `generate-bindings` feature is enabled.
+//!
+//! The calls go through rustix's typed `ioctl` patterns: each request is
+//! tied to the argument type the kernel expects, and the result is returned
+//! by value instead of through a raw pointer.
 
 #![allow(non_camel_case_types)]
+#[cfg(feature = "generate-bindings")]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[cfg(not(feature = "generate-bindings"))]
+pub use super::bindings::*;
 
-// 手动定义 FBIO_WAITFORVSYNC
-// _IOW('F', 0x20, __u32)
-// 在大多数架构 (x86, ARM, AArch64) 上:
-// Dir(2bit=01) | Size(14bit=4) | Type(8bit='F'=0x46) | Nr(8bit=0x20)
-// = 0x40044620
-const FBIO_WAITFORVSYNC: u32 = 0x40044620;
-
-use std::default::Default;
+use rustix::ioctl::{ioctl, opcode, Getter, IntegerSetter, Opcode, Setter, Updater};
+use std::os::fd::BorrowedFd;
 use std::os::unix::io::AsRawFd;
 
+// The classic fbdev requests predate the direction/size encoding and are plain
+// numbers on every architecture, so the bindgen values are used as they are.
+const GET_VSCREENINFO: Opcode = FBIOGET_VSCREENINFO as Opcode;
+const PUT_VSCREENINFO: Opcode = FBIOPUT_VSCREENINFO as Opcode;
+const GET_FSCREENINFO: Opcode = FBIOGET_FSCREENINFO as Opcode;
+const PAN_DISPLAY: Opcode = FBIOPAN_DISPLAY as Opcode;
+const BLANK: Opcode = FBIOBLANK as Opcode;
+const SETMODE: Opcode = KDSETMODE as Opcode;
+const GETKBMODE: Opcode = KDGKBMODE as Opcode;
+const SETKBMODE: Opcode = KDSKBMODE as Opcode;
+
+/// `_IOW('F', 0x20, __u32)`; bindgen cannot evaluate the `_IOW` macro, and the
+/// encoding differs between architectures (e.g. MIPS and PowerPC).
+const WAITFORVSYNC: Opcode = opcode::write::<u32>(b'F', 0x20);
+
+/// `_IOR('F', 0x12, struct fb_vblank)`; `struct fb_vblank` is not part of the
+/// generated bindings, so it is declared as [`FbVblank`] below.
+const GET_VBLANK: Opcode = opcode::read::<FbVblank>(b'F', 0x12);
+
+/// `FB_VBLANK_HAVE_COUNT`: the driver maintains the `count` field of `struct fb_vblank`
+const FB_VBLANK_HAVE_COUNT: u32 = 0x010;
+
+/// `struct fb_vblank` from `<linux/fb.h>`
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+struct FbVblank {
+    flags: u32,
+    count: u32,
+    vcount: u32,
+    hcount: u32,
+    reserved: [u32; 4],
+}
+
 /// Represents an error read from the libc global `errno`
 ///
 /// These errors are returned, when `ioctl` or other wrapped
@@ -26,6 +61,7 @@ pub struct ErrnoError {
     ///
     /// e.g.:
     /// ```no_run
+    /// # use slint_backend_linuxfb::linuxfb;
     /// # let error = linuxfb::ErrnoError { errno: libc::EBUSY, message: String::from("") };
     /// error.errno == libc::EBUSY; // true
     /// println!("{}", error.message); // prints "Resource busy" (on my system)
@@ -36,11 +72,11 @@ pub struct ErrnoError {
     pub message: String,
 }
 
-impl ErrnoError {
-    fn new() -> Self {
-        let errno = unsafe { *libc::__errno_location() };
+impl From<rustix::io::Errno> for ErrnoError {
+    fn from(errno: rustix::io::Errno) -> Self {
+        let errno = errno.raw_os_error();
         let message_c = unsafe { std::ffi::CStr::from_ptr(libc::strerror(errno)) };
-        let message = String::from(message_c.to_str().unwrap());
+        let message = message_c.to_string_lossy().into_owned();
         Self { errno, message }
     }
 }
@@ -122,9 +158,45 @@ impl VarScreeninfo {
         self.internal.yoffset = y;
     }
 
+    /// Sets or clears `FB_VMODE_YWRAP`, which makes the vertical offset
+    /// wrap around the virtual screen instead of panning
+    pub fn set_ywrap(&mut self, ywrap: bool) {
+        if ywrap {
+            self.internal.vmode |= FB_VMODE_YWRAP;
+        } else {
+            self.internal.vmode &= !FB_VMODE_YWRAP;
+        }
+    }
+
+    /// Computes the refresh rate in Hz from the display timings reported by the driver.
+    ///
+    /// Returns `None` when the driver does not report timings (`pixclock == 0`),
+    /// or when the computed value is implausible.
+    pub fn refresh_rate(&self) -> Option<f32> {
+        let v = &self.internal;
+        if v.pixclock == 0 {
+            return None;
+        }
+        let htotal = (v.xres + v.left_margin + v.right_margin + v.hsync_len) as f64;
+        let vtotal = (v.yres + v.upper_margin + v.lower_margin + v.vsync_len) as f64;
+        // pixclock is given in picoseconds
+        let hz = 1e12 / (v.pixclock as f64 * htotal * vtotal);
+        if (10.0..=240.0).contains(&hz) {
+            Some(hz as f32)
+        } else {
+            None
+        }
+    }
+
     pub fn activate_now(&mut self) {
         self.internal.activate = FB_ACTIVATE_NOW;
     }
+
+    /// Like [`activate_now`](Self::activate_now), but makes the driver apply
+    /// the mode even if it is unchanged
+    pub fn activate_force(&mut self) {
+        self.internal.activate = FB_ACTIVATE_NOW | FB_ACTIVATE_FORCE;
+    }
 }
 
 #[derive(Default, Clone)]
@@ -137,15 +209,40 @@ impl FixScreeninfo {
         let c_string = unsafe { std::ffi::CStr::from_ptr(self.internal.id.as_ptr()) };
         String::from(c_string.to_str().unwrap())
     }
+
+    /// Length of a line in bytes, including any padding
+    pub fn line_length(&self) -> u32 {
+        self.internal.line_length
+    }
+
+    /// Size of the framebuffer memory in bytes
+    pub fn memory_size(&self) -> u32 {
+        self.internal.smem_len
+    }
+
+    /// Horizontal and vertical panning step in pixels, zero if the driver
+    /// does not support panning in that direction
+    pub fn pan_steps(&self) -> (u16, u16) {
+        (self.internal.xpanstep, self.internal.ypanstep)
+    }
+
+    /// Vertical wrap step in lines, zero if the driver does not support `FB_VMODE_YWRAP`
+    pub fn ywrap_step(&self) -> u16 {
+        self.internal.ywrapstep
+    }
+}
+
+/// Borrows the descriptor of `file` for one `ioctl` call.
+fn borrow_fd(file: &impl AsRawFd) -> BorrowedFd<'_> {
+    // SAFETY: `file` owns the descriptor and keeps it open while it is borrowed.
+    unsafe { BorrowedFd::borrow_raw(file.as_raw_fd()) }
 }
 
 /// Wrapper around `ioctl(fd, FBIOGET_VSCREENINFO, ...)`.
 pub fn get_vscreeninfo(file: &impl AsRawFd) -> Result<VarScreeninfo, ErrnoError> {
-    let mut vinfo: fb_var_screeninfo = Default::default();
-    match unsafe { libc::ioctl(file.as_raw_fd(), FBIOGET_VSCREENINFO as _, &mut vinfo) } {
-        -1 => Err(ErrnoError::new()),
-        _ => Ok(VarScreeninfo { internal: vinfo }),
-    }
+    // SAFETY: FBIOGET_VSCREENINFO writes a `struct fb_var_screeninfo`.
+    let internal = unsafe { ioctl(borrow_fd(file), Getter::<GET_VSCREENINFO, fb_var_screeninfo>::new()) }?;
+    Ok(VarScreeninfo { internal })
 }
 
 /// Wrapper around `ioctl(fd, FBIOPUT_VSCREENINFO, ...)`.
@@ -153,33 +250,47 @@ pub fn put_vscreeninfo(
     file: &impl AsRawFd,
     var_screeninfo: &mut VarScreeninfo,
 ) -> Result<(), ErrnoError> {
+    // The driver writes the adjusted settings back; callers re-read them with `get_vscreeninfo`.
     let mut vinfo = var_screeninfo.internal;
-    match unsafe { libc::ioctl(file.as_raw_fd(), FBIOPUT_VSCREENINFO as _, &mut vinfo) } {
-        -1 => Err(ErrnoError::new()),
-        _ => Ok(()),
-    }
+    // SAFETY: FBIOPUT_VSCREENINFO reads and updates a `struct fb_var_screeninfo`.
+    unsafe { ioctl(borrow_fd(file), Updater::<PUT_VSCREENINFO, _>::new(&mut vinfo)) }?;
+    Ok(())
 }
 
 /// Wrapper around `ioctl(fd, FBIOGET_FSCREENINFO, ...)`.
 pub fn get_fscreeninfo(file: &impl AsRawFd) -> Result<FixScreeninfo, ErrnoError> {
-    let mut finfo: fb_fix_screeninfo = Default::default();
-    match unsafe { libc::ioctl(file.as_raw_fd(), FBIOGET_FSCREENINFO as _, &mut finfo) } {
-        -1 => Err(ErrnoError::new()),
-        _ => Ok(FixScreeninfo { internal: finfo }),
-    }
+    // SAFETY: FBIOGET_FSCREENINFO writes a `struct fb_fix_screeninfo`.
+    let internal = unsafe { ioctl(borrow_fd(file), Getter::<GET_FSCREENINFO, fb_fix_screeninfo>::new()) }?;
+    Ok(FixScreeninfo { internal })
+}
+
+/// Wrapper around `ioctl(fd, FBIOPAN_DISPLAY, ...)`.
+///
+/// Only the offsets and the `FB_VMODE_YWRAP` flag of `vinfo` are used by the driver.
+pub fn pan_display(file: &impl AsRawFd, vinfo: &mut VarScreeninfo) -> Result<(), ErrnoError> {
+    // SAFETY: FBIOPAN_DISPLAY reads a `struct fb_var_screeninfo`.
+    unsafe { ioctl(borrow_fd(file), Updater::<PAN_DISPLAY, _>::new(&mut vinfo.internal)) }?;
+    Ok(())
 }
 
 /// Wrapper around `ioctl(fd, FBIO_WAITFORVSYNC, ...)`.
 ///
 /// Blocks until the next vertical blanking interval.
 pub fn wait_for_vsync(file: &impl AsRawFd) -> Result<(), ErrnoError> {
-    let mut dummy: u32 = 0;
-    match unsafe { libc::ioctl(file.as_raw_fd(), FBIO_WAITFORVSYNC as _, &mut dummy) } {
-        -1 => Err(ErrnoError::new()),
-        _ => Ok(()),
-    }
+    // SAFETY: FBIO_WAITFORVSYNC reads the CRTC index as a `__u32`.
+    unsafe { ioctl(borrow_fd(file), Setter::<WAITFORVSYNC, u32>::new(0)) }?;
+    Ok(())
 }
 
+/// Wrapper around `ioctl(fd, FBIOGET_VBLANK, ...)`.
+///
+/// Returns the number of vertical blanking intervals counted by the driver,
+/// or `None` when the driver does not maintain a counter.
+pub fn get_vblank_count(file: &impl AsRawFd) -> Result<Option<u32>, ErrnoError> {
+    // SAFETY: FBIOGET_VBLANK writes a `struct fb_vblank`.
+    let vblank = unsafe { ioctl(borrow_fd(file), Getter::<GET_VBLANK, FbVblank>::new()) }?;
+    Ok((vblank.flags & FB_VBLANK_HAVE_COUNT != 0).then_some(vblank.count))
+}
 
 /// Represents a screen blanking level
 ///
@@ -189,7 +300,7 @@ pub fn wait_for_vsync(file: &impl AsRawFd) -> Result<(), ErrnoError> {
 /// In particular the `VsyncSuspend` and `HsyncSuspend` values
 /// may not be supported, in which case `Normal` behaves
 /// exactly the same as `Powerdown`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub enum BlankingLevel {
     /// Undoes any blank, and turns the screen back on.
     /// Note that the picture is usually not retained while
@@ -207,23 +318,22 @@ pub enum BlankingLevel {
 }
 
 impl BlankingLevel {
-    fn to_ulong(&self) -> std::os::raw::c_ulong {
-        match self {
+    fn to_usize(self) -> usize {
+        let value = match self {
             BlankingLevel::Unblank => FB_BLANK_UNBLANK,
             BlankingLevel::Normal => FB_BLANK_NORMAL,
             BlankingLevel::VsyncSuspend => FB_BLANK_VSYNC_SUSPEND,
             BlankingLevel::HsyncSuspend => FB_BLANK_HSYNC_SUSPEND,
             BlankingLevel::Powerdown => FB_BLANK_POWERDOWN,
-        }
-        .into()
+        };
+        value as usize
     }
 }
 
 pub fn blank(file: &impl AsRawFd, level: BlankingLevel) -> Result<(), ErrnoError> {
-    match unsafe { libc::ioctl(file.as_raw_fd(), FBIOBLANK as _, level.to_ulong()) } {
-        -1 => Err(ErrnoError::new()),
-        _ => Ok(()),
-    }
+    // SAFETY: FBIOBLANK takes one of the `FB_BLANK_*` levels as an integer argument.
+    unsafe { ioctl(borrow_fd(file), IntegerSetter::<BLANK>::new_usize(level.to_usize())) }?;
+    Ok(())
 }
 
 #[derive(Copy, Clone)]
@@ -233,12 +343,12 @@ pub enum TerminalMode {
 }
 
 impl TerminalMode {
-    fn to_ulong(&self) -> std::os::raw::c_ulong {
-        match self {
+    fn to_usize(self) -> usize {
+        let value = match self {
             TerminalMode::Text => KD_TEXT,
             TerminalMode::Graphics => KD_GRAPHICS,
-        }
-        .into()
+        };
+        value as usize
     }
 }
 
@@ -255,13 +365,79 @@ impl TerminalMode {
 ///
 /// Example:
 /// ```no_run
+/// # use slint_backend_linuxfb::linuxfb;
 /// # use linuxfb::{set_terminal_mode, TerminalMode};
 /// let tty = std::fs::File::open("/dev/tty1").unwrap();
 /// set_terminal_mode(&tty, TerminalMode::Graphics);
 /// ```
 pub fn set_terminal_mode(tty: &impl AsRawFd, mode: TerminalMode) -> Result<(), ErrnoError> {
-    match unsafe { libc::ioctl(tty.as_raw_fd(), KDSETMODE as _, mode.to_ulong()) } {
-        -1 => Err(ErrnoError::new()),
-        _ => Ok(()),
+    // SAFETY: KDSETMODE takes `KD_TEXT` or `KD_GRAPHICS` as an integer argument.
+    unsafe { ioctl(borrow_fd(tty), IntegerSetter::<SETMODE>::new_usize(mode.to_usize())) }?;
+    Ok(())
+}
+
+/// Represents the keyboard mode of a terminal
+///
+/// See [`set_keyboard_mode`] for usage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyboardMode {
+    /// Raw scancodes
+    Raw,
+    /// Translated to characters using the keymap (the usual mode)
+    Xlate,
+    /// Keycodes
+    MediumRaw,
+    /// Translated to UTF-8 using the keymap
+    Unicode,
+    /// Key presses are ignored by the terminal
+    Off,
+}
+
+impl KeyboardMode {
+    fn to_usize(self) -> usize {
+        let value = match self {
+            KeyboardMode::Raw => K_RAW,
+            KeyboardMode::Xlate => K_XLATE,
+            KeyboardMode::MediumRaw => K_MEDIUMRAW,
+            KeyboardMode::Unicode => K_UNICODE,
+            KeyboardMode::Off => K_OFF,
+        };
+        value as usize
+    }
+
+    fn from_raw(value: u32) -> Option<Self> {
+        match value {
+            K_RAW => Some(KeyboardMode::Raw),
+            K_XLATE => Some(KeyboardMode::Xlate),
+            K_MEDIUMRAW => Some(KeyboardMode::MediumRaw),
+            K_UNICODE => Some(KeyboardMode::Unicode),
+            K_OFF => Some(KeyboardMode::Off),
+            _ => None,
+        }
     }
 }
+
+/// Wrapper around `ioctl(fd, KDGKBMODE, ...)`.
+///
+/// Returns the current keyboard mode of the given terminal (`/dev/tty*`).
+pub fn get_keyboard_mode(tty: &impl AsRawFd) -> Result<KeyboardMode, ErrnoError> {
+    // SAFETY: KDGKBMODE writes the mode as an `int`.
+    let value = unsafe { ioctl(borrow_fd(tty), Getter::<GETKBMODE, libc::c_int>::new()) }?;
+    KeyboardMode::from_raw(value as u32).ok_or(ErrnoError {
+        errno: libc::EINVAL,
+        message: format!("Unknown keyboard mode {}", value),
+    })
+}
+
+/// Wrapper around `ioctl(fd, KDSKBMODE, ...)`.
+///
+/// With [`KeyboardMode::Off`], keys typed on the attached keyboard no longer reach
+/// the process reading the terminal (e.g. a login shell), while input devices read
+/// through evdev keep working. Like the terminal mode, make sure to restore the
+/// original mode (see [`get_keyboard_mode`]) whenever the application exits, otherwise
+/// the console will not accept any input.
+pub fn set_keyboard_mode(tty: &impl AsRawFd, mode: KeyboardMode) -> Result<(), ErrnoError> {
+    // SAFETY: KDSKBMODE takes one of the `K_*` modes as an integer argument.
+    unsafe { ioctl(borrow_fd(tty), IntegerSetter::<SETKBMODE>::new_usize(mode.to_usize())) }?;
+    Ok(())
+}
@@ -10,6 +10,8 @@
 extern crate libc;
 extern crate memmap2;
 
+#[cfg(not(feature = "generate-bindings"))]
+mod bindings;
 pub mod double;
 pub mod fbio;
 mod proc;
@@ -19,7 +21,8 @@ use std::fs::{File, OpenOptions};
 use std::path::{Path, PathBuf};
 
 pub use self::fbio::{
-    set_terminal_mode, BlankingLevel, ErrnoError, PixelLayout, PixelLayoutChannel, TerminalMode,
+    get_keyboard_mode, set_keyboard_mode, set_terminal_mode, BlankingLevel, ErrnoError, KeyboardMode,
+    PixelLayout, PixelLayoutChannel, TerminalMode,
 };
 
 /// Errors returned by `Framebuffer` methods
@@ -36,6 +39,7 @@ pub enum Error {
 /// Example usage:
 ///
 /// ```no_run
+/// # use slint_backend_linuxfb::linuxfb;
 /// // Instead of hardcoding the path, you could also use `Framebuffer::list()`
 /// // to find paths to available devices.
 /// let fb = linuxfb::Framebuffer::new("/dev/fb0").unwrap();
@@ -73,11 +77,13 @@ impl Framebuffer {
     ///
     /// Example, assuming there is one framebuffer named `fb0`:
     ///
-    ///     let devices = linuxfb::Framebuffer::list().unwrap();
-    ///     println!("Devices: {:?}", devices);
-    ///     // prints:
-    ///     //   Devices: ["/dev/fb0"]
-    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::linuxfb;
+    /// let devices = linuxfb::Framebuffer::list().unwrap();
+    /// println!("Devices: {:?}", devices);
+    /// // prints:
+    /// //   Devices: ["/dev/fb0"]
+    /// ```
     pub fn list() -> std::io::Result<Vec<PathBuf>> {
         match proc::devices()?.find(|device| device.driver == "fb") {
             None => Ok(vec![]),
@@ -191,6 +197,7 @@ impl Framebuffer {
     ///
     /// **16-bit, RGB565**, meaning `rrrrrggggggrrrrr`, with LSB right, aka HighColor:
     /// ```
+    /// # use slint_backend_linuxfb::linuxfb;
     /// # use linuxfb::*;
     /// PixelLayout {
     ///   red: PixelLayoutChannel { offset: 11, length: 5, msb_right: false },
@@ -202,6 +209,7 @@ impl Framebuffer {
     ///
     /// **32-bit, ABGR**, meaning `aaaaaaaabbbbbbbbbggggggggrrrrrrrr`, with LSB right:
     /// ```
+    /// # use slint_backend_linuxfb::linuxfb;
     /// # use linuxfb::*;
     /// PixelLayout {
     ///   red: PixelLayoutChannel { offset: 0, length: 8, msb_right: false },
@@ -213,6 +221,7 @@ impl Framebuffer {
     ///
     /// **32-bit, RGBA**, meaning: `rrrrrrrrggggggggbbbbbbbbaaaaaaaa`, with LSB right:
     /// ```
+    /// # use slint_backend_linuxfb::linuxfb;
     /// # use linuxfb::*;
     /// PixelLayout {
     ///   red: PixelLayoutChannel { offset: 24, length: 8, msb_right: false },
@@ -260,6 +269,19 @@ impl Framebuffer {
         Ok(())
     }
 
+    /// Re-applies the current mode, even though it did not change.
+    ///
+    /// Useful after a display has been connected to the output backing this
+    /// framebuffer (e.g. HDMI hotplug), as some drivers only program the
+    /// output when a mode is set.
+    pub fn reactivate(&mut self) -> Result<(), Error> {
+        let mut vinfo = self.vinfo.clone();
+        vinfo.activate_force();
+        fbio::put_vscreeninfo(&self.file, &mut vinfo)?;
+        self.vinfo = fbio::get_vscreeninfo(&self.file)?;
+        Ok(())
+    }
+
     /// Returns the current `xoffset` and `yoffset` of the underlying device.
     pub fn get_offset(&self) -> (u32, u32) {
         self.vinfo.offset()
@@ -280,12 +302,34 @@ impl Framebuffer {
         Ok(())
     }
 
+    /// Wraps the display to start at line `y`, using `FBIOPAN_DISPLAY` with `FB_VMODE_YWRAP`.
+    ///
+    /// Cheaper than [`set_offset`](Self::set_offset) on drivers that support it,
+    /// see [`fbio::FixScreeninfo::ywrap_step`]. `y` must be a multiple of the wrap step.
+    pub fn set_offset_ywrap(&mut self, y: u32) -> Result<(), Error> {
+        let mut vinfo = self.vinfo.clone();
+        vinfo.set_offset(0, y);
+        vinfo.set_ywrap(true);
+        fbio::pan_display(&self.file, &mut vinfo)?;
+        self.vinfo = fbio::get_vscreeninfo(&self.file)?;
+        Ok(())
+    }
+
     /// Returns the physical size of the device
     /// in millimeters, as reported by the driver.
     pub fn get_physical_size(&self) -> (u32, u32) {
         self.vinfo.size_in_mm()
     }
 
+    /// Returns the refresh rate in Hz, computed from the display timings
+    /// reported by the driver.
+    ///
+    /// Many drivers (especially for SPI panels and virtual devices) do not
+    /// report timings, in which case `None` is returned.
+    pub fn get_refresh_rate(&self) -> Option<f32> {
+        self.vinfo.refresh_rate()
+    }
+
     /// Get identifier string of the device, as reported by the driver.
     pub fn get_id(&self) -> String {
         self.finfo.id()
@@ -298,6 +342,7 @@ impl Framebuffer {
     ///
     /// Brief example:
     /// ```no_run
+    /// # use slint_backend_linuxfb::linuxfb;
     /// use linuxfb::{Framebuffer, BlankingLevel};
     ///
     /// let mut fb = Framebuffer::new("/dev/fb0").unwrap();
@@ -316,6 +361,7 @@ impl Framebuffer {
     ///
     /// For example:
     /// ```no_run
+    /// # use slint_backend_linuxfb::linuxfb;
     /// # use linuxfb::{Framebuffer, BlankingLevel};
     /// # let mut fb = Framebuffer::new("/dev/fb0").unwrap();
     /// fb.blank(BlankingLevel::Powerdown).unwrap(); // this call goes through fine
@@ -336,6 +382,11 @@ impl Framebuffer {
         fbio::wait_for_vsync(&self.file)?;
         Ok(())
     }
+
+    /// 读取驱动统计的垂直消隐次数，驱动不维护计数时返回 `None`
+    pub fn vblank_count(&self) -> Result<Option<u32>, Error> {
+        Ok(fbio::get_vblank_count(&self.file)?)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,73 @@
+//! 远程查看使用的帧副本
+//!
+//! VNC、MJPEG、屏幕录制等在各自的线程中读取画面，不能直接访问事件循环独占的渲染目标。
+//! 每次渲染后把损坏区域的像素转换为统一的 0x00RRGGBB 格式复制到副本中。
+
+use crate::canvas::Rect;
+use crate::pixels::PixelFormat;
+
+/// 屏幕 (Framebuffer) 画面的副本
+pub(crate) struct FrameMirror {
+    pub width: u32,
+    pub height: u32,
+    /// 每个像素为 0x00RRGGBB
+    pub pixels: Vec<u32>,
+}
+
+impl FrameMirror {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, pixels: vec![0; (width * height) as usize] }
+    }
+
+    /// 复制渲染目标中 `region` (缓冲区坐标) 内的像素，返回裁剪到屏幕范围内的非空矩形
+    pub fn update(&mut self, bytes: &[u8], format: PixelFormat, region: &[Rect]) -> Vec<Rect> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        let mut damage = Vec::new();
+        for rect in region {
+            let x0 = rect.x.clamp(0, width);
+            let y0 = rect.y.clamp(0, height);
+            let x1 = (rect.x + rect.w).clamp(x0, width);
+            let y1 = (rect.y + rect.h).clamp(y0, height);
+            if x0 == x1 || y0 == y1 {
+                continue;
+            }
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let index = (y * width + x) as usize;
+                    self.pixels[index] = read_pixel(bytes, format, index);
+                }
+            }
+            damage.push(Rect { x: x0, y: y0, w: x1 - x0, h: y1 - y0 });
+        }
+        damage
+    }
+}
+
+/// 读取第 `index` 个像素，返回 0x00RRGGBB
+pub(crate) fn read_pixel(bytes: &[u8], format: PixelFormat, index: usize) -> u32 {
+    match format {
+        PixelFormat::Rgb565 => {
+            let value = u16::from_le_bytes([bytes[index * 2], bytes[index * 2 + 1]]) as u32;
+            let (r, g, b) = ((value >> 11) & 0x1f, (value >> 5) & 0x3f, value & 0x1f);
+            ((r << 3 | r >> 2) << 16) | ((g << 2 | g >> 4) << 8) | (b << 3 | b >> 2)
+        }
+        PixelFormat::Rgba8888 => u32::from_be_bytes([0, bytes[index * 4], bytes[index * 4 + 1], bytes[index * 4 + 2]]),
+        // Abgr8888 与 Bgra8888 的内存序相同：BB GG RR AA
+        _ => u32::from_le_bytes([bytes[index * 4], bytes[index * 4 + 1], bytes[index * 4 + 2], 0]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_clips_and_converts() {
+        let mut mirror = FrameMirror::new(2, 2);
+        // RGB565：纯红、纯绿、纯蓝、白色
+        let bytes: Vec<u8> = [0xf800u16, 0x07e0, 0x001f, 0xffff].iter().flat_map(|p| p.to_le_bytes()).collect();
+        let damage = mirror.update(&bytes, PixelFormat::Rgb565, &[Rect { x: -1, y: 0, w: 5, h: 1 }, Rect { x: 2, y: 0, w: 1, h: 1 }]);
+        assert_eq!(damage, [Rect { x: 0, y: 0, w: 2, h: 1 }]);
+        assert_eq!(mirror.pixels, [0x00ff_0000, 0x0000_ff00, 0, 0]);
+    }
+}
@@ -0,0 +1,166 @@
+//! MJPEG over HTTP 画面推流 (只读)
+//!
+//! 比 VNC 更轻量：浏览器或监控面板直接用 `<img src="http://设备:端口/">` 即可显示，
+//! 适合同时查看大量设备。画面来自与 VNC 相同的帧副本，只在有损坏区域时编码新帧，
+//! 并按最大帧率限速；同一帧的 JPEG 在所有客户端之间共享。
+//!
+//! - `GET /snapshot.jpg`：返回当前画面的单张 JPEG
+//! - 其他路径：`multipart/x-mixed-replace` 连续推流
+
+use crate::canvas::Rect;
+use crate::mirror::FrameMirror;
+use crate::pixels::PixelFormat;
+use image::codecs::jpeg::JpegEncoder;
+use image::ExtendedColorType;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 推流的最大帧率
+const MAX_FPS: u32 = 10;
+/// JPEG 质量 (1-100)
+const JPEG_QUALITY: u8 = 75;
+/// multipart 分隔符
+const BOUNDARY: &str = "slint-frame";
+
+struct StreamState {
+    mirror: FrameMirror,
+    /// 画面版本号，每次有损坏区域时递增
+    generation: u64,
+    /// 最近一次编码的 (版本号, JPEG)
+    encoded: Option<(u64, Arc<Vec<u8>>)>,
+}
+
+struct Shared {
+    state: Mutex<StreamState>,
+    /// 画面有更新
+    changed: Condvar,
+}
+
+/// MJPEG 推流服务器 (由事件循环持有)
+pub(crate) struct MjpegServer {
+    shared: Arc<Shared>,
+}
+
+impl MjpegServer {
+    /// 在 `addr` 上监听 HTTP 请求，`width` x `height` 为屏幕 (Framebuffer) 尺寸
+    pub fn start(addr: SocketAddr, width: u32, height: u32) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(StreamState { mirror: FrameMirror::new(width, height), generation: 0, encoded: None }),
+            changed: Condvar::new(),
+        });
+        let accept_shared = shared.clone();
+        thread::Builder::new().name("linuxfb-mjpeg".into()).spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let shared = accept_shared.clone();
+                        let result = thread::Builder::new()
+                            .name("linuxfb-mjpeg-client".into())
+                            .spawn(move || {
+                                if let Err(e) = serve_client(&shared, stream) {
+                                    tracing::debug!("MJPEG 客户端断开: {}", e);
+                                }
+                            });
+                        if let Err(e) = result {
+                            tracing::warn!("无法创建 MJPEG 客户端线程: {}", e);
+                        }
+                    }
+                    Err(e) => tracing::warn!("MJPEG 接受连接失败: {}", e),
+                }
+            }
+        })?;
+        tracing::info!("MJPEG 推流服务器监听 http://{}/", addr);
+        Ok(Self { shared })
+    }
+
+    /// 把渲染目标中 `region` (缓冲区坐标) 内的像素复制到帧副本
+    pub fn update(&self, bytes: &[u8], format: PixelFormat, region: &[Rect]) {
+        let mut state = self.shared.state.lock().unwrap();
+        if !state.mirror.update(bytes, format, region).is_empty() {
+            state.generation += 1;
+            self.shared.changed.notify_all();
+        }
+    }
+
+    /// 屏幕尺寸改变：重新分配帧副本，之后的 JPEG 使用新的尺寸
+    pub fn resize(&self, width: u32, height: u32) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.mirror = FrameMirror::new(width, height);
+        state.generation += 1;
+        state.encoded = None;
+    }
+}
+
+fn serve_client(shared: &Shared, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // 忽略请求头
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let mut stream = stream;
+
+    if path == "/snapshot.jpg" {
+        let (_, jpeg) = current_jpeg(shared)?;
+        write!(
+            stream,
+            "HTTP/1.0 200 OK\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\nCache-Control: no-cache\r\n\r\n",
+            jpeg.len()
+        )?;
+        return stream.write_all(&jpeg);
+    }
+
+    write!(
+        stream,
+        "HTTP/1.0 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary={}\r\nCache-Control: no-cache\r\n\r\n",
+        BOUNDARY
+    )?;
+    let interval = Duration::from_secs(1) / MAX_FPS;
+    let mut sent = None;
+    loop {
+        let started = Instant::now();
+        {
+            let state = shared.state.lock().unwrap();
+            let _state = shared.changed.wait_while(state, |state| Some(state.generation) == sent).unwrap();
+        }
+        let (generation, jpeg) = current_jpeg(shared)?;
+        write!(stream, "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n", BOUNDARY, jpeg.len())?;
+        stream.write_all(&jpeg)?;
+        stream.write_all(b"\r\n")?;
+        sent = Some(generation);
+        thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+/// 返回当前画面的 JPEG，同一版本只编码一次
+fn current_jpeg(shared: &Shared) -> io::Result<(u64, Arc<Vec<u8>>)> {
+    let (generation, rgb, width, height) = {
+        let state = shared.state.lock().unwrap();
+        if let Some((generation, jpeg)) = state.encoded.as_ref().filter(|(generation, _)| *generation == state.generation) {
+            return Ok((*generation, jpeg.clone()));
+        }
+        (state.generation, to_rgb(&state.mirror.pixels), state.mirror.width, state.mirror.height)
+    };
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+        .encode(&rgb, width, height, ExtendedColorType::Rgb8)
+        .map_err(io::Error::other)?;
+    let jpeg = Arc::new(jpeg);
+    let mut state = shared.state.lock().unwrap();
+    if state.encoded.as_ref().is_none_or(|(encoded, _)| *encoded < generation) {
+        state.encoded = Some((generation, jpeg.clone()));
+    }
+    Ok((generation, jpeg))
+}
+
+/// 0x00RRGGBB 像素转换为 RGB888 字节
+fn to_rgb(pixels: &[u32]) -> Vec<u8> {
+    pixels.iter().flat_map(|&pixel| [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8]).collect()
+}
@@ -0,0 +1,115 @@
+//! OSD 叠加层
+//!
+//! 应用提供的像素图层 (例如连接状态栏、调试文字) 不属于 Slint 场景，由平台在每帧渲染完成后
+//! 按 Alpha 混合到画面之上，始终位于应用窗口和虚拟键盘之上。图层通过
+//! [`LinuxFbPlatform::set_osd_layer`](crate::LinuxFbPlatform::set_osd_layer) 设置，
+//! 坐标为窗口坐标 (物理像素)，随显示旋转一起旋转。
+//!
+//! 与调试叠加层相同，每帧开始前图层覆盖的区域被标记为脏区，让渲染器重绘其下方的应用画面。
+
+use crate::canvas::Rect;
+use crate::window::Rotation;
+use i_slint_core::api::PhysicalSize;
+use i_slint_core::graphics::{Rgba8Pixel, SharedPixelBuffer};
+use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
+use i_slint_core::partial_renderer::DirtyRegion;
+use i_slint_core::platform::software_renderer::{PremultipliedRgbaColor, SoftwareRenderer, TargetPixel};
+use i_slint_core::renderer::RendererSealed;
+use std::collections::BTreeMap;
+
+/// 一个 OSD 图层：放在窗口坐标 (`x`, `y`) 处的 RGBA 像素 (非预乘 Alpha)
+#[derive(Debug, Clone)]
+pub struct OsdLayer {
+    pub x: i32,
+    pub y: i32,
+    pub image: SharedPixelBuffer<Rgba8Pixel>,
+}
+
+impl OsdLayer {
+    pub fn new(x: i32, y: i32, image: SharedPixelBuffer<Rgba8Pixel>) -> Self {
+        Self { x, y, image }
+    }
+
+    fn bounds(&self) -> Rect {
+        Rect { x: self.x, y: self.y, w: self.image.width() as i32, h: self.image.height() as i32 }
+    }
+}
+
+/// 按编号排列的 OSD 图层，编号大的绘制在上面
+#[derive(Default)]
+pub(crate) struct OsdLayers {
+    layers: BTreeMap<u32, OsdLayer>,
+    /// 上一帧绘制过图层的区域，需要在下一帧重绘
+    drawn: Vec<Rect>,
+}
+
+impl OsdLayers {
+    /// 设置或移除 (`None`) 编号为 `id` 的图层
+    pub fn set(&mut self, id: u32, layer: Option<OsdLayer>) {
+        match layer {
+            Some(layer) => self.layers.insert(id, layer),
+            None => self.layers.remove(&id),
+        };
+    }
+
+    /// 将上一帧绘制过的区域和本帧要绘制的区域标记为脏区
+    pub fn invalidate(&mut self, renderer: &SoftwareRenderer, scale_factor: f32) {
+        let rects: Vec<Rect> = self.drawn.drain(..).chain(self.layers.values().map(OsdLayer::bounds)).collect();
+        if rects.is_empty() {
+            return;
+        }
+        let mut region = DirtyRegion::default();
+        for rect in rects {
+            region.add_rect(LogicalRect::new(
+                LogicalPoint::new(rect.x as f32 / scale_factor, rect.y as f32 / scale_factor),
+                LogicalSize::new(rect.w as f32 / scale_factor, rect.h as f32 / scale_factor),
+            ));
+        }
+        renderer.mark_dirty_region(region);
+    }
+
+    /// 把所有图层混合到已渲染的像素缓冲区上
+    ///
+    /// `size` 为缓冲区尺寸，图层以窗口坐标绘制并按 `rotation` 旋转。
+    pub fn draw<P: TargetPixel>(&mut self, pixels: &mut [P], stride: usize, size: PhysicalSize, rotation: Rotation) {
+        let (window_width, window_height) = rotation.rotate_size(size.width, size.height);
+        for layer in self.layers.values() {
+            let width = layer.image.width() as usize;
+            for (index, pixel) in layer.image.as_slice().iter().enumerate() {
+                let (x, y) = (layer.x + (index % width) as i32, layer.y + (index / width) as i32);
+                if pixel.a == 0 || x < 0 || y < 0 || x >= window_width as i32 || y >= window_height as i32 {
+                    continue;
+                }
+                let (x, y) = rotation.rotate_point(x, y, window_width, window_height);
+                pixels[y as usize * stride + x as usize].blend(premultiply(*pixel));
+            }
+            self.drawn.push(layer.bounds());
+        }
+    }
+}
+
+fn premultiply(pixel: Rgba8Pixel) -> PremultipliedRgbaColor {
+    let scale = |channel: u8| (channel as u16 * pixel.a as u16 / 255) as u8;
+    PremultipliedRgbaColor { red: scale(pixel.r), green: scale(pixel.g), blue: scale(pixel.b), alpha: pixel.a }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixels::PixelBgra8888;
+
+    #[test]
+    fn test_draw_rotated() {
+        let mut image = SharedPixelBuffer::<Rgba8Pixel>::new(2, 1);
+        image.make_mut_slice()[0] = Rgba8Pixel::new(0xff, 0, 0, 0xff);
+        let mut layers = OsdLayers::default();
+        layers.set(1, Some(OsdLayer::new(0, 0, image)));
+
+        // 4x2 的缓冲区旋转 90 度后窗口为 2x4，窗口左上角位于缓冲区右上角
+        let mut pixels = [PixelBgra8888(0); 8];
+        layers.draw(&mut pixels, 4, PhysicalSize::new(4, 2), Rotation::Rotate90);
+        assert_eq!(pixels[3].0, 0xffff0000);
+        assert_eq!(pixels.iter().filter(|pixel| pixel.0 != 0).count(), 1);
+        assert_eq!(layers.drawn, [Rect { x: 0, y: 0, w: 2, h: 1 }]);
+    }
+}
@@ -0,0 +1,168 @@
+//! 调试叠加层 (Debug Overlay)
+//!
+//! 在每一帧渲染完成后、翻转之前，直接在后缓冲区上绘制诊断信息：
+//! - 左上角的 FPS 计数器 (界面静止时也定期刷新)
+//! - 本帧损坏区域 (damage rect) 的轮廓
+//! - 当前活跃触点的十字标记
+//!
+//! 叠加层绘制的像素不属于 Slint 场景，因此下一帧开始前会把这些区域标记为脏区，
+//! 让渲染器重新绘制并擦除旧的叠加内容。
+
+use crate::canvas::{Canvas, Rect, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::window::Rotation;
+use i_slint_core::api::{PhysicalPosition, PhysicalSize};
+use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
+use i_slint_core::partial_renderer::DirtyRegion;
+use i_slint_core::platform::software_renderer::{PhysicalRegion, SoftwareRenderer, TargetPixel};
+use i_slint_core::renderer::RendererSealed;
+use std::time::{Duration, Instant};
+
+/// 字形放大倍数
+const GLYPH_SCALE: usize = 2;
+/// 触点十字标记的半径
+const MARKER_RADIUS: i32 = 8;
+/// 没有其他重绘时检查 FPS 是否需要刷新的间隔
+const FPS_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 调试叠加层状态
+pub struct DebugOverlay {
+    fps: f32,
+    /// 屏幕上显示的 FPS
+    shown_fps: f32,
+    /// 上一次检查 FPS 是否需要刷新的时间
+    fps_checked_at: Instant,
+    touch_points: Vec<PhysicalPosition>,
+    /// 复用的触点缓冲区，与 `touch_points` 比较后交换
+    next_touch_points: Vec<PhysicalPosition>,
+    /// 上一帧绘制过叠加内容的区域，需要在下一帧重绘
+    drawn: Vec<Rect>,
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self {
+            fps: 0.0,
+            shown_fps: 0.0,
+            fps_checked_at: crate::clock::now(),
+            touch_points: Vec::new(),
+            next_touch_points: Vec::new(),
+            drawn: Vec::new(),
+        }
+    }
+
+    /// 更新下一帧要显示的数据
+    ///
+    /// 返回 `true` 表示触点发生了变化，或到了刷新间隔而显示的 FPS 已过时，需要重绘一帧。
+    pub fn update(&mut self, now: Instant, fps: f32, touch_points: impl IntoIterator<Item = PhysicalPosition>) -> bool {
+        self.fps = fps;
+        self.next_touch_points.clear();
+        self.next_touch_points.extend(touch_points);
+        let mut changed = self.next_touch_points != self.touch_points;
+        std::mem::swap(&mut self.touch_points, &mut self.next_touch_points);
+        if self.time_until_refresh(now).is_zero() {
+            self.fps_checked_at = now;
+            changed |= fps != self.shown_fps;
+        }
+        changed
+    }
+
+    /// 距离下一次检查 FPS 是否需要刷新的时间，事件循环最迟在此时醒来
+    pub fn time_until_refresh(&self, now: Instant) -> Duration {
+        FPS_REFRESH_INTERVAL.saturating_sub(now.saturating_duration_since(self.fps_checked_at))
+    }
+
+    /// 将上一帧叠加层覆盖的区域标记为脏区，使渲染器重绘这些区域
+    pub fn invalidate_previous(&mut self, renderer: &SoftwareRenderer, scale_factor: f32) {
+        if self.drawn.is_empty() {
+            return;
+        }
+        let mut region = DirtyRegion::default();
+        for rect in self.drawn.drain(..) {
+            region.add_rect(LogicalRect::new(
+                LogicalPoint::new(rect.x as f32 / scale_factor, rect.y as f32 / scale_factor),
+                LogicalSize::new(rect.w as f32 / scale_factor, rect.h as f32 / scale_factor),
+            ));
+        }
+        renderer.mark_dirty_region(region);
+    }
+
+    /// 在已渲染的像素缓冲区上绘制叠加层
+    ///
+    /// `size` 为缓冲区尺寸，叠加层以窗口坐标绘制并按 `rotation` 旋转。
+    pub fn draw<P: TargetPixel>(
+        &mut self,
+        pixels: &mut [P],
+        stride: usize,
+        size: PhysicalSize,
+        rotation: Rotation,
+        damage: &PhysicalRegion,
+    ) {
+        let mut canvas = Canvas {
+            pixels,
+            stride,
+            width: size.width as i32,
+            height: size.height as i32,
+            rotation,
+        };
+
+        // 1. 损坏区域轮廓 (红色)，渲染器给出的是缓冲区坐标，先转换回窗口坐标
+        let red = P::from_rgb(0xff, 0x20, 0x20);
+        for (origin, damage_size) in damage.iter() {
+            let rect = Rect { x: origin.x, y: origin.y, w: damage_size.width as i32, h: damage_size.height as i32 };
+            let rect = rotation.inverse().rotate_rect(rect, size.width, size.height);
+            canvas.outline_rect(rect, red);
+            self.drawn.push(rect);
+        }
+
+        // 2. 触点十字标记 (绿色)
+        let green = P::from_rgb(0x20, 0xff, 0x20);
+        for point in &self.touch_points {
+            let horizontal = Rect { x: point.x - MARKER_RADIUS, y: point.y, w: MARKER_RADIUS * 2 + 1, h: 1 };
+            let vertical = Rect { x: point.x, y: point.y - MARKER_RADIUS, w: 1, h: MARKER_RADIUS * 2 + 1 };
+            canvas.fill_rect(horizontal, green);
+            canvas.fill_rect(vertical, green);
+            self.drawn.push(Rect {
+                x: point.x - MARKER_RADIUS,
+                y: point.y - MARKER_RADIUS,
+                w: MARKER_RADIUS * 2 + 1,
+                h: MARKER_RADIUS * 2 + 1,
+            });
+        }
+
+        // 3. FPS 计数器 (黑底白字)
+        self.shown_fps = self.fps;
+        let text = format!("{:.1} FPS", self.fps);
+        let scale = GLYPH_SCALE as i32;
+        let background = Rect {
+            x: 0,
+            y: 0,
+            w: (text.chars().count() as i32 * (GLYPH_WIDTH as i32 + 1) + 1) * scale,
+            h: (GLYPH_HEIGHT as i32 + 2) * scale,
+        };
+        canvas.fill_rect(background, P::from_rgb(0, 0, 0));
+        canvas.draw_text(scale, scale, &text, scale, P::from_rgb(0xff, 0xff, 0xff));
+        self.drawn.push(background);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fps_refresh() {
+        let mut overlay = DebugOverlay::new();
+        let start = overlay.fps_checked_at;
+        assert!(!overlay.update(start, 30.0, []));
+        assert_eq!(overlay.time_until_refresh(start), FPS_REFRESH_INTERVAL);
+
+        // 到了刷新间隔，显示的 FPS 已过时
+        let due = start + FPS_REFRESH_INTERVAL;
+        assert!(overlay.update(due, 30.0, []));
+        assert_eq!(overlay.time_until_refresh(due), FPS_REFRESH_INTERVAL);
+
+        // 触点变化立即重绘
+        assert!(overlay.update(due, 30.0, [PhysicalPosition::new(1, 2)]));
+        assert!(!overlay.update(due, 30.0, [PhysicalPosition::new(1, 2)]));
+    }
+}
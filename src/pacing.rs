@@ -0,0 +1,198 @@
+//! 自适应帧节奏控制
+//!
+//! 当驱动不支持 `FBIO_WAITFORVSYNC` 时，渲染循环无法与硬件刷新同步。
+//! 此时按刷新间隔将帧对齐到固定的刷新相位上，避免帧与帧之间的间隔忽长忽短 (抖动)，
+//! 同时减少无意义的超刷新率渲染。刷新间隔由 [`RefreshMeter`] 实测，
+//! 驱动报告的显示时序只作为测量结果出来之前的初始估计。
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// 无法从驱动获取时序时假定的刷新率
+const FALLBACK_REFRESH_HZ: f32 = 60.0;
+
+/// 可信的刷新间隔范围 (240Hz ~ 10Hz)
+const MIN_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / 240);
+const MAX_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 计算滑动中位数的呈现间隔样本数
+const PRESENT_SAMPLES: usize = 32;
+
+/// 由呈现间隔得出结果至少需要的样本数
+const MIN_PRESENT_SAMPLES: usize = 8;
+
+/// 由 vblank 计数得出结果至少需要经过的 vblank 数，基线越长计时误差的影响越小
+const MIN_VBLANK_SPAN: u32 = 30;
+
+/// 实测的刷新间隔
+///
+/// 记录每帧呈现完成 (VSync 等待和翻转返回) 的时间点，取最近若干个呈现间隔的滑动中位数：
+/// 等待 VSync 或翻转阻塞到 vblank 时，连续渲染的帧之间恰好相隔一个刷新周期。
+/// 超出可信范围的间隔 (例如空闲之后的第一帧) 不计入。驱动维护 vblank 计数时
+/// 按计数和经过的时间直接计算，不受帧率影响，优先采用。
+#[derive(Debug, Default)]
+pub(crate) struct RefreshMeter {
+    last_present: Option<Instant>,
+    present_intervals: VecDeque<Duration>,
+    /// 开始测量时的时间点和 vblank 计数
+    vblank_anchor: Option<(Instant, u32)>,
+    vblank_interval: Option<Duration>,
+}
+
+impl RefreshMeter {
+    /// 一帧呈现完成
+    pub fn presented(&mut self, now: Instant) {
+        let Some(last) = self.last_present.replace(now) else {
+            return;
+        };
+        let interval = now.duration_since(last);
+        if (MIN_INTERVAL..=MAX_INTERVAL).contains(&interval) {
+            if self.present_intervals.len() == PRESENT_SAMPLES {
+                self.present_intervals.pop_front();
+            }
+            self.present_intervals.push_back(interval);
+        }
+    }
+
+    /// 驱动报告的 vblank 计数
+    pub fn vblank_counted(&mut self, now: Instant, count: u32) {
+        let Some((start, start_count)) = self.vblank_anchor else {
+            self.vblank_anchor = Some((now, count));
+            return;
+        };
+        let vblanks = count.wrapping_sub(start_count);
+        if vblanks < MIN_VBLANK_SPAN {
+            return;
+        }
+        let interval = now.duration_since(start) / vblanks;
+        if (MIN_INTERVAL..=MAX_INTERVAL).contains(&interval) {
+            self.vblank_interval = Some(interval);
+        } else {
+            // 计数被重置 (例如显示模式改变) 或不可信：重新开始测量
+            self.vblank_anchor = Some((now, count));
+        }
+    }
+
+    /// 实测的刷新间隔，样本不足时返回 `None`
+    pub fn interval(&self) -> Option<Duration> {
+        if self.vblank_interval.is_some() {
+            return self.vblank_interval;
+        }
+        if self.present_intervals.len() < MIN_PRESENT_SAMPLES {
+            return None;
+        }
+        let mut intervals: Vec<Duration> = self.present_intervals.iter().copied().collect();
+        intervals.sort();
+        Some(intervals[intervals.len() / 2])
+    }
+}
+
+/// 基于刷新间隔的帧节奏控制器
+pub(crate) struct FramePacer {
+    interval: Duration,
+    /// 上一帧呈现的时间点 (刷新相位的锚点)
+    last_frame: Option<Instant>,
+}
+
+impl FramePacer {
+    /// 根据刷新率创建节奏控制器，`None` 表示使用默认 60Hz
+    pub fn new(refresh_rate: Option<f32>) -> Self {
+        let hz = refresh_rate.unwrap_or(FALLBACK_REFRESH_HZ);
+        Self {
+            interval: Duration::from_secs_f64(1.0 / hz as f64),
+            last_frame: None,
+        }
+    }
+
+    /// 刷新间隔
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 改用实测的刷新间隔，已建立的相位保持不变
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// 距离下一个可以呈现帧的时间点还有多久，`Duration::ZERO` 表示可以立即渲染
+    pub fn time_until_next_frame(&self, now: Instant) -> Duration {
+        match self.last_frame {
+            Some(last) => (last + self.interval).saturating_duration_since(now),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// 记录一帧已呈现
+    ///
+    /// 锚点按整数个刷新间隔前进，而不是直接取当前时间，
+    /// 这样偶尔的延迟不会让后续所有帧的相位都随之漂移。
+    pub fn frame_presented(&mut self, now: Instant) {
+        self.last_frame = Some(match self.last_frame {
+            Some(last) if now.duration_since(last) < self.interval * 2 => {
+                let periods = (now.duration_since(last).as_nanos() / self.interval.as_nanos()).max(1);
+                last + self.interval * periods as u32
+            }
+            // 空闲了较长时间：以当前时间重新建立相位
+            _ => now,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_is_kept() {
+        let mut pacer = FramePacer::new(Some(50.0));
+        let start = Instant::now();
+        assert_eq!(pacer.time_until_next_frame(start), Duration::ZERO);
+
+        pacer.frame_presented(start);
+        assert_eq!(pacer.time_until_next_frame(start), Duration::from_millis(20));
+
+        // 略微延迟的帧不会推迟后续相位
+        pacer.frame_presented(start + Duration::from_millis(23));
+        assert_eq!(
+            pacer.time_until_next_frame(start + Duration::from_millis(23)),
+            Duration::from_millis(17)
+        );
+    }
+
+    #[test]
+    fn test_measure_present_intervals() {
+        let mut meter = RefreshMeter::default();
+        let mut now = Instant::now();
+        meter.presented(now);
+        // 50Hz 的面板，偶尔跳过一次 vblank，返回时间有少量抖动
+        for i in 0..20u64 {
+            let periods = if i % 5 == 4 { 2 } else { 1 };
+            now += Duration::from_millis(20) * periods + Duration::from_micros(i % 3 * 100);
+            meter.presented(now);
+            if i < 6 {
+                assert_eq!(meter.interval(), None);
+            }
+        }
+        let interval = meter.interval().unwrap();
+        assert!(interval >= Duration::from_millis(20) && interval <= Duration::from_micros(20_200));
+
+        // 空闲之后的第一帧不计入
+        meter.presented(now + Duration::from_secs(5));
+        assert_eq!(meter.interval(), Some(interval));
+    }
+
+    #[test]
+    fn test_measure_vblank_count() {
+        let mut meter = RefreshMeter::default();
+        let start = Instant::now();
+        meter.vblank_counted(start, 1000);
+        meter.vblank_counted(start + Duration::from_millis(200), 1012);
+        assert_eq!(meter.interval(), None);
+        meter.vblank_counted(start + Duration::from_secs(1), 1050);
+        assert_eq!(meter.interval(), Some(Duration::from_millis(20)));
+
+        // 计数被重置后重新开始测量，保留上一次的结果
+        meter.vblank_counted(start + Duration::from_secs(2), 3);
+        assert_eq!(meter.interval(), Some(Duration::from_millis(20)));
+    }
+}
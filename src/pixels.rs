@@ -3,6 +3,8 @@
 //! 负责将 Slint 的 RGBA 颜色数据转换并混合到底层 Framebuffer 的特定格式中。
 
 use i_slint_core::platform::software_renderer::{PremultipliedRgbaColor, TargetPixel};
+use i_slint_core::Color;
+use crate::canvas::Rect;
 use crate::linuxfb::fbio;
 
 /// 支持的 Framebuffer 像素格式
@@ -74,6 +76,39 @@ impl PixelFormat {
             }
         }
     }
+
+    /// 每个像素占用的字节数 (未知格式返回 0)
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Abgr8888 | PixelFormat::Rgba8888 | PixelFormat::Bgra8888 => 4,
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::Unknown => 0,
+        }
+    }
+
+    /// 用纯色填充整个缓冲区 (忽略颜色的透明度)，未知格式时不做处理
+    pub(crate) fn fill(self, bytes: &mut [u8], color: Color) {
+        fn fill_pixels<P: TargetPixel + bytemuck::Pod>(bytes: &mut [u8], color: Color) {
+            let pixels: &mut [P] = bytemuck::cast_slice_mut(bytes);
+            pixels.fill(P::from_rgb(color.red(), color.green(), color.blue()));
+        }
+        match self {
+            PixelFormat::Abgr8888 => fill_pixels::<PixelAbgr8888>(bytes, color),
+            PixelFormat::Rgba8888 => fill_pixels::<PixelRgba8888>(bytes, color),
+            PixelFormat::Bgra8888 => fill_pixels::<PixelBgra8888>(bytes, color),
+            PixelFormat::Rgb565 => fill_pixels::<PixelRgb565>(bytes, color),
+            PixelFormat::Unknown => {}
+        }
+    }
+
+    /// 用纯色填充每行 `stride` 个像素的缓冲区中的矩形区域
+    pub(crate) fn fill_rect(self, bytes: &mut [u8], stride: usize, rect: Rect, color: Color) {
+        let bytes_per_pixel = self.bytes_per_pixel();
+        for y in rect.y..rect.y + rect.h {
+            let start = (y as usize * stride + rect.x as usize) * bytes_per_pixel;
+            self.fill(&mut bytes[start..start + rect.w as usize * bytes_per_pixel], color);
+        }
+    }
 }
 
 // --- 32-bpp ABGR ---
@@ -210,4 +245,28 @@ impl TargetPixel for PixelRgb565 {
             for px in slice { px.blend(color); }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        let mut bytes = [0u8; 8];
+        PixelFormat::Bgra8888.fill(&mut bytes, Color::from_rgb_u8(0x10, 0x20, 0x30));
+        assert_eq!(bytes, [0x30, 0x20, 0x10, 0xff, 0x30, 0x20, 0x10, 0xff]);
+        let mut bytes = [0u8; 4];
+        PixelFormat::Rgb565.fill(&mut bytes, Color::from_rgb_u8(0xff, 0, 0));
+        assert_eq!(bytes, [0x00, 0xf8, 0x00, 0xf8]);
+    }
+
+    #[test]
+    fn test_fill_rect() {
+        // 3x3 的 RGB565 缓冲区，只填充右下角 2x2
+        let mut bytes = vec![0u8; 3 * 3 * 2];
+        PixelFormat::Rgb565.fill_rect(&mut bytes, 3, Rect { x: 1, y: 1, w: 2, h: 2 }, Color::from_rgb_u8(0xff, 0xff, 0xff));
+        let pixels: Vec<u16> = bytes.chunks(2).map(|p| u16::from_le_bytes([p[0], p[1]])).collect();
+        assert_eq!(pixels, [0, 0, 0, 0, 0xffff, 0xffff, 0, 0xffff, 0xffff]);
+    }
+}
@@ -1,35 +1,84 @@
-use crate::error::Error;
-use crate::input::{InputConfig, InputManager}; 
+use crate::backlight::Backlight;
+use crate::blitter::{Blitter, CpuBlitter, SharedBlitter};
+use crate::clipboard::ClipboardStore;
+use crate::clock::SuspendAwareClock;
+use crate::device::sealed::DisplaySource;
+use crate::device::{DisplaySink, FbdevDevice, FramebufferDevice, IntoFramebufferDevice, Rect};
+use crate::diagnostics::{self, ProbeReport};
+use crate::error::{BackendEvent, BackendIssue, Error};
+use crate::hotplug::ConnectorMonitor;
+#[cfg(feature = "ime")]
+use crate::input::ImeEvent;
+use crate::input::{Beeper, BeeperConfig, DeviceEvent, DeviceFilter, DeviceTransform, EncoderConfig, KeypadNavigation, GestureConfig, InputConfig, InputManager, PointerAcceleration, SmoothingConfig, TouchCalibration, TouchContact, TouchGesture, KeyInterceptor, KeyRepeat, Modifiers, PointerState, RawKeyCode, Switch, SystemKey};
 use crate::pixels::PixelFormat;
-use crate::window::LinuxFbWindowAdapter;
-use i_slint_core::api::EventLoopError;
+use crate::stats::{FrameStats, FrameStatsCollector};
+use crate::osd::{OsdLayer, OsdLayers};
+use crate::overlay::DebugOverlay;
+use crate::pacing::{FramePacer, RefreshMeter};
+use crate::power::{Easing, Fade, IdleTracker, PowerPolicy, PowerStage};
+use crate::recorder::{Recorder, RecordingFormat};
+use crate::signals::{self, SignalAction, SignalPolicy};
+use crate::presenter::{
+    fill_device, DeviceRecovery, HeadlessBuffer, Presenter, RecoveryBackoff, SinkPresenter, SwapDamage, ThreadedPresenter,
+};
+use crate::virtual_keyboard::VirtualKeyboard;
+#[cfg(feature = "vnc")]
+use crate::vnc::VncServer;
+#[cfg(feature = "mjpeg")]
+use crate::mjpeg::MjpegServer;
+#[cfg(feature = "preview")]
+use crate::preview::PreviewWindow;
+use crate::watchdog::{StallCallback, Watchdog};
+use crate::window::{border_rects, letterbox_rect, LinuxFbWindowAdapter, Rotation};
+use i_slint_core::api::{EventLoopError, LogicalPosition};
 use i_slint_core::platform::{
     software_renderer::{RepaintBufferType, SoftwareRenderer},
-    EventLoopProxy, Platform, PlatformError, WindowAdapter, WindowEvent,
+    Clipboard, EventLoopProxy, Platform, PlatformError, PointerEventButton, WindowAdapter, WindowEvent,
 };
 use i_slint_core::renderer::RendererSealed;
+use i_slint_core::Color;
 use crate::linuxfb::{
-    double::Buffer,
-    fbio::{self, TerminalMode},
+    fbio::{self, BlankingLevel, KeyboardMode, TerminalMode},
     Framebuffer,
 };
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::sync::{Arc, Mutex};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{OwnedFd, RawFd};
 use libc;
 
-// 全局静态变量，用于在 Ctrl+C 信号处理器中恢复 TTY
-static ACTIVE_TTY_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+// 全局静态变量，用于在 Ctrl+C 信号处理器中恢复 TTY (平台所用 TTY 的副本，以及需要恢复的键盘模式)
+static ACTIVE_TTY: Mutex<Option<(File, Option<KeyboardMode>)>> = Mutex::new(None);
+
+/// 把平台使用的 TTY 恢复为文本模式，启用了终端输入时同时恢复 stdin 的终端属性
+///
+/// 供使用 [`SignalPolicy::None`] 或 [`SignalPolicy::Custom`] 自行处理信号的应用
+/// 在退出前调用。只执行几次 ioctl，不分配内存也不阻塞 (平台正在更新 TTY 时直接返回)，因此可以在信号处理函数中调用。
+/// 平台没有使用 TTY 时什么也不做；平台正常销毁时会自动恢复，无需调用。
+pub fn restore_tty() {
+    crate::input::restore_terminal();
+    if let Ok(guard) = ACTIVE_TTY.try_lock() {
+        if let Some((file, keyboard_mode)) = guard.as_ref() {
+            if let Some(mode) = keyboard_mode {
+                let _ = fbio::set_keyboard_mode(file, *mode);
+            }
+            let _ = fbio::set_terminal_mode(file, TerminalMode::Text);
+        }
+    }
+}
 
 // 常量定义
 const EVENTFD_BUFFER_LEN: usize = 8;
-const DEFAULT_TIMEOUT: Duration = Duration::from_millis(16);
+/// 动画、渐变等需要逐帧推进时的唤醒间隔 (约 60fps)
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+/// 检查显示模式是否被外部改变的间隔
+const MODE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
 /// 用于跨线程唤醒事件循环的代理
 #[derive(Clone)]
@@ -73,13 +122,203 @@ impl EventLoopProxy for LinuxFbProxy {
     }
 }
 
+/// 空闲回调：在事件循环即将休眠前调用，参数为计算出的休眠时长
+pub type IdleCallback = Box<dyn FnMut(Duration)>;
+
+/// 触点回调：原始多点触控模式下每个触点变化调用一次
+pub type TouchCallback = Box<dyn FnMut(&TouchContact)>;
+
+/// 手势回调：识别出应用需要处理的触摸手势 (例如捏合) 时调用
+pub type GestureCallback = Box<dyn FnMut(&TouchGesture)>;
+
+/// 事件过滤器：每个输入事件分发给窗口之前调用，返回 `None` 丢弃该事件
+pub type EventFilter = Box<dyn FnMut(WindowEvent) -> Option<WindowEvent>>;
+
+/// 开关回调：开关 ([`Switch`]) 状态变化时调用，参数为是否激活
+pub type SwitchCallback = Box<dyn FnMut(Switch, bool)>;
+
+/// 设备添加或移除回调
+pub type DeviceCallback = Box<dyn FnMut(&DeviceEvent)>;
+
+/// 运行时问题回调：VSync 不可用、翻转失败、输入设备断开等
+pub type BackendEventCallback = Box<dyn FnMut(&BackendEvent)>;
+
+/// 系统按键 ([`SystemKey`]) 触发的动作
+pub enum SystemKeyAction {
+    /// 忽略该按键
+    Ignore,
+    /// 按最大亮度的百分比调整背光 (正数调亮，负数调暗)
+    AdjustBacklight(i32),
+    /// 关闭或恢复屏幕显示 (FBIOBLANK)
+    ToggleBlank,
+    /// 退出事件循环
+    Quit,
+    /// 向窗口分发 `CloseRequested`，由应用的 close-requested 回调决定是否关闭
+    RequestClose,
+    /// 交给应用回调处理
+    Callback(Box<dyn Fn(SystemKey)>),
+}
+
+/// 将输入法事件作为组合输入送入获得焦点的文本输入框
+#[cfg(feature = "ime")]
+fn dispatch_ime_event(window: &i_slint_core::api::Window, event: ImeEvent) {
+    use i_slint_core::input::{KeyEvent, KeyEventType};
+
+    let key_event = match event {
+        ImeEvent::Preedit { text, cursor } => KeyEvent {
+            event_type: KeyEventType::UpdateComposition,
+            preedit_selection: (!text.is_empty()).then_some(cursor as i32..cursor as i32),
+            preedit_text: text.into(),
+            ..Default::default()
+        },
+        ImeEvent::Commit(text) => KeyEvent {
+            event_type: KeyEventType::CommitComposition,
+            text: text.into(),
+            ..Default::default()
+        },
+    };
+    i_slint_core::window::WindowInner::from_pub(window).process_key_input(key_event);
+}
+
+/// 未配置时系统按键的默认动作
+fn default_system_key_action(key: SystemKey) -> SystemKeyAction {
+    match key {
+        SystemKey::BrightnessUp => SystemKeyAction::AdjustBacklight(10),
+        SystemKey::BrightnessDown => SystemKeyAction::AdjustBacklight(-10),
+        // 电源键和睡眠键通常由 systemd-logind 等系统服务处理，默认不改变屏幕状态
+        SystemKey::Power | SystemKey::Sleep => SystemKeyAction::Ignore,
+    }
+}
+
+fn is_valid_scale_factor(scale_factor: f32) -> bool {
+    scale_factor.is_finite() && scale_factor > 0.0
+}
+
+/// 输入模块按物理像素产生指针坐标，交给 Slint 前按缩放系数换算为逻辑坐标
+fn to_logical_event(event: WindowEvent, scale_factor: f32) -> WindowEvent {
+    if scale_factor == 1.0 {
+        return event;
+    }
+    map_event_position(event, |position| {
+        LogicalPosition::new(position.x / scale_factor, position.y / scale_factor)
+    })
+}
+
+/// 对指针事件的位置应用 `map`，其他事件保持不变
+fn map_event_position(event: WindowEvent, map: impl Fn(LogicalPosition) -> LogicalPosition) -> WindowEvent {
+    match event {
+        WindowEvent::PointerPressed { position, button } => {
+            WindowEvent::PointerPressed { position: map(position), button }
+        }
+        WindowEvent::PointerReleased { position, button } => {
+            WindowEvent::PointerReleased { position: map(position), button }
+        }
+        WindowEvent::PointerMoved { position } => WindowEvent::PointerMoved { position: map(position) },
+        WindowEvent::PointerScrolled { position, delta_x, delta_y } => {
+            WindowEvent::PointerScrolled { position: map(position), delta_x, delta_y }
+        }
+        event => event,
+    }
+}
+
+/// 按像素数和物理长度 (毫米) 计算 DPI，长度为 0 或结果不在合理范围内时返回 `None`
+fn dots_per_inch(pixels: u32, millimeters: u32) -> Option<f32> {
+    if millimeters == 0 {
+        return None;
+    }
+    let dpi = pixels as f32 * 25.4 / millimeters as f32;
+    (20.0..=1000.0).contains(&dpi).then_some(dpi)
+}
+
+/// 在呈现方式默认的重绘缓冲区类型 `default` 与构建器指定的 `requested` 之间选择
+///
+/// 两个缓冲区交替使用时，`ReusedBuffer` 会把后缓冲区中两帧之前的内容当作上一帧，因此不采用。
+fn resolve_repaint_buffer_type(
+    requested: Option<RepaintBufferType>,
+    default: RepaintBufferType,
+) -> RepaintBufferType {
+    match requested {
+        Some(RepaintBufferType::ReusedBuffer) if default == RepaintBufferType::SwappedBuffers => {
+            tracing::warn!("缓冲区交替使用时不能使用 ReusedBuffer，改用 SwappedBuffers");
+            default
+        }
+        Some(requested) => requested,
+        None => default,
+    }
+}
+
 /// Linux Framebuffer 平台构建器 (V2)
 #[derive(Default)]
 pub struct LinuxFbPlatformBuilder {
     tty_path: Option<PathBuf>,
+    /// 应用提供的已打开 TTY
+    tty_fd: Option<OwnedFd>,
+    /// 不使用 TTY
+    no_tty: bool,
+    /// 运行期间把 TTY 的键盘模式设为 K_OFF
+    tty_keyboard_off: bool,
+    /// 不创建输入管理器
+    no_input: bool,
+    /// 收到 SIGINT/SIGTERM/SIGHUP 时的处理方式
+    signal_policy: SignalPolicy,
     fb_path: Option<PathBuf>,
+    /// 替代 fbdev 的显示设备
+    framebuffer_device: Option<DisplaySource>,
     input_config: InputConfig,
     vsync: bool,
+    debug_overlay: bool,
+    virtual_keyboard: bool,
+    rotation: Rotation,
+    render_thread: bool,
+    /// 整帧复制与填充使用的 blitter，默认为 CPU
+    blitter: Option<Box<dyn Blitter>>,
+    /// 覆盖呈现方式默认的重绘缓冲区类型
+    repaint_buffer_type: Option<RepaintBufferType>,
+    /// 覆盖驱动报告的物理尺寸 (毫米)
+    physical_size_mm: Option<(u32, u32)>,
+    /// 初始的缩放系数
+    scale_factor: Option<f32>,
+    /// 首帧之前填充前后缓冲区的颜色
+    clear_color: Option<Color>,
+    /// 窗口尺寸 (旋转后)，小于屏幕时居中显示
+    window_size: Option<(u32, u32)>,
+    /// 窗口之外区域的填充颜色，默认黑色
+    letterbox_color: Option<Color>,
+    /// 不填充窗口之外的区域
+    keep_letterbox_contents: bool,
+    /// 最后一个窗口关闭后不退出事件循环
+    keep_running_without_windows: bool,
+    /// 无头模式的缓冲区尺寸与像素格式
+    headless: Option<(u32, u32, PixelFormat)>,
+    /// 在桌面窗口中显示无头模式的画面
+    #[cfg(feature = "preview")]
+    preview: bool,
+    virtual_time: bool,
+    #[cfg(feature = "vnc")]
+    vnc_addr: Option<std::net::SocketAddr>,
+    #[cfg(feature = "mjpeg")]
+    mjpeg_addr: Option<std::net::SocketAddr>,
+    idle_callback: Option<IdleCallback>,
+    touch_callback: Option<TouchCallback>,
+    gesture_callback: Option<GestureCallback>,
+    switch_callback: Option<SwitchCallback>,
+    device_callback: Option<DeviceCallback>,
+    backend_event_callback: Option<BackendEventCallback>,
+    event_filter: Option<EventFilter>,
+    watchdog_budget: Option<Duration>,
+    stall_callback: Option<StallCallback>,
+    clipboard_file: Option<PathBuf>,
+    suspend_aware_timers: bool,
+    wake_alarm: bool,
+    backlight_path: Option<PathBuf>,
+    power_policy: Option<PowerPolicy>,
+    beeper: Option<BeeperConfig>,
+    /// 监视显示接口的热插拔
+    display_hotplug: bool,
+    /// 显示接口状态文件，`None` 时自动查找
+    display_connector: Option<PathBuf>,
+    system_key_actions: HashMap<SystemKey, SystemKeyAction>,
+    key_interceptor: Option<KeyInterceptor>,
 }
 
 impl LinuxFbPlatformBuilder {
@@ -87,6 +326,36 @@ impl LinuxFbPlatformBuilder {
         Self::default()
     }
 
+    /// 从 TOML 配置文件创建构建器 (需要 `config` 特性)
+    ///
+    /// 配置项与构建器方法一一对应，格式见 [`config`](crate::config) 模块。
+    /// 回调等无法写在配置文件中的选项可以在加载后继续链式设置：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::LinuxFbPlatformBuilder;
+    /// let platform = LinuxFbPlatformBuilder::from_config_file("/etc/slint-linuxfb.toml")
+    ///     .unwrap()
+    ///     .with_idle_callback(|_| {})
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn from_config_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("无法读取 {:?}: {}", path, e)))?;
+        Self::from_config_str(&text).map_err(|e| match e {
+            Error::Config(message) => Error::Config(format!("{:?}: {}", path, message)),
+            e => e,
+        })
+    }
+
+    /// 从 TOML 文本创建构建器，格式与 [`from_config_file`](Self::from_config_file) 相同
+    #[cfg(feature = "config")]
+    pub fn from_config_str(text: &str) -> Result<Self, Error> {
+        crate::config::apply(Self::new(), text)
+    }
+
     /// 设置 TTY 设备路径 (例如 "/dev/tty3")
     /// 如果不设置，默认尝试使用环境变量 `SLINT_TTY_DEVICE`，然后是 /dev/tty1, /dev/tty0
     pub fn with_tty(mut self, path: impl Into<PathBuf>) -> Self {
@@ -94,13 +363,97 @@ impl LinuxFbPlatformBuilder {
         self
     }
 
+    /// 使用应用已经打开的 TTY (例如启动器持有并负责 VT 切换的终端)
+    ///
+    /// 平台不再自行打开 TTY，而是对该描述符执行 KDSETMODE：启动时切换到图形模式，
+    /// 退出时恢复文本模式。描述符在平台销毁时关闭。优先于 [`with_tty`](Self::with_tty)。
+    pub fn with_tty_fd(mut self, fd: OwnedFd) -> Self {
+        self.tty_fd = Some(fd);
+        self
+    }
+
+    /// 完全不使用 TTY (不切换图形模式)，适用于由其他进程管理控制台的场合
+    ///
+    /// 注意 fbcon 的光标和控制台输出可能会覆盖界面。
+    pub fn without_tty(mut self) -> Self {
+        self.no_tty = true;
+        self
+    }
+
+    /// 运行期间把 TTY 的键盘模式设为 `K_OFF`，默认关闭
+    ///
+    /// 图形模式只是不再显示控制台，在键盘上的输入仍会交给 TTY 上的 shell 或登录程序；
+    /// 开启后这些按键被丢弃，界面通过 evdev 读取的输入不受影响。原来的键盘模式在事件循环退出、
+    /// 平台销毁和 [`restore_tty`] 时恢复。进程被强制杀死 (SIGKILL) 时无法恢复，控制台将不接受键盘输入。
+    pub fn with_tty_keyboard_off(mut self, enable: bool) -> Self {
+        self.tty_keyboard_off = enable;
+        self
+    }
+
+    /// 完全不处理输入，适用于没有任何输入设备的纯显示设备 (例如广告屏)
+    ///
+    /// 不创建输入管理器：不扫描 `/dev/input`、不启动热插拔线程，也不加载键盘映射，
+    /// 其他输入相关的构建器选项随之无效。VNC 的输入和 [`inject_event`](LinuxFbPlatform::inject_event) 注入的事件仍会分发给窗口。
+    pub fn without_input(mut self) -> Self {
+        self.no_input = true;
+        self
+    }
+
+    /// 设置收到 SIGINT、SIGTERM 或 SIGHUP 时的处理方式，默认为 [`SignalPolicy::RestoreTtyAndExit`]
+    ///
+    /// 信号属于整个进程，最后构建的平台的设置生效；平台销毁时恢复系统默认行为。
+    pub fn with_signal_policy(mut self, policy: SignalPolicy) -> Self {
+        self.signal_policy = policy;
+        self
+    }
+
+    /// 是否安装信号处理函数，`false` 等价于 [`SignalPolicy::None`]，`true` 等价于默认的
+    /// [`SignalPolicy::RestoreTtyAndExit`]
+    ///
+    /// 应用自己处理信号 (例如使用 `signal-hook`) 时关闭，并在退出前调用 [`restore_tty`]。
+    pub fn with_signal_handler(self, enable: bool) -> Self {
+        self.with_signal_policy(if enable { SignalPolicy::RestoreTtyAndExit } else { SignalPolicy::None })
+    }
+
     /// 设置 Framebuffer 设备路径 (例如 "/dev/fb1")
-    /// 如果不设置，默认尝试使用环境变量 `SLINT_FRAMEBUFFER`，然后是 /dev/fb0
+    /// 如果不设置，默认尝试使用环境变量 `SLINT_FRAMEBUFFER`，然后在所有 /dev/fb* 中自动选择
+    /// (见 [`select_framebuffer`](crate::diagnostics::select_framebuffer))，都没有时使用 /dev/fb0
     pub fn with_framebuffer(mut self, path: impl Into<PathBuf>) -> Self {
         self.fb_path = Some(path.into());
         self
     }
 
+    /// 使用自定义的显示设备代替 fbdev (例如 SPI 屏幕驱动或测试用的 [`MockFramebuffer`](crate::device::MockFramebuffer))
+    ///
+    /// 设置后不再打开 Framebuffer 设备文件，[`with_framebuffer`](Self::with_framebuffer) 被忽略；
+    /// VSync、呈现线程等其余配置照常生效。
+    ///
+    /// 也可以传入应用已经打开的 [`Framebuffer`]，例如在平台接管之前修改色深：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::{linuxfb::Framebuffer, LinuxFbPlatformBuilder};
+    /// let mut fb = Framebuffer::new("/dev/fb0").unwrap();
+    /// fb.set_bytes_per_pixel(2).unwrap();
+    /// let platform = LinuxFbPlatformBuilder::new().with_framebuffer_device(fb).build().unwrap();
+    /// ```
+    ///
+    /// 此时创建窗口时直接使用该设备，只把虚拟高度调整为两倍屏幕高度以进行双缓冲，
+    /// 其余 vinfo 设置 (色深、时序等) 保持不变。
+    pub fn with_framebuffer_device(mut self, device: impl IntoFramebufferDevice) -> Self {
+        self.framebuffer_device = Some(device.into_source());
+        self
+    }
+
+    /// 使用按区域接收像素的自定义输出代替 Framebuffer (例如用户态 SPI 屏幕驱动)
+    ///
+    /// 平台渲染到内存中的整帧缓冲区，每帧把重绘区域交给 [`DisplaySink::write_region`]，
+    /// 输入、旋转、虚拟键盘等其余功能不变。与 [`with_framebuffer_device`](Self::with_framebuffer_device)
+    /// 互相取代，后设置的生效；VSync 和呈现线程设置对输出无效。
+    pub fn with_display_sink(mut self, sink: impl DisplaySink + 'static) -> Self {
+        self.framebuffer_device = Some(DisplaySource::Sink(Box::new(sink)));
+        self
+    }
+
     /// 配置是否自动发现输入设备
     pub fn with_input_autodiscovery(mut self, enable: bool) -> Self {
         self.input_config.autodiscovery = enable;
@@ -128,201 +481,2216 @@ impl LinuxFbPlatformBuilder {
         self
     }
 
-    /// 启用垂直同步 (VSync)
+    /// 独占 (EVIOCGRAB) 打开的输入设备
     ///
-    /// 如果启用，渲染循环将尝试等待硬件垂直消隐信号。
-    /// 这可以消除撕裂并降低静态画面下的 CPU 占用，但需要 Framebuffer 驱动支持。
-    pub fn with_vsync(mut self, enable: bool) -> Self {
-        self.vsync = enable;
+    /// 避免 getty、gpm 等进程同时读取触摸屏和键盘，造成重复输入和控制台上的杂散字符。
+    /// 设备关闭时自动释放。注意：事件循环运行期间 (例如在回调中) 无法临时释放设备，
+    /// 启用后应在事件循环启动前调用 [`LinuxFbPlatform::run_touch_calibration`]。
+    pub fn with_grab_input_devices(mut self, grab: bool) -> Self {
+        self.input_config.grab_devices = grab;
         self
     }
 
-    /// 构建并初始化平台
-    pub fn build(self) -> Result<LinuxFbPlatform, Error> {
-        LinuxFbPlatform::new_with_config(self)
+    /// 设置自定义输入设备过滤条件，返回 `false` 的设备将被忽略
+    ///
+    /// 在名称白名单/黑名单之后调用，例如只接受指定厂商的触摸屏：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::LinuxFbPlatformBuilder;
+    /// let builder = LinuxFbPlatformBuilder::new().with_input_filter(|device| device.input_id().vendor() == 0x0eef);
+    /// ```
+    pub fn with_input_filter(mut self, filter: impl Fn(&evdev::Device) -> bool + Send + Sync + 'static) -> Self {
+        self.input_config.device_filter = Some(DeviceFilter::new(filter));
+        self
     }
-}
 
-pub struct LinuxFbPlatform {
-    adapter: RefCell<Option<Rc<LinuxFbWindowAdapter>>>,
-    input_manager: RefCell<Option<InputManager>>,
-    tty: Option<File>,
-    config: LinuxFbPlatformBuilder,
+    /// 加载 Linux 控制台键盘映射文件 (例如 "/usr/share/keymaps/de-latin1.map")
+    ///
+    /// 支持 `loadkeys` 文本格式和 `loadkeys -b` 生成的二进制 `bkeymap` 格式 (需先解压)。
+    /// 仅在未启用 `xkb` 特性时生效，用于在没有 xkbcommon 数据的系统上使用非 US 布局。
+    pub fn with_console_keymap(mut self, path: impl Into<PathBuf>) -> Self {
+        self.input_config.console_keymap = Some(path.into());
+        self
+    }
 
-    event_fd: RawFd,
-    quit_flag: Arc<AtomicBool>,
-    event_receiver: Receiver<Box<dyn FnOnce() + Send>>,
-    proxy: LinuxFbProxy,
-}
+    /// 从控制终端 (stdin) 读取键盘输入
+    ///
+    /// 启用后 stdin 被切换到原始模式，按键与转义序列 (方向键、Home/End 等) 被转换为 Slint 按键事件，
+    /// 适合通过 SSH 或串口调试时操作文本框等控件。退出时终端属性会被恢复。
+    /// 不要在 stdin 为本地控制台时启用，否则按键会被 evdev 和终端重复输入。
+    pub fn with_terminal_input(mut self, enable: bool) -> Self {
+        self.input_config.terminal_input = enable;
+        self
+    }
 
-impl LinuxFbPlatform {
-    /// 使用默认配置创建平台
-    pub fn new() -> Result<Self, Error> {
-        LinuxFbPlatformBuilder::new().build()
+    /// 是否连接输入法 (需要 `ime` 特性，默认关闭)
+    ///
+    /// 通过会话总线上的 IBus portal 接口连接 ibus 或 fcitx5，
+    /// 文本输入框获得焦点时按键先交给输入法，预编辑与上屏文本随后送入该输入框。
+    /// 按键在事件循环中同步交给输入法，输入法无响应时每次按键最多等待 200ms。
+    #[cfg(feature = "ime")]
+    pub fn with_ime(mut self, enable: bool) -> Self {
+        self.input_config.ime = enable;
+        self
     }
 
-    fn new_with_config(config: LinuxFbPlatformBuilder) -> Result<Self, Error> {
-        // --- 确定 TTY 路径 ---
-        let tty_path = config.tty_path.clone()
-            .or_else(|| std::env::var("SLINT_TTY_DEVICE").ok().map(PathBuf::from))
-            .or_else(|| Some(PathBuf::from("/dev/tty1")));
+    /// 将所有输入设备的原始事件 (含时间戳) 录制到文件，用于复现现场问题
+    #[cfg(feature = "input-replay")]
+    pub fn with_input_recording(mut self, path: impl Into<PathBuf>) -> Self {
+        self.input_config.record_path = Some(path.into());
+        self
+    }
 
-        // 尝试打开 TTY
-        let tty = if let Some(path) = &tty_path {
-            match OpenOptions::new().read(true).write(true).open(path) {
-                Ok(file) => {
-                    tracing::info!("使用 TTY: {:?}", path);
-                    Some(file)
-                },
-                Err(_) => {
-                    // 如果首选失败且是默认的 tty1，尝试 tty0
-                    if path == &PathBuf::from("/dev/tty1") {
-                        OpenOptions::new().read(true).write(true).open("/dev/tty0").ok()
-                    } else {
-                        None
-                    }
-                }
-            }
-        } else {
-            None
-        };
+    /// 按录制时的节奏回放 [`with_input_recording`](Self::with_input_recording) 录制的事件
+    ///
+    /// 事件按设备名称交给当前打开的同名设备，经过与真实输入相同的处理流程 (包括手势识别)。
+    #[cfg(feature = "input-replay")]
+    pub fn with_input_replay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.input_config.replay_path = Some(path.into());
+        self
+    }
 
-        if let Some(ref tty_file) = tty {
-            // 保存实际打开的路径用于恢复
-            let path_to_save = tty_path.unwrap_or_else(|| PathBuf::from("/dev/tty0"));
-            *ACTIVE_TTY_PATH.lock().unwrap() = Some(path_to_save);
+    /// 使用 tslib 的触摸校准文件 (例如 `/etc/pointercal`)
+    ///
+    /// 文件由 `ts_calibrate` 生成，设置后触摸坐标按其中的仿射参数转换，
+    /// 已有设备的校准结果在切换到本后端后可以继续使用。
+    /// 未设置时会尝试读取环境变量 `TSLIB_CALIBFILE` 指向的文件。
+    pub fn with_pointercal(mut self, path: impl Into<PathBuf>) -> Self {
+        self.input_config.pointercal = Some(path.into());
+        self
+    }
 
-            if let Err(e) = fbio::set_terminal_mode(tty_file, TerminalMode::Graphics) {
-                tracing::warn!("无法将 TTY 切换到图形模式: {}", e);
-            } else {
-                tracing::info!("TTY 已切换到图形模式 (KD_GRAPHICS)。");
-            }
-        } else {
-            tracing::warn!("无法打开 TTY。fbcon 光标可能会干扰 UI。");
-        }
+    /// 设置触摸坐标的旋转方向
+    ///
+    /// 默认与显示旋转方向 ([`with_rotation`](Self::with_rotation)) 一致，
+    /// 仅当触摸层与显示面板的安装方向不同时才需要单独设置。
+    pub fn with_touch_rotation(mut self, rotation: Rotation) -> Self {
+        self.input_config.touch_rotation = Some(rotation);
+        self
+    }
 
-        // --- 注册信号处理器 (处理 SIGINT/SIGTERM) ---
-        let _ = ctrlc::set_handler(move || {
-            tracing::info!("接收到退出信号，正在恢复 TTY...");
-            if let Ok(guard) = ACTIVE_TTY_PATH.lock() {
-                if let Some(ref path) = *guard {
-                    if let Ok(file) = OpenOptions::new().read(true).write(true).open(path) {
-                        let _ = fbio::set_terminal_mode(&file, TerminalMode::Text);
-                    }
-                }
-            }
-            std::process::exit(0);
-        });
+    /// 设置触摸压力阈值 (设备原始单位，与 tslib 的 `pthres` 相同)
+    ///
+    /// 压力低于阈值的触点视为悬停，不会产生按下事件，可避免廉价电阻屏的误触。
+    /// 阈值需根据设备的压力范围 (可用 `evtest` 查看) 选择；设备不报告压力时无效。
+    pub fn with_pressure_threshold(mut self, threshold: i32) -> Self {
+        self.input_config.pressure_threshold = Some(threshold);
+        self
+    }
 
-        // 创建非阻塞的 eventfd
-        let event_fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
-        if event_fd == -1 {
-            return Err(Error::Other(
-                "Failed to create eventfd for event loop".into(),
-            ));
-        }
+    /// 启用触摸坐标平滑 (1€ 滤波器)
+    ///
+    /// 适用于噪声较大的触摸面板：固定的去抖阈值会让慢速拖动出现阶梯状跳动，
+    /// 平滑后指针移动连续。默认参数可通过 `SmoothingConfig::default()` 获得，
+    /// 抖动明显时减小 `min_cutoff`，快速拖动跟手性不足时增大 `beta`。
+    pub fn with_touch_smoothing(mut self, smoothing: SmoothingConfig) -> Self {
+        self.input_config.touch_smoothing = Some(smoothing);
+        self
+    }
 
-        let (sender, receiver) = channel();
-        let quit_flag = Arc::new(AtomicBool::new(false));
+    /// 设置手写笔橡皮擦端接触屏幕时按下的按钮 (默认为左键，与笔尖相同)
+    ///
+    /// 设置为 `None` 时忽略橡皮擦端的接触，只保留悬停移动。
+    pub fn with_pen_eraser_button(mut self, button: Option<PointerEventButton>) -> Self {
+        self.input_config.pen_eraser_button = button;
+        self
+    }
 
-        // 直接创建代理实例
-        let proxy = LinuxFbProxy {
-            quit_flag: quit_flag.clone(),
-            sender,
-            event_fd,
-        };
+    /// 为名称匹配的触摸设备设置单独的映射区域或旋转方向
+    ///
+    /// 可多次调用，设备使用第一个匹配的变换。适用于多块触摸层分别对应屏幕不同部分的场合，例如：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::{LinuxFbPlatformBuilder, input::DeviceTransform};
+    /// let builder = LinuxFbPlatformBuilder::new()
+    ///     .with_device_transform(DeviceTransform::new("Front Panel").with_region(0, 0, 800, 480))
+    ///     .with_device_transform(DeviceTransform::new("Side Panel").with_region(800, 0, 224, 480));
+    /// ```
+    pub fn with_device_transform(mut self, transform: DeviceTransform) -> Self {
+        self.input_config.device_transforms.push(transform);
+        self
+    }
 
-        Ok(Self {
-            adapter: RefCell::new(None),
-            input_manager: RefCell::new(None),
-            tty,
-            config,
-            event_fd,
-            quit_flag,
-            event_receiver: receiver,
-            proxy,
-        })
+    /// 设置指针移动事件的最小间隔 (默认 8ms，约 120Hz)，间隔内多余的移动事件被丢弃
+    pub fn with_move_throttle(mut self, interval: Duration) -> Self {
+        self.input_config.move_throttle = Some(interval);
+        self
     }
-}
 
-impl Drop for LinuxFbPlatform {
-    fn drop(&mut self) {
-        if let Some(ref tty) = self.tty {
-            tracing::info!("正在恢复 TTY 到文本模式 (Drop)...");
-            if let Err(e) = fbio::set_terminal_mode(tty, TerminalMode::Text) {
-                tracing::error!("无法恢复 TTY 到文本模式: {}", e);
-            }
-        }
-        if let Ok(mut guard) = ACTIVE_TTY_PATH.lock() {
-            *guard = None;
-        }
-        if self.event_fd != -1 {
-            unsafe { libc::close(self.event_fd) };
-        }
+    /// 不丢弃也不合并指针移动事件
+    ///
+    /// 适用于手写、签名等需要完整笔迹的场景，以及高采样率的数位板。
+    pub fn disable_move_throttle(mut self) -> Self {
+        self.input_config.move_throttle = None;
+        self
     }
-}
 
-impl Platform for LinuxFbPlatform {
-    fn create_window_adapter(&self) -> Result<Rc<dyn WindowAdapter>, PlatformError> {
-        // --- 获取 Framebuffer 路径 ---
-        let fb_path = self.config.fb_path.clone()
-            .or_else(|| std::env::var("SLINT_FRAMEBUFFER").ok().map(PathBuf::from))
-            .unwrap_or_else(|| PathBuf::from("/dev/fb0"));
-            
-        tracing::info!("打开 Framebuffer 设备: {:?}", fb_path);
+    /// 设置鼠标指针加速 (默认不加速，每个设备计数移动一个像素)
+    ///
+    /// 高分辨率屏幕上可增大 `speed`，或使用 [`AccelProfile::Adaptive`](crate::input::AccelProfile::Adaptive) 让快速移动时指针走得更远。
+    pub fn with_pointer_acceleration(mut self, acceleration: PointerAcceleration) -> Self {
+        self.input_config.pointer_acceleration = acceleration;
+        self
+    }
 
-        let fb = Framebuffer::new(&fb_path).map_err(|e| PlatformError::Other(e.to_string()))?;
-        let vinfo = fb.vinfo.clone();
-        let pixel_format = PixelFormat::from_fb_info(&vinfo);
+    /// 设置指针速度倍数 (鼠标与触摸板，默认 `1.0`)
+    pub fn with_pointer_speed(mut self, speed: f32) -> Self {
+        self.input_config.pointer_acceleration.speed = speed;
+        self
+    }
 
-        if pixel_format == PixelFormat::Unknown {
-            return Err(PlatformError::Other(
-                Error::UnsupportedPixelFormat.to_string(),
-            ));
-        }
+    /// 启用自然滚动 (鼠标滚轮与触摸板的滚动方向反转，触摸屏不受影响)
+    pub fn with_natural_scrolling(mut self, natural: bool) -> Self {
+        self.input_config.natural_scrolling = natural;
+        self
+    }
 
-        let fb_buffer = Buffer::new(fb).map_err(|e| PlatformError::Other(e.to_string()))?;
-        let (width, height) = (fb_buffer.width, fb_buffer.height);
+    /// 设置鼠标滚轮每一格的滚动距离 (默认 20 像素)
+    pub fn with_scroll_step(mut self, step: f32) -> Self {
+        self.input_config.scroll_step = step;
+        self
+    }
 
-        // --- 初始化输入管理器 ---
-        let input_manager = InputManager::new(width, height, self.config.input_config.clone())
-            .map_err(|e| PlatformError::Other(e.to_string()))?;
-            
-        *self.input_manager.borrow_mut() = Some(input_manager);
+    /// 反转水平滚轮的方向 (部分鼠标的倾斜滚轮方向与预期相反)
+    pub fn with_inverted_horizontal_scroll(mut self, invert: bool) -> Self {
+        self.input_config.invert_horizontal_scroll = invert;
+        self
+    }
 
-        // --- 创建 Window Adapter ---
-        let adapter = Rc::<LinuxFbWindowAdapter>::new_cyclic(|weak_adapter| {
-            let window = Rc::new(i_slint_core::api::Window::new(weak_adapter.clone()));
-            let renderer =
-                SoftwareRenderer::new_with_repaint_buffer_type(RepaintBufferType::SwappedBuffers);
+    /// 将名称匹配的设备作为旋转编码器 (旋钮)
+    ///
+    /// 旋转按 `action` 转换为滚动或焦点切换，按压旋钮转换为回车：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::{LinuxFbPlatformBuilder, input::{EncoderAction, EncoderConfig}};
+    /// let builder = LinuxFbPlatformBuilder::new()
+    ///     .with_encoder(EncoderConfig::new("rotary@", EncoderAction::FocusNavigation));
+    /// ```
+    pub fn with_encoder(mut self, encoder: EncoderConfig) -> Self {
+        self.input_config.encoders.push(encoder);
+        self
+    }
 
-            LinuxFbWindowAdapter {
-                window,
-                fb_buffer: RefCell::new(fb_buffer),
-                renderer,
-                pixel_format,
-                needs_redraw: RefCell::new(true),
-            }
-        });
+    /// 启用纯按键导航模式 (用于只有少量硬件按键、没有指针设备的面板)
+    ///
+    /// 不再产生指针事件，启动后自动聚焦第一个可获得焦点的元素：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::{LinuxFbPlatformBuilder, input::{KeypadNavigation, NavigationAction, RawKeyCode}};
+    /// let builder = LinuxFbPlatformBuilder::new().with_keypad_navigation(
+    ///     KeypadNavigation::new()
+    ///         .with_key(RawKeyCode::KEY_DOWN, NavigationAction::FocusNext)
+    ///         .with_key(RawKeyCode::KEY_UP, NavigationAction::FocusPrevious)
+    ///         .with_key(RawKeyCode::KEY_OK, NavigationAction::Activate),
+    /// );
+    /// ```
+    pub fn with_keypad_navigation(mut self, navigation: KeypadNavigation) -> Self {
+        self.input_config.keypad_navigation = Some(navigation);
+        self
+    }
 
-        adapter
-            .renderer
-            .set_window_adapter(&(adapter.clone() as Rc<dyn WindowAdapter>));
-        *self.adapter.borrow_mut() = Some(adapter.clone());
+    /// 设置请求关闭窗口的快捷键，例如 Ctrl+Q
+    ///
+    /// 按下 `key` 且按住的修饰键与 `modifiers` 完全相同时，向窗口分发 `WindowEvent::CloseRequested`
+    /// (该按键不再转发给 Slint)，应用可以在 close-requested 回调中拒绝关闭。
+    /// 电源键等系统按键使用 [`SystemKeyAction::RequestClose`]，见 [`with_system_key_action`](Self::with_system_key_action)。
+    pub fn with_close_shortcut(mut self, key: RawKeyCode, modifiers: Modifiers) -> Self {
+        self.input_config.close_shortcut = Some((key, modifiers));
+        self
+    }
 
-        adapter.window.dispatch_event(WindowEvent::Resized {
-            size: i_slint_core::api::LogicalSize::new(width as f32, height as f32),
-        });
-        adapter
-            .window
-            .dispatch_event(WindowEvent::ScaleFactorChanged { scale_factor: 1.0 });
+    /// 设置键盘自动重复的延迟和间隔 (默认 250ms / 33ms)
+    pub fn with_key_repeat(mut self, delay: Duration, period: Duration) -> Self {
+        self.input_config.key_repeat = Some(KeyRepeat { delay, period });
+        self
+    }
 
-        Ok(adapter)
+    /// 禁用键盘自动重复
+    ///
+    /// 适用于扫码枪等以键盘方式输入的设备，避免按键被误识别为重复输入。
+    pub fn disable_key_repeat(mut self) -> Self {
+        self.input_config.key_repeat = None;
+        self
     }
 
-    fn run_event_loop(&self) -> Result<(), PlatformError> {
-        let adapter = self
-            .adapter
+    /// 设置背光设备目录 (例如 "/sys/class/backlight/backlight")
+    /// 如果不设置，使用 /sys/class/backlight 下的第一个设备
+    pub fn with_backlight(mut self, path: impl Into<PathBuf>) -> Self {
+        self.backlight_path = Some(path.into());
+        self
+    }
+
+    /// 设置空闲电源策略：无输入一段时间后依次调暗画面、降低背光、关闭显示和断电
+    ///
+    /// 任何输入都会恢复正常显示，唤醒关闭的屏幕的那次输入不会交给界面。
+    /// 各阶段见 [`PowerStage`](crate::power::PowerStage)。
+    pub fn with_power_policy(mut self, policy: PowerPolicy) -> Self {
+        self.power_policy = Some(policy);
+        self
+    }
+
+    /// 按键按下或轻点时通过蜂鸣器 (EV_SND) 发出提示音
+    ///
+    /// 设备支持 `SND_TONE` 时按配置的频率鸣响，否则使用 `SND_BELL`。找不到蜂鸣器时
+    /// 以 [`BackendIssue::ServiceUnavailable`] 报告，不影响其他功能。
+    pub fn with_beeper(mut self, config: BeeperConfig) -> Self {
+        self.beeper = Some(config);
+        self
+    }
+
+    /// 监视 HDMI 等显示接口的热插拔：显示器接入时重新设置模式并重新配置缓冲区
+    ///
+    /// 应用启动后才插入线缆时，部分驱动不会为新的显示器设置模式，屏幕保持黑屏。
+    /// 启用后事件循环每秒读取一次 `/sys/class/drm` 下第一个 HDMI 连接器 (其次是 extcon 设备) 的状态，
+    /// 见 [`with_display_connector`](Self::with_display_connector)。
+    pub fn with_display_hotplug(mut self, enable: bool) -> Self {
+        self.display_hotplug = enable;
+        self
+    }
+
+    /// 指定监视的显示接口并启用热插拔检测
+    ///
+    /// `path` 为 DRM 连接器目录 (例如 "/sys/class/drm/card0-HDMI-A-1")、其中的 `status` 文件，
+    /// 或 extcon 设备的 `state` 文件。
+    pub fn with_display_connector(mut self, path: impl Into<PathBuf>) -> Self {
+        self.display_hotplug = true;
+        self.display_connector = Some(path.into());
+        self
+    }
+
+    /// 设置系统按键 (亮度、电源、睡眠键) 的动作
+    ///
+    /// 默认情况下亮度键按 10% 调整背光，电源键和睡眠键被忽略 (可设置为 [`SystemKeyAction::ToggleBlank`] 关闭/恢复屏幕显示)。
+    /// 系统按键不会作为普通按键事件转发给 Slint。
+    pub fn with_system_key_action(mut self, key: SystemKey, action: SystemKeyAction) -> Self {
+        self.system_key_actions.insert(key, action);
+        self
+    }
+
+    /// 设置原始按键拦截器，用于实现全局快捷键 (例如 Ctrl+Alt+Del 打开维护菜单)
+    ///
+    /// 拦截器在每个键盘按键事件转换为 Slint 事件之前调用，参数为 evdev 键码、
+    /// 值 (0 释放 / 1 按下 / 2 重复) 和当前的修饰键状态。返回 `true` 表示事件已被消费，
+    /// 不会再转发给 Slint 或作为系统按键处理。无论 Slint 中是否有元素获得焦点都会调用。
+    pub fn with_key_interceptor(
+        mut self,
+        interceptor: impl FnMut(RawKeyCode, i32, Modifiers) -> bool + 'static,
+    ) -> Self {
+        self.key_interceptor = Some(Box::new(interceptor));
+        self
+    }
+
+    /// 启用垂直同步 (VSync)
+    ///
+    /// 如果启用，渲染循环将尝试等待硬件垂直消隐信号。
+    /// 这可以消除撕裂并降低静态画面下的 CPU 占用，但需要 Framebuffer 驱动支持。
+    pub fn with_vsync(mut self, enable: bool) -> Self {
+        self.vsync = enable;
+        self
+    }
+
+    /// 设置显示旋转方向 (顺时针)
+    ///
+    /// 由软件渲染器 (`SoftwareRenderer::set_rendering_rotation`) 直接按旋转后的方向绘制到缓冲区，
+    /// 不需要额外的整帧旋转复制；旋转 90 或 270 度时窗口的宽高互换。
+    /// 触摸坐标默认随之旋转，见 [`with_touch_rotation`](Self::with_touch_rotation)。
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// 设置屏幕的物理尺寸 (毫米)，覆盖驱动报告的值
+    ///
+    /// 很多 fbdev 驱动报告 0x0 或错误的物理尺寸，按 DPI 计算缩放系数时会得到错误的结果。
+    /// 尺寸按面板方向 (旋转前) 给出，影响 [`LinuxFbPlatform::physical_size_mm`]、
+    /// [`LinuxFbPlatform::dpi`] 和 [`probe`](Self::probe) 的报告。
+    pub fn with_physical_size_mm(mut self, width: u32, height: u32) -> Self {
+        self.physical_size_mm = Some((width, height));
+        self
+    }
+
+    /// 设置界面的缩放系数 (每个逻辑像素对应的物理像素数)
+    ///
+    /// 未设置时使用环境变量 `SLINT_SCALE_FACTOR`，默认为 1。运行时可以通过
+    /// [`LinuxFbPlatform::set_scale_factor`] 修改，例如按 [`LinuxFbPlatform::dpi`] 计算。
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = Some(scale_factor);
+        self
+    }
+
+    /// 打开显示设备后、第一帧之前，用 `color` 填充前后两个缓冲区
+    ///
+    /// 双缓冲的后半部分显存在启动时是残留的随机内容，第一次翻转时会闪现一下。
+    /// 设置后两个缓冲区都先填充为纯色 (忽略透明度)，通常设为界面的背景色。
+    /// 显示模式改变后重新配置缓冲区时同样生效。
+    pub fn with_clear_color(mut self, color: Color) -> Self {
+        self.clear_color = Some(color);
+        self
+    }
+
+    /// 设置窗口尺寸 (旋转后的物理像素)，窗口在屏幕上居中显示
+    ///
+    /// 用于界面按固定分辨率设计而屏幕更大的场合 (例如 4:3 的界面显示在 16:9 的屏幕上)，超出屏幕的部分被截掉。
+    /// 窗口之外的区域按 [`with_letterbox_color`](Self::with_letterbox_color) 填充，输入设备的坐标换算到窗口内。
+    /// [`with_clear_color`](Self::with_clear_color) 和隐藏窗口时的清屏只作用于窗口区域。
+    ///
+    /// 界面先渲染到窗口大小的缓冲区再复制到屏幕，[`with_repaint_buffer_type`](Self::with_repaint_buffer_type) 不再生效。
+    pub fn with_window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = Some((width, height));
+        self
+    }
+
+    /// 设置窗口之外区域 (见 [`with_window_size`](Self::with_window_size)) 的填充颜色，默认黑色
+    ///
+    /// 在打开显示设备后和显示模式改变后填充 (忽略透明度)，此后后端不再改写这些区域。
+    pub fn with_letterbox_color(mut self, color: Color) -> Self {
+        self.letterbox_color = Some(color);
+        self.keep_letterbox_contents = false;
+        self
+    }
+
+    /// 不填充窗口之外的区域，保持显存中原有的内容 (例如启动画面)
+    ///
+    /// 呈现线程模式下每帧复制整个影子缓冲区，窗口之外的区域始终是黑色。
+    pub fn keep_letterbox_contents(mut self) -> Self {
+        self.keep_letterbox_contents = true;
+        self
+    }
+
+    /// 窗口之外区域的填充颜色，`None` 表示保持原样
+    fn letterbox_fill(&self) -> Option<Color> {
+        (!self.keep_letterbox_contents).then(|| self.letterbox_color.unwrap_or(Color::from_rgb_u8(0, 0, 0)))
+    }
+
+    /// 最后一个窗口关闭 (隐藏) 时是否退出事件循环，默认退出
+    ///
+    /// 与其他 Slint 后端一致，默认情况下应用隐藏唯一的窗口 (包括 close-requested 回调允许关闭)
+    /// 后 `run_event_loop` 返回。设为 `false` 时事件循环继续运行，效果等同于调用
+    /// `slint::run_event_loop_until_quit`，只能通过 `slint::quit_event_loop` 或系统按键退出。
+    pub fn with_quit_on_last_window_closed(mut self, enable: bool) -> Self {
+        self.keep_running_without_windows = !enable;
+        self
+    }
+
+    /// 启用调试叠加层
+    ///
+    /// 启用后每一帧都会在画面上绘制 FPS 计数器、本帧损坏区域的轮廓以及当前触点的标记。
+    /// 主要用于新硬件的调试，会带来额外的绘制开销，不建议在生产环境中开启。
+    pub fn with_debug_overlay(mut self, enable: bool) -> Self {
+        self.debug_overlay = enable;
+        self
+    }
+
+    /// 启用屏幕虚拟键盘
+    ///
+    /// 适用于没有实体键盘的触摸设备：文本输入框获得焦点时，键盘从屏幕底部弹出，
+    /// 点击按键会向输入框发送对应的按键事件；焦点离开输入框或点击 HIDE 键时收起。
+    /// 键盘覆盖在应用画面之上，会遮挡屏幕下方约五分之二的区域。
+    pub fn with_virtual_keyboard(mut self, enable: bool) -> Self {
+        self.virtual_keyboard = enable;
+        self
+    }
+
+    /// 启用独立的呈现线程
+    ///
+    /// 启用后 Slint 轮流渲染到内存中的两个影子缓冲区，渲染完成的缓冲区经帧队列交给独立线程
+    /// 复制到 Framebuffer、等待 VSync 和翻转，事件循环在此期间可以继续渲染下一帧、处理输入和定时器。
+    /// 两个缓冲区都在排队或呈现时暂停渲染 (输入照常处理)，直到呈现线程归还缓冲区，帧不会堆积。
+    ///
+    /// 注意：Slint 场景本身的软件渲染仍在事件循环线程中进行 (Slint 对象不能跨线程)。
+    /// 此外，影子缓冲区会额外占用两帧大小的内存，且每帧需要一次完整复制。
+    pub fn with_render_thread(mut self, enable: bool) -> Self {
+        self.render_thread = enable;
+        self
+    }
+
+    /// 设置整帧复制和填充使用的 [`Blitter`]，默认为 [`CpuBlitter`] (memcpy)
+    ///
+    /// 呈现线程 ([`with_render_thread`](Self::with_render_thread)) 每帧把影子缓冲区复制到 Framebuffer，
+    /// 在 i.MX6/8 上这是主要的 CPU 开销之一；启用 `g2d` 特性后传入 `G2dBlitter::open()?` 交给 2D 引擎 (GPU 或 PXP)。
+    /// 影子缓冲区由 blitter 分配，首帧之前的背景色填充 ([`with_clear_color`](Self::with_clear_color)) 也经过 blitter。
+    /// 显示旋转仍由软件渲染器完成。
+    pub fn with_blitter(mut self, blitter: impl Blitter + 'static) -> Self {
+        self.blitter = Some(Box::new(blitter));
+        self
+    }
+
+    /// 设置渲染器的重绘缓冲区类型，决定每帧可以复用多少上一帧的内容
+    ///
+    /// 默认与呈现方式一致：直接模式的前后缓冲区和呈现线程的两个影子缓冲区交替使用，使用 `SwappedBuffers`；
+    /// 无头模式的内存缓冲区保留上一帧，使用 `ReusedBuffer`。
+    /// 驱动不保留缓冲区内容 (例如部分 SPI 屏幕) 时可设置为 `NewBuffer`，每帧整屏重绘。
+    /// 直接模式和呈现线程模式下设置 `ReusedBuffer` 会留下过期的画面，因此会被忽略并记录警告。
+    pub fn with_repaint_buffer_type(mut self, repaint_buffer_type: RepaintBufferType) -> Self {
+        self.repaint_buffer_type = Some(repaint_buffer_type);
+        self
+    }
+
+    /// 无头模式：不打开 Framebuffer 和 TTY，渲染到 `width` x `height` 的内存缓冲区
+    ///
+    /// 同时关闭输入设备自动发现和终端输入，输入只来自 [`LinuxFbPlatform::inject_event`]
+    /// (以及显式配置的 VNC、输入回放等)。用于在 CI 等没有 `/dev/fb0` 的环境中运行界面集成测试，
+    /// 渲染结果通过 [`LinuxFbPlatform::headless_frame`] 读取：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::{pixels::PixelFormat, LinuxFbPlatformBuilder};
+    /// let platform = LinuxFbPlatformBuilder::new()
+    ///     .with_headless(800, 480, PixelFormat::Bgra8888)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn with_headless(mut self, width: u32, height: u32, format: PixelFormat) -> Self {
+        self.headless = Some((width, height, format));
+        self.input_config.autodiscovery = false;
+        self.input_config.terminal_input = false;
+        self
+    }
+
+    /// 使用虚拟时间 (用于确定性测试)
+    ///
+    /// 启用后手势识别 (长按、轻点、惯性滚动等) 和 Slint 的定时器、动画都只在
+    /// [`LinuxFbPlatform::step`] 推进时间时前进，事件循环等待输入时也不再休眠。
+    /// 通常与 [`with_headless`](Self::with_headless) 一起使用：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::{pixels::PixelFormat, LinuxFbPlatformBuilder};
+    /// # use evdev::{AbsoluteAxisCode, EventType, InputEvent};
+    /// # use std::time::Duration;
+    /// let platform = LinuxFbPlatformBuilder::new()
+    ///     .with_headless(800, 480, PixelFormat::Bgra8888)
+    ///     .with_virtual_time(true)
+    ///     .build()
+    ///     .unwrap();
+    /// // ... 设置平台并显示窗口 ...
+    /// let abs = |code: AbsoluteAxisCode, value| InputEvent::new(EventType::ABSOLUTE.0, code.0, value);
+    /// let syn = InputEvent::new(EventType::SYNCHRONIZATION.0, 0, 0);
+    /// // 在 (100, 100) 处按下手指，保持 700ms 触发长按
+    /// platform.inject_input_events([
+    ///     abs(AbsoluteAxisCode::ABS_MT_TRACKING_ID, 1),
+    ///     abs(AbsoluteAxisCode::ABS_MT_POSITION_X, 100),
+    ///     abs(AbsoluteAxisCode::ABS_MT_POSITION_Y, 100),
+    ///     syn,
+    /// ]);
+    /// platform.step(Duration::ZERO).unwrap();
+    /// platform.inject_input_events([syn]);
+    /// platform.step(Duration::from_millis(700)).unwrap();
+    /// ```
+    pub fn with_virtual_time(mut self, enable: bool) -> Self {
+        self.virtual_time = enable;
+        self
+    }
+
+    /// 桌面预览：在开发机上打开 `width` x `height` (屏幕尺寸) 的窗口代替 Framebuffer
+    ///
+    /// 基于无头模式，画面与设备上使用相同的渲染流程 (包括旋转)。鼠标左键模拟单指触摸、
+    /// 右键模拟双指触摸，键盘按键映射为 evdev 键码，都经过与设备上相同的手势识别和键盘映射。
+    /// 关闭窗口时事件循环退出。
+    #[cfg(feature = "preview")]
+    pub fn with_preview(self, width: u32, height: u32) -> Self {
+        let mut builder = self.with_headless(width, height, PixelFormat::Bgra8888);
+        builder.preview = true;
+        builder
+    }
+
+    /// 在 `addr` 上启动 VNC 服务器，用于远程查看和控制界面
+    ///
+    /// 连接不加密也不认证，请只监听本机地址或在 VPN / SSH 隧道中使用。
+    /// 客户端看到的是屏幕 (旋转后的 Framebuffer) 画面，输入坐标会转换回窗口坐标。
+    #[cfg(feature = "vnc")]
+    pub fn with_vnc_server(mut self, addr: std::net::SocketAddr) -> Self {
+        self.vnc_addr = Some(addr);
+        self
+    }
+
+    /// 在 `addr` 上启动 MJPEG 推流 HTTP 服务器 (只读，比 VNC 更轻量)
+    ///
+    /// 访问 `http://<addr>/` 得到连续画面，`http://<addr>/snapshot.jpg` 得到当前画面的截图。
+    #[cfg(feature = "mjpeg")]
+    pub fn with_mjpeg_server(mut self, addr: std::net::SocketAddr) -> Self {
+        self.mjpeg_addr = Some(addr);
+        self
+    }
+
+    /// 设置空闲回调
+    ///
+    /// 回调在每次循环迭代中、事件循环进入 poll 休眠之前调用，参数为本次将要休眠的时长；
+    /// 没有任何定时任务、事件循环将无限期等待输入或唤醒时为 `Duration::MAX`。
+    /// 适合执行低优先级的后台工作 (刷新日志、清理缓存等)，这些工作只会在 UI 空闲时运行。
+    /// 回调应尽快返回，否则会推迟输入处理和下一帧渲染。
+    pub fn with_idle_callback(mut self, callback: impl FnMut(Duration) + 'static) -> Self {
+        self.idle_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// 设置启用的触摸手势 (默认全部启用)
+    ///
+    /// 例如关闭长按右键，避免用户按住按钮时误触发：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::{LinuxFbPlatformBuilder, input::GestureConfig};
+    /// let builder = LinuxFbPlatformBuilder::new().with_gestures(GestureConfig {
+    ///     long_press_right_click: false,
+    ///     ..Default::default()
+    /// });
+    /// ```
+    pub fn with_gestures(mut self, gestures: GestureConfig) -> Self {
+        self.input_config.gestures = gestures;
+        self
+    }
+
+    /// 设置触摸手势回调
+    ///
+    /// 用于接收 Slint 指针事件无法表达的手势，例如双指捏合与多指点击/滑动
+    /// (需在 [`GestureConfig`] 中开启 `pinch_zoom` / `multi_finger`)。
+    pub fn with_gesture_callback(mut self, callback: impl FnMut(&TouchGesture) + 'static) -> Self {
+        self.gesture_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// 设置输入事件过滤器
+    ///
+    /// 每个由输入设备产生的 [`WindowEvent`] 在分发给窗口之前都会交给过滤器，
+    /// 过滤器可以原样返回、修改或返回 `None` 丢弃该事件。
+    /// 例如在展台模式下屏蔽某些按键，或统计触摸次数：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::LinuxFbPlatformBuilder;
+    /// # use i_slint_core::platform::WindowEvent;
+    /// let builder = LinuxFbPlatformBuilder::new().with_event_filter(|event| match &event {
+    ///     WindowEvent::KeyPressed { text } | WindowEvent::KeyReleased { text } if text == "\u{1b}" => None,
+    ///     _ => Some(event),
+    /// });
+    /// ```
+    pub fn with_event_filter(mut self, filter: impl FnMut(WindowEvent) -> Option<WindowEvent> + 'static) -> Self {
+        self.event_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// 设置开关回调 (盖子、平板模式、扩展坞)
+    ///
+    /// 例如盖子合上时关闭屏幕，或连接扩展坞时切换布局。
+    /// 设备打开时已激活的开关 (例如启动时盖子已合上) 也会报告一次。
+    pub fn with_switch_callback(mut self, callback: impl FnMut(Switch, bool) + 'static) -> Self {
+        self.switch_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// 设置设备添加与移除回调 (例如提示"键盘已连接"，或在触摸屏断开时切换界面)
+    ///
+    /// 启动时已连接的设备会在事件循环开始后各报告一次添加。
+    pub fn with_device_callback(mut self, callback: impl FnMut(&DeviceEvent) + 'static) -> Self {
+        self.device_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// 设置运行时问题回调 (例如在界面上提示"触摸屏已断开"，或上报遥测数据)
+    ///
+    /// 回调在事件循环线程中调用。无论是否设置回调，这些问题都会同时输出到日志。
+    pub fn with_backend_event_callback(mut self, callback: impl FnMut(&BackendEvent) + 'static) -> Self {
+        self.backend_event_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// 启用原始多点触控模式，逐个触点回调给应用
+    ///
+    /// 启用后不再合成触摸手势 (长按右键、双指滚动)，每个手指的按下、移动和抬起
+    /// 都以 [`TouchContact`] 报告，适用于自行实现旋转、缩放等多指交互的应用。
+    /// 第一个按下的手指仍作为左键指针发送给窗口，使普通控件可以点击。
+    pub fn with_touch_callback(mut self, callback: impl FnMut(&TouchContact) + 'static) -> Self {
+        self.input_config.raw_touch = true;
+        self.touch_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// 设置剪贴板桥接文件 (例如 "/run/slint-clipboard")
+    ///
+    /// 剪贴板默认只在进程内有效。设置后，复制的文本会同时写入该文件，
+    /// 粘贴时优先读取文件内容，以便与本机上的其他进程共享剪贴板。
+    pub fn with_clipboard_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.clipboard_file = Some(path.into());
+        self
+    }
+
+    /// 使用感知系统挂起的时钟 (`CLOCK_BOOTTIME`) 驱动 Slint 定时器和动画
+    ///
+    /// 默认时钟在系统挂起期间停止，恢复后定时器会被推迟整个挂起时长。
+    /// 启用后挂起时间也会计入，恢复后已经到期的定时器立即触发，
+    /// 同时整个画面会被重绘 (部分驱动在挂起期间不保留 Framebuffer 内容)。
+    pub fn with_suspend_aware_timers(mut self, enable: bool) -> Self {
+        self.suspend_aware_timers = enable;
+        self
+    }
+
+    /// 使用 `CLOCK_BOOTTIME_ALARM` 定时器，使 Slint 定时器到期时能把系统从挂起中唤醒
+    ///
+    /// 需要 `CAP_WAKE_ALARM` 权限，没有权限时退回普通的 `CLOCK_BOOTTIME`。
+    /// 启用该选项隐含 [`with_suspend_aware_timers`](Self::with_suspend_aware_timers)。
+    pub fn with_wake_alarm(mut self, enable: bool) -> Self {
+        self.wake_alarm = enable;
+        self
+    }
+
+    /// 启用事件循环卡顿看门狗
+    ///
+    /// 看门狗线程监视每次循环迭代 (输入处理、渲染、VSync 等待与翻转) 的耗时，
+    /// 超过 `budget` 时调用 [`with_stall_callback`](Self::with_stall_callback) 设置的回调，
+    /// 未设置回调时记录错误日志。即使循环阻塞在驱动 ioctl 中无法返回，卡顿也能被报告。
+    ///
+    /// 如果超时发生在等待 VSync 时，循环恢复后会停用硬件 VSync 并改用软件帧节奏，
+    /// 避免之后的每一帧都卡在同一个驱动调用上。
+    pub fn with_watchdog(mut self, budget: Duration) -> Self {
+        self.watchdog_budget = Some(budget);
+        self
+    }
+
+    /// 设置卡顿回调
+    ///
+    /// 回调在看门狗线程上调用 (此时事件循环可能仍处于阻塞状态)，参数为当前迭代已持续的时长。
+    /// 每次卡顿的迭代只调用一次。仅在通过 [`with_watchdog`](Self::with_watchdog) 启用看门狗后生效。
+    pub fn with_stall_callback(
+        mut self,
+        callback: impl Fn(Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.stall_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// 按当前配置试探显示设备和输入设备，不创建平台 (用于安装时的验证工具)
+    ///
+    /// 打开 Framebuffer，识别分辨率、像素格式并试探双缓冲平移 (试探后恢复原来的显示设置，
+    /// 画面保持不变)，再按自动发现、白名单/黑名单和过滤条件列出会使用的输入设备。
+    /// 所有设备在返回前关闭，构建器不受影响，随后仍可调用 [`build`](Self::build)：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::LinuxFbPlatformBuilder;
+    /// let builder = LinuxFbPlatformBuilder::new().with_framebuffer("/dev/fb1");
+    /// match builder.probe() {
+    ///     Ok(report) if report.is_usable() => println!("{}", report),
+    ///     Ok(report) => eprintln!("显示设备不可用:\n{}", report),
+    ///     Err(e) => eprintln!("{} ({})", e, e.hint().unwrap_or_default()),
+    /// }
+    /// ```
+    ///
+    /// Framebuffer 无法打开时返回与 [`build`](Self::build) 之后创建窗口时相同的错误。
+    /// 使用自定义显示设备或无头模式时只报告其尺寸与像素格式。
+    pub fn probe(&self) -> Result<ProbeReport, Error> {
+        let mut report = match (&self.headless, &self.framebuffer_device) {
+            (Some((width, height, format)), _) => ProbeReport {
+                framebuffer: None,
+                size: (*width, *height),
+                pixel_format: *format,
+                refresh_rate: None,
+                physical_size_mm: None,
+                panning: Ok(()),
+                input_devices: Vec::new(),
+            },
+            (None, Some(DisplaySource::Framebuffer(fb))) => diagnostics::probe_display(fb)?,
+            (None, Some(DisplaySource::Device(device))) => ProbeReport {
+                framebuffer: None,
+                size: device.size(),
+                pixel_format: device.pixel_format(),
+                refresh_rate: device.refresh_rate(),
+                physical_size_mm: None,
+                panning: Ok(()),
+                input_devices: Vec::new(),
+            },
+            (None, Some(DisplaySource::Sink(sink))) => ProbeReport {
+                framebuffer: None,
+                size: sink.size(),
+                pixel_format: sink.pixel_format(),
+                refresh_rate: None,
+                physical_size_mm: None,
+                panning: Ok(()),
+                input_devices: Vec::new(),
+            },
+            (None, None) => {
+                let path = self.framebuffer_path();
+                let fb = Framebuffer::new(&path).map_err(|e| Error::from_framebuffer(&path, e))?;
+                diagnostics::probe_display(&fb)?
+            }
+        };
+        report.physical_size_mm = self.physical_size_mm.or(report.physical_size_mm);
+        report.input_devices = diagnostics::probe_input_devices(&self.input_config);
+        Ok(report)
+    }
+
+    /// 使用的 Framebuffer 路径：构建器设置、环境变量 `SLINT_FRAMEBUFFER`、自动选择，最后是 /dev/fb0
+    fn framebuffer_path(&self) -> PathBuf {
+        self.fb_path.clone()
+            .or_else(|| std::env::var("SLINT_FRAMEBUFFER").ok().map(PathBuf::from))
+            .or_else(diagnostics::select_framebuffer)
+            .unwrap_or_else(|| PathBuf::from("/dev/fb0"))
+    }
+
+    /// 构建并初始化平台
+    pub fn build(self) -> Result<LinuxFbPlatform, Error> {
+        LinuxFbPlatform::new_with_config(self)
+    }
+
+}
+
+/// Linux Framebuffer 平台
+///
+/// 该类型是一个轻量句柄 (内部为 `Rc`)，可以 `clone()` 后交给 Slint，
+/// 同时在应用侧保留一份用于调用 [`LinuxFbPlatform::run_event_loop_until`] 等扩展接口:
+///
+/// ```no_run
+/// # use slint_backend_linuxfb::LinuxFbPlatformBuilder;
+/// let platform = LinuxFbPlatformBuilder::new().build().unwrap();
+/// i_slint_core::platform::set_platform(Box::new(platform.clone())).unwrap();
+/// // ... 创建并显示窗口 ...
+/// platform.run_event_loop_until(|| std::path::Path::new("/tmp/done").exists()).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct LinuxFbPlatform {
+    inner: Rc<PlatformInner>,
+}
+
+/// 平台的共享内部状态，由所有 `LinuxFbPlatform` 句柄共同持有
+struct PlatformInner {
+    adapter: RefCell<Option<Rc<LinuxFbWindowAdapter>>>,
+    input_manager: RefCell<Option<InputManager>>,
+    tty: Option<File>,
+    /// 关闭 TTY 键盘前的键盘模式，退出时恢复
+    tty_keyboard_mode: Option<KeyboardMode>,
+    config: LinuxFbPlatformBuilder,
+
+    event_fd: RawFd,
+    quit_flag: Arc<AtomicBool>,
+    /// 本平台安装的信号处理动作
+    signal_token: Option<u64>,
+    event_receiver: Receiver<Box<dyn FnOnce() + Send>>,
+    proxy: LinuxFbProxy,
+    idle_callback: RefCell<Option<IdleCallback>>,
+    touch_callback: RefCell<Option<TouchCallback>>,
+    gesture_callback: RefCell<Option<GestureCallback>>,
+    switch_callback: RefCell<Option<SwitchCallback>>,
+    device_callback: RefCell<Option<DeviceCallback>>,
+    backend_event_callback: RefCell<Option<BackendEventCallback>>,
+    event_filter: RefCell<Option<EventFilter>>,
+    /// 构建器提供的显示设备，创建窗口时取出
+    framebuffer_device: RefCell<Option<DisplaySource>>,
+    /// 整帧复制与填充使用的 blitter (与呈现线程共享)
+    blitter: SharedBlitter,
+    frame_stats: RefCell<FrameStatsCollector>,
+    /// 最近一次轮询输入后的指针与触摸状态
+    pointer_state: RefCell<PointerState>,
+    /// 硬件 VSync 是否可用 (首次等待失败后置为 false)
+    vsync_supported: Cell<bool>,
+    /// 硬件 VSync 不可用时的软件帧节奏控制
+    frame_pacer: RefCell<Option<FramePacer>>,
+    /// 按呈现时间实测的刷新间隔 (软件帧节奏使用)
+    refresh_meter: RefCell<RefreshMeter>,
+    /// 最近一次提交给呈现线程的帧的渲染耗时
+    last_render_time: Cell<Duration>,
+    watchdog: Option<Watchdog>,
+    clipboard: ClipboardStore,
+    /// 感知挂起的时钟 (启用时替代默认的单调时钟)
+    clock: Option<RefCell<SuspendAwareClock>>,
+    /// 默认时钟的起点
+    start_instant: Instant,
+    /// Framebuffer 设备的控制句柄 (用于 FBIOBLANK 和查询显示模式，与呈现方式无关)
+    fb_control: RefCell<Option<File>>,
+    /// 后端打开的 Framebuffer 的路径，显示模式改变后按此重新打开
+    fb_open_path: RefCell<Option<PathBuf>>,
+    /// 上一次检查显示模式的时间
+    last_mode_check: Cell<Instant>,
+    /// 下一次迭代立即检查显示模式 (VSync 或翻转失败后)
+    mode_check_due: Cell<bool>,
+    /// 直接模式下翻转失败后进行中的设备恢复，以及下一次重新打开设备的时间 (期间不渲染)
+    display_recovery: RefCell<Option<(DeviceRecovery, Instant)>>,
+    /// VNC 服务器 (创建窗口时启动)
+    #[cfg(feature = "vnc")]
+    vnc: RefCell<Option<VncServer>>,
+    /// MJPEG 推流服务器 (创建窗口时启动)
+    #[cfg(feature = "mjpeg")]
+    mjpeg: RefCell<Option<MjpegServer>>,
+    /// 桌面预览窗口 (创建窗口时打开)
+    #[cfg(feature = "preview")]
+    preview: RefCell<Option<PreviewWindow>>,
+    /// 正在进行的屏幕录制
+    recorder: RefCell<Option<Recorder>>,
+    /// 屏幕当前是否处于关闭 (blank) 状态
+    screen_blanked: Cell<bool>,
+    /// 背光设备 (首次使用时打开)
+    backlight: RefCell<Option<Backlight>>,
+    /// 空闲电源策略的状态
+    idle_tracker: RefCell<Option<IdleTracker>>,
+    /// 电源策略降低背光前的亮度，恢复时使用
+    saved_backlight: Cell<Option<u32>>,
+    /// 操作反馈蜂鸣器 (创建窗口时打开)
+    beeper: RefCell<Option<Beeper>>,
+    /// 显示接口热插拔监视 (创建窗口时打开)
+    connector: RefCell<Option<ConnectorMonitor>>,
+    /// 进行中的背光渐变 (sysfs 原始亮度值)
+    backlight_fade: RefCell<Option<Fade>>,
+    /// 进行中的软件亮度渐变
+    brightness_fade: RefCell<Option<Fade>>,
+    /// OSD 图层，与窗口适配器共享
+    osd: Rc<RefCell<OsdLayers>>,
+    /// 已经为 "最后一个窗口关闭后不退出" 登记过占位窗口
+    window_count_held: Cell<bool>,
+    /// 界面的缩放系数
+    scale_factor: Cell<f32>,
+    /// 缩放系数在运行时被修改，等待在下一次迭代中分发
+    scale_factor_changed: Cell<bool>,
+    /// 等待交给输入管理器的按键拦截器
+    key_interceptor: RefCell<Option<KeyInterceptor>>,
+    /// 交互式校准得到、尚未应用到输入管理器的校准参数
+    pending_touch_calibration: Cell<Option<TouchCalibration>>,
+    /// 等待应用到输入管理器的设备启用状态变化 (名称或路径, 是否启用)
+    pending_device_states: RefCell<Vec<(String, bool)>>,
+    /// 等待分发的注入事件
+    injected_events: RefCell<Vec<WindowEvent>>,
+    /// 等待交给输入管理器的注入 evdev 事件
+    injected_input: RefCell<Vec<evdev::InputEvent>>,
+}
+
+impl LinuxFbPlatform {
+    /// 使用默认配置创建平台
+    pub fn new() -> Result<Self, Error> {
+        LinuxFbPlatformBuilder::new().build()
+    }
+
+    fn new_with_config(mut config: LinuxFbPlatformBuilder) -> Result<Self, Error> {
+        // --- 确定 TTY 路径 (无头模式不使用 TTY) ---
+        let provided_tty = config.tty_fd.take().map(File::from);
+        let tty_path = if config.headless.is_some() || config.no_tty || provided_tty.is_some() {
+            None
+        } else {
+            config.tty_path.clone()
+            .or_else(|| std::env::var("SLINT_TTY_DEVICE").ok().map(PathBuf::from))
+                .or_else(|| Some(PathBuf::from("/dev/tty1")))
+        };
+
+        // 尝试打开 TTY
+        let tty = if let Some(file) = provided_tty {
+            tracing::info!("使用应用提供的 TTY");
+            Some(file)
+        } else if let Some(path) = &tty_path {
+            match OpenOptions::new().read(true).write(true).open(path) {
+                Ok(file) => {
+                    tracing::info!("使用 TTY: {:?}", path);
+                    Some(file)
+                },
+                Err(e) => {
+                    if e.kind() == std::io::ErrorKind::PermissionDenied {
+                        tracing::warn!("{}", Error::PermissionDenied { path: path.clone() });
+                    }
+                    // 如果首选失败且是默认的 tty1，尝试 tty0
+                    if path == &PathBuf::from("/dev/tty1") {
+                        OpenOptions::new().read(true).write(true).open("/dev/tty0").ok()
+                    } else {
+                        None
+                    }
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut tty_keyboard_mode = None;
+        if let Some(ref tty_file) = tty {
+            if let Err(e) = fbio::set_terminal_mode(tty_file, TerminalMode::Graphics) {
+                tracing::warn!("无法将 TTY 切换到图形模式: {}", e);
+            } else {
+                tracing::info!("TTY 已切换到图形模式 (KD_GRAPHICS)。");
+            }
+
+            if config.tty_keyboard_off {
+                let switched = fbio::get_keyboard_mode(tty_file).and_then(|mode| {
+                    fbio::set_keyboard_mode(tty_file, KeyboardMode::Off)?;
+                    Ok(mode)
+                });
+                match switched {
+                    Ok(mode) => {
+                        tracing::info!("TTY 键盘已关闭 (K_OFF)，原来的模式: {:?}", mode);
+                        tty_keyboard_mode = Some(mode);
+                    }
+                    Err(e) => tracing::warn!("无法关闭 TTY 键盘: {}", e),
+                }
+            }
+
+            // 保存副本用于在信号处理器中恢复
+            *ACTIVE_TTY.lock().unwrap() = tty_file.try_clone().ok().map(|file| (file, tty_keyboard_mode));
+        } else if config.no_tty {
+            tracing::info!("不使用 TTY。");
+        } else if config.headless.is_none() {
+            tracing::warn!("无法打开 TTY。fbcon 光标可能会干扰 UI。");
+        }
+
+        if config.virtual_time {
+            crate::clock::enable_virtual_time();
+        }
+
+        // 创建非阻塞的 eventfd
+        let event_fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+        if event_fd == -1 {
+            return Err(Error::Other(
+                "Failed to create eventfd for event loop".into(),
+            ));
+        }
+
+        let idle_callback = config.idle_callback.take();
+        let touch_callback = config.touch_callback.take();
+        let gesture_callback = config.gesture_callback.take();
+        let switch_callback = config.switch_callback.take();
+        let device_callback = config.device_callback.take();
+        let backend_event_callback = config.backend_event_callback.take();
+        let event_filter = config.event_filter.take();
+        let key_interceptor = config.key_interceptor.take();
+        let framebuffer_device = config.framebuffer_device.take();
+        let blitter: SharedBlitter = Arc::new(Mutex::new(config.blitter.take().unwrap_or_else(|| Box::new(CpuBlitter))));
+        let clipboard = ClipboardStore::new(config.clipboard_file.clone());
+        let idle_tracker = config.power_policy.clone().map(|policy| IdleTracker::new(policy, crate::clock::now()));
+        let scale_factor = config
+            .scale_factor
+            .or_else(|| std::env::var("SLINT_SCALE_FACTOR").ok().and_then(|value| value.parse().ok()))
+            .filter(|scale_factor| is_valid_scale_factor(*scale_factor))
+            .unwrap_or(1.0);
+
+        let clock = if config.suspend_aware_timers || config.wake_alarm {
+            let clock = SuspendAwareClock::new(config.wake_alarm)
+                .map_err(|e| Error::Other(format!("Failed to create timerfd: {}", e)))?;
+            Some(RefCell::new(clock))
+        } else {
+            None
+        };
+
+        let watchdog = match config.watchdog_budget {
+            Some(budget) => Some(
+                Watchdog::new(budget, config.stall_callback.take())
+                    .map_err(|e| Error::Other(format!("Failed to start watchdog: {}", e)))?,
+            ),
+            None => None,
+        };
+        let (sender, receiver) = channel();
+        let quit_flag = Arc::new(AtomicBool::new(false));
+
+        // 直接创建代理实例
+        let proxy = LinuxFbProxy {
+            quit_flag: quit_flag.clone(),
+            sender,
+            event_fd,
+        };
+
+        // --- 注册信号处理器 (处理 SIGINT/SIGTERM/SIGHUP) ---
+        let signal_action: Option<SignalAction> = match &config.signal_policy {
+            SignalPolicy::None => None,
+            SignalPolicy::RestoreTtyAndExit => Some(Arc::new(|signal| {
+                tracing::info!("接收到退出信号 {:?}，正在恢复 TTY...", signal);
+                restore_tty();
+                std::process::exit(0);
+            })),
+            SignalPolicy::QuitEventLoop => {
+                let proxy = proxy.clone();
+                Some(Arc::new(move |signal| {
+                    tracing::info!("接收到退出信号 {:?}，退出事件循环", signal);
+                    let _ = proxy.quit_event_loop();
+                }))
+            }
+            SignalPolicy::Custom(callback) => Some(callback.clone()),
+        };
+        let signal_token = signal_action.and_then(|action| {
+            signals::install(action).map_err(|e| tracing::warn!("无法安装信号处理函数: {}", e)).ok()
+        });
+
+        Ok(Self {
+            inner: Rc::new(PlatformInner {
+                adapter: RefCell::new(None),
+                input_manager: RefCell::new(None),
+                tty,
+                tty_keyboard_mode,
+                config,
+                event_fd,
+                quit_flag,
+                signal_token,
+                event_receiver: receiver,
+                proxy,
+                idle_callback: RefCell::new(idle_callback),
+                touch_callback: RefCell::new(touch_callback),
+                gesture_callback: RefCell::new(gesture_callback),
+                switch_callback: RefCell::new(switch_callback),
+                device_callback: RefCell::new(device_callback),
+                backend_event_callback: RefCell::new(backend_event_callback),
+                event_filter: RefCell::new(event_filter),
+                framebuffer_device: RefCell::new(framebuffer_device),
+                blitter,
+                frame_stats: RefCell::new(FrameStatsCollector::new()),
+                pointer_state: RefCell::new(PointerState::default()),
+                vsync_supported: Cell::new(true),
+                frame_pacer: RefCell::new(None),
+                refresh_meter: RefCell::default(),
+                last_render_time: Cell::new(Duration::ZERO),
+                watchdog,
+                clipboard,
+                clock,
+                start_instant: crate::clock::now(),
+                fb_control: RefCell::new(None),
+                fb_open_path: RefCell::new(None),
+                last_mode_check: Cell::new(crate::clock::now()),
+                mode_check_due: Cell::new(false),
+                display_recovery: RefCell::new(None),
+                #[cfg(feature = "vnc")]
+                vnc: RefCell::new(None),
+                #[cfg(feature = "mjpeg")]
+                mjpeg: RefCell::new(None),
+                #[cfg(feature = "preview")]
+                preview: RefCell::new(None),
+                recorder: RefCell::new(None),
+                screen_blanked: Cell::new(false),
+                backlight: RefCell::new(None),
+                idle_tracker: RefCell::new(idle_tracker),
+                saved_backlight: Cell::new(None),
+                beeper: RefCell::new(None),
+                connector: RefCell::new(None),
+                backlight_fade: RefCell::new(None),
+                brightness_fade: RefCell::new(None),
+                osd: Rc::default(),
+                window_count_held: Cell::new(false),
+                scale_factor: Cell::new(scale_factor),
+                scale_factor_changed: Cell::new(false),
+                key_interceptor: RefCell::new(key_interceptor),
+                pending_touch_calibration: Cell::new(None),
+                pending_device_states: RefCell::new(Vec::new()),
+                injected_events: RefCell::new(Vec::new()),
+                injected_input: RefCell::new(Vec::new()),
+            }),
+        })
+    }
+
+    /// 运行事件循环，直到 `predicate` 返回 `true` 或收到退出请求
+    ///
+    /// `predicate` 在每次循环迭代开始时求值，适用于工厂测试等需要等待外部条件
+    /// (GPIO、标志文件等) 的场景。返回时窗口和 TTY 状态保持不变，可以再次进入事件循环。
+    ///
+    /// 注意：为了检测外部条件，循环在空闲时最长休眠 16ms (`run_event_loop` 则在没有定时任务时无限期休眠)，
+    /// 因此条件变化最多在一个心跳后被检测到。
+    /// 如需立即响应，可在外部线程中通过 `slint::invoke_from_event_loop` 唤醒事件循环。
+    pub fn run_event_loop_until(
+        &self,
+        mut predicate: impl FnMut() -> bool,
+    ) -> Result<(), PlatformError> {
+        self.inner.run_loop(&mut predicate, Some(FRAME_INTERVAL))
+    }
+
+    /// 运行交互式触摸校准，并将结果以 tslib 的 pointercal 格式写入 `output`
+    ///
+    /// 依次在屏幕四角和中心显示十字准星，用户需逐个点击。准星直接绘制在 Framebuffer 上，
+    /// 完成后画面会被完整重绘，新的校准参数立即生效。
+    /// 将同一路径传给 [`LinuxFbPlatformBuilder::with_pointercal`] 即可在之后的运行中沿用校准结果。
+    ///
+    /// 该调用会阻塞直到校准完成；收到退出请求 (如 Ctrl+C) 时返回错误。
+    pub fn run_touch_calibration(&self, output: impl AsRef<Path>) -> Result<(), Error> {
+        if self.inner.config.headless.is_some() {
+            return Err(Error::Other("无头模式下无法进行触摸校准".into()));
+        }
+        // 独占的设备不会把事件交给校准程序，校准期间临时释放
+        let grab = self.inner.config.input_config.grab_devices;
+        let release_grab = |grab: bool| {
+            if let Ok(mut input_manager) = self.inner.input_manager.try_borrow_mut() {
+                if let Some(input_manager) = input_manager.as_mut() {
+                    input_manager.set_grab(grab);
+                }
+            }
+        };
+        if grab {
+            release_grab(false);
+        }
+        let result = crate::touch_calibration::run(
+            &self.inner.fb_path(),
+            self.inner.config.rotation,
+            &self.inner.quit_flag,
+        );
+        if grab {
+            release_grab(true);
+        }
+        let calibration = result?;
+        calibration
+            .save(output.as_ref())
+            .map_err(|e| Error::Other(format!("无法保存触摸校准文件 {:?}: {}", output.as_ref(), e)))?;
+        tracing::info!("触摸校准已保存到 {:?}", output.as_ref());
+
+        // 事件循环运行期间输入管理器处于借用状态，因此在下一次迭代中应用
+        self.inner.pending_touch_calibration.set(Some(calibration));
+        if let Some(adapter) = self.inner.adapter.borrow().as_ref() {
+            adapter.invalidate_all();
+        }
+        Ok(())
+    }
+
+    /// 返回事件循环收集的帧时间统计 (渲染、复制、VSync 等待、翻转耗时、帧率和掉帧数)
+    pub fn frame_stats(&self) -> FrameStats {
+        self.inner.frame_stats.borrow().snapshot()
+    }
+
+    /// 清空帧时间统计
+    pub fn reset_frame_stats(&self) {
+        self.inner.frame_stats.borrow_mut().reset();
+    }
+
+    /// 启用或禁用输入设备，`name_or_path` 匹配设备名称中的子串或完整的设备路径
+    ///
+    /// 禁用的设备在读取后丢弃所有事件，其他设备不受影响；设置对之后插入的匹配设备同样生效。
+    /// 在下一次事件循环迭代中生效。例如清洁屏幕期间暂停触摸屏 30 秒：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::LinuxFbPlatform;
+    /// # let platform = LinuxFbPlatform::new().unwrap();
+    /// platform.set_input_device_enabled("/dev/input/event2", false);
+    /// let handle = platform.clone();
+    /// i_slint_core::timers::Timer::single_shot(std::time::Duration::from_secs(30), move || {
+    ///     handle.set_input_device_enabled("/dev/input/event2", true);
+    /// });
+    /// ```
+    pub fn set_input_device_enabled(&self, name_or_path: &str, enabled: bool) {
+        self.inner.pending_device_states.borrow_mut().push((name_or_path.to_string(), enabled));
+    }
+
+    /// 返回当前的指针位置、按下的指针按键和活跃触点数量
+    ///
+    /// 状态在事件循环每次轮询输入后更新，可在 Slint 回调中调用，例如在用户操作期间暂停传感器数据刷新：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::LinuxFbPlatform;
+    /// # let platform = LinuxFbPlatform::new().unwrap();
+    /// if !platform.pointer_state().is_interacting() {
+    ///     // 刷新界面上的传感器读数
+    /// }
+    /// ```
+    pub fn pointer_state(&self) -> PointerState {
+        self.inner.pointer_state.borrow().clone()
+    }
+
+    /// 注入一个窗口事件 (窗口坐标)，在下一次事件循环迭代中分发
+    ///
+    /// 注入的事件与真实输入一样经过事件过滤器和虚拟键盘，主要用于无头模式下的界面测试。
+    pub fn inject_event(&self, event: WindowEvent) {
+        self.inner.injected_events.borrow_mut().push(event);
+    }
+
+    /// 注入原始 evdev 事件，在下一次事件循环迭代中处理
+    ///
+    /// 事件来自一个虚拟的多点触控触摸屏 (Protocol B，坐标为窗口像素) 兼键盘，
+    /// 与真实设备一样经过手势识别和键盘映射，每组事件以 `SYN_REPORT` 结束。
+    /// 配合 [`LinuxFbPlatformBuilder::with_virtual_time`] 可以确定性地测试长按、轻点等手势。
+    pub fn inject_input_events(&self, events: impl IntoIterator<Item = evdev::InputEvent>) {
+        self.inner.injected_input.borrow_mut().extend(events);
+    }
+
+    /// 把虚拟时间推进 `advance`，然后执行一次事件循环迭代
+    ///
+    /// 一次迭代依次处理跨线程回调、Slint 定时器与动画、输入 (包括注入的事件) 和渲染，
+    /// 不会等待。需要启用 [`LinuxFbPlatformBuilder::with_virtual_time`]。
+    pub fn step(&self, advance: Duration) -> Result<(), PlatformError> {
+        if !self.inner.config.virtual_time {
+            return Err(PlatformError::Other("step 需要启用虚拟时间 (with_virtual_time)".into()));
+        }
+        crate::clock::advance_virtual_time(advance);
+        let mut iterations = 0;
+        self.inner.run_loop(
+            &mut || {
+                iterations += 1;
+                iterations > 1
+            },
+            None,
+        )
+    }
+
+    /// 开始把呈现的画面录制到文件 `path` (格式见 [`RecordingFormat`])
+    ///
+    /// 需要在窗口创建之后调用；录制从下一帧开始，该帧会整屏重绘。
+    /// 已有录制在进行时先结束它。录制在后台线程写入，不阻塞事件循环。
+    pub fn start_recording(&self, path: impl AsRef<Path>, format: RecordingFormat) -> Result<(), Error> {
+        let adapter = self.inner.adapter.borrow();
+        let adapter = adapter.as_ref().ok_or_else(|| Error::Other("窗口尚未创建，无法开始录制".into()))?;
+        let (width, height) = adapter.presenter.borrow().size();
+        let recorder = Recorder::start(path.as_ref(), format, width, height)
+            .map_err(|e| Error::Other(format!("无法创建录制文件 {:?}: {}", path.as_ref(), e)))?;
+        if let Some(previous) = self.inner.recorder.replace(Some(recorder)) {
+            if let Err(e) = previous.finish() {
+                tracing::error!("屏幕录制写入失败: {}", e);
+            }
+        }
+        adapter.invalidate_all();
+        Ok(())
+    }
+
+    /// 结束屏幕录制，等待剩余的帧写入文件
+    ///
+    /// 没有正在进行的录制时直接返回 `Ok(())`；写入过程中出现的错误在这里返回。
+    pub fn stop_recording(&self) -> Result<(), Error> {
+        match self.inner.recorder.take() {
+            Some(recorder) => recorder.finish().map_err(|e| Error::Other(format!("屏幕录制写入失败: {}", e))),
+            None => Ok(()),
+        }
+    }
+
+    /// 在 `duration` 内把 sysfs 背光渐变到最大亮度的 `percent`%
+    ///
+    /// 渐变由事件循环逐帧推进，不阻塞渲染；新的渐变从当前亮度开始并取代进行中的渐变。
+    /// 例如床头设备熄屏前先用两秒缓慢调暗：
+    ///
+    /// ```no_run
+    /// # use slint_backend_linuxfb::LinuxFbPlatform;
+    /// # use slint_backend_linuxfb::power::Easing;
+    /// # use std::time::Duration;
+    /// # let platform = LinuxFbPlatform::new().unwrap();
+    /// platform.fade_backlight(0, Duration::from_secs(2), Easing::EaseOut).unwrap();
+    /// ```
+    pub fn fade_backlight(&self, percent: u8, duration: Duration, easing: Easing) -> Result<(), Error> {
+        let backlight = self.inner.backlight();
+        let device = backlight.as_ref().ok_or_else(|| Error::Other("无法打开背光设备".into()))?;
+        let current = device.brightness().map_err(|e| Error::Other(format!("无法读取背光亮度: {}", e)))?;
+        let target = device.max_brightness() as f32 * f32::from(percent.min(100)) / 100.0;
+        let fade = Fade::new(current as f32, target, duration, easing, crate::clock::now());
+        *self.inner.backlight_fade.borrow_mut() = Some(fade);
+        Ok(())
+    }
+
+    /// 在 `duration` 内把软件亮度渐变到 `brightness` (0.0 ~ 1.0)
+    ///
+    /// 软件亮度把渲染结果压暗，适用于没有可调背光的屏幕。渐变期间每帧整屏重绘。
+    pub fn fade_brightness(&self, brightness: f32, duration: Duration, easing: Easing) {
+        let current = self.inner.adapter.borrow().as_ref().map_or(1.0, |adapter| adapter.brightness.get());
+        let fade = Fade::new(current, brightness.clamp(0.0, 1.0), duration, easing, crate::clock::now());
+        *self.inner.brightness_fade.borrow_mut() = Some(fade);
+    }
+
+    /// 设置编号为 `id` 的 OSD 图层，`None` 移除该图层
+    ///
+    /// 图层在每帧渲染后混合到应用画面之上 (编号大的在上面)，适合显示连接状态栏、调试文字等
+    /// 不属于 Slint 场景的内容。更新内容时用同一编号再次设置即可，见 [`osd`](crate::osd) 模块。
+    pub fn set_osd_layer(&self, id: u32, layer: Option<OsdLayer>) {
+        self.inner.osd.borrow_mut().set(id, layer);
+        if let Some(adapter) = self.inner.adapter.borrow().as_ref() {
+            adapter.request_redraw();
+        }
+    }
+
+    /// 运行时修改界面的缩放系数，例如实现无障碍的 "大字体" 选项
+    ///
+    /// 事件循环在下一次迭代中分发 `ScaleFactorChanged` 和新的逻辑窗口尺寸，并整屏重绘，应用无需重启。
+    /// 不是正有限数的值被忽略。
+    pub fn set_scale_factor(&self, scale_factor: f32) {
+        if !is_valid_scale_factor(scale_factor) {
+            tracing::warn!("忽略无效的缩放系数: {}", scale_factor);
+            return;
+        }
+        self.inner.scale_factor.set(scale_factor);
+        self.inner.scale_factor_changed.set(true);
+    }
+
+    /// 当前界面的缩放系数
+    pub fn scale_factor(&self) -> f32 {
+        self.inner.scale_factor.get()
+    }
+
+    /// 屏幕的物理尺寸 (毫米，面板方向)
+    ///
+    /// 构建器的 [`with_physical_size_mm`](LinuxFbPlatformBuilder::with_physical_size_mm) 优先，
+    /// 否则使用驱动报告的值；驱动报告 0x0 或使用自定义显示设备、无头模式时返回 `None`。
+    pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+        self.inner.config.physical_size_mm.or_else(|| {
+            let vinfo = fbio::get_vscreeninfo(self.inner.fb_control.borrow().as_ref()?).ok()?;
+            Some(vinfo.size_in_mm()).filter(|&(width, height)| width != 0 && height != 0)
+        })
+    }
+
+    /// 按屏幕分辨率和 [`physical_size_mm`](Self::physical_size_mm) 计算的水平 DPI，用于确定界面的缩放系数
+    ///
+    /// 物理尺寸未知、窗口尚未创建或结果明显不合理 (驱动报告的尺寸有误) 时返回 `None`。
+    pub fn dpi(&self) -> Option<f32> {
+        let (width, _) = self.inner.adapter.borrow().as_ref()?.presenter.borrow().size();
+        dots_per_inch(width, self.physical_size_mm()?.0)
+    }
+
+    /// 返回无头模式下最近一次渲染的画面 (按构建时指定的像素格式排列，行间没有填充)
+    ///
+    /// 未启用无头模式或窗口尚未创建时返回 `None`。
+    pub fn headless_frame(&self) -> Option<Vec<u8>> {
+        let adapter = self.inner.adapter.borrow();
+        let presenter = adapter.as_ref()?.presenter.borrow();
+        match &*presenter {
+            Presenter::Headless(headless) => Some(headless.pixels.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Drop for PlatformInner {
+    fn drop(&mut self) {
+        if let Some(ref tty) = self.tty {
+            tracing::info!("正在恢复 TTY 到文本模式 (Drop)...");
+            if let Some(mode) = self.tty_keyboard_mode {
+                if let Err(e) = fbio::set_keyboard_mode(tty, mode) {
+                    tracing::error!("无法恢复 TTY 键盘模式: {}", e);
+                }
+            }
+            if let Err(e) = fbio::set_terminal_mode(tty, TerminalMode::Text) {
+                tracing::error!("无法恢复 TTY 到文本模式: {}", e);
+            }
+        }
+        if let Ok(mut guard) = ACTIVE_TTY.lock() {
+            *guard = None;
+        }
+        // 信号处理动作引用了即将关闭的 eventfd
+        if let Some(token) = self.signal_token {
+            signals::uninstall(token);
+        }
+        if self.event_fd != -1 {
+            unsafe { libc::close(self.event_fd) };
+        }
+    }
+}
+
+impl Platform for LinuxFbPlatform {
+    fn create_window_adapter(&self) -> Result<Rc<dyn WindowAdapter>, PlatformError> {
+        self.inner.create_window_adapter()
+    }
+
+    fn run_event_loop(&self) -> Result<(), PlatformError> {
+        self.inner.set_foreground(true);
+        let result = self.inner.run_loop(&mut || false, None);
+        self.inner.set_foreground(false);
+        result
+    }
+
+    fn new_event_loop_proxy(&self) -> Option<Box<dyn EventLoopProxy>> {
+        Some(Box::new(self.inner.proxy.clone()))
+    }
+
+    fn duration_since_start(&self) -> Duration {
+        match &self.inner.clock {
+            Some(clock) => clock.borrow().duration_since_start(),
+            None => crate::clock::elapsed(self.inner.start_instant),
+        }
+    }
+
+    fn set_clipboard_text(&self, text: &str, clipboard: Clipboard) {
+        self.inner.clipboard.set_text(text, clipboard);
+    }
+
+    fn clipboard_text(&self, clipboard: Clipboard) -> Option<String> {
+        self.inner.clipboard.text(clipboard)
+    }
+}
+
+impl PlatformInner {
+    /// Framebuffer 设备路径：构建器配置 > 环境变量 `SLINT_FRAMEBUFFER` > `/dev/fb0`
+    fn fb_path(&self) -> PathBuf {
+        self.config.framebuffer_path()
+    }
+
+    /// 打开 Framebuffer 设备 (或使用构建器提供的显示设备) 并按配置选择呈现方式
+    fn open_framebuffer(&self) -> Result<(Presenter, RepaintBufferType, PixelFormat), PlatformError> {
+        let device: Box<dyn FramebufferDevice> = match self.framebuffer_device.take() {
+            Some(DisplaySource::Device(device)) => device,
+            Some(DisplaySource::Sink(sink)) => {
+                let pixel_format = sink.pixel_format();
+                if pixel_format == PixelFormat::Unknown {
+                    return Err(Error::UnsupportedPixelFormat.into());
+                }
+                tracing::info!("使用自定义显示输出: {:?} {:?}", sink.size(), pixel_format);
+                let presenter = Presenter::Sink(SinkPresenter::new(sink));
+                return Ok((presenter, RepaintBufferType::ReusedBuffer, pixel_format));
+            }
+            source => {
+                let (fb_path, fb) = match source {
+                    Some(DisplaySource::Framebuffer(fb)) => {
+                        let fb_path = crate::device::framebuffer_path(&fb);
+                        tracing::info!("使用应用提供的 Framebuffer: {:?}", fb_path);
+                        (fb_path, *fb)
+                    }
+                    _ => {
+                        // --- 获取 Framebuffer 路径 ---
+                        let fb_path = self.fb_path();
+                        tracing::info!("打开 Framebuffer 设备: {:?}", fb_path);
+                        let fb = Framebuffer::new(&fb_path).map_err(|e| Error::from_framebuffer(&fb_path, e))?;
+                        (fb_path, fb)
+                    }
+                };
+                self.open_fbdev(fb_path, fb)?
+            }
+        };
+        self.present_device(device)
+    }
+
+    /// 在已打开的 Framebuffer 上配置双缓冲，并记录控制句柄和路径
+    fn open_fbdev(&self, fb_path: PathBuf, fb: Framebuffer) -> Result<Box<dyn FramebufferDevice>, Error> {
+        *self.fb_control.borrow_mut() = fb.file.try_clone().ok();
+        let device = FbdevDevice::new(fb).map_err(|e| Error::from_double_buffer(&fb_path, e))?;
+        *self.fb_open_path.borrow_mut() = Some(fb_path);
+        Ok(Box::new(device))
+    }
+
+    /// 填充设备的后缓冲区：窗口区域填充初始背景色，窗口之外填充边框颜色 (未设置的不填充)
+    fn fill_initial(&self, device: &mut dyn FramebufferDevice) {
+        if self.config.window_size.is_none() {
+            if let Some(color) = self.config.clear_color {
+                fill_device(&self.blitter, device, color);
+            }
+            return;
+        }
+        let (width, height) = device.size();
+        let window = letterbox_rect(self.config.window_size, self.config.rotation, width, height);
+        let format = device.pixel_format();
+        let buffer = device.back_buffer();
+        if let Some(color) = self.config.clear_color {
+            format.fill_rect(buffer, width as usize, window, color);
+        }
+        if let Some(color) = self.config.letterbox_fill() {
+            for rect in border_rects(width, height, window) {
+                format.fill_rect(buffer, width as usize, rect, color);
+            }
+        }
+    }
+
+    /// 用边框颜色填充渲染目标中窗口之外的区域 (未设置窗口尺寸或保持原样时不做处理)
+    ///
+    /// 直接模式下前后缓冲区已在 [`present_device`](Self::present_device) 中填充，这里覆盖影子缓冲区和自定义输出。
+    fn fill_letterbox(&self, adapter: &LinuxFbWindowAdapter) -> Result<(), PlatformError> {
+        let Some(color) = self.config.letterbox_fill() else {
+            return Ok(());
+        };
+        let window = adapter.window_rect();
+        let pixel_format = adapter.pixel_format.get();
+        let mut presenter = adapter.presenter.borrow_mut();
+        let (width, height) = presenter.size();
+        let border = border_rects(width, height, window);
+        if border.is_empty() {
+            return Ok(());
+        }
+        presenter.for_each_render_target(|target| {
+            for rect in &border {
+                pixel_format.fill_rect(target, width as usize, *rect, color);
+            }
+        });
+        if let Presenter::Sink(sink) = &mut *presenter {
+            if let Err(e) = sink.present(&border) {
+                self.report(BackendEvent::Error(BackendIssue::FlipFailed { reason: e.to_string() }));
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// 按配置为显示设备选择呈现方式
+    fn present_device(
+        &self,
+        mut device: Box<dyn FramebufferDevice>,
+    ) -> Result<(Presenter, RepaintBufferType, PixelFormat), PlatformError> {
+        let pixel_format = device.pixel_format();
+
+        if pixel_format == PixelFormat::Unknown {
+            return Err(Error::UnsupportedPixelFormat.into());
+        }
+
+        // 后缓冲区是残留的显存内容，填充两次 (中间翻转一次) 覆盖前后两个缓冲区
+        let letterbox = self.config.window_size.is_some() && self.config.letterbox_fill().is_some();
+        if self.config.clear_color.is_some() || letterbox {
+            self.fill_initial(device.as_mut());
+            match device.flip() {
+                Ok(()) => self.fill_initial(device.as_mut()),
+                Err(e) => tracing::warn!("填充初始背景色时翻转失败: {}", e),
+            }
+        }
+
+        // --- 选择呈现方式 ---
+        //     直接模式下前后缓冲区交替使用，呈现线程的两个影子缓冲区同样交替使用
+        if self.config.render_thread {
+            tracing::info!("启用独立呈现线程。");
+            let proxy = self.proxy.clone();
+            let threaded = ThreadedPresenter::new(device, self.config.vsync, self.blitter.clone(), move || {
+                let _ = proxy.notify_event_loop();
+            })?;
+            Ok((Presenter::Threaded(threaded), RepaintBufferType::SwappedBuffers, pixel_format))
+        } else {
+            Ok((Presenter::Direct(device), RepaintBufferType::SwappedBuffers, pixel_format))
+        }
+    }
+
+    fn create_window_adapter(&self) -> Result<Rc<dyn WindowAdapter>, PlatformError> {
+        let (presenter, repaint_buffer_type, pixel_format) = match self.config.headless {
+            Some((width, height, format)) => {
+                if format == PixelFormat::Unknown {
+                    return Err(Error::UnsupportedPixelFormat.into());
+                }
+                tracing::info!("无头模式: {}x{} {:?}", width, height, format);
+                let buffer = HeadlessBuffer::new(width, height, format);
+                (Presenter::Headless(buffer), RepaintBufferType::ReusedBuffer, format)
+            }
+            None => self.open_framebuffer()?,
+        };
+        // 设置了窗口尺寸时渲染到内容保留的窗口缓冲区
+        let repaint_buffer_type = match self.config.window_size {
+            Some(_) => RepaintBufferType::ReusedBuffer,
+            None => resolve_repaint_buffer_type(self.config.repaint_buffer_type, repaint_buffer_type),
+        };
+        tracing::debug!("重绘缓冲区类型: {:?}", repaint_buffer_type);
+        let (width, height) = presenter.size();
+
+        #[cfg(feature = "preview")]
+        if self.config.preview {
+            let preview = PreviewWindow::new(width, height, self.config.rotation)
+                .map_err(|e| PlatformError::Other(format!("无法创建预览窗口: {}", e)))?;
+            *self.preview.borrow_mut() = Some(preview);
+        }
+
+        #[cfg(feature = "vnc")]
+        if let Some(addr) = self.config.vnc_addr {
+            let proxy = self.proxy.clone();
+            match VncServer::start(addr, width, height, move || {
+                let _ = proxy.notify_event_loop();
+            }) {
+                Ok(vnc) => *self.vnc.borrow_mut() = Some(vnc),
+                Err(e) => self.report(BackendEvent::Error(BackendIssue::ServiceUnavailable {
+                    service: "VNC 服务器",
+                    reason: format!("{}: {}", addr, e),
+                })),
+            }
+        }
+        #[cfg(feature = "mjpeg")]
+        if let Some(addr) = self.config.mjpeg_addr {
+            match MjpegServer::start(addr, width, height) {
+                Ok(mjpeg) => *self.mjpeg.borrow_mut() = Some(mjpeg),
+                Err(e) => self.report(BackendEvent::Error(BackendIssue::ServiceUnavailable {
+                    service: "MJPEG 推流服务器",
+                    reason: format!("{}: {}", addr, e),
+                })),
+            }
+        }
+
+        // --- 显示旋转：窗口使用旋转后的尺寸 ---
+        let rotation = self.config.rotation;
+        let window = letterbox_rect(self.config.window_size, rotation, width, height);
+        let (window_width, window_height) = rotation.rotate_size(window.w as u32, window.h as u32);
+        let (width, height) = rotation.rotate_size(width, height);
+        if rotation != Rotation::Rotate0 {
+            tracing::info!("显示旋转: {:?}, 屏幕尺寸 {}x{}", rotation, width, height);
+        }
+        if self.config.window_size.is_some() {
+            tracing::info!("窗口尺寸 {}x{}，位于屏幕 ({}, {})", window_width, window_height, window.x, window.y);
+        }
+
+        // --- 初始化输入管理器 ---
+        //     触摸坐标未单独配置旋转时跟随显示旋转
+        if self.config.no_input {
+            tracing::info!("已禁用输入");
+        } else {
+            let mut input_config = self.config.input_config.clone();
+            input_config.touch_rotation.get_or_insert(rotation);
+            let proxy = self.proxy.clone();
+            let mut input_manager = InputManager::new(width, height, input_config, move || {
+                let _ = proxy.notify_event_loop();
+            })
+                .map_err(|e| PlatformError::Other(e.to_string()))?;
+            input_manager.set_key_interceptor(self.key_interceptor.borrow_mut().take());
+            *self.input_manager.borrow_mut() = Some(input_manager);
+        }
+
+        if let (Some(config), None) = (&self.config.beeper, self.config.headless) {
+            match Beeper::open(config) {
+                Ok(beeper) => *self.beeper.borrow_mut() = Some(beeper),
+                Err(e) => self.report(BackendEvent::Warning(BackendIssue::ServiceUnavailable {
+                    service: "蜂鸣器",
+                    reason: e.to_string(),
+                })),
+            }
+        }
+
+        if self.config.display_hotplug && self.fb_open_path.borrow().is_some() {
+            match ConnectorMonitor::open(self.config.display_connector.as_deref()) {
+                Ok(connector) => *self.connector.borrow_mut() = Some(connector),
+                Err(e) => self.report(BackendEvent::Warning(BackendIssue::ServiceUnavailable {
+                    service: "显示接口热插拔检测",
+                    reason: e.to_string(),
+                })),
+            }
+        }
+
+        // --- 创建 Window Adapter ---
+        let adapter = Rc::<LinuxFbWindowAdapter>::new_cyclic(|weak_adapter| {
+            let window = Rc::new(i_slint_core::api::Window::new(weak_adapter.clone()));
+            let renderer = SoftwareRenderer::new_with_repaint_buffer_type(repaint_buffer_type);
+            renderer.set_rendering_rotation(rotation.to_rendering_rotation());
+
+            LinuxFbWindowAdapter {
+                window,
+                presenter: RefCell::new(presenter),
+                renderer,
+                swap_damage: RefCell::default(),
+                pixel_format: Cell::new(pixel_format),
+                rotation,
+                needs_redraw: RefCell::new(true),
+                debug_overlay: self.config.debug_overlay.then(|| RefCell::new(DebugOverlay::new())),
+                virtual_keyboard: self.config.virtual_keyboard.then(|| {
+                    RefCell::new(VirtualKeyboard::new(i_slint_core::api::PhysicalSize::new(window_width, window_height)))
+                }),
+                brightness: Cell::new(1.0),
+                visible: Cell::new(true),
+                visibility_changed: Cell::new(false),
+                osd: self.osd.clone(),
+                window_size: self.config.window_size,
+                window_buffer: RefCell::default(),
+            }
+        });
+
+        adapter
+            .renderer
+            .set_window_adapter(&(adapter.clone() as Rc<dyn WindowAdapter>));
+        *self.adapter.borrow_mut() = Some(adapter.clone());
+
+        self.fill_letterbox(&adapter)?;
+        self.dispatch_window_geometry(&adapter);
+
+        Ok(adapter)
+    }
+
+    /// 当前的显示模式 (分辨率和像素格式) 是否与 `size`、`pixel_format` 不同
+    ///
+    /// 只检查后端打开的 Framebuffer；自定义显示设备和无头模式总是返回 `false`。
+    fn display_mode_changed(&self, size: (u32, u32), pixel_format: PixelFormat) -> bool {
+        match self.fb_control.borrow().as_ref().map(fbio::get_vscreeninfo) {
+            Some(Ok(vinfo)) => {
+                vinfo.size_in_pixels() != size || PixelFormat::from_fb_info(&vinfo) != pixel_format
+            }
+            _ => false,
+        }
+    }
+
+    /// 显示模式被外部 (fbset、HDMI 重新协商等) 改变时重新打开 Framebuffer、重新映射缓冲区，
+    /// 并以新的尺寸分发 `WindowEvent::Resized` 让界面重新布局
+    ///
+    /// `reprogram` 为 `true` 时 (显示器刚刚接入) 即使模式没有改变也强制驱动重新设置模式并重新配置。
+    fn update_display_mode(
+        &self,
+        adapter: &LinuxFbWindowAdapter,
+        input_manager: Option<&mut InputManager>,
+        reprogram: bool,
+    ) -> Result<(), PlatformError> {
+        let from = adapter.presenter.borrow().size();
+        let from_format = adapter.pixel_format.get();
+        if !reprogram && !self.display_mode_changed(from, from_format) {
+            return Ok(());
+        }
+        let Some(fb_path) = self.fb_open_path.borrow().clone() else {
+            return Ok(());
+        };
+        // 先释放旧的映射和呈现线程，再按新的模式配置双缓冲
+        *adapter.presenter.borrow_mut() = Presenter::Headless(HeadlessBuffer::new(0, 0, PixelFormat::Unknown));
+        let mut fb = Framebuffer::new(&fb_path).map_err(|e| Error::from_framebuffer(&fb_path, e))?;
+        if reprogram {
+            if let Err(e) = fb.reactivate() {
+                tracing::warn!("无法重新设置显示模式: {}", e);
+            }
+        }
+        let (presenter, _, pixel_format) = self.present_device(self.open_fbdev(fb_path, fb)?)?;
+        let to = presenter.size();
+        *adapter.presenter.borrow_mut() = presenter;
+        *adapter.swap_damage.borrow_mut() = SwapDamage::default();
+        *self.display_recovery.borrow_mut() = None;
+        adapter.pixel_format.set(pixel_format);
+        self.fill_letterbox(adapter)?;
+        if to == from && pixel_format == from_format {
+            adapter.invalidate_all();
+            return Ok(());
+        }
+        tracing::info!("显示模式改变: {}x{} -> {}x{} {:?}", from.0, from.1, to.0, to.1, pixel_format);
+        *self.refresh_meter.borrow_mut() = RefreshMeter::default();
+
+        #[cfg(feature = "vnc")]
+        if let Some(vnc) = self.vnc.borrow().as_ref() {
+            vnc.resize(to.0, to.1);
+        }
+        #[cfg(feature = "mjpeg")]
+        if let Some(mjpeg) = self.mjpeg.borrow().as_ref() {
+            mjpeg.resize(to.0, to.1);
+        }
+        // 录制文件的画面尺寸在开始时确定，无法继续
+        if let Some(recorder) = self.recorder.borrow_mut().take() {
+            tracing::warn!("显示模式改变，停止屏幕录制");
+            if let Err(e) = recorder.finish() {
+                tracing::warn!("无法完成屏幕录制: {}", e);
+            }
+        }
+
+        let (width, height) = adapter.rotation.rotate_size(to.0, to.1);
+        if let Some(input_manager) = input_manager {
+            input_manager.set_screen_size(width, height);
+        }
+        if let Some(keyboard) = &adapter.virtual_keyboard {
+            *keyboard.borrow_mut() = VirtualKeyboard::new(adapter.size());
+        }
+        self.dispatch_window_geometry(adapter);
+        adapter.invalidate_all();
+        self.report(BackendEvent::Warning(BackendIssue::DisplayModeChanged { from, to }));
+        Ok(())
+    }
+
+    /// 按当前的缩放系数向窗口分发 `ScaleFactorChanged` 和逻辑尺寸的 `Resized`
+    fn dispatch_window_geometry(&self, adapter: &LinuxFbWindowAdapter) {
+        let scale_factor = self.scale_factor.get();
+        adapter.window.dispatch_event(WindowEvent::ScaleFactorChanged { scale_factor });
+        adapter.window.dispatch_event(WindowEvent::Resized { size: adapter.size().to_logical(scale_factor) });
+    }
+
+    /// 执行系统按键对应的动作
+    fn handle_system_key(&self, adapter: &LinuxFbWindowAdapter, key: SystemKey) {
+        let default_action;
+        let action = match self.config.system_key_actions.get(&key) {
+            Some(action) => action,
+            None => {
+                default_action = default_system_key_action(key);
+                &default_action
+            }
+        };
+        tracing::debug!("系统按键 {:?}", key);
+
+        match action {
+            SystemKeyAction::Ignore => {}
+            SystemKeyAction::AdjustBacklight(percent) => {
+                if let Some(device) = self.backlight().as_ref() {
+                    if let Err(e) = device.adjust_percent(*percent) {
+                        tracing::warn!("调整背光失败: {}", e);
+                    }
+                }
+            }
+            SystemKeyAction::ToggleBlank => {
+                let level = if self.screen_blanked.get() { BlankingLevel::Unblank } else { BlankingLevel::Powerdown };
+                self.set_blank_level(adapter, level);
+            }
+            SystemKeyAction::Quit => self.quit_flag.store(true, Ordering::Relaxed),
+            SystemKeyAction::RequestClose => adapter.window.dispatch_event(WindowEvent::CloseRequested),
+            SystemKeyAction::Callback(callback) => callback(key),
+        }
+    }
+
+    /// 背光设备，首次调用时打开
+    fn backlight(&self) -> std::cell::RefMut<'_, Option<Backlight>> {
+        let mut backlight = self.backlight.borrow_mut();
+        if backlight.is_none() {
+            match Backlight::open(self.config.backlight_path.as_deref()) {
+                Ok(device) => *backlight = Some(device),
+                Err(e) => tracing::warn!("无法打开背光设备: {}", e),
+            }
+        }
+        backlight
+    }
+
+    /// 用初始背景色 (默认黑色) 填充窗口区域并呈现一帧，用于窗口隐藏时
+    fn clear_screen(&self, adapter: &LinuxFbWindowAdapter) -> Result<(), PlatformError> {
+        let color = self.config.clear_color.unwrap_or(Color::from_rgb_u8(0, 0, 0));
+        let pixel_format = adapter.pixel_format.get();
+        let rect = adapter.window_rect();
+        let mut presenter = adapter.presenter.borrow_mut();
+        let (width, _) = presenter.size();
+        presenter.with_render_target(|target| pixel_format.fill_rect(target, width as usize, rect, color));
+        match &mut *presenter {
+            Presenter::Direct(device) => {
+                let mut swap_damage = adapter.swap_damage.borrow_mut();
+                swap_damage.overwritten(rect);
+                match device.flip() {
+                    Ok(()) => swap_damage.flipped(),
+                    Err(e) => tracing::warn!("清空屏幕时翻转失败: {}", e),
+                }
+            }
+            Presenter::Threaded(threaded) => {
+                let mut swap_damage = adapter.swap_damage.borrow_mut();
+                swap_damage.overwritten(rect);
+                threaded.submit();
+                swap_damage.flipped();
+            }
+            Presenter::Sink(sink) => {
+                if let Err(e) = sink.present(&[rect]) {
+                    self.report(BackendEvent::Error(BackendIssue::FlipFailed { reason: e.to_string() }));
+                    return Err(e.into());
+                }
+            }
+            Presenter::Headless(_) => {}
+        }
+        Ok(())
+    }
+
+    /// 关闭或恢复屏幕显示
+    fn set_blank_level(&self, adapter: &LinuxFbWindowAdapter, level: BlankingLevel) {
+        let Some(fb) = self.fb_control.borrow().as_ref().and_then(|f| f.try_clone().ok()) else {
+            return;
+        };
+        let blanked = !matches!(level, BlankingLevel::Unblank);
+        if let Err(e) = fbio::blank(&fb, level) {
+            tracing::warn!("无法切换屏幕显示状态: {}", e);
+            return;
+        }
+        self.screen_blanked.set(blanked);
+        if !blanked {
+            // 部分驱动在关闭显示期间不保留画面内容
+            adapter.invalidate_all();
+        }
+    }
+
+    /// 记录一次输入：退出空闲电源策略的所有阶段
+    ///
+    /// 返回 `true` 表示屏幕此前处于关闭状态，这次输入只用于唤醒。
+    fn wake_from_idle(&self, adapter: &LinuxFbWindowAdapter) -> bool {
+        let left = match self.idle_tracker.borrow_mut().as_mut() {
+            Some(tracker) => tracker.activity(crate::clock::now()),
+            None => return false,
+        };
+        let woke = left.iter().any(|stage| stage.is_blanking());
+        for stage in left {
+            self.leave_power_stage(adapter, stage);
+        }
+        woke
+    }
+
+    /// 推进进行中的背光和软件亮度渐变
+    fn update_fades(&self, adapter: &LinuxFbWindowAdapter) {
+        let now = crate::clock::now();
+        let mut backlight_fade = self.backlight_fade.borrow_mut();
+        if let Some(fade) = backlight_fade.as_ref() {
+            let (value, finished) = fade.value_at(now);
+            if let Some(device) = self.backlight.borrow().as_ref() {
+                let value = value.round() as u32;
+                // sysfs 写入有开销，亮度级数较少时大部分帧的值不变
+                if device.brightness().ok() != Some(value) {
+                    if let Err(e) = device.set_brightness(value) {
+                        tracing::warn!("调整背光失败: {}", e);
+                    }
+                }
+            }
+            if finished {
+                *backlight_fade = None;
+            }
+        }
+
+        let mut brightness_fade = self.brightness_fade.borrow_mut();
+        if let Some(fade) = brightness_fade.as_ref() {
+            let (value, finished) = fade.value_at(now);
+            adapter.brightness.set(value);
+            adapter.invalidate_all();
+            if finished {
+                *brightness_fade = None;
+            }
+        }
+    }
+
+    /// 是否有进行中的渐变
+    fn is_fading(&self) -> bool {
+        self.backlight_fade.borrow().is_some() || self.brightness_fade.borrow().is_some()
+    }
+
+    /// 进入空闲时长已经达到的电源阶段
+    fn update_idle_power(&self, adapter: &LinuxFbWindowAdapter) {
+        let entered = match self.idle_tracker.borrow_mut().as_mut() {
+            Some(tracker) => tracker.poll(crate::clock::now()),
+            None => return,
+        };
+        for stage in entered {
+            tracing::debug!("进入空闲电源阶段 {:?}", stage);
+            match stage {
+                PowerStage::Dim { brightness } => {
+                    *self.brightness_fade.borrow_mut() = None;
+                    adapter.brightness.set(brightness.clamp(0.0, 1.0));
+                    adapter.invalidate_all();
+                }
+                PowerStage::Backlight { percent } => {
+                    *self.backlight_fade.borrow_mut() = None;
+                    if let Some(device) = self.backlight().as_ref() {
+                        if self.saved_backlight.get().is_none() {
+                            self.saved_backlight.set(device.brightness().ok());
+                        }
+                        if let Err(e) = device.set_percent(percent) {
+                            tracing::warn!("降低背光失败: {}", e);
+                        }
+                    }
+                }
+                PowerStage::Blank => self.set_blank_level(adapter, BlankingLevel::Normal),
+                PowerStage::Powerdown => self.set_blank_level(adapter, BlankingLevel::Powerdown),
+            }
+        }
+    }
+
+    fn leave_power_stage(&self, adapter: &LinuxFbWindowAdapter, stage: PowerStage) {
+        tracing::debug!("退出空闲电源阶段 {:?}", stage);
+        match stage {
+            PowerStage::Dim { .. } => {
+                adapter.brightness.set(1.0);
+                adapter.invalidate_all();
+            }
+            PowerStage::Backlight { .. } => {
+                if let (Some(device), Some(value)) = (self.backlight().as_ref(), self.saved_backlight.take()) {
+                    if let Err(e) = device.set_brightness(value) {
+                        tracing::warn!("恢复背光失败: {}", e);
+                    }
+                }
+            }
+            PowerStage::Blank | PowerStage::Powerdown => {
+                if self.screen_blanked.get() {
+                    self.set_blank_level(adapter, BlankingLevel::Unblank);
+                }
+            }
+        }
+    }
+
+    /// 记录运行时问题并交给应用的回调
+    fn report(&self, event: BackendEvent) {
+        match &event {
+            BackendEvent::Warning(issue) => tracing::warn!("{}", issue),
+            BackendEvent::Error(issue) => tracing::error!("{}", issue),
+        }
+        if let Some(callback) = self.backend_event_callback.borrow_mut().as_mut() {
+            callback(&event);
+        }
+    }
+
+    /// 停用硬件 VSync，改用基于刷新间隔的软件帧节奏
+    ///
+    /// 已经实测到刷新间隔时直接采用，否则以驱动报告的刷新率 `refresh_rate` 作为初始估计，
+    /// 之后按呈现间隔和 vblank 计数修正。
+    fn fall_back_to_software_pacing(&self, refresh_rate: Option<f32>, reason: std::fmt::Arguments) {
+        let mut pacer = FramePacer::new(refresh_rate);
+        let measured = self.refresh_meter.borrow().interval();
+        if let Some(interval) = measured {
+            pacer.set_interval(interval);
+        }
+        self.report(BackendEvent::Warning(BackendIssue::VsyncUnsupported { reason: reason.to_string() }));
+        tracing::info!(
+            "软件帧节奏刷新间隔 {:?} ({})",
+            pacer.interval(),
+            if measured.is_some() { "实测" } else { "估计" }
+        );
+        self.vsync_supported.set(false);
+        *self.frame_pacer.borrow_mut() = Some(pacer);
+        // VSync 失败可能是显示模式被改变的结果
+        self.mode_check_due.set(true);
+    }
+
+    /// 记录一帧在 `now` 呈现完成：更新实测的刷新间隔，软件帧节奏启用时按它修正并前进相位
+    ///
+    /// `vblank_count` 只在软件帧节奏启用期间调用，VSync 可用时不额外读取。
+    fn record_present(&self, now: Instant, vblank_count: impl FnOnce() -> Option<u32>) {
+        let mut refresh_meter = self.refresh_meter.borrow_mut();
+        refresh_meter.presented(now);
+        if let Some(pacer) = self.frame_pacer.borrow_mut().as_mut() {
+            if let Some(count) = vblank_count() {
+                refresh_meter.vblank_counted(now, count);
+            }
+            if let Some(interval) = refresh_meter.interval() {
+                pacer.set_interval(interval);
+            }
+            pacer.frame_presented(now);
+        }
+    }
+
+    /// 把本帧重绘的区域同步给远程查看服务 (VNC、MJPEG)、桌面预览窗口和屏幕录制
+    fn update_mirrors(&self, adapter: &LinuxFbWindowAdapter, rects: &[Rect]) {
+        adapter.presenter.borrow_mut().with_render_target(|bytes| {
+            #[cfg(feature = "vnc")]
+            if let Some(vnc) = self.vnc.borrow().as_ref() {
+                vnc.update(bytes, adapter.pixel_format.get(), rects);
+            }
+            #[cfg(feature = "mjpeg")]
+            if let Some(mjpeg) = self.mjpeg.borrow().as_ref() {
+                mjpeg.update(bytes, adapter.pixel_format.get(), rects);
+            }
+            #[cfg(feature = "preview")]
+            if let Some(preview) = self.preview.borrow_mut().as_mut() {
+                preview.update(bytes, adapter.pixel_format.get(), rects);
+            }
+            if let Some(recorder) = self.recorder.borrow_mut().as_mut() {
+                recorder.update(bytes, adapter.pixel_format.get(), rects);
+            }
+        });
+    }
+
+    /// 直接模式下推进进行中的设备恢复：到达重试时间时重新打开设备
+    ///
+    /// 返回恢复是否仍在进行 (期间不渲染)；重试用尽时报告 [`BackendIssue::FlipFailed`] 并返回错误。
+    fn poll_display_recovery(&self, adapter: &LinuxFbWindowAdapter) -> Result<bool, PlatformError> {
+        let mut pending = self.display_recovery.borrow_mut();
+        let Some((recovery, retry_at)) = pending.as_mut() else {
+            return Ok(false);
+        };
+        if Instant::now() < *retry_at {
+            return Ok(true);
+        }
+        let Presenter::Direct(device) = &mut *adapter.presenter.borrow_mut() else {
+            *pending = None;
+            return Ok(false);
+        };
+        let result = recovery.attempt(device.as_mut());
+        match result {
+            Ok(None) => {
+                *retry_at = Instant::now() + recovery.next_delay();
+                Ok(true)
+            }
+            Ok(Some(attempts)) => {
+                let reason = std::mem::take(&mut recovery.reason);
+                *pending = None;
+                drop(pending);
+                self.report(BackendEvent::Warning(BackendIssue::DisplayRecovered { reason, attempts }));
+                Ok(false)
+            }
+            Err(e) => {
+                *pending = None;
+                drop(pending);
+                self.report(BackendEvent::Error(BackendIssue::FlipFailed { reason: e.to_string() }));
+                Err(e.into())
+            }
+        }
+    }
+
+    /// 直接模式：在事件循环线程中等待 VSync 并翻转，然后记录帧统计
+    ///
+    /// 翻转失败 (显示模式已改变，或开始重新打开失联的设备) 时返回 `true`，本帧丢失，需要重新渲染整帧。
+    fn present_direct(
+        &self,
+        device: &mut dyn FramebufferDevice,
+        render_time: Duration,
+    ) -> Result<bool, PlatformError> {
+        // VSync 等待
+        let vsync_start = Instant::now();
+        if self.config.vsync && self.vsync_supported.get() {
+            let _span = tracing::trace_span!("vsync").entered();
+            if let Err(e) = device.wait_for_vsync() {
+                // 驱动不支持：改用基于刷新间隔的软件节奏
+                self.fall_back_to_software_pacing(
+                    device.refresh_rate(),
+                    format_args!("等待 VSync 失败 (可能驱动不支持): {}", e),
+                );
+            }
+        }
+        let vsync_wait = vsync_start.elapsed();
+
+        // 看门狗自恢复：VSync 等待本身就超出预算，说明驱动的实现不可靠
+        if let Some(watchdog) = &self.watchdog {
+            if self.vsync_supported.get() && vsync_wait > watchdog.budget() {
+                self.fall_back_to_software_pacing(
+                    device.refresh_rate(),
+                    format_args!("等待 VSync 耗时 {:?}，超出看门狗预算", vsync_wait),
+                );
+            }
+        }
+
+        // 缓冲区翻转
+        let flip_start = Instant::now();
+        {
+            let _span = tracing::trace_span!("flip").entered();
+            if let Err(e) = device.flip() {
+                // 显示模式被外部改变后按旧模式翻转会失败，由下一次迭代重新配置
+                if self.display_mode_changed(device.size(), device.pixel_format()) {
+                    tracing::debug!("翻转失败，显示模式已改变: {}", e);
+                    self.mode_check_due.set(true);
+                    return Ok(true);
+                }
+                match DeviceRecovery::start(e, RecoveryBackoff::default()) {
+                    Ok(recovery) => {
+                        // 本帧已丢失，由调用方标记整个窗口重新渲染；重新打开设备由事件循环按退避时间调度
+                        let retry_at = Instant::now() + recovery.next_delay();
+                        *self.display_recovery.borrow_mut() = Some((recovery, retry_at));
+                        return Ok(true);
+                    }
+                    Err(e) => {
+                        self.report(BackendEvent::Error(BackendIssue::FlipFailed { reason: e.to_string() }));
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+        let flip_time = flip_start.elapsed();
+
+        self.record_present(Instant::now(), || device.vblank_count());
+
+        // 在 Tracy 中标记帧边界
+        #[cfg(feature = "tracy")]
+        if let Some(client) = tracing_tracy::client::Client::running() {
+            client.frame_mark();
+        }
+
+        // 直接渲染到后缓冲区，没有额外的复制阶段
+        self.frame_stats.borrow_mut().record_frame(
+            render_time,
+            Duration::ZERO,
+            vsync_wait,
+            flip_time,
+        );
+
+        Ok(false)
+    }
+
+    /// 事件循环主体，`should_exit` 在每次迭代开始时求值
+    ///
+    /// `heartbeat` 为空闲时的最长休眠时长，`None` 表示没有定时任务时无限期等待唤醒。
+    fn run_loop(
+        &self,
+        should_exit: &mut dyn FnMut() -> bool,
+        heartbeat: Option<Duration>,
+    ) -> Result<(), PlatformError> {
+        // 上一次运行的退出请求不影响本次运行
+        self.quit_flag.store(false, Ordering::Relaxed);
+        if self.config.keep_running_without_windows && !self.window_count_held.get() {
+            self.hold_window_count();
+        }
+        let result = self.run_iterations(should_exit, heartbeat);
+        // 无论以何种方式退出，都不再让看门狗把循环视为忙碌
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.iteration_finished();
+        }
+        result
+    }
+
+    /// 进入或离开 `run_event_loop`：切换 TTY 的图形/文本模式，独占或释放输入设备
+    ///
+    /// 两次运行之间控制台恢复为文本模式，应用可以输出文字或把键盘交给其他程序 (例如固件升级阶段)；
+    /// 再次进入时整屏重绘，覆盖控制台在此期间留下的内容。
+    fn set_foreground(&self, foreground: bool) {
+        if let Some(tty) = &self.tty {
+            let mode = if foreground { TerminalMode::Graphics } else { TerminalMode::Text };
+            if let Err(e) = fbio::set_terminal_mode(tty, mode) {
+                tracing::warn!("无法切换 TTY 模式: {}", e);
+            }
+            if let Some(original) = self.tty_keyboard_mode {
+                let mode = if foreground { KeyboardMode::Off } else { original };
+                if let Err(e) = fbio::set_keyboard_mode(tty, mode) {
+                    tracing::warn!("无法切换 TTY 键盘模式: {}", e);
+                }
+            }
+        }
+        if self.config.input_config.grab_devices {
+            if let Some(input_manager) = self.input_manager.borrow_mut().as_mut() {
+                input_manager.set_grab(foreground);
+            }
+        }
+        if foreground {
+            if let Some(adapter) = self.adapter.borrow().as_ref() {
+                adapter.invalidate_all();
+            }
+        }
+    }
+
+    /// 最后一个窗口隐藏时 Slint 通过事件循环代理退出循环；像 `run_event_loop_until_quit` 一样
+    /// 额外登记一个占位窗口，窗口计数就不会归零
+    ///
+    /// 需要已经通过 `set_platform` 设置了平台，否则留到下一次运行事件循环时再登记。
+    fn hold_window_count(&self) {
+        let held = i_slint_core::with_global_context(
+            || Err(PlatformError::NoPlatform),
+            |ctx| {
+                #[allow(deprecated)]
+                ctx.platform().set_event_loop_quit_on_last_window_closed(false);
+            },
+        );
+        self.window_count_held.set(held.is_ok());
+    }
+
+    fn run_iterations(
+        &self,
+        should_exit: &mut dyn FnMut() -> bool,
+        heartbeat: Option<Duration>,
+    ) -> Result<(), PlatformError> {
+        let adapter = self
+            .adapter
             .borrow()
             .as_ref()
             .cloned()
@@ -330,55 +2698,389 @@ impl Platform for LinuxFbPlatform {
 
         let window = adapter.window.clone();
 
+        // 未启用输入 (`without_input`) 时为 None
         let mut input_manager_guard = self.input_manager.borrow_mut();
-        let input_manager = input_manager_guard
-            .as_mut()
-            .expect("Input manager not initialized");
+        let mut input_manager = input_manager_guard.as_mut();
+        // 每次迭代的输入事件，在迭代之间复用以免轮询时分配
+        let mut events: Vec<WindowEvent> = Vec::new();
+        // 等待的文件描述符：输入源在前，然后是可选的 timerfd 和 event_fd；只在输入源变化时重建
+        let mut poll_fds: Vec<libc::pollfd> = Vec::new();
 
         if self.config.vsync {
             tracing::info!("VSync 已启用。渲染循环将等待硬件垂直消隐。");
         }
 
         loop {
-            // 0. 检查退出标志
-            if self.quit_flag.load(Ordering::Relaxed) {
+            // 0. 检查退出标志与用户条件
+            if self.quit_flag.load(Ordering::Relaxed) || should_exit() {
                 break;
             }
 
+            if let Some(watchdog) = &self.watchdog {
+                watchdog.iteration_started();
+            }
+
+            // 系统刚从挂起中恢复：Framebuffer 内容可能已丢失，重绘整个画面
+            if let Some(clock) = &self.clock {
+                if let Some(suspended) = clock.borrow_mut().check_resume() {
+                    tracing::info!("系统已从挂起中恢复 (挂起 {:?})，重绘整个画面", suspended);
+                    adapter.invalidate_all();
+                }
+            }
+
             // 处理来自 EventLoopProxy 的事件 (跨线程回调)
-            while let Ok(task) = self.event_receiver.try_recv() {
-                task();
+            {
+                let _span = tracing::trace_span!("proxy_tasks").entered();
+                while let Ok(task) = self.event_receiver.try_recv() {
+                    task();
+                }
+            }
+
+            // 运行时修改了缩放系数：按新的逻辑尺寸重新布局并整屏重绘
+            if self.scale_factor_changed.take() {
+                tracing::info!("缩放系数改为 {}", self.scale_factor.get());
+                self.dispatch_window_geometry(&adapter);
+                adapter.invalidate_all();
             }
 
             // 1. 处理 Slint 定时器和动画
-            i_slint_core::platform::update_timers_and_animations();
+            {
+                let _span = tracing::trace_span!("timers").entered();
+                i_slint_core::platform::update_timers_and_animations();
+            }
+
+            if let Some(input_manager) = input_manager.as_deref_mut() {
+                if let Some(calibration) = self.pending_touch_calibration.take() {
+                    input_manager.set_touch_calibration(Some(calibration));
+                }
+                for (name_or_path, enabled) in self.pending_device_states.take() {
+                    input_manager.set_device_enabled(&name_or_path, enabled);
+                }
+            }
 
             // 2. 轮询输入事件
-            for event in input_manager.poll() {
-                window.dispatch_event(event);
+            {
+                let _span = tracing::trace_span!("input").entered();
+                #[cfg(feature = "ime")]
+                if let Some(input_manager) = input_manager.as_deref_mut() {
+                    input_manager.set_text_input_focused(
+                        i_slint_core::window::WindowInner::from_pub(&window).text_input_focused(),
+                    );
+                }
+                #[cfg(feature = "preview")]
+                if let Some(preview) = self.preview.borrow_mut().as_mut() {
+                    if !preview.is_open() {
+                        tracing::info!("预览窗口已关闭，退出事件循环");
+                        self.quit_flag.store(true, Ordering::Relaxed);
+                    }
+                    let preview_input = preview.take_input();
+                    if let Some(input_manager) = input_manager.as_deref_mut() {
+                        input_manager.push_synthetic_events(preview_input);
+                    }
+                }
+                match input_manager.as_deref_mut() {
+                    Some(input_manager) => {
+                        input_manager.push_synthetic_events(self.injected_input.take());
+                        input_manager.poll(&mut events);
+                        *self.pointer_state.borrow_mut() = input_manager.pointer_state();
+                    }
+                    None => events.clear(),
+                }
+                #[cfg(feature = "vnc")]
+                if let Some(vnc) = self.vnc.borrow().as_ref() {
+                    events.extend(vnc.take_events(adapter.rotation, self.config.input_config.scroll_step));
+                }
+                // 输入设备报告屏幕坐标，窗口小于屏幕时换算到窗口内 (注入的事件已经是窗口坐标)
+                let (origin_x, origin_y) = adapter.window_origin();
+                if (origin_x, origin_y) != (0, 0) {
+                    let offset = |position: LogicalPosition| {
+                        LogicalPosition::new(position.x - origin_x as f32, position.y - origin_y as f32)
+                    };
+                    for event in events.iter_mut() {
+                        *event = map_event_position(event.clone(), offset);
+                    }
+                }
+                events.append(&mut self.injected_events.borrow_mut());
+                // 空闲电源策略：任何输入都恢复正常显示，唤醒关闭的屏幕的那次输入不交给界面
+                let system_keys = input_manager.as_deref().is_some_and(InputManager::has_system_keys);
+                if (!events.is_empty() || system_keys) && self.wake_from_idle(&adapter) {
+                    events.clear();
+                    if let Some(input_manager) = input_manager.as_deref_mut() {
+                        input_manager.take_system_keys();
+                    }
+                }
+                let mut feedback = false;
+                for event in events.drain(..) {
+                    let event = match self.event_filter.borrow_mut().as_mut() {
+                        Some(filter) => match filter(event) {
+                            Some(event) => event,
+                            None => continue,
+                        },
+                        None => event,
+                    };
+                    feedback |= self.config.beeper.as_ref().is_some_and(|config| match event {
+                        WindowEvent::KeyPressed { .. } => config.on_key,
+                        WindowEvent::PointerPressed { .. } => config.on_tap,
+                        _ => false,
+                    });
+                    // 落在虚拟键盘上的指针事件由键盘消费，转换为按键事件
+                    let keyboard_events = adapter
+                        .virtual_keyboard
+                        .as_ref()
+                        .and_then(|keyboard| keyboard.borrow_mut().handle_event(&event));
+                    match keyboard_events {
+                        Some(events) => events.into_iter().for_each(|e| window.dispatch_event(e)),
+                        None => window.dispatch_event(to_logical_event(event, self.scale_factor.get())),
+                    }
+                }
+                // 本轮有按键按下或轻点时鸣响一次
+                if feedback {
+                    if let Some(beeper) = self.beeper.borrow().as_ref() {
+                        beeper.beep();
+                    }
+                }
+                if let Some(input_manager) = input_manager.as_deref_mut() {
+                    if let Some(callback) = self.touch_callback.borrow_mut().as_mut() {
+                        for contact in input_manager.take_touch_contacts() {
+                            callback(&contact);
+                        }
+                    }
+                    let switches = input_manager.take_switch_events();
+                    if let Some(callback) = self.switch_callback.borrow_mut().as_mut() {
+                        for (switch, active) in switches {
+                            callback(switch, active);
+                        }
+                    }
+                    for event in input_manager.take_backend_events() {
+                        self.report(event);
+                    }
+                    let devices = input_manager.take_device_events();
+                    if let Some(callback) = self.device_callback.borrow_mut().as_mut() {
+                        for event in &devices {
+                            callback(event);
+                        }
+                    }
+                    let gestures = input_manager.take_touch_gestures();
+                    if let Some(callback) = self.gesture_callback.borrow_mut().as_mut() {
+                        for gesture in &gestures {
+                            callback(gesture);
+                        }
+                    }
+                    #[cfg(feature = "ime")]
+                    for event in input_manager.take_ime_events() {
+                        dispatch_ime_event(&window, event);
+                    }
+                }
+            }
+
+            // 纯按键导航模式下无法用指针选择元素，保证始终有焦点
+            if input_manager.as_deref().is_some_and(InputManager::keypad_navigation) {
+                let window_inner = i_slint_core::window::WindowInner::from_pub(&window);
+                if window_inner.focus_item.borrow().upgrade().is_none() {
+                    window_inner.focus_next_item();
+                }
+            }
+
+            // 虚拟键盘随文本输入框的焦点弹出或收起
+            if let Some(keyboard) = &adapter.virtual_keyboard {
+                let mut keyboard = keyboard.borrow_mut();
+                keyboard.set_text_input_focused(
+                    i_slint_core::window::WindowInner::from_pub(&window).text_input_focused(),
+                );
+                if keyboard.take_needs_redraw() {
+                    adapter.request_redraw();
+                }
+            }
+
+            // 系统按键动作
+            for key in input_manager.as_deref_mut().map(InputManager::take_system_keys).unwrap_or_default() {
+                self.handle_system_key(&adapter, key);
+            }
+
+            // 调试叠加层：同步 FPS 与触点，触点变化或 FPS 到了刷新间隔时需要重绘
+            if let Some(overlay) = &adapter.debug_overlay {
+                let fps = self.frame_stats.borrow().snapshot().fps;
+                let (origin_x, origin_y) = adapter.window_origin();
+                let touch_points = input_manager
+                    .as_deref_mut()
+                    .map(InputManager::touch_points)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|point| i_slint_core::api::PhysicalPosition::new(point.x - origin_x, point.y - origin_y));
+                if overlay.borrow_mut().update(crate::clock::now(), fps, touch_points) {
+                    adapter.request_redraw();
+                }
+            }
+
+            self.update_idle_power(&adapter);
+            self.update_fades(&adapter);
+
+            // 定期 (以及 VSync 或翻转失败后) 检查显示模式是否被外部改变、显示器是否接入
+            let now = crate::clock::now();
+            let mode_check_due = now.saturating_duration_since(self.last_mode_check.get()) >= MODE_CHECK_INTERVAL;
+            if self.mode_check_due.take() || mode_check_due {
+                self.last_mode_check.set(now);
+                let hotplug = self.connector.borrow_mut().as_mut().and_then(ConnectorMonitor::poll);
+                if let Some(connected) = hotplug {
+                    tracing::info!("显示器{}", if connected { "已接入，重新设置显示模式" } else { "已断开" });
+                    self.report(BackendEvent::Warning(BackendIssue::DisplayHotplug { connected }));
+                }
+                self.update_display_mode(&adapter, input_manager.as_deref_mut(), hotplug == Some(true))?;
+            }
+
+            // 软件帧节奏：尚未到达下一个刷新相位时推迟渲染
+            let pacing_delay = self
+                .frame_pacer
+                .borrow()
+                .as_ref()
+                .map_or(Duration::ZERO, |pacer| pacer.time_until_next_frame(Instant::now()));
+
+            // 窗口被隐藏：清空屏幕，此后不再渲染和翻转，直到重新显示
+            if adapter.visibility_changed.take() && !adapter.visible.get() {
+                tracing::debug!("窗口已隐藏，清空屏幕");
+                self.clear_screen(&adapter)?;
             }
 
+            // 直接模式的设备恢复期间不渲染，按重试时间醒来；
+            // 呈现线程模式：两个影子缓冲区都在呈现线程中时推迟渲染，归还缓冲区后呈现线程会唤醒事件循环
+            let recovering = self.poll_display_recovery(&adapter)?;
+            let render_target_ready = !recovering
+                && match &mut *adapter.presenter.borrow_mut() {
+                    Presenter::Threaded(threaded) => threaded.acquire(),
+                    _ => true,
+                };
+
             // 3. 渲染逻辑
-            if *adapter.needs_redraw.borrow() {
+            if *adapter.needs_redraw.borrow() && adapter.visible.get() && pacing_delay.is_zero() && render_target_ready {
                 *adapter.needs_redraw.borrow_mut() = false;
+                let _frame_span = tracing::trace_span!("frame").entered();
 
-                if let Err(e) = adapter.render_frame(&adapter.renderer) {
-                    tracing::error!("帧渲染错误: {}", e);
+                if let Some(keyboard) = &adapter.virtual_keyboard {
+                    keyboard
+                        .borrow_mut()
+                        .invalidate_previous(&adapter.renderer, window.scale_factor());
+                }
+                if let Some(overlay) = &adapter.debug_overlay {
+                    overlay
+                        .borrow_mut()
+                        .invalidate_previous(&adapter.renderer, window.scale_factor());
+                }
+                adapter.osd.borrow_mut().invalidate(&adapter.renderer, window.scale_factor());
+                // 缓冲区交替使用时补上后缓冲区在渲染器之外被改写的区域
+                if matches!(*adapter.presenter.borrow(), Presenter::Direct(_) | Presenter::Threaded(_)) {
+                    let stale = adapter.swap_damage.borrow_mut().take_back();
+                    adapter.mark_screen_rects_dirty(&stale);
                 }
 
-                let mut fb_buffer = adapter.fb_buffer.borrow_mut();
+                let render_start = Instant::now();
+                let mut damage = Vec::new();
+                {
+                    let _span = tracing::trace_span!("render").entered();
+                    match adapter.render_frame(&adapter.renderer) {
+                        Ok(region) => {
+                            let window = adapter.window_rect();
+                            damage = region
+                                .iter()
+                                .map(|(origin, size)| Rect {
+                                    x: window.x + origin.x,
+                                    y: window.y + origin.y,
+                                    w: size.width as i32,
+                                    h: size.height as i32,
+                                })
+                                .collect();
+                            self.update_mirrors(&adapter, &damage);
+                        }
+                        Err(e) => self.report(BackendEvent::Error(BackendIssue::RenderFailed { reason: e.to_string() })),
+                    }
+                }
+                let render_time = render_start.elapsed();
 
-                // VSync 等待
-                if self.config.vsync {
-                    if let Err(e) = fb_buffer.wait_for_vsync() {
-                        tracing::warn!("等待 VSync 失败 (可能驱动不支持): {}", e);
+                let reopened = match &mut *adapter.presenter.borrow_mut() {
+                    Presenter::Direct(device) => {
+                        let reopened = self.present_direct(device.as_mut(), render_time)?;
+                        let mut swap_damage = adapter.swap_damage.borrow_mut();
+                        if reopened {
+                            let (width, height) = device.size();
+                            swap_damage.invalidate(width, height);
+                        } else {
+                            swap_damage.flipped();
+                        }
+                        reopened
+                    }
+                    Presenter::Threaded(threaded) => {
+                        // 交给呈现线程，不在此等待；耗时在呈现完成后汇总
+                        threaded.submit();
+                        adapter.swap_damage.borrow_mut().flipped();
+                        self.last_render_time.set(render_time);
+                        false
+                    }
+                    Presenter::Sink(sink) => {
+                        // 区域写入计入复制阶段
+                        let write_start = Instant::now();
+                        if let Err(e) = sink.present(&damage) {
+                            self.report(BackendEvent::Error(BackendIssue::FlipFailed { reason: e.to_string() }));
+                            return Err(e.into());
+                        }
+                        self.frame_stats.borrow_mut().record_frame(
+                            render_time,
+                            write_start.elapsed(),
+                            Duration::ZERO,
+                            Duration::ZERO,
+                        );
+                        false
+                    }
+                    Presenter::Headless(_) => {
+                        // 内存缓冲区没有复制、VSync 和翻转阶段
+                        self.frame_stats.borrow_mut().record_frame(
+                            render_time,
+                            Duration::ZERO,
+                            Duration::ZERO,
+                            Duration::ZERO,
+                        );
+                        false
                     }
+                };
+                if reopened {
+                    adapter.invalidate_all();
                 }
+            }
+
+            // 汇总呈现线程已完成的帧
+            if let Presenter::Threaded(threaded) = &*adapter.presenter.borrow() {
+                let size = (threaded.width, threaded.height);
+                for result in threaded.take_results() {
+                    let timing = match result {
+                        Ok(timing) => timing,
+                        // 显示模式被外部改变：由下一次迭代重新配置，不视为致命错误
+                        Err(e) if self.display_mode_changed(size, adapter.pixel_format.get()) => {
+                            tracing::debug!("呈现线程翻转失败，显示模式已改变: {}", e);
+                            self.mode_check_due.set(true);
+                            continue;
+                        }
+                        Err(e) => {
+                            self.report(BackendEvent::Error(BackendIssue::FlipFailed { reason: e.clone() }));
+                            return Err(PlatformError::Other(e));
+                        }
+                    };
+                    if let Some(reason) = timing.vsync_error {
+                        self.fall_back_to_software_pacing(threaded.refresh_rate, format_args!("{}", reason));
+                    }
+                    self.record_present(timing.presented_at, || timing.vblank_count);
+                    if let Some((reason, attempts)) = timing.recovered {
+                        self.report(BackendEvent::Warning(BackendIssue::DisplayRecovered { reason, attempts }));
+                    }
+
+                    #[cfg(feature = "tracy")]
+                    if let Some(client) = tracing_tracy::client::Client::running() {
+                        client.frame_mark();
+                    }
 
-                // 缓冲区翻转
-                if let Err(e) = fb_buffer.flip() {
-                    tracing::error!("Framebuffer 翻转(Flip)失败: {}", e);
-                    return Err(PlatformError::Other(e.to_string()));
+                    self.frame_stats.borrow_mut().record_frame(
+                        self.last_render_time.get(),
+                        timing.copy_time,
+                        timing.vsync_wait,
+                        timing.flip_time,
+                    );
                 }
             }
 
@@ -388,35 +3090,116 @@ impl Platform for LinuxFbPlatform {
             }
 
             // 4. 计算休眠时间 & 等待事件 (Poll)
-            let next_timer = i_slint_core::platform::duration_until_next_timer_update();
-            
-            // 保持心跳，处理跨线程事件回调。默认 16ms 约等于 60fps 的检查频率
-            let timeout = next_timer.unwrap_or(DEFAULT_TIMEOUT);
+            //    没有待触发的定时器、进行中的动画、待渲染的帧或其他定时任务时无限期等待 (`None`)，
+            //    由输入设备、eventfd (跨线程事件、热插拔、呈现线程) 和 timerfd 唤醒
+            let mut timeout = i_slint_core::platform::duration_until_next_timer_update();
+            let mut wake_within = |duration: Duration| {
+                timeout = Some(timeout.map_or(duration, |timeout| timeout.min(duration)));
+            };
+
+            // 有待渲染的帧：被节奏控制推迟时在下一个刷新相位醒来，否则按帧间隔；
+            // 等待影子缓冲区时由呈现线程唤醒
+            if *adapter.needs_redraw.borrow() && adapter.visible.get() && render_target_ready {
+                wake_within(if pacing_delay.is_zero() { FRAME_INTERVAL } else { pacing_delay });
+            }
+
+            // 调试叠加层：界面静止时也定期刷新 FPS
+            if let Some(overlay) = adapter.debug_overlay.as_ref().filter(|_| adapter.visible.get()) {
+                wake_within(overlay.borrow().time_until_refresh(crate::clock::now()));
+            }
+
+            // 设备恢复中：在下一次重新打开设备时醒来
+            if let Some((_, retry_at)) = &*self.display_recovery.borrow() {
+                wake_within(retry_at.saturating_duration_since(Instant::now()));
+            }
+
+            // 动画进行中：推进动画时间，由动画属性请求重绘
+            if window.has_active_animations() {
+                wake_within(FRAME_INTERVAL);
+            }
+
+            // 惯性滚动、设备重试和热插拔扫描等需要输入模块定时推进
+            if let Some(input_timeout) = input_manager.as_deref().and_then(InputManager::next_timeout) {
+                wake_within(input_timeout);
+            }
+
+            // 渐变进行中：逐帧推进
+            if self.is_fading() {
+                wake_within(FRAME_INTERVAL);
+            }
+
+            // 在下一个空闲电源阶段到来时醒来
+            if let Some(power_timeout) =
+                self.idle_tracker.borrow().as_ref().and_then(|tracker| tracker.next_timeout(crate::clock::now()))
+            {
+                wake_within(power_timeout);
+            }
+
+            // 监测显示器热插拔时按检查间隔醒来；否则显示模式在下一次被唤醒时检查
+            if self.connector.borrow().is_some() {
+                let since_check = crate::clock::now().saturating_duration_since(self.last_mode_check.get());
+                wake_within(MODE_CHECK_INTERVAL.saturating_sub(since_check));
+            }
+
+            // 预览窗口的消息没有可以等待的文件描述符，按帧间隔处理
+            #[cfg(feature = "preview")]
+            if self.preview.borrow().is_some() {
+                wake_within(FRAME_INTERVAL);
+            }
+
+            // 调用方需要定期检查外部条件
+            if let Some(heartbeat) = heartbeat {
+                wake_within(heartbeat);
+            }
+
+            // 虚拟时间只在 step 中前进，等待没有意义
+            if self.config.virtual_time {
+                timeout = Some(Duration::ZERO);
+            }
 
             // 获取所有输入设备的文件描述符
-            let input_fds = input_manager.get_poll_fds();
-            
-            // 构建 pollfd 向量，预留 +1 空间给 event_fd
-            let mut poll_fds: Vec<libc::pollfd> = Vec::with_capacity(input_fds.len() + 1);
-            
-            for fd in input_fds {
-                poll_fds.push(libc::pollfd {
-                    fd,
-                    events: libc::POLLIN,
-                    revents: 0
-                });
+            let input_fds = input_manager.as_deref().map(InputManager::get_poll_fds).unwrap_or_default();
+            let fixed_fds = usize::from(self.clock.is_some()) + 1;
+            let unchanged = poll_fds.len() == input_fds.len() + fixed_fds
+                && poll_fds.iter().zip(input_fds).all(|(pollfd, &fd)| pollfd.fd == fd);
+            if unchanged {
+                for pollfd in &mut poll_fds {
+                    pollfd.revents = 0;
+                }
+            } else {
+                poll_fds.clear();
+                let clock_fd = self.clock.as_ref().map(|clock| clock.borrow().fd());
+                // 将 event_fd 加入 poll 列表，以便被 proxy 唤醒
+                for fd in input_fds.iter().copied().chain(clock_fd).chain([self.event_fd]) {
+                    poll_fds.push(libc::pollfd { fd, events: libc::POLLIN, revents: 0 });
+                }
+            }
+
+            // 感知挂起的时钟：由 timerfd 负责定时唤醒，poll 本身不再设置超时
+            let mut timeout_ms = timeout.map_or(-1, |timeout| timeout.as_millis().min(i32::MAX as u128) as i32);
+            if let Some(clock) = &self.clock {
+                let clock = clock.borrow();
+                match timeout {
+                    Some(timeout) => clock.arm(timeout),
+                    None => clock.disarm(),
+                }
+                timeout_ms = -1;
             }
 
-            // 将 event_fd 加入 poll 列表，以便被 proxy 唤醒
-            poll_fds.push(libc::pollfd {
-                fd: self.event_fd,
-                events: libc::POLLIN,
-                revents: 0,
-            });
+            // 即将休眠：调用空闲回调
+            if let Some(callback) = self.idle_callback.borrow_mut().as_mut() {
+                callback(timeout.unwrap_or(Duration::MAX));
+            }
 
-            let timeout_ms = timeout.as_millis() as i32;
+            if let Some(watchdog) = &self.watchdog {
+                let elapsed = watchdog.iteration_finished();
+                if elapsed > watchdog.budget() {
+                    self.report(BackendEvent::Warning(BackendIssue::LoopOverBudget { elapsed, budget: watchdog.budget() }));
+                }
+            }
 
             // 调用 libc::poll 挂起线程
+            let _poll_span = tracing::trace_span!("poll", timeout_ms).entered();
             if !poll_fds.is_empty() || timeout_ms > 0 {
                 // SAFETY: poll_fds.as_mut_ptr() 是有效的，长度也正确
                 let ret = unsafe {
@@ -444,15 +3227,216 @@ impl Platform for LinuxFbPlatform {
                 }
             } else {
                 // 如果没有 fd 可轮询，则使用线程休眠
-                if timeout_ms > 0 {
+                if let Some(timeout) = timeout.filter(|_| timeout_ms > 0) {
                     std::thread::sleep(timeout);
                 }
             }
         }
         Ok(())
     }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evdev::{AbsoluteAxisCode, EventType, InputEvent};
+
+    #[test]
+    fn test_resolve_repaint_buffer_type() {
+        use RepaintBufferType::*;
+        assert_eq!(resolve_repaint_buffer_type(None, SwappedBuffers), SwappedBuffers);
+        assert_eq!(resolve_repaint_buffer_type(Some(NewBuffer), SwappedBuffers), NewBuffer);
+        assert_eq!(resolve_repaint_buffer_type(Some(ReusedBuffer), SwappedBuffers), SwappedBuffers);
+        assert_eq!(resolve_repaint_buffer_type(Some(SwappedBuffers), ReusedBuffer), SwappedBuffers);
+    }
 
-    fn new_event_loop_proxy(&self) -> Option<Box<dyn EventLoopProxy>> {
-        Some(Box::new(self.proxy.clone()))
+    #[test]
+    fn test_to_logical_event() {
+        let event = WindowEvent::PointerMoved { position: LogicalPosition::new(300.0, 150.0) };
+        assert_eq!(to_logical_event(event.clone(), 1.0), event);
+        assert_eq!(
+            to_logical_event(event, 1.5),
+            WindowEvent::PointerMoved { position: LogicalPosition::new(200.0, 100.0) }
+        );
+        let key = WindowEvent::KeyPressed { text: "a".into() };
+        assert_eq!(to_logical_event(key.clone(), 2.0), key);
+    }
+
+    #[test]
+    fn test_dots_per_inch() {
+        assert_eq!(dots_per_inch(800, 0), None);
+        assert_eq!(dots_per_inch(1270, 254), Some(127.0));
+        // 驱动把未知尺寸报告为 1mm 之类的值
+        assert_eq!(dots_per_inch(800, 1), None);
+    }
+
+    /// 翻转前等待测试放行的设备，模拟阻塞在 VSync 或翻转中的显示
+    struct GatedDevice {
+        inner: crate::device::MockFramebuffer,
+        gate: std::sync::mpsc::Receiver<()>,
+    }
+
+    impl FramebufferDevice for GatedDevice {
+        fn size(&self) -> (u32, u32) {
+            self.inner.size()
+        }
+
+        fn pixel_format(&self) -> PixelFormat {
+            self.inner.pixel_format()
+        }
+
+        fn back_buffer(&mut self) -> &mut [u8] {
+            self.inner.back_buffer()
+        }
+
+        fn flip(&mut self) -> Result<(), Error> {
+            // 超时避免断言失败时呈现线程无法退出
+            self.gate.recv_timeout(Duration::from_secs(5)).map_err(|_| Error::Other("测试未放行".into()))?;
+            self.inner.flip()
+        }
+
+        fn wait_for_vsync(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_render_thread_input_during_present() {
+        let (release, gate) = std::sync::mpsc::channel();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let filter_events = events.clone();
+        let platform = LinuxFbPlatformBuilder::new()
+            .with_framebuffer_device(GatedDevice { inner: crate::device::MockFramebuffer::new(16, 16, PixelFormat::Rgb565), gate })
+            .with_render_thread(true)
+            .with_virtual_time(true)
+            .without_input()
+            // 只有一个测试可以设置 Slint 平台，事件在过滤器中消费，不分发给窗口
+            .with_event_filter(move |event| {
+                filter_events.borrow_mut().push(event);
+                None
+            })
+            .build()
+            .unwrap();
+        let adapter = platform.create_window_adapter().unwrap();
+
+        // 第一帧阻塞在呈现线程中，第二帧渲染到另一个影子缓冲区，此后两个缓冲区都在排队或呈现
+        for _ in 0..3 {
+            adapter.request_redraw();
+            platform.step(Duration::ZERO).unwrap();
+        }
+
+        // 帧仍在呈现时输入照常处理
+        platform.inject_event(WindowEvent::PointerMoved { position: LogicalPosition::new(4.0, 4.0) });
+        platform.step(Duration::ZERO).unwrap();
+        assert_eq!(events.borrow().len(), 1);
+        assert_eq!(platform.frame_stats().frame_count, 0);
+
+        // 放行后两帧呈现完成，推迟的一帧随后渲染
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+        for _ in 0..1000 {
+            if platform.frame_stats().frame_count == 3 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+            platform.step(Duration::ZERO).unwrap();
+        }
+        assert_eq!(platform.frame_stats().frame_count, 3);
+        // 呈现线程不再等待放行，平台可以正常退出
+        drop(release);
     }
-}
\ No newline at end of file
+
+    // Slint 的事件循环代理是进程级的，每个测试进程只能设置一次平台，因此只有一个测试
+    #[test]
+    fn test_without_input() {
+        let platform = LinuxFbPlatformBuilder::new()
+            .with_headless(16, 16, PixelFormat::Rgb565)
+            .with_virtual_time(true)
+            .without_input()
+            .build()
+            .unwrap();
+        platform.create_window_adapter().unwrap();
+        assert!(platform.inner.input_manager.borrow().is_none());
+        platform.step(Duration::ZERO).unwrap();
+    }
+
+    #[test]
+    fn test_headless_virtual_time() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let filter_events = events.clone();
+        let platform = LinuxFbPlatformBuilder::new()
+            .with_headless(64, 32, PixelFormat::Rgb565)
+            .with_virtual_time(true)
+            .with_close_shortcut(RawKeyCode::KEY_Q, Modifiers { control: true, ..Default::default() })
+            .with_event_filter(move |event| {
+                filter_events.borrow_mut().push(event.clone());
+                Some(event)
+            })
+            .build()
+            .unwrap();
+        i_slint_core::platform::set_platform(Box::new(platform.clone())).unwrap();
+        let adapter = platform.create_window_adapter().unwrap();
+        assert_eq!(adapter.size(), i_slint_core::api::PhysicalSize::new(64, 32));
+
+        platform.inject_event(WindowEvent::PointerMoved {
+            position: i_slint_core::api::LogicalPosition::new(10.0, 10.0),
+        });
+        platform.step(Duration::ZERO).unwrap();
+        assert_eq!(events.borrow().len(), 1);
+        assert_eq!(platform.headless_frame().unwrap().len(), 64 * 32 * 2);
+        assert_eq!(platform.frame_stats().frame_count, 1);
+
+        let abs = |code: AbsoluteAxisCode, value| InputEvent::new(EventType::ABSOLUTE.0, code.0, value);
+        let syn = InputEvent::new(EventType::SYNCHRONIZATION.0, 0, 0);
+        let pressed = |button| {
+            events
+                .borrow()
+                .iter()
+                .any(|event| matches!(event, WindowEvent::PointerPressed { button: b, .. } if *b == button))
+        };
+
+        platform.inject_input_events([
+            abs(AbsoluteAxisCode::ABS_MT_TRACKING_ID, 1),
+            abs(AbsoluteAxisCode::ABS_MT_POSITION_X, 20),
+            abs(AbsoluteAxisCode::ABS_MT_POSITION_Y, 20),
+            syn,
+        ]);
+        platform.step(Duration::ZERO).unwrap();
+        assert!(pressed(PointerEventButton::Left));
+
+        // 长按时间未到
+        platform.inject_input_events([syn]);
+        platform.step(Duration::from_millis(300)).unwrap();
+        assert!(!pressed(PointerEventButton::Right));
+
+        platform.inject_input_events([syn]);
+        platform.step(Duration::from_millis(400)).unwrap();
+        assert!(pressed(PointerEventButton::Right));
+
+        // 隐藏窗口后屏幕被清空，不再渲染
+        let frames = platform.frame_stats().frame_count;
+        adapter.set_visible(false).unwrap();
+        adapter.request_redraw();
+        platform.step(Duration::ZERO).unwrap();
+        assert!(platform.headless_frame().unwrap().iter().all(|&byte| byte == 0));
+        assert_eq!(platform.frame_stats().frame_count, frames);
+        adapter.set_visible(true).unwrap();
+        platform.step(Duration::ZERO).unwrap();
+        assert_eq!(platform.frame_stats().frame_count, frames + 1);
+
+        // Ctrl+Q 请求关闭窗口
+        let key = |code: RawKeyCode, value| InputEvent::new(EventType::KEY.0, code.0, value);
+        platform.inject_input_events([key(RawKeyCode::KEY_LEFTCTRL, 1), key(RawKeyCode::KEY_Q, 1), syn]);
+        platform.step(Duration::ZERO).unwrap();
+        assert!(events.borrow().iter().any(|event| matches!(event, WindowEvent::CloseRequested)));
+
+        // 退出后可以再次运行事件循环
+        for _ in 0..2 {
+            i_slint_core::api::invoke_from_event_loop(|| {
+                i_slint_core::api::quit_event_loop().unwrap();
+            })
+            .unwrap();
+            platform.run_event_loop().unwrap();
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! 空闲电源策略
+//!
+//! 事件循环记录最后一次输入的时间，无输入的时长依次达到各阶段的超时后进入该阶段：
+//! 软件调暗画面、降低背光、关闭显示 (FBIOBLANK)，最后让显示器断电。
+//! 阶段是累积的 (例如调暗后再降低背光，画面保持调暗)，任何输入都会按相反顺序退出所有阶段；
+//! 处于关闭显示阶段时，唤醒屏幕的那次输入不会交给界面，避免误触。
+//!
+//! 此外提供亮度渐变 ([`LinuxFbPlatform::fade_backlight`](crate::LinuxFbPlatform::fade_backlight)、
+//! [`LinuxFbPlatform::fade_brightness`](crate::LinuxFbPlatform::fade_brightness))：
+//! 由事件循环逐次推进，不阻塞渲染。
+
+use std::time::{Duration, Instant};
+
+/// 电源策略的一个阶段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerStage {
+    /// 软件调暗：渲染结果按 `brightness` (0.0 ~ 1.0) 压暗，不依赖背光设备
+    Dim { brightness: f32 },
+    /// 把 sysfs 背光降低到最大亮度的 `percent`%，退出时恢复原来的亮度
+    Backlight { percent: u8 },
+    /// 关闭显示 (FBIOBLANK 的 `FB_BLANK_NORMAL`)
+    Blank,
+    /// 显示器断电 (FBIOBLANK 的 `FB_BLANK_POWERDOWN`)
+    Powerdown,
+}
+
+impl PowerStage {
+    /// 该阶段是否关闭了显示
+    pub(crate) fn is_blanking(self) -> bool {
+        matches!(self, PowerStage::Blank | PowerStage::Powerdown)
+    }
+}
+
+/// 多阶段空闲电源策略，通过构建器的 `with_power_policy` 启用
+///
+/// ```rust,no_run
+/// use slint_backend_linuxfb::power::{PowerPolicy, PowerStage};
+/// use std::time::Duration;
+///
+/// let policy = PowerPolicy::new()
+///     .with_stage(Duration::from_secs(30), PowerStage::Dim { brightness: 0.5 })
+///     .with_stage(Duration::from_secs(60), PowerStage::Backlight { percent: 10 })
+///     .with_stage(Duration::from_secs(120), PowerStage::Blank)
+///     .with_stage(Duration::from_secs(600), PowerStage::Powerdown);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PowerPolicy {
+    /// 按超时排序的阶段
+    stages: Vec<(Duration, PowerStage)>,
+}
+
+impl PowerPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一个阶段：距最后一次输入 `after` 后进入
+    pub fn with_stage(mut self, after: Duration, stage: PowerStage) -> Self {
+        let index = self.stages.partition_point(|(timeout, _)| *timeout <= after);
+        self.stages.insert(index, (after, stage));
+        self
+    }
+
+    /// 按进入顺序排列的阶段
+    pub fn stages(&self) -> &[(Duration, PowerStage)] {
+        &self.stages
+    }
+}
+
+/// 亮度渐变的缓动曲线
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// 匀速
+    Linear,
+    /// 先慢后快
+    EaseIn,
+    /// 先快后慢
+    EaseOut,
+    /// 两端慢、中间快
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// 把线性进度 `t` (0.0 ~ 1.0) 映射为缓动后的进度
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// 进行中的渐变：在 `duration` 内从 `from` 过渡到 `to`
+pub(crate) struct Fade {
+    from: f32,
+    to: f32,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl Fade {
+    pub fn new(from: f32, to: f32, duration: Duration, easing: Easing, now: Instant) -> Self {
+        Self { from, to, start: now, duration, easing }
+    }
+
+    /// `now` 时的值，以及渐变是否已经结束
+    pub fn value_at(&self, now: Instant) -> (f32, bool) {
+        let elapsed = now.saturating_duration_since(self.start);
+        if elapsed >= self.duration {
+            return (self.to, true);
+        }
+        let progress = self.easing.apply(elapsed.as_secs_f32() / self.duration.as_secs_f32());
+        (self.from + (self.to - self.from) * progress, false)
+    }
+}
+
+/// 事件循环中的空闲跟踪
+pub(crate) struct IdleTracker {
+    policy: PowerPolicy,
+    last_activity: Instant,
+    /// 已进入的阶段数
+    entered: usize,
+}
+
+impl IdleTracker {
+    pub fn new(policy: PowerPolicy, now: Instant) -> Self {
+        Self { policy, last_activity: now, entered: 0 }
+    }
+
+    /// 记录一次输入，返回需要退出的阶段 (按相反顺序)
+    pub fn activity(&mut self, now: Instant) -> Vec<PowerStage> {
+        self.last_activity = now;
+        let left = self.policy.stages[..self.entered].iter().rev().map(|(_, stage)| *stage).collect();
+        self.entered = 0;
+        left
+    }
+
+    /// 返回到达超时、需要进入的阶段
+    pub fn poll(&mut self, now: Instant) -> Vec<PowerStage> {
+        let idle = now.saturating_duration_since(self.last_activity);
+        let start = self.entered;
+        while self.policy.stages.get(self.entered).is_some_and(|(after, _)| *after <= idle) {
+            self.entered += 1;
+        }
+        self.policy.stages[start..self.entered].iter().map(|(_, stage)| *stage).collect()
+    }
+
+    /// 距离进入下一个阶段的时间，所有阶段都已进入时返回 `None`
+    pub fn next_timeout(&self, now: Instant) -> Option<Duration> {
+        let idle = now.saturating_duration_since(self.last_activity);
+        self.policy.stages.get(self.entered).map(|(after, _)| after.saturating_sub(idle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_stages() {
+        let policy = PowerPolicy::new()
+            .with_stage(Duration::from_secs(20), PowerStage::Blank)
+            .with_stage(Duration::from_secs(10), PowerStage::Dim { brightness: 0.5 });
+        let start = Instant::now();
+        let mut tracker = IdleTracker::new(policy, start);
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(tracker.poll(at(5)).is_empty());
+        assert_eq!(tracker.next_timeout(at(5)), Some(Duration::from_secs(5)));
+        assert_eq!(tracker.poll(at(25)), [PowerStage::Dim { brightness: 0.5 }, PowerStage::Blank]);
+        assert_eq!(tracker.next_timeout(at(25)), None);
+
+        assert_eq!(tracker.activity(at(26)), [PowerStage::Blank, PowerStage::Dim { brightness: 0.5 }]);
+        assert!(tracker.activity(at(27)).is_empty());
+        assert_eq!(tracker.poll(at(37)), [PowerStage::Dim { brightness: 0.5 }]);
+    }
+
+    #[test]
+    fn test_fade() {
+        let start = Instant::now();
+        let fade = Fade::new(100.0, 0.0, Duration::from_secs(2), Easing::EaseOut, start);
+        assert_eq!(fade.value_at(start), (100.0, false));
+        assert_eq!(fade.value_at(start + Duration::from_secs(1)), (25.0, false));
+        assert_eq!(fade.value_at(start + Duration::from_secs(3)), (0.0, true));
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::EaseIn.apply(2.0), 1.0);
+    }
+}
@@ -0,0 +1,596 @@
+//! 帧呈现策略
+//!
+//! 渲染结果有两种方式送到屏幕上：
+//! - **直接模式**：Slint 直接渲染到 Framebuffer 的后缓冲区，
+//!   在事件循环线程内等待 VSync 并翻转。
+//! - **线程模式**：Slint 渲染到内存中两个轮流使用的影子缓冲区之一，渲染完成的缓冲区经帧队列交给
+//!   独立的呈现线程复制到 Framebuffer、等待 VSync 和翻转，翻转后再归还给事件循环。
+//!   这些可能阻塞的操作不再占用事件循环，呈现线程处理上一帧时事件循环可以渲染下一帧，输入持续得到处理。
+//!
+//! 注意：Slint 场景的软件渲染本身必须在事件循环线程中执行 (Slint 对象不是 `Send`)，
+//! 线程模式移走的是渲染之后的复制、VSync 等待和翻转阶段；复制经过 [`Blitter`](crate::blitter::Blitter)，
+//! 可以交给硬件 2D 引擎完成。
+//!
+//! 自定义的 [`DisplaySink`] 使用**区域模式**：Slint 渲染到影子缓冲区，
+//! 事件循环随后把重绘区域逐个写入输出。
+//!
+//! 此外还有不依赖任何设备的**无头模式**：渲染到普通的内存缓冲区，
+//! 用于在 CI 等没有 `/dev/fb0` 的环境中运行界面集成测试。
+
+use crate::blitter::{BlitBuffer, SharedBlitter, Surface, SurfaceMut};
+use crate::device::{DisplaySink, FramebufferDevice, Rect};
+use crate::error::Error;
+use crate::pixels::PixelFormat;
+use crate::window::Rotation;
+use i_slint_core::Color;
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, sync_channel, Receiver, RecvTimeoutError, Sender, SyncSender, TryRecvError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 翻转失败后重新打开设备的退避策略
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RecoveryBackoff {
+    /// 第一次重新打开设备前的等待时间，之后每次加倍
+    pub initial: Duration,
+    /// 放弃恢复前重新打开设备的次数
+    pub attempts: u32,
+}
+
+impl Default for RecoveryBackoff {
+    /// 50ms 起每次加倍，共 6 次 (总等待约 3 秒)
+    fn default() -> Self {
+        Self { initial: Duration::from_millis(50), attempts: 6 }
+    }
+}
+
+impl RecoveryBackoff {
+    /// 第 `attempt` 次 (从 1 开始) 重新打开设备前的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial.saturating_mul(1 << attempt.saturating_sub(1).min(16))
+    }
+}
+
+/// 翻转因设备失联 (EIO / ENODEV) 失败后逐次重新打开设备的进度
+///
+/// 不自行等待：调用方在 [`next_delay`](Self::next_delay) 之后调用 [`attempt`](Self::attempt)，
+/// 直接模式由事件循环按重试时间调度，呈现线程在线程中等待。
+pub(crate) struct DeviceRecovery {
+    /// 首次翻转失败的原因
+    pub reason: String,
+    backoff: RecoveryBackoff,
+    /// 已经尝试的次数
+    attempts: u32,
+}
+
+impl DeviceRecovery {
+    /// 翻转失败后开始恢复；与设备失联无关的错误原样返回
+    pub fn start(error: Error, backoff: RecoveryBackoff) -> Result<Self, Error> {
+        if !error.is_device_lost() {
+            return Err(error);
+        }
+        tracing::warn!("Framebuffer 翻转失败，尝试重新打开设备: {}", error);
+        Ok(Self { reason: error.to_string(), backoff, attempts: 0 })
+    }
+
+    /// 下一次重新打开设备前的等待时间
+    pub fn next_delay(&self) -> Duration {
+        self.backoff.delay(self.attempts + 1)
+    }
+
+    /// 重新打开一次设备：成功时返回第几次尝试成功 (缓冲区内容未定义，需要重新渲染整帧)，
+    /// 失败但还可以重试时返回 `None`，重试用尽时返回最后的错误
+    pub fn attempt(&mut self, device: &mut dyn FramebufferDevice) -> Result<Option<u32>, Error> {
+        self.attempts += 1;
+        match device.reopen() {
+            Ok(()) => {
+                tracing::info!("第 {} 次重新打开 Framebuffer 成功", self.attempts);
+                Ok(Some(self.attempts))
+            }
+            Err(e) if self.attempts >= self.backoff.attempts => Err(e),
+            Err(e) => {
+                tracing::debug!("第 {} 次重新打开 Framebuffer 失败: {}", self.attempts, e);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// 翻转因设备失联失败时，按 `backoff` 重新打开设备，每次尝试前调用 `wait` 等待
+///
+/// `wait` 返回 `false` 时放弃恢复 (例如呈现线程正在退出)。
+/// 成功时返回第几次尝试成功，其他错误或重试用尽时返回最后的错误。
+pub(crate) fn reopen_with_backoff(
+    device: &mut dyn FramebufferDevice,
+    error: Error,
+    backoff: RecoveryBackoff,
+    mut wait: impl FnMut(Duration) -> bool,
+) -> Result<u32, Error> {
+    let mut recovery = DeviceRecovery::start(error, backoff)?;
+    loop {
+        if !wait(recovery.next_delay()) {
+            return Err(Error::Other(format!("放弃恢复 Framebuffer: {}", recovery.reason)));
+        }
+        if let Some(attempts) = recovery.attempt(device)? {
+            return Ok(attempts);
+        }
+    }
+}
+
+/// 用 blitter 把整帧 `shadow` 复制到设备的后缓冲区，blitter 失败时退回 memcpy
+pub(crate) fn copy_to_device(blitter: &SharedBlitter, device: &mut dyn FramebufferDevice, shadow: &BlitBuffer) {
+    let (width, height) = device.size();
+    let format = device.pixel_format();
+    let phys_addr = device.back_buffer_phys_addr();
+    let src = Surface { bytes: shadow, width, height, format, phys_addr: shadow.phys_addr() };
+    let mut dst = SurfaceMut { bytes: device.back_buffer(), width, height, format, phys_addr };
+    if let Err(e) = blitter.lock().unwrap().blit(&src, &mut dst, Rotation::Rotate0) {
+        tracing::warn!("blitter 复制失败，改用 memcpy: {}", e);
+        dst.bytes.copy_from_slice(shadow);
+    }
+}
+
+/// 用 blitter 以纯色填充设备的后缓冲区，blitter 失败时退回 CPU 填充
+pub(crate) fn fill_device(blitter: &SharedBlitter, device: &mut dyn FramebufferDevice, color: Color) {
+    let (width, height) = device.size();
+    let format = device.pixel_format();
+    let phys_addr = device.back_buffer_phys_addr();
+    let mut dst = SurfaceMut { bytes: device.back_buffer(), width, height, format, phys_addr };
+    if let Err(e) = blitter.lock().unwrap().fill(&mut dst, color) {
+        tracing::warn!("blitter 填充失败，改用 CPU: {}", e);
+        format.fill(dst.bytes, color);
+    }
+}
+
+/// 直接模式 (前后缓冲区) 和线程模式 (两个影子缓冲区) 下各缓冲区缺失、渲染器不知道的内容
+///
+/// Slint 的 `SwappedBuffers` 假定两个缓冲区严格交替且只由渲染器写入，渲染到后缓冲区时重绘本帧和上一帧的损坏区域。
+/// 平台在渲染器之外改写缓冲区 (隐藏窗口时清屏)、翻转失败或重新打开设备后这一假定不再成立，
+/// 这里按缓冲区记录这些改写，渲染到该缓冲区之前交给渲染器一并重绘，其余帧仍然只重绘损坏区域。
+#[derive(Debug, Default)]
+pub(crate) struct SwapDamage {
+    /// 当前后缓冲区的下标
+    back: usize,
+    /// 每个缓冲区自上次渲染以来在渲染器之外被改写的区域 (屏幕坐标)
+    stale: [Vec<Rect>; 2],
+}
+
+impl SwapDamage {
+    /// 后缓冲区的 `rect` 区域在渲染器之外被改写
+    pub fn overwritten(&mut self, rect: Rect) {
+        self.stale[self.back].push(rect);
+    }
+
+    /// 两个缓冲区的内容都不可信 (例如重新打开设备后)
+    pub fn invalidate(&mut self, width: u32, height: u32) {
+        let rect = Rect { x: 0, y: 0, w: width as i32, h: height as i32 };
+        self.stale = [vec![rect], vec![rect]];
+    }
+
+    /// 前后缓冲区已交换
+    pub fn flipped(&mut self) {
+        self.back ^= 1;
+    }
+
+    /// 即将渲染到后缓冲区：取出它需要额外重绘的区域
+    pub fn take_back(&mut self) -> Vec<Rect> {
+        std::mem::take(&mut self.stale[self.back])
+    }
+}
+
+/// 呈现线程报告的单帧耗时
+#[derive(Debug, Clone)]
+pub struct PresentTiming {
+    pub copy_time: Duration,
+    pub vsync_wait: Duration,
+    pub flip_time: Duration,
+    /// 本帧首次发现 VSync 不可用 (之后不再等待 VSync)
+    pub vsync_error: Option<String>,
+    /// 本帧翻转失败后重新打开了设备：失败原因和第几次尝试成功
+    pub recovered: Option<(String, u32)>,
+    /// 翻转完成的时间点，用于测量刷新间隔
+    pub presented_at: Instant,
+    /// 软件帧节奏启用期间设备报告的 vblank 计数
+    pub vblank_count: Option<u32>,
+}
+
+/// 帧呈现方式
+// 每个窗口只有一个实例，变体大小差异无关紧要
+#[allow(clippy::large_enum_variant)]
+pub enum Presenter {
+    /// 直接渲染到 Framebuffer 后缓冲区
+    Direct(Box<dyn FramebufferDevice>),
+    /// 渲染到影子缓冲区，由呈现线程完成复制与翻转
+    Threaded(ThreadedPresenter),
+    /// 渲染到影子缓冲区，按重绘区域写入自定义输出
+    Sink(SinkPresenter),
+    /// 无头模式：只渲染到内存缓冲区
+    Headless(HeadlessBuffer),
+}
+
+impl Presenter {
+    /// 单个缓冲区的尺寸 (像素)
+    pub fn size(&self) -> (u32, u32) {
+        match self {
+            Presenter::Direct(device) => device.size(),
+            Presenter::Threaded(threaded) => (threaded.width, threaded.height),
+            Presenter::Sink(sink) => sink.sink.size(),
+            Presenter::Headless(headless) => (headless.width, headless.height),
+        }
+    }
+
+    /// 以字节切片的形式访问当前的渲染目标
+    pub fn with_render_target<R>(&mut self, f: impl FnOnce(&mut [u8]) -> R) -> R {
+        match self {
+            Presenter::Direct(device) => f(device.back_buffer()),
+            Presenter::Threaded(threaded) => f(threaded.back_buffer()),
+            Presenter::Sink(sink) => f(&mut sink.frame),
+            Presenter::Headless(headless) => f(&mut headless.pixels),
+        }
+    }
+
+    /// 对每个渲染目标调用 `f`，用于填充所有缓冲区都需要的内容 (例如边框)
+    ///
+    /// 线程模式下等待呈现线程归还正在呈现的影子缓冲区；其他模式与 [`with_render_target`](Self::with_render_target) 相同。
+    pub fn for_each_render_target(&mut self, mut f: impl FnMut(&mut [u8])) {
+        match self {
+            Presenter::Threaded(threaded) => threaded.for_each_shadow(f),
+            _ => self.with_render_target(|target| f(target)),
+        }
+    }
+}
+
+/// 无头模式使用的内存 "Framebuffer"
+pub struct HeadlessBuffer {
+    pub width: u32,
+    pub height: u32,
+    /// 按像素格式排列的画面内容，行间没有填充
+    pub pixels: Vec<u8>,
+}
+
+impl HeadlessBuffer {
+    pub fn new(width: u32, height: u32, format: PixelFormat) -> Self {
+        Self { width, height, pixels: vec![0; width as usize * height as usize * format.bytes_per_pixel()] }
+    }
+}
+
+/// 区域模式的呈现器
+pub struct SinkPresenter {
+    sink: Box<dyn DisplaySink>,
+    /// 整帧影子缓冲区，在帧之间保留内容
+    frame: Vec<u8>,
+}
+
+impl SinkPresenter {
+    pub fn new(sink: Box<dyn DisplaySink>) -> Self {
+        let (width, height) = sink.size();
+        let len = width as usize * height as usize * sink.pixel_format().bytes_per_pixel();
+        Self { sink, frame: vec![0; len] }
+    }
+
+    /// 把本帧的重绘区域写入输出并提交
+    pub fn present(&mut self, region: &[Rect]) -> Result<(), Error> {
+        for rect in region {
+            self.sink.write_region(*rect, &self.frame)?;
+        }
+        self.sink.flush()
+    }
+}
+
+/// 影子缓冲区的数量：一个在事件循环中渲染，一个在呈现线程中复制和翻转
+const SHADOW_BUFFERS: usize = 2;
+
+/// 在独立线程中复制、等待 VSync 并翻转的呈现器
+pub struct ThreadedPresenter {
+    pub width: u32,
+    pub height: u32,
+    /// 设备报告的刷新率，软件帧节奏的初始估计
+    pub refresh_rate: Option<f32>,
+    /// 影子缓冲区的字节数
+    buffer_len: usize,
+    /// 事件循环持有的影子缓冲区 (由 blitter 分配)，队首为下一帧的渲染目标
+    ///
+    /// 缓冲区按提交顺序归还，渲染目标因此在两个缓冲区之间严格交替。
+    idle: VecDeque<BlitBuffer>,
+    /// 帧队列：渲染完成的影子缓冲区交给呈现线程
+    frame_sender: Option<SyncSender<BlitBuffer>>,
+    /// 呈现线程翻转后归还的影子缓冲区
+    free_receiver: Receiver<BlitBuffer>,
+    /// 呈现线程返回的结果
+    result_receiver: Receiver<Result<PresentTiming, String>>,
+    /// 关闭时通知呈现线程停止恢复设备的等待
+    shutdown: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ThreadedPresenter {
+    /// 接管 Framebuffer 并启动呈现线程，每帧通过 `blitter` 复制影子缓冲区
+    ///
+    /// `wake` 在每帧呈现完成 (影子缓冲区已归还) 后调用，用于唤醒事件循环汇总结果。
+    pub fn new(
+        mut device: Box<dyn FramebufferDevice>,
+        vsync: bool,
+        blitter: SharedBlitter,
+        wake: impl Fn() + Send + 'static,
+    ) -> Result<Self, Error> {
+        let (width, height) = device.size();
+        let refresh_rate = device.refresh_rate();
+        let buffer_len = device.back_buffer().len();
+        let idle = (0..SHADOW_BUFFERS)
+            .map(|_| blitter.lock().unwrap().allocate(buffer_len))
+            .collect::<Result<VecDeque<_>, _>>()?;
+        // 队列容量等于缓冲区数量，提交永远不会阻塞
+        let (frame_sender, frame_receiver) = sync_channel::<BlitBuffer>(SHADOW_BUFFERS);
+        let (free_sender, free_receiver) = channel();
+        let (result_sender, result_receiver) = channel();
+        let (shutdown, shutdown_receiver) = channel::<()>();
+
+        let handle = thread::Builder::new()
+            .name("linuxfb-present".into())
+            .spawn(move || {
+                let mut vsync_supported = vsync;
+                while let Ok(shadow) = frame_receiver.recv() {
+                    let copy_start = Instant::now();
+                    copy_to_device(&blitter, device.as_mut(), &shadow);
+                    let copy_time = copy_start.elapsed();
+
+                    let vsync_start = Instant::now();
+                    let mut vsync_error = None;
+                    if vsync_supported {
+                        if let Err(e) = device.wait_for_vsync() {
+                            vsync_error = Some(format!("呈现线程等待 VSync 失败 (可能驱动不支持): {}", e));
+                            vsync_supported = false;
+                        }
+                    }
+                    let vsync_wait = vsync_start.elapsed();
+
+                    let flip_start = Instant::now();
+                    let mut recovered = None;
+                    let result = match device.flip() {
+                        Ok(()) => Ok(()),
+                        // 影子缓冲区保存着完整画面，重新打开后再复制一次即可
+                        Err(e) => {
+                            let reason = e.to_string();
+                            let wait = |delay| shutdown_receiver.recv_timeout(delay) == Err(RecvTimeoutError::Timeout);
+                            reopen_with_backoff(device.as_mut(), e, RecoveryBackoff::default(), wait).and_then(|attempts| {
+                                recovered = Some((reason, attempts));
+                                copy_to_device(&blitter, device.as_mut(), &shadow);
+                                device.flip()
+                            })
+                        }
+                    };
+                    let presented_at = Instant::now();
+                    // VSync 失败后事件循环改用软件帧节奏，由它读取的 vblank 计数修正刷新间隔
+                    let vblank_count = if vsync && !vsync_supported { device.vblank_count() } else { None };
+                    let result = result.map(|_| PresentTiming {
+                        copy_time,
+                        vsync_wait,
+                        flip_time: presented_at - flip_start,
+                        vsync_error,
+                        recovered,
+                        presented_at,
+                        vblank_count,
+                    });
+                    if free_sender.send(shadow).is_err() || result_sender.send(result.map_err(|e| e.to_string())).is_err() {
+                        break;
+                    }
+                    wake();
+                }
+            })
+            .map_err(|e| Error::Other(format!("无法启动呈现线程: {}", e)))?;
+
+        Ok(Self {
+            width,
+            height,
+            refresh_rate,
+            buffer_len,
+            idle,
+            frame_sender: Some(frame_sender),
+            free_receiver,
+            result_receiver,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    /// 收回呈现线程已归还的影子缓冲区，返回是否有可以渲染的缓冲区
+    ///
+    /// 两个缓冲区都在呈现线程中时返回 `false`，事件循环应推迟渲染，直到呈现线程归还缓冲区后唤醒它。
+    pub fn acquire(&mut self) -> bool {
+        self.idle.extend(self.free_receiver.try_iter());
+        !self.idle.is_empty()
+    }
+
+    /// 当前的渲染目标，没有空闲的缓冲区时等待呈现线程归还
+    fn back_buffer(&mut self) -> &mut BlitBuffer {
+        if !self.acquire() {
+            // 呈现线程已退出 (错误在 take_results 中报告) 时渲染到一个不会被呈现的缓冲区
+            let shadow = self.free_receiver.recv().unwrap_or_else(|_| BlitBuffer::heap(self.buffer_len));
+            self.idle.push_back(shadow);
+        }
+        &mut self.idle[0]
+    }
+
+    /// 对所有影子缓冲区调用 `f`，先等待呈现线程归还正在呈现的缓冲区
+    fn for_each_shadow(&mut self, mut f: impl FnMut(&mut [u8])) {
+        self.idle.extend(self.free_receiver.try_iter());
+        while self.idle.len() < SHADOW_BUFFERS {
+            match self.free_receiver.recv() {
+                Ok(shadow) => self.idle.push_back(shadow),
+                Err(_) => break,
+            }
+        }
+        for shadow in &mut self.idle {
+            f(shadow);
+        }
+    }
+
+    /// 把渲染完成的影子缓冲区交给呈现线程，不阻塞事件循环
+    pub fn submit(&mut self) {
+        let Some(shadow) = self.idle.pop_front() else {
+            return;
+        };
+        // 队列容量等于缓冲区数量，只有呈现线程退出时才会失败
+        if let Some(sender) = &self.frame_sender {
+            if sender.try_send(shadow).is_err() {
+                tracing::error!("呈现线程已退出");
+            }
+        }
+    }
+
+    /// 取出呈现线程已完成的帧的结果
+    pub fn take_results(&self) -> Vec<Result<PresentTiming, String>> {
+        let mut results = Vec::new();
+        loop {
+            match self.result_receiver.try_recv() {
+                Ok(result) => results.push(result),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    results.push(Err("呈现线程已退出".into()));
+                    break;
+                }
+            }
+        }
+        results
+    }
+}
+
+impl Drop for ThreadedPresenter {
+    fn drop(&mut self) {
+        // 关闭帧队列并中断恢复设备的等待，呈现线程随之退出
+        self.frame_sender = None;
+        self.shutdown = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::MockFramebuffer;
+    use std::sync::{Arc, Mutex};
+
+    /// 前 `failures` 次重新打开失败的设备
+    struct FlakyDevice {
+        inner: MockFramebuffer,
+        failures: u32,
+    }
+
+    impl FramebufferDevice for FlakyDevice {
+        fn size(&self) -> (u32, u32) {
+            self.inner.size()
+        }
+
+        fn pixel_format(&self) -> PixelFormat {
+            self.inner.pixel_format()
+        }
+
+        fn back_buffer(&mut self) -> &mut [u8] {
+            self.inner.back_buffer()
+        }
+
+        fn flip(&mut self) -> Result<(), Error> {
+            self.inner.flip()
+        }
+
+        fn wait_for_vsync(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn reopen(&mut self) -> Result<(), Error> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(io_error(libc::ENODEV));
+            }
+            Ok(())
+        }
+    }
+
+    fn io_error(errno: i32) -> Error {
+        Error::LinuxFb(crate::linuxfb::Error::Io(std::io::Error::from_raw_os_error(errno)))
+    }
+
+    /// 把写入的区域和提交记录到共享列表的输出 (`None` 表示一次提交)
+    struct RecordingSink(Arc<Mutex<Vec<Option<Rect>>>>);
+
+    impl DisplaySink for RecordingSink {
+        fn size(&self) -> (u32, u32) {
+            (4, 2)
+        }
+
+        fn pixel_format(&self) -> PixelFormat {
+            PixelFormat::Rgb565
+        }
+
+        fn write_region(&mut self, rect: Rect, frame: &[u8]) -> Result<(), Error> {
+            assert_eq!(frame.len(), 4 * 2 * 2);
+            self.0.lock().unwrap().push(Some(rect));
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            self.0.lock().unwrap().push(None);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sink_presenter_writes_damage() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut presenter = SinkPresenter::new(Box::new(RecordingSink(log.clone())));
+        let rect = Rect { x: 1, y: 0, w: 2, h: 2 };
+        presenter.present(&[rect]).unwrap();
+        presenter.present(&[]).unwrap();
+        assert_eq!(*log.lock().unwrap(), [Some(rect), None, None]);
+    }
+
+    #[test]
+    fn test_swap_damage_follows_buffers() {
+        let mut damage = SwapDamage::default();
+        let rect = Rect { x: 0, y: 0, w: 4, h: 2 };
+        // 清屏写入后缓冲区并翻转：下一帧渲染到另一个缓冲区，不受影响
+        damage.overwritten(rect);
+        damage.flipped();
+        assert!(damage.take_back().is_empty());
+        damage.flipped();
+        // 再下一帧回到被清空的缓冲区，只需重绘一次
+        assert_eq!(damage.take_back(), [rect]);
+        assert!(damage.take_back().is_empty());
+
+        damage.invalidate(4, 2);
+        assert_eq!(damage.take_back(), [rect]);
+        damage.flipped();
+        assert_eq!(damage.take_back(), [rect]);
+    }
+
+    #[test]
+    fn test_reopen_with_backoff() {
+        let backoff = RecoveryBackoff { initial: Duration::from_millis(50), attempts: 3 };
+        let mut waits = Vec::new();
+        let mut device = FlakyDevice { inner: MockFramebuffer::new(2, 1, PixelFormat::Rgb565), failures: 1 };
+        let result = reopen_with_backoff(&mut device, io_error(libc::EIO), backoff, |delay| {
+            waits.push(delay);
+            true
+        });
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(waits, [Duration::from_millis(50), Duration::from_millis(100)]);
+
+        // 与设备失联无关的错误不尝试恢复
+        assert!(reopen_with_backoff(&mut device, io_error(libc::EINVAL), backoff, |_| unreachable!()).is_err());
+        assert_eq!(device.failures, 0);
+
+        // 重试用尽或等待被中断时放弃
+        device.failures = 10;
+        waits.clear();
+        let result = reopen_with_backoff(&mut device, io_error(libc::ENODEV), backoff, |delay| {
+            waits.push(delay);
+            true
+        });
+        assert!(result.is_err());
+        assert_eq!(waits.len(), 3);
+        assert!(reopen_with_backoff(&mut device, io_error(libc::EIO), backoff, |_| false).is_err());
+        assert_eq!(device.failures, 7);
+    }
+}
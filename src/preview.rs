@@ -0,0 +1,302 @@
+//! 桌面预览窗口
+//!
+//! 在开发机上用普通的桌面窗口代替 Framebuffer：画面来自与设备上相同的软件渲染流程，
+//! 鼠标和键盘被转换为 evdev 事件，经过与真实触摸屏、键盘相同的手势识别和键盘映射。
+//!
+//! - 鼠标左键：单指触摸
+//! - 鼠标右键：双指触摸 (水平排列，用于测试双指滚动)
+//! - 滚轮：鼠标滚轮
+
+use crate::canvas::Rect;
+use crate::mirror::FrameMirror;
+use crate::pixels::PixelFormat;
+use crate::window::Rotation;
+use evdev::{AbsoluteAxisCode, EventType, InputEvent, KeyCode, RelativeAxisCode, SynchronizationCode};
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+use std::collections::HashSet;
+
+/// 右键模拟双指触摸时两个手指的间距 (像素)
+const TWO_FINGER_SPACING: i32 = 40;
+
+/// 显示画面并采集鼠标键盘输入的桌面窗口
+pub(crate) struct PreviewWindow {
+    window: Window,
+    mirror: FrameMirror,
+    /// 显示旋转方向 (鼠标坐标需要转换回窗口坐标)
+    rotation: Rotation,
+    touch: MouseTouch,
+    held_keys: HashSet<Key>,
+}
+
+/// 用鼠标按键模拟的触摸状态
+#[derive(Default)]
+struct MouseTouch {
+    /// 当前按下的手指数量 (0 到 2)
+    fingers: usize,
+    /// 最近一次报告的触摸位置 (窗口坐标)
+    position: (i32, i32),
+    next_tracking_id: i32,
+}
+
+impl PreviewWindow {
+    /// 创建 `width` x `height` (屏幕尺寸，即旋转前的尺寸) 的预览窗口
+    pub fn new(width: u32, height: u32, rotation: Rotation) -> Result<Self, minifb::Error> {
+        let mut window = Window::new(
+            "slint-backend-linuxfb preview",
+            width as usize,
+            height as usize,
+            WindowOptions::default(),
+        )?;
+        // 帧节奏由事件循环控制，窗口本身不限速
+        window.set_target_fps(0);
+        Ok(Self {
+            window,
+            mirror: FrameMirror::new(width, height),
+            rotation,
+            touch: MouseTouch::default(),
+            held_keys: HashSet::new(),
+        })
+    }
+
+    /// 窗口是否仍然打开 (用户关闭窗口后事件循环应退出)
+    pub fn is_open(&self) -> bool {
+        self.window.is_open()
+    }
+
+    /// 复制渲染目标中 `region` (缓冲区坐标) 内的像素并刷新窗口
+    pub fn update(&mut self, bytes: &[u8], format: PixelFormat, region: &[Rect]) {
+        self.mirror.update(bytes, format, region);
+        let (width, height) = (self.mirror.width as usize, self.mirror.height as usize);
+        if let Err(e) = self.window.update_with_buffer(&self.mirror.pixels, width, height) {
+            tracing::warn!("预览窗口刷新失败: {}", e);
+        }
+    }
+
+    /// 处理窗口消息，返回鼠标和键盘转换得到的 evdev 事件 (触摸坐标为窗口像素)
+    pub fn take_input(&mut self) -> Vec<InputEvent> {
+        // 只处理窗口系统消息，不重新显示画面
+        self.window.update();
+
+        let mut events = Vec::new();
+        for key in self.window.get_keys_pressed(KeyRepeat::Yes) {
+            if let Some(code) = map_key(key) {
+                let value = if self.held_keys.insert(key) { 1 } else { 2 };
+                events.push(InputEvent::new(EventType::KEY.0, code.code(), value));
+            }
+        }
+        for key in self.window.get_keys_released() {
+            self.held_keys.remove(&key);
+            if let Some(code) = map_key(key) {
+                events.push(InputEvent::new(EventType::KEY.0, code.code(), 0));
+            }
+        }
+
+        let position = self.window.get_mouse_pos(MouseMode::Clamp).map(|(x, y)| {
+            self.rotation
+                .inverse()
+                .rotate_point(x as i32, y as i32, self.mirror.width, self.mirror.height)
+        });
+        let fingers = match position {
+            Some(_) if self.window.get_mouse_down(MouseButton::Right) => 2,
+            Some(_) if self.window.get_mouse_down(MouseButton::Left) => 1,
+            _ => 0,
+        };
+        let position = position.unwrap_or(self.touch.position);
+        self.touch.update(fingers, position, &mut events);
+
+        if let Some((_, dy)) = self.window.get_scroll_wheel().filter(|&(_, dy)| dy != 0.0) {
+            events.push(InputEvent::new(
+                EventType::RELATIVE.0,
+                RelativeAxisCode::REL_WHEEL.0,
+                dy.signum() as i32,
+            ));
+        }
+
+        if !events.is_empty() {
+            events.push(InputEvent::new(EventType::SYNCHRONIZATION.0, SynchronizationCode::SYN_REPORT.0, 0));
+        }
+        events
+    }
+}
+
+impl MouseTouch {
+    /// 按手指数量和位置的变化生成多点触控 (Protocol B) 事件
+    fn update(&mut self, fingers: usize, (x, y): (i32, i32), events: &mut Vec<InputEvent>) {
+        if fingers == self.fingers && (fingers == 0 || (x, y) == self.position) {
+            return;
+        }
+        let abs = |code: AbsoluteAxisCode, value: i32| InputEvent::new(EventType::ABSOLUTE.0, code.0, value);
+        for slot in 0..self.fingers.max(fingers) {
+            events.push(abs(AbsoluteAxisCode::ABS_MT_SLOT, slot as i32));
+            if slot >= fingers {
+                events.push(abs(AbsoluteAxisCode::ABS_MT_TRACKING_ID, -1));
+                continue;
+            }
+            if slot >= self.fingers {
+                events.push(abs(AbsoluteAxisCode::ABS_MT_TRACKING_ID, self.next_tracking_id));
+                self.next_tracking_id = (self.next_tracking_id + 1) % i32::from(u16::MAX);
+            }
+            // 双指时两个手指以鼠标位置为中心水平排列
+            let offset = if fingers == 2 { (slot as i32 * 2 - 1) * TWO_FINGER_SPACING / 2 } else { 0 };
+            events.push(abs(AbsoluteAxisCode::ABS_MT_POSITION_X, x + offset));
+            events.push(abs(AbsoluteAxisCode::ABS_MT_POSITION_Y, y));
+        }
+        self.fingers = fingers;
+        self.position = (x, y);
+    }
+}
+
+/// 桌面键盘按键对应的 evdev 键码
+fn map_key(key: Key) -> Option<KeyCode> {
+    Some(match key {
+        Key::Key0 => KeyCode::KEY_0,
+        Key::Key1 => KeyCode::KEY_1,
+        Key::Key2 => KeyCode::KEY_2,
+        Key::Key3 => KeyCode::KEY_3,
+        Key::Key4 => KeyCode::KEY_4,
+        Key::Key5 => KeyCode::KEY_5,
+        Key::Key6 => KeyCode::KEY_6,
+        Key::Key7 => KeyCode::KEY_7,
+        Key::Key8 => KeyCode::KEY_8,
+        Key::Key9 => KeyCode::KEY_9,
+        Key::A => KeyCode::KEY_A,
+        Key::B => KeyCode::KEY_B,
+        Key::C => KeyCode::KEY_C,
+        Key::D => KeyCode::KEY_D,
+        Key::E => KeyCode::KEY_E,
+        Key::F => KeyCode::KEY_F,
+        Key::G => KeyCode::KEY_G,
+        Key::H => KeyCode::KEY_H,
+        Key::I => KeyCode::KEY_I,
+        Key::J => KeyCode::KEY_J,
+        Key::K => KeyCode::KEY_K,
+        Key::L => KeyCode::KEY_L,
+        Key::M => KeyCode::KEY_M,
+        Key::N => KeyCode::KEY_N,
+        Key::O => KeyCode::KEY_O,
+        Key::P => KeyCode::KEY_P,
+        Key::Q => KeyCode::KEY_Q,
+        Key::R => KeyCode::KEY_R,
+        Key::S => KeyCode::KEY_S,
+        Key::T => KeyCode::KEY_T,
+        Key::U => KeyCode::KEY_U,
+        Key::V => KeyCode::KEY_V,
+        Key::W => KeyCode::KEY_W,
+        Key::X => KeyCode::KEY_X,
+        Key::Y => KeyCode::KEY_Y,
+        Key::Z => KeyCode::KEY_Z,
+        Key::F1 => KeyCode::KEY_F1,
+        Key::F2 => KeyCode::KEY_F2,
+        Key::F3 => KeyCode::KEY_F3,
+        Key::F4 => KeyCode::KEY_F4,
+        Key::F5 => KeyCode::KEY_F5,
+        Key::F6 => KeyCode::KEY_F6,
+        Key::F7 => KeyCode::KEY_F7,
+        Key::F8 => KeyCode::KEY_F8,
+        Key::F9 => KeyCode::KEY_F9,
+        Key::F10 => KeyCode::KEY_F10,
+        Key::F11 => KeyCode::KEY_F11,
+        Key::F12 => KeyCode::KEY_F12,
+        Key::Down => KeyCode::KEY_DOWN,
+        Key::Left => KeyCode::KEY_LEFT,
+        Key::Right => KeyCode::KEY_RIGHT,
+        Key::Up => KeyCode::KEY_UP,
+        Key::Apostrophe => KeyCode::KEY_APOSTROPHE,
+        Key::Backquote => KeyCode::KEY_GRAVE,
+        Key::Backslash => KeyCode::KEY_BACKSLASH,
+        Key::Comma => KeyCode::KEY_COMMA,
+        Key::Equal => KeyCode::KEY_EQUAL,
+        Key::LeftBracket => KeyCode::KEY_LEFTBRACE,
+        Key::Minus => KeyCode::KEY_MINUS,
+        Key::Period => KeyCode::KEY_DOT,
+        Key::RightBracket => KeyCode::KEY_RIGHTBRACE,
+        Key::Semicolon => KeyCode::KEY_SEMICOLON,
+        Key::Slash => KeyCode::KEY_SLASH,
+        Key::Backspace => KeyCode::KEY_BACKSPACE,
+        Key::Delete => KeyCode::KEY_DELETE,
+        Key::End => KeyCode::KEY_END,
+        Key::Enter => KeyCode::KEY_ENTER,
+        Key::Escape => KeyCode::KEY_ESC,
+        Key::Home => KeyCode::KEY_HOME,
+        Key::Insert => KeyCode::KEY_INSERT,
+        Key::Menu => KeyCode::KEY_MENU,
+        Key::PageDown => KeyCode::KEY_PAGEDOWN,
+        Key::PageUp => KeyCode::KEY_PAGEUP,
+        Key::Pause => KeyCode::KEY_PAUSE,
+        Key::Space => KeyCode::KEY_SPACE,
+        Key::Tab => KeyCode::KEY_TAB,
+        Key::NumLock => KeyCode::KEY_NUMLOCK,
+        Key::CapsLock => KeyCode::KEY_CAPSLOCK,
+        Key::ScrollLock => KeyCode::KEY_SCROLLLOCK,
+        Key::LeftShift => KeyCode::KEY_LEFTSHIFT,
+        Key::RightShift => KeyCode::KEY_RIGHTSHIFT,
+        Key::LeftCtrl => KeyCode::KEY_LEFTCTRL,
+        Key::RightCtrl => KeyCode::KEY_RIGHTCTRL,
+        Key::LeftAlt => KeyCode::KEY_LEFTALT,
+        Key::RightAlt => KeyCode::KEY_RIGHTALT,
+        Key::LeftSuper => KeyCode::KEY_LEFTMETA,
+        Key::RightSuper => KeyCode::KEY_RIGHTMETA,
+        Key::NumPad0 => KeyCode::KEY_KP0,
+        Key::NumPad1 => KeyCode::KEY_KP1,
+        Key::NumPad2 => KeyCode::KEY_KP2,
+        Key::NumPad3 => KeyCode::KEY_KP3,
+        Key::NumPad4 => KeyCode::KEY_KP4,
+        Key::NumPad5 => KeyCode::KEY_KP5,
+        Key::NumPad6 => KeyCode::KEY_KP6,
+        Key::NumPad7 => KeyCode::KEY_KP7,
+        Key::NumPad8 => KeyCode::KEY_KP8,
+        Key::NumPad9 => KeyCode::KEY_KP9,
+        Key::NumPadDot => KeyCode::KEY_KPDOT,
+        Key::NumPadSlash => KeyCode::KEY_KPSLASH,
+        Key::NumPadAsterisk => KeyCode::KEY_KPASTERISK,
+        Key::NumPadMinus => KeyCode::KEY_KPMINUS,
+        Key::NumPadPlus => KeyCode::KEY_KPPLUS,
+        Key::NumPadEnter => KeyCode::KEY_KPENTER,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mouse_touch_events() {
+        let mut touch = MouseTouch::default();
+        let mut events = Vec::new();
+        touch.update(1, (100, 50), &mut events);
+        let values: Vec<_> = events.iter().map(|e| (e.code(), e.value())).collect();
+        assert_eq!(
+            values,
+            [
+                (AbsoluteAxisCode::ABS_MT_SLOT.0, 0),
+                (AbsoluteAxisCode::ABS_MT_TRACKING_ID.0, 0),
+                (AbsoluteAxisCode::ABS_MT_POSITION_X.0, 100),
+                (AbsoluteAxisCode::ABS_MT_POSITION_Y.0, 50),
+            ]
+        );
+
+        // 位置不变时不产生事件
+        events.clear();
+        touch.update(1, (100, 50), &mut events);
+        assert!(events.is_empty());
+
+        // 切换为双指：第一个手指移到左侧，第二个手指在右侧按下
+        touch.update(2, (100, 50), &mut events);
+        let x: Vec<_> = events
+            .iter()
+            .filter(|e| e.code() == AbsoluteAxisCode::ABS_MT_POSITION_X.0)
+            .map(|e| e.value())
+            .collect();
+        assert_eq!(x, [80, 120]);
+
+        // 抬起：两个触点都结束
+        events.clear();
+        touch.update(0, (100, 50), &mut events);
+        let ended = events
+            .iter()
+            .filter(|e| e.code() == AbsoluteAxisCode::ABS_MT_TRACKING_ID.0 && e.value() == -1)
+            .count();
+        assert_eq!(ended, 2);
+    }
+}
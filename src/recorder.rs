@@ -0,0 +1,227 @@
+//! 屏幕录制
+//!
+//! 每次渲染后把损坏区域复制到帧副本 (与 VNC、MJPEG 相同)，再交给写入线程编码到文件，
+//! 磁盘 I/O 不占用事件循环。写入线程跟不上时丢弃帧，下一帧改为整屏重新同步。
+//! 录制的是 Framebuffer 上的画面，即旋转之后的屏幕方向。
+//!
+//! 支持两种格式：
+//! - [`RecordingFormat::Y4m`]：YUV4MPEG2 (4:4:4)，固定帧率，ffmpeg、mpv 等可直接播放或转码。
+//!   两次渲染之间重复上一帧，视频时长与实际时间一致。
+//! - [`RecordingFormat::Damage`]：只记录损坏区域和时间戳的原始格式，体积小，适合附在缺陷报告中。
+//!   所有整数均为小端序：
+//!   - 文件头：8 字节魔数 `SLFBREC1`，宽度 `u32`，高度 `u32`
+//!   - 每帧：距录制开始的微秒数 `u64`，矩形数量 `u32`，随后每个矩形为
+//!     `x`、`y`、`w`、`h` (`u32`) 及按行排列的 `w * h` 个 0x00RRGGBB 像素 (`u32`)
+
+use crate::canvas::Rect;
+use crate::mirror::FrameMirror;
+use crate::pixels::PixelFormat;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 等待写入的最大帧数
+const QUEUE_LEN: usize = 4;
+/// 原始损坏区域格式的魔数
+const DAMAGE_MAGIC: &[u8; 8] = b"SLFBREC1";
+
+/// 录制文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// YUV4MPEG2 视频，`fps` 为输出帧率
+    Y4m { fps: u32 },
+    /// 只包含损坏区域和时间戳的原始格式 (见模块文档)
+    Damage,
+}
+
+/// 一帧：距录制开始的时间、损坏区域和整屏像素 (0x00RRGGBB)
+struct Frame {
+    time: Duration,
+    damage: Vec<Rect>,
+    pixels: Vec<u32>,
+}
+
+/// 正在进行的录制 (由事件循环持有)
+pub(crate) struct Recorder {
+    mirror: FrameMirror,
+    started: Instant,
+    sender: Option<SyncSender<Frame>>,
+    handle: Option<JoinHandle<io::Result<()>>>,
+    /// 上一帧被丢弃，下一帧需要整屏同步
+    resync: bool,
+    dropped: usize,
+}
+
+impl Recorder {
+    /// 创建 `path` 并启动写入线程，`width` x `height` 为屏幕 (Framebuffer) 尺寸
+    pub fn start(path: &Path, format: RecordingFormat, width: u32, height: u32) -> io::Result<Self> {
+        if matches!(format, RecordingFormat::Y4m { fps: 0 }) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "帧率不能为 0"));
+        }
+        let file = BufWriter::new(File::create(path)?);
+        let (sender, receiver) = sync_channel(QUEUE_LEN);
+        let handle = thread::Builder::new()
+            .name("linuxfb-record".into())
+            .spawn(move || write_frames(file, format, width, height, receiver))?;
+        tracing::info!("开始录制屏幕到 {:?} ({:?})", path, format);
+        Ok(Self {
+            mirror: FrameMirror::new(width, height),
+            started: crate::clock::now(),
+            sender: Some(sender),
+            handle: Some(handle),
+            resync: true,
+            dropped: 0,
+        })
+    }
+
+    /// 把渲染目标中 `region` (缓冲区坐标) 内的像素作为新的一帧提交
+    pub fn update(&mut self, bytes: &[u8], format: PixelFormat, region: &[Rect]) {
+        let mut damage = self.mirror.update(bytes, format, region);
+        if damage.is_empty() {
+            return;
+        }
+        if self.resync {
+            damage = vec![Rect { x: 0, y: 0, w: self.mirror.width as i32, h: self.mirror.height as i32 }];
+        }
+        let frame = Frame {
+            time: crate::clock::elapsed(self.started),
+            damage,
+            pixels: self.mirror.pixels.clone(),
+        };
+        let Some(sender) = &self.sender else { return };
+        match sender.try_send(frame) {
+            Ok(()) => self.resync = false,
+            Err(TrySendError::Full(_)) => {
+                self.dropped += 1;
+                self.resync = true;
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                // 写入线程已因错误退出，错误在结束录制时返回
+                self.sender = None;
+            }
+        }
+    }
+
+    /// 写入剩余的帧并关闭文件
+    pub fn finish(mut self) -> io::Result<()> {
+        self.sender = None;
+        if self.dropped > 0 {
+            tracing::warn!("录制期间写入跟不上，丢弃了 {} 帧", self.dropped);
+        }
+        let result = match self.handle.take() {
+            Some(handle) => handle.join().unwrap_or_else(|_| Err(io::Error::other("录制线程崩溃"))),
+            None => Ok(()),
+        };
+        tracing::info!("屏幕录制结束");
+        result
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(handle) = self.handle.take() {
+            if let Ok(Err(e)) = handle.join() {
+                tracing::error!("屏幕录制写入失败: {}", e);
+            }
+        }
+    }
+}
+
+/// 写入线程：接收帧直到发送端关闭
+fn write_frames(
+    mut out: impl Write,
+    format: RecordingFormat,
+    width: u32,
+    height: u32,
+    receiver: Receiver<Frame>,
+) -> io::Result<()> {
+    match format {
+        RecordingFormat::Y4m { fps } => {
+            writeln!(out, "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444", width, height, fps)?;
+            let mut written = 0u64;
+            let mut last: Option<Vec<u8>> = None;
+            for frame in receiver {
+                // 以固定帧率重复上一帧，直到本帧的时间点
+                let index = (frame.time.as_secs_f64() * f64::from(fps)) as u64;
+                if let Some(last) = &last {
+                    while written < index {
+                        write_y4m_frame(&mut out, last)?;
+                        written += 1;
+                    }
+                }
+                last = Some(to_yuv444(&frame.pixels));
+            }
+            if let Some(last) = &last {
+                write_y4m_frame(&mut out, last)?;
+            }
+        }
+        RecordingFormat::Damage => {
+            out.write_all(DAMAGE_MAGIC)?;
+            out.write_all(&width.to_le_bytes())?;
+            out.write_all(&height.to_le_bytes())?;
+            for frame in receiver {
+                out.write_all(&(frame.time.as_micros() as u64).to_le_bytes())?;
+                out.write_all(&(frame.damage.len() as u32).to_le_bytes())?;
+                for rect in &frame.damage {
+                    for value in [rect.x, rect.y, rect.w, rect.h] {
+                        out.write_all(&(value as u32).to_le_bytes())?;
+                    }
+                    for y in rect.y..rect.y + rect.h {
+                        let start = (y * width as i32 + rect.x) as usize;
+                        for pixel in &frame.pixels[start..start + rect.w as usize] {
+                            out.write_all(&pixel.to_le_bytes())?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    out.flush()
+}
+
+fn write_y4m_frame(out: &mut impl Write, planes: &[u8]) -> io::Result<()> {
+    out.write_all(b"FRAME\n")?;
+    out.write_all(planes)
+}
+
+/// 0x00RRGGBB 像素转换为 Y、U、V 三个平面 (BT.601 有限范围)
+fn to_yuv444(pixels: &[u32]) -> Vec<u8> {
+    let len = pixels.len();
+    let mut planes = vec![0u8; len * 3];
+    for (i, &pixel) in pixels.iter().enumerate() {
+        let (r, g, b) = ((pixel >> 16 & 0xff) as i32, (pixel >> 8 & 0xff) as i32, (pixel & 0xff) as i32);
+        planes[i] = (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8;
+        planes[len + i] = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+        planes[len * 2 + i] = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+    }
+    planes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_y4m_repeats_frames_at_fixed_rate() {
+        let (sender, receiver) = sync_channel(QUEUE_LEN);
+        let frame = |ms, pixel| Frame { time: Duration::from_millis(ms), damage: Vec::new(), pixels: vec![pixel; 2] };
+        // 10 fps：第 0ms 的白色帧持续到第 250ms 的黑色帧，共 2 帧白色 + 1 帧黑色
+        sender.send(frame(0, 0x00ff_ffff)).unwrap();
+        sender.send(frame(250, 0)).unwrap();
+        drop(sender);
+        let mut out = Vec::new();
+        write_frames(&mut out, RecordingFormat::Y4m { fps: 10 }, 2, 1, receiver).unwrap();
+
+        let header = b"YUV4MPEG2 W2 H1 F10:1 Ip A1:1 C444\n";
+        assert!(out.starts_with(header));
+        let frames: Vec<_> = out[header.len()..].chunks(6 + 6).collect();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(&frames[1][..6], b"FRAME\n");
+        assert_eq!(frames[1][6], 235);
+        assert_eq!(frames[2][6], 16);
+    }
+}
@@ -0,0 +1,176 @@
+//! 进程信号处理
+//!
+//! SIGINT、SIGTERM 和 SIGHUP 的处理函数只把信号编号写入管道 (这是信号处理函数中可以安全执行的操作)，
+//! 由后台线程读取后执行构建器设置的 [`SignalPolicy`]。信号属于整个进程：管道和线程只创建一次，
+//! 之后构建的平台替换当前的处理动作，平台销毁时恢复系统默认行为。
+
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicI32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// 平台处理的进程信号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// SIGINT (Ctrl+C)
+    Interrupt,
+    /// SIGTERM (例如 `systemctl stop`、`kill`)
+    Terminate,
+    /// SIGHUP (控制终端断开，或守护进程约定的重新加载)
+    Hangup,
+}
+
+impl Signal {
+    const ALL: [Signal; 3] = [Signal::Interrupt, Signal::Terminate, Signal::Hangup];
+
+    /// 对应的信号编号 (`libc::SIGINT` 等)
+    pub fn as_raw(self) -> i32 {
+        match self {
+            Signal::Interrupt => libc::SIGINT,
+            Signal::Terminate => libc::SIGTERM,
+            Signal::Hangup => libc::SIGHUP,
+        }
+    }
+
+    fn from_raw(signo: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|signal| signal.as_raw() == signo)
+    }
+}
+
+/// 收到 SIGINT、SIGTERM 或 SIGHUP 时的处理方式
+///
+/// 通过 [`LinuxFbPlatformBuilder::with_signal_policy`](crate::LinuxFbPlatformBuilder::with_signal_policy) 设置。
+#[derive(Clone, Default)]
+pub enum SignalPolicy {
+    /// 不安装信号处理函数，保留系统默认行为或应用自己的处理 (退出前调用 [`restore_tty`](crate::restore_tty))
+    None,
+    /// 恢复 TTY 文本模式后立即退出进程 (默认)
+    #[default]
+    RestoreTtyAndExit,
+    /// 让正在运行的事件循环返回，应用随后按正常流程退出，TTY 在平台销毁时恢复；
+    /// 适用于由 systemd 管理、需要在停止前保存状态的服务
+    QuitEventLoop,
+    /// 调用应用的回调 (在信号处理线程中，而不是信号处理函数中)
+    Custom(Arc<dyn Fn(Signal) + Send + Sync>),
+}
+
+impl SignalPolicy {
+    /// 使用回调处理信号
+    pub fn custom(callback: impl Fn(Signal) + Send + Sync + 'static) -> Self {
+        SignalPolicy::Custom(Arc::new(callback))
+    }
+}
+
+impl fmt::Debug for SignalPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignalPolicy::None => write!(f, "None"),
+            SignalPolicy::RestoreTtyAndExit => write!(f, "RestoreTtyAndExit"),
+            SignalPolicy::QuitEventLoop => write!(f, "QuitEventLoop"),
+            SignalPolicy::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// 信号处理线程执行的动作
+pub(crate) type SignalAction = Arc<dyn Fn(Signal) + Send + Sync>;
+
+/// 当前的动作及安装它的编号
+static ACTION: Mutex<Option<(u64, SignalAction)>> = Mutex::new(None);
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+/// 管道的写端，未创建时为 -1
+static PIPE_WRITE: AtomicI32 = AtomicI32::new(-1);
+
+/// 安装信号处理函数，收到信号时在信号处理线程中调用 `action`
+///
+/// 返回的编号交给 [`uninstall`]，避免销毁旧平台时移除新平台安装的动作。
+pub(crate) fn install(action: SignalAction) -> io::Result<u64> {
+    let mut current = ACTION.lock().unwrap();
+    if PIPE_WRITE.load(Ordering::Acquire) == -1 {
+        spawn_signal_thread()?;
+    }
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    *current = Some((token, action));
+    for signal in Signal::ALL {
+        set_disposition(signal, on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t)?;
+    }
+    Ok(token)
+}
+
+/// 恢复系统默认行为，`token` 不是当前动作的编号时什么也不做
+pub(crate) fn uninstall(token: u64) {
+    let mut current = ACTION.lock().unwrap();
+    if current.as_ref().is_some_and(|(current, _)| *current == token) {
+        for signal in Signal::ALL {
+            let _ = set_disposition(signal, libc::SIG_DFL);
+        }
+        *current = None;
+    }
+}
+
+fn set_disposition(signal: Signal, handler: libc::sighandler_t) -> io::Result<()> {
+    // SAFETY: sigaction 结构体全部初始化，处理函数只调用异步信号安全的 write
+    let result = unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handler;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(signal.as_raw(), &action, std::ptr::null_mut())
+    };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+extern "C" fn on_signal(signo: libc::c_int) {
+    // write 可能改变 errno，被打断的代码不应看到变化
+    // SAFETY: 只访问当前线程的 errno 和一个字节的缓冲区
+    unsafe {
+        let errno = *libc::__errno_location();
+        let byte = signo as u8;
+        libc::write(PIPE_WRITE.load(Ordering::Relaxed), &byte as *const u8 as *const libc::c_void, 1);
+        *libc::__errno_location() = errno;
+    }
+}
+
+fn spawn_signal_thread() -> io::Result<()> {
+    let mut fds = [0; 2];
+    // SAFETY: fds 有两个元素
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let [read_fd, write_fd] = fds;
+    thread::Builder::new().name("linuxfb-signals".into()).spawn(move || loop {
+        let mut byte = 0u8;
+        // SAFETY: 读取一个字节到 byte
+        match unsafe { libc::read(read_fd, &mut byte as *mut u8 as *mut libc::c_void, 1) } {
+            1 => {
+                let Some(signal) = Signal::from_raw(byte as i32) else { continue };
+                // 调用期间不持有锁，回调中可以构建新的平台 (从而调用 install)
+                let action = ACTION.lock().unwrap().as_ref().map(|(_, action)| action.clone());
+                if let Some(action) = action {
+                    action(signal);
+                }
+            }
+            -1 if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => {}
+            _ => break,
+        }
+    })?;
+    PIPE_WRITE.store(write_fd, Ordering::Release);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_numbers() {
+        for signal in Signal::ALL {
+            assert_eq!(Signal::from_raw(signal.as_raw()), Some(signal));
+        }
+        assert_eq!(Signal::from_raw(libc::SIGUSR1), None);
+    }
+}
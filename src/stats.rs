@@ -0,0 +1,115 @@
+//! 帧时间与帧率统计。
+//!
+//! 事件循环在每一帧渲染时记录各阶段耗时，应用可以通过
+//! [`LinuxFbPlatform::frame_stats`](crate::LinuxFbPlatform::frame_stats) 查询，
+//! 用于在目标硬件上调优性能。
+
+use std::time::{Duration, Instant};
+
+/// 统计帧率的时间窗口
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// 单帧预算：超过该时长的帧被计为掉帧 (约 60Hz)
+pub(crate) const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+/// 帧时间统计快照
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// 自平台创建 (或上次重置) 以来呈现的总帧数
+    pub frame_count: u64,
+    /// 最近一帧 Slint 软件渲染耗时
+    pub render_time: Duration,
+    /// 最近一帧将渲染结果复制到 Framebuffer 的耗时
+    ///
+    /// 直接渲染到后缓冲区时该值为 0。
+    pub copy_time: Duration,
+    /// 最近一帧等待垂直同步的耗时 (未启用 VSync 时为 0)
+    pub vsync_wait: Duration,
+    /// 最近一帧缓冲区翻转 (平移) 的耗时
+    pub flip_time: Duration,
+    /// 最近一个统计窗口 (1 秒) 内的平均帧率
+    pub fps: f32,
+    /// 总耗时超过单帧预算 (约 16.7ms) 的帧数
+    pub dropped_frames: u64,
+}
+
+/// 帧统计收集器 (事件循环内部使用)
+pub(crate) struct FrameStatsCollector {
+    stats: FrameStats,
+    window_start: Instant,
+    window_frames: u32,
+}
+
+impl FrameStatsCollector {
+    pub fn new() -> Self {
+        Self {
+            stats: FrameStats::default(),
+            window_start: Instant::now(),
+            window_frames: 0,
+        }
+    }
+
+    /// 记录一帧各阶段的耗时
+    pub fn record_frame(
+        &mut self,
+        render_time: Duration,
+        copy_time: Duration,
+        vsync_wait: Duration,
+        flip_time: Duration,
+    ) {
+        let stats = &mut self.stats;
+        stats.frame_count += 1;
+        stats.render_time = render_time;
+        stats.copy_time = copy_time;
+        stats.vsync_wait = vsync_wait;
+        stats.flip_time = flip_time;
+
+        // 等待 VSync 的时间不算作"慢"，只统计实际工作耗时
+        if render_time + copy_time + flip_time > FRAME_BUDGET {
+            stats.dropped_frames += 1;
+        }
+
+        self.window_frames += 1;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= FPS_WINDOW {
+            stats.fps = self.window_frames as f32 / elapsed.as_secs_f32();
+            self.window_start = Instant::now();
+            self.window_frames = 0;
+        }
+    }
+
+    /// 返回当前统计快照
+    ///
+    /// 如果当前统计窗口已经过期 (画面静止，没有新帧来结束窗口)，
+    /// 则按窗口内实际帧数重新计算帧率，避免一直报告旧值。
+    pub fn snapshot(&self) -> FrameStats {
+        let mut stats = self.stats;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= FPS_WINDOW {
+            stats.fps = self.window_frames as f32 / elapsed.as_secs_f32();
+        }
+        stats
+    }
+
+    /// 清空所有统计
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropped_frames() {
+        let mut collector = FrameStatsCollector::new();
+        let ms = Duration::from_millis;
+        collector.record_frame(ms(5), Duration::ZERO, ms(10), ms(1));
+        collector.record_frame(ms(20), Duration::ZERO, Duration::ZERO, ms(1));
+        let stats = collector.snapshot();
+        assert_eq!(stats.frame_count, 2);
+        assert_eq!(stats.dropped_frames, 1);
+        assert_eq!(stats.render_time, ms(20));
+    }
+}
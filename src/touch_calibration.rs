@@ -0,0 +1,217 @@
+//! 交互式触摸校准
+//!
+//! 与 tslib 的 `ts_calibrate` 类似：依次在屏幕四角和中心显示十字准星，
+//! 读取用户点击时的原始触摸坐标，最后用最小二乘法求出仿射校准参数。
+//! 准星直接绘制到 Framebuffer 当前显示的页面上，不经过 Slint；
+//! 校准期间独占 (grab) 触摸设备，避免点击被事件循环当作普通输入。
+//!
+//! 准星按显示旋转方向绘制，但校准结果始终是原始坐标到未旋转屏幕坐标的映射，
+//! 与 pointercal 文件的约定一致；旋转由触摸坐标变换在其后单独完成。
+
+use crate::canvas::{text_width, Canvas, Rect, GLYPH_HEIGHT};
+use crate::error::Error;
+use crate::input::{open_touchscreens, TouchCalibration};
+use crate::linuxfb::Framebuffer;
+use crate::pixels::{PixelAbgr8888, PixelBgra8888, PixelFormat, PixelRgb565, PixelRgba8888};
+use crate::window::Rotation;
+use evdev::{AbsoluteAxisCode, Device, EventSummary, KeyCode, SynchronizationCode};
+use i_slint_core::platform::software_renderer::TargetPixel;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 准星的半径 (像素)
+const CROSSHAIR_RADIUS: i32 = 12;
+/// 标签文字的放大倍数
+const LABEL_SCALE: i32 = 3;
+
+/// 当前显示的 Framebuffer 页面
+struct Screen {
+    map: memmap2::MmapMut,
+    format: PixelFormat,
+    width: u32,
+    height: u32,
+    stride: usize,
+    /// 当前显示页面在映射中的字节偏移
+    page_offset: usize,
+    rotation: Rotation,
+}
+
+impl Screen {
+    fn open(path: &Path, rotation: Rotation) -> Result<Self, Error> {
+        let fb = Framebuffer::new(path)?;
+        let format = PixelFormat::from_fb_info(&fb.vinfo);
+        let (width, height) = fb.get_size();
+        let (virtual_width, _) = fb.get_virtual_size();
+        let (_, offset_y) = fb.get_offset();
+        let bytes_per_pixel = fb.get_bytes_per_pixel() as usize;
+        let map = fb.map()?;
+        Ok(Self {
+            map,
+            format,
+            width,
+            height,
+            stride: virtual_width as usize,
+            page_offset: offset_y as usize * virtual_width as usize * bytes_per_pixel,
+            rotation,
+        })
+    }
+
+    /// 旋转后的屏幕尺寸 (准星坐标以此为准)
+    fn window_size(&self) -> (u32, u32) {
+        self.rotation.rotate_size(self.width, self.height)
+    }
+
+    /// 清屏并绘制准星；`target` 为 `None` 时只清屏
+    fn draw(&mut self, target: Option<(i32, i32)>, label: &str) -> Result<(), Error> {
+        match self.format {
+            PixelFormat::Abgr8888 => self.draw_pixels::<PixelAbgr8888>(target, label),
+            PixelFormat::Rgba8888 => self.draw_pixels::<PixelRgba8888>(target, label),
+            PixelFormat::Bgra8888 => self.draw_pixels::<PixelBgra8888>(target, label),
+            PixelFormat::Rgb565 => self.draw_pixels::<PixelRgb565>(target, label),
+            _ => return Err(Error::UnsupportedPixelFormat),
+        }
+        Ok(())
+    }
+
+    fn draw_pixels<P: TargetPixel + bytemuck::Pod>(&mut self, target: Option<(i32, i32)>, label: &str) {
+        let (width, height) = self.window_size();
+        let (width, height) = (width as i32, height as i32);
+        let page_len = self.stride * self.height as usize * std::mem::size_of::<P>();
+        let bytes = &mut self.map[self.page_offset..self.page_offset + page_len];
+        let mut canvas = Canvas {
+            pixels: bytemuck::cast_slice_mut::<u8, P>(bytes),
+            stride: self.stride,
+            width: self.width as i32,
+            height: self.height as i32,
+            rotation: self.rotation,
+        };
+        canvas.fill_rect(Rect { x: 0, y: 0, w: width, h: height }, P::from_rgb(0, 0, 0));
+
+        let Some((x, y)) = target else { return };
+        let white = P::from_rgb(0xff, 0xff, 0xff);
+        let r = CROSSHAIR_RADIUS;
+        canvas.fill_rect(Rect { x: x - r, y, w: r * 2 + 1, h: 1 }, white);
+        canvas.fill_rect(Rect { x, y: y - r, w: 1, h: r * 2 + 1 }, white);
+        canvas.outline_rect(Rect { x: x - r / 2, y: y - r / 2, w: r + 1, h: r + 1 }, white);
+
+        let text_x = (width - text_width(label.chars().count(), LABEL_SCALE)) / 2;
+        let text_y = height / 4 - GLYPH_HEIGHT as i32 * LABEL_SCALE / 2;
+        canvas.draw_text(text_x, text_y, label, LABEL_SCALE, white);
+    }
+}
+
+/// 从触摸设备读取原始坐标
+struct RawTouch {
+    devices: Vec<Device>,
+    x: i32,
+    y: i32,
+    touching: bool,
+    samples: Vec<(i32, i32)>,
+}
+
+impl RawTouch {
+    fn open() -> Result<Self, Error> {
+        let mut devices = open_touchscreens();
+        if devices.is_empty() {
+            return Err(Error::Other("未找到触摸屏设备".into()));
+        }
+        for device in &mut devices {
+            let _ = device.set_nonblocking(true);
+            if let Err(e) = device.grab() {
+                tracing::warn!("无法独占触摸设备 {:?}: {}", device.name(), e);
+            }
+        }
+        Ok(Self { devices, x: 0, y: 0, touching: false, samples: Vec::new() })
+    }
+
+    /// 等待一次完整的点击，返回按下期间采样坐标的中位数
+    fn wait_for_tap(&mut self, quit_flag: &AtomicBool) -> Result<(i32, i32), Error> {
+        self.samples.clear();
+        loop {
+            if quit_flag.load(Ordering::Relaxed) {
+                return Err(Error::Other("触摸校准被中断".into()));
+            }
+            let mut poll_fds: Vec<libc::pollfd> = self
+                .devices
+                .iter()
+                .map(|dev| libc::pollfd { fd: dev.as_raw_fd(), events: libc::POLLIN, revents: 0 })
+                .collect();
+            // SAFETY: poll_fds 是有效的 pollfd 数组
+            unsafe { libc::poll(poll_fds.as_mut_ptr(), poll_fds.len() as libc::nfds_t, 100) };
+
+            let mut released = false;
+            for device in &mut self.devices {
+                let Ok(events) = device.fetch_events() else { continue };
+                for event in events {
+                    match event.destructure() {
+                        EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_X | AbsoluteAxisCode::ABS_MT_POSITION_X, value) => {
+                            self.x = value;
+                        }
+                        EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_Y | AbsoluteAxisCode::ABS_MT_POSITION_Y, value) => {
+                            self.y = value;
+                        }
+                        // 只支持多点触控协议的设备没有 BTN_TOUCH，以追踪 ID 判断按下与抬起
+                        EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_MT_TRACKING_ID, value) => {
+                            released |= self.touching && value < 0;
+                            self.touching = value >= 0;
+                        }
+                        EventSummary::Key(_, KeyCode::BTN_TOUCH, value) => {
+                            released |= self.touching && value == 0;
+                            self.touching = value != 0;
+                        }
+                        EventSummary::Synchronization(_, SynchronizationCode::SYN_REPORT, _) if self.touching => {
+                            self.samples.push((self.x, self.y));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            if released && !self.samples.is_empty() {
+                let mut xs: Vec<i32> = self.samples.iter().map(|s| s.0).collect();
+                let mut ys: Vec<i32> = self.samples.iter().map(|s| s.1).collect();
+                xs.sort_unstable();
+                ys.sort_unstable();
+                return Ok((xs[xs.len() / 2], ys[ys.len() / 2]));
+            }
+        }
+    }
+}
+
+impl Drop for RawTouch {
+    fn drop(&mut self) {
+        for device in &mut self.devices {
+            let _ = device.ungrab();
+        }
+    }
+}
+
+/// 运行交互式校准，返回计算出的校准参数
+pub(crate) fn run(fb_path: &Path, rotation: Rotation, quit_flag: &AtomicBool) -> Result<TouchCalibration, Error> {
+    let mut screen = Screen::open(fb_path, rotation)?;
+    let mut touch = RawTouch::open()?;
+    let (window_width, window_height) = screen.window_size();
+    let (width, height) = (window_width as i32, window_height as i32);
+    let margin = width.min(height) / 10;
+    let targets = [
+        (margin, margin),
+        (width - margin, margin),
+        (width - margin, height - margin),
+        (margin, height - margin),
+        (width / 2, height / 2),
+    ];
+
+    tracing::info!("开始触摸校准 ({} 个目标点)", targets.len());
+    let mut samples = Vec::with_capacity(targets.len());
+    for (index, &target) in targets.iter().enumerate() {
+        screen.draw(Some(target), &format!("TOUCH {} OF {}", index + 1, targets.len()))?;
+        let raw = touch.wait_for_tap(quit_flag)?;
+        tracing::debug!("校准点 {:?}: 原始坐标 {:?}", target, raw);
+        samples.push((raw, rotation.rotate_point(target.0, target.1, window_width, window_height)));
+    }
+    screen.draw(None, "")?;
+
+    TouchCalibration::from_samples(&samples, screen.width, screen.height)
+        .ok_or_else(|| Error::Other("校准采样无效 (采样点共线)，请重试".into()))
+}
@@ -0,0 +1,295 @@
+//! 屏幕虚拟键盘
+//!
+//! 供没有实体键盘的触摸设备输入文本。文本输入框获得焦点时，键盘从屏幕底部弹出，
+//! 由后端在 Slint 渲染完成后直接绘制到像素缓冲区 (与调试叠加层相同)，
+//! 落在键盘区域内的指针事件被拦截并转换为按键事件。
+//!
+//! 键盘会遮挡应用窗口的下半部分，应用应避免把输入框放在该区域。
+
+use crate::canvas::{text_width, Canvas, Rect, GLYPH_HEIGHT};
+use crate::window::Rotation;
+use i_slint_core::api::PhysicalSize;
+use i_slint_core::input::key_codes;
+use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
+use i_slint_core::partial_renderer::DirtyRegion;
+use i_slint_core::platform::software_renderer::{SoftwareRenderer, TargetPixel};
+use i_slint_core::platform::WindowEvent;
+use i_slint_core::renderer::RendererSealed;
+use i_slint_core::SharedString;
+
+/// 键盘高度占屏幕高度的比例 (分子/分母)
+const HEIGHT_RATIO: (u32, u32) = (2, 5);
+/// 每行的宽度 (以半个按键为单位)
+const ROW_UNITS: i32 = 20;
+/// 按键之间的间隙 (像素)
+const KEY_GAP: i32 = 2;
+
+/// 虚拟按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VirtualKey {
+    Char(char),
+    Shift,
+    Backspace,
+    Space,
+    Enter,
+    Hide,
+}
+
+impl VirtualKey {
+    fn label(self, shifted: bool) -> String {
+        match self {
+            VirtualKey::Char(c) if shifted => c.to_uppercase().to_string(),
+            VirtualKey::Char(c) => c.to_string(),
+            VirtualKey::Shift => "^".into(),
+            VirtualKey::Backspace => "<".into(),
+            VirtualKey::Space => "SPACE".into(),
+            VirtualKey::Enter => "ENTER".into(),
+            VirtualKey::Hide => "HIDE".into(),
+        }
+    }
+}
+
+/// 键盘布局：每行为 (按键, 宽度)，宽度以半个按键为单位
+fn layout() -> Vec<Vec<(VirtualKey, i32)>> {
+    let chars = |s: &str| s.chars().map(|c| (VirtualKey::Char(c), 2)).collect::<Vec<_>>();
+    let mut row3 = vec![(VirtualKey::Shift, 3)];
+    row3.extend(chars("zxcvbnm"));
+    row3.push((VirtualKey::Backspace, 3));
+    vec![
+        chars("1234567890"),
+        chars("qwertyuiop"),
+        chars("asdfghjkl"),
+        row3,
+        vec![
+            (VirtualKey::Hide, 3),
+            (VirtualKey::Char(','), 2),
+            (VirtualKey::Space, 10),
+            (VirtualKey::Char('.'), 2),
+            (VirtualKey::Enter, 3),
+        ],
+    ]
+}
+
+/// 虚拟键盘状态
+pub struct VirtualKeyboard {
+    bounds: Rect,
+    keys: Vec<(VirtualKey, Rect)>,
+    visible: bool,
+    /// 用户通过 HIDE 键收起了键盘，直到焦点离开输入框前不再弹出
+    dismissed: bool,
+    /// 下一个字母输入为大写 (单次生效)
+    shifted: bool,
+    /// 当前按下的按键索引
+    pressed: Option<usize>,
+    /// 上一帧是否绘制过键盘
+    drawn: bool,
+    needs_redraw: bool,
+}
+
+impl VirtualKeyboard {
+    /// `screen` 为窗口尺寸 (即旋转后的屏幕尺寸)
+    pub fn new(screen: PhysicalSize) -> Self {
+        let height = screen.height * HEIGHT_RATIO.0 / HEIGHT_RATIO.1;
+        let bounds = Rect {
+            x: 0,
+            y: (screen.height - height) as i32,
+            w: screen.width as i32,
+            h: height as i32,
+        };
+
+        let rows = layout();
+        let row_height = bounds.h / rows.len() as i32;
+        let unit = bounds.w / ROW_UNITS;
+        let mut keys = Vec::new();
+        for (row_index, row) in rows.into_iter().enumerate() {
+            let units: i32 = row.iter().map(|(_, width)| width).sum();
+            let mut x = bounds.x + (bounds.w - units * unit) / 2;
+            let y = bounds.y + row_index as i32 * row_height;
+            for (key, width) in row {
+                keys.push((key, Rect { x, y, w: width * unit, h: row_height }));
+                x += width * unit;
+            }
+        }
+
+        Self {
+            bounds,
+            keys,
+            visible: false,
+            dismissed: false,
+            shifted: false,
+            pressed: None,
+            drawn: false,
+            needs_redraw: false,
+        }
+    }
+
+    /// 根据文本输入框的焦点状态显示或隐藏键盘
+    pub fn set_text_input_focused(&mut self, focused: bool) {
+        if !focused {
+            self.dismissed = false;
+        }
+        let visible = focused && !self.dismissed;
+        if visible != self.visible {
+            self.visible = visible;
+            self.pressed = None;
+            self.needs_redraw = true;
+        }
+    }
+
+    /// 返回并清除重绘请求
+    pub fn take_needs_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.needs_redraw)
+    }
+
+    fn key_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.keys.iter().position(|(_, rect)| rect.contains(x, y))
+    }
+
+    /// 处理指针事件
+    ///
+    /// 返回 `None` 表示事件不属于键盘，应照常交给窗口；
+    /// 返回 `Some` 表示事件已被键盘消费，其中包含要代替它发送给窗口的按键事件。
+    pub fn handle_event(&mut self, event: &WindowEvent) -> Option<Vec<WindowEvent>> {
+        if !self.visible {
+            return None;
+        }
+        match event {
+            WindowEvent::PointerPressed { position, .. } => {
+                let (x, y) = (position.x as i32, position.y as i32);
+                if !self.bounds.contains(x, y) {
+                    return None;
+                }
+                self.pressed = self.key_at(x, y);
+                self.needs_redraw = true;
+                Some(Vec::new())
+            }
+            WindowEvent::PointerMoved { .. } if self.pressed.is_some() => Some(Vec::new()),
+            WindowEvent::PointerReleased { position, .. } => {
+                let pressed = self.pressed.take()?;
+                self.needs_redraw = true;
+                if self.key_at(position.x as i32, position.y as i32) != Some(pressed) {
+                    // 手指移出了按下的按键：取消
+                    return Some(Vec::new());
+                }
+                Some(self.activate(self.keys[pressed].0))
+            }
+            _ => None,
+        }
+    }
+
+    fn activate(&mut self, key: VirtualKey) -> Vec<WindowEvent> {
+        let text: SharedString = match key {
+            VirtualKey::Char(c) => {
+                let text = VirtualKey::Char(c).label(self.shifted);
+                self.shifted = false;
+                text.into()
+            }
+            VirtualKey::Space => " ".into(),
+            VirtualKey::Backspace => key_codes::Backspace.into(),
+            VirtualKey::Enter => key_codes::Return.into(),
+            VirtualKey::Shift => {
+                self.shifted = !self.shifted;
+                return Vec::new();
+            }
+            VirtualKey::Hide => {
+                self.visible = false;
+                self.dismissed = true;
+                return Vec::new();
+            }
+        };
+        vec![
+            WindowEvent::KeyPressed { text: text.clone() },
+            WindowEvent::KeyReleased { text },
+        ]
+    }
+
+    /// 键盘收起后，将其曾覆盖的区域标记为脏区，使渲染器重绘应用内容
+    pub fn invalidate_previous(&mut self, renderer: &SoftwareRenderer, scale_factor: f32) {
+        if !self.drawn || self.visible {
+            return;
+        }
+        self.drawn = false;
+        let mut region = DirtyRegion::default();
+        region.add_rect(LogicalRect::new(
+            LogicalPoint::new(self.bounds.x as f32 / scale_factor, self.bounds.y as f32 / scale_factor),
+            LogicalSize::new(self.bounds.w as f32 / scale_factor, self.bounds.h as f32 / scale_factor),
+        ));
+        renderer.mark_dirty_region(region);
+    }
+
+    /// 在已渲染的像素缓冲区上绘制键盘
+    ///
+    /// `size` 为缓冲区尺寸，键盘按 `rotation` 旋转后绘制。
+    pub fn draw<P: TargetPixel>(&mut self, pixels: &mut [P], stride: usize, size: PhysicalSize, rotation: Rotation) {
+        if !self.visible {
+            return;
+        }
+        let mut canvas = Canvas {
+            pixels,
+            stride,
+            width: size.width as i32,
+            height: size.height as i32,
+            rotation,
+        };
+
+        canvas.fill_rect(self.bounds, P::from_rgb(0x20, 0x20, 0x20));
+        for (index, (key, rect)) in self.keys.iter().enumerate() {
+            let highlighted = self.pressed == Some(index) || (*key == VirtualKey::Shift && self.shifted);
+            let color = if highlighted {
+                P::from_rgb(0x80, 0x80, 0x80)
+            } else {
+                P::from_rgb(0x48, 0x48, 0x48)
+            };
+            let cap = Rect {
+                x: rect.x + KEY_GAP,
+                y: rect.y + KEY_GAP,
+                w: rect.w - KEY_GAP * 2,
+                h: rect.h - KEY_GAP * 2,
+            };
+            canvas.fill_rect(cap, color);
+
+            // 标签居中，放大倍数按按键尺寸选择
+            let label = key.label(self.shifted);
+            let len = label.chars().count();
+            let scale = (cap.h / 2 / GLYPH_HEIGHT as i32)
+                .min(cap.w * 3 / 4 / text_width(len, 1).max(1))
+                .max(1);
+            let x = cap.x + (cap.w - text_width(len, scale)) / 2;
+            let y = cap.y + (cap.h - GLYPH_HEIGHT as i32 * scale) / 2;
+            canvas.draw_text(x, y, &label, scale, P::from_rgb(0xff, 0xff, 0xff));
+        }
+        self.drawn = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i_slint_core::api::LogicalPosition;
+    use i_slint_core::platform::PointerEventButton;
+
+    fn tap(keyboard: &mut VirtualKeyboard, key: VirtualKey) -> Option<Vec<WindowEvent>> {
+        let rect = keyboard.keys.iter().find(|(k, _)| *k == key).unwrap().1;
+        let position = LogicalPosition::new((rect.x + rect.w / 2) as f32, (rect.y + rect.h / 2) as f32);
+        let button = PointerEventButton::Left;
+        keyboard.handle_event(&WindowEvent::PointerPressed { position, button })?;
+        keyboard.handle_event(&WindowEvent::PointerReleased { position, button })
+    }
+
+    #[test]
+    fn test_tap_keys() {
+        let mut keyboard = VirtualKeyboard::new(PhysicalSize::new(800, 480));
+        assert!(tap(&mut keyboard, VirtualKey::Char('q')).is_none());
+
+        keyboard.set_text_input_focused(true);
+        assert!(tap(&mut keyboard, VirtualKey::Shift).unwrap().is_empty());
+        let events = tap(&mut keyboard, VirtualKey::Char('q')).unwrap();
+        assert_eq!(events[0], WindowEvent::KeyPressed { text: "Q".into() });
+        let events = tap(&mut keyboard, VirtualKey::Char('q')).unwrap();
+        assert_eq!(events[0], WindowEvent::KeyPressed { text: "q".into() });
+
+        tap(&mut keyboard, VirtualKey::Hide);
+        keyboard.set_text_input_focused(true);
+        assert!(tap(&mut keyboard, VirtualKey::Space).is_none());
+    }
+}
@@ -0,0 +1,557 @@
+//! VNC (RFB 协议) 远程查看与控制
+//!
+//! 每次渲染后把损坏区域的像素复制到共享的帧副本中，由每个客户端的发送线程按
+//! 客户端请求以增量方式 (只发送变化的矩形) 推送。客户端的指针和按键事件经由
+//! 事件循环注入窗口，与本地输入使用相同的处理路径。
+//!
+//! 只实现 Raw 编码和 "None" 安全类型：连接不加密也不认证，
+//! 请只在本机、VPN 或 SSH 隧道中监听。同一时间只服务一个客户端，新连接会断开旧连接。
+
+use crate::canvas::Rect;
+use crate::mirror::FrameMirror;
+use crate::pixels::PixelFormat;
+use crate::window::Rotation;
+use i_slint_core::api::LogicalPosition;
+use i_slint_core::input::key_codes;
+use i_slint_core::platform::{PointerEventButton, WindowEvent};
+use i_slint_core::SharedString;
+use std::cell::Cell;
+use std::io::{self, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+/// 服务器名称 (显示在客户端的窗口标题中)
+const DESKTOP_NAME: &str = "slint-backend-linuxfb";
+
+/// 损坏矩形超过此数量时合并为外接矩形
+const MAX_DAMAGE_RECTS: usize = 16;
+
+/// 客户端指针事件的按键掩码
+const BUTTON_LEFT: u8 = 1 << 0;
+const BUTTON_MIDDLE: u8 = 1 << 1;
+const BUTTON_RIGHT: u8 = 1 << 2;
+const WHEEL_UP: u8 = 1 << 3;
+const WHEEL_DOWN: u8 = 1 << 4;
+const WHEEL_LEFT: u8 = 1 << 5;
+const WHEEL_RIGHT: u8 = 1 << 6;
+
+/// 客户端发来的输入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RemoteInput {
+    /// 指针位置 (屏幕坐标) 与按键掩码
+    Pointer { x: u16, y: u16, buttons: u8 },
+    Key { keysym: u32, down: bool },
+}
+
+/// 客户端请求的像素格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ClientPixelFormat {
+    bits_per_pixel: u8,
+    big_endian: bool,
+    max: [u16; 3],
+    shift: [u8; 3],
+}
+
+impl ClientPixelFormat {
+    /// 服务器的默认格式：32 位小端 XRGB
+    const DEFAULT: Self = Self { bits_per_pixel: 32, big_endian: false, max: [255; 3], shift: [16, 8, 0] };
+
+    fn parse(bytes: &[u8; 16]) -> io::Result<Self> {
+        let format = Self {
+            bits_per_pixel: bytes[0],
+            big_endian: bytes[2] != 0,
+            max: [
+                u16::from_be_bytes([bytes[4], bytes[5]]),
+                u16::from_be_bytes([bytes[6], bytes[7]]),
+                u16::from_be_bytes([bytes[8], bytes[9]]),
+            ],
+            shift: [bytes[10], bytes[11], bytes[12]],
+        };
+        if bytes[3] == 0 || !matches!(format.bits_per_pixel, 8 | 16 | 32) {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "不支持调色板或非 8/16/32 位的像素格式"));
+        }
+        Ok(format)
+    }
+
+    fn to_bytes(self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[0] = self.bits_per_pixel;
+        bytes[1] = 24;
+        bytes[2] = self.big_endian as u8;
+        bytes[3] = 1;
+        for (i, max) in self.max.iter().enumerate() {
+            bytes[4 + i * 2..6 + i * 2].copy_from_slice(&max.to_be_bytes());
+        }
+        bytes[10..13].copy_from_slice(&self.shift);
+        bytes
+    }
+
+    /// 把 0x00RRGGBB 编码为客户端格式并追加到 `out`
+    fn encode(&self, rgb: u32, out: &mut Vec<u8>) {
+        let channels = [(rgb >> 16) & 0xff, (rgb >> 8) & 0xff, rgb & 0xff];
+        let value = channels
+            .iter()
+            .zip(self.max)
+            .zip(self.shift)
+            .fold(0u32, |value, ((&channel, max), shift)| value | (channel * u32::from(max) / 255) << shift);
+        match (self.bits_per_pixel, self.big_endian) {
+            (8, _) => out.push(value as u8),
+            (16, false) => out.extend_from_slice(&(value as u16).to_le_bytes()),
+            (16, true) => out.extend_from_slice(&(value as u16).to_be_bytes()),
+            (_, false) => out.extend_from_slice(&value.to_le_bytes()),
+            (_, true) => out.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+}
+
+/// 当前客户端的更新状态
+struct ClientState {
+    /// 客户端编号，用于识别已被新连接替换的旧连接
+    id: u64,
+    /// 尚未发送的损坏区域
+    damage: Vec<Rect>,
+    /// 客户端已请求更新
+    requested: bool,
+    format: ClientPixelFormat,
+    closed: bool,
+}
+
+/// 事件循环与客户端线程共享的状态
+struct FrameState {
+    mirror: FrameMirror,
+    client: Option<ClientState>,
+    /// 等待注入窗口的输入
+    input: Vec<RemoteInput>,
+}
+
+struct Shared {
+    state: Mutex<FrameState>,
+    /// 有新的损坏区域、更新请求或连接关闭
+    changed: Condvar,
+    /// 唤醒事件循环
+    wake: Box<dyn Fn() + Send + Sync>,
+}
+
+/// VNC 服务器 (由事件循环持有)
+pub(crate) struct VncServer {
+    shared: Arc<Shared>,
+    /// 上一次指针事件的按键掩码
+    buttons: Cell<u8>,
+}
+
+impl VncServer {
+    /// 在 `addr` 上监听，`width` x `height` 为屏幕 (Framebuffer) 尺寸
+    ///
+    /// `wake` 在收到客户端输入时调用，用于唤醒事件循环。
+    pub fn start(
+        addr: SocketAddr,
+        width: u32,
+        height: u32,
+        wake: impl Fn() + Send + Sync + 'static,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(FrameState {
+                mirror: FrameMirror::new(width, height),
+                client: None,
+                input: Vec::new(),
+            }),
+            changed: Condvar::new(),
+            wake: Box::new(wake),
+        });
+        let accept_shared = shared.clone();
+        thread::Builder::new().name("linuxfb-vnc".into()).spawn(move || {
+            for (id, stream) in (0u64..).zip(listener.incoming()) {
+                match stream {
+                    Ok(stream) => spawn_client(accept_shared.clone(), stream, id),
+                    Err(e) => tracing::warn!("VNC 接受连接失败: {}", e),
+                }
+            }
+        })?;
+        tracing::info!("VNC 服务器监听 {}", addr);
+        Ok(Self { shared, buttons: Cell::new(0) })
+    }
+
+    /// 把渲染目标中 `region` (缓冲区坐标) 内的像素复制到帧副本，并通知客户端
+    ///
+    /// 没有客户端时同样更新帧副本，新连接的客户端可以立即得到完整画面。
+    pub fn update(&self, bytes: &[u8], format: PixelFormat, region: &[Rect]) {
+        let mut state = self.shared.state.lock().unwrap();
+        let damage = state.mirror.update(bytes, format, region);
+        if let Some(client) = state.client.as_mut() {
+            add_damage(&mut client.damage, damage);
+        }
+        self.shared.changed.notify_all();
+    }
+
+    /// 屏幕尺寸改变：重新分配帧副本并断开当前客户端
+    ///
+    /// RFB 的屏幕尺寸在握手时确定，客户端重新连接后得到新的尺寸。
+    pub fn resize(&self, width: u32, height: u32) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.mirror = FrameMirror::new(width, height);
+        if let Some(client) = state.client.as_mut() {
+            client.closed = true;
+        }
+        self.shared.changed.notify_all();
+    }
+
+    /// 取出客户端输入并转换为窗口事件
+    ///
+    /// 客户端坐标为屏幕坐标，按 `rotation` 转换为窗口坐标；滚轮每一格滚动 `scroll_step` 像素。
+    pub fn take_events(&self, rotation: Rotation, scroll_step: f32) -> Vec<WindowEvent> {
+        let (input, width, height) = {
+            let mut state = self.shared.state.lock().unwrap();
+            (std::mem::take(&mut state.input), state.mirror.width, state.mirror.height)
+        };
+        let mut events = Vec::new();
+        for input in input {
+            match input {
+                RemoteInput::Pointer { x, y, buttons } => {
+                    let (x, y) = rotation.inverse().rotate_point(x.into(), y.into(), width, height);
+                    let position = LogicalPosition::new(x as f32, y as f32);
+                    let previous = self.buttons.replace(buttons);
+                    events.push(WindowEvent::PointerMoved { position });
+                    for (mask, button) in [
+                        (BUTTON_LEFT, PointerEventButton::Left),
+                        (BUTTON_MIDDLE, PointerEventButton::Middle),
+                        (BUTTON_RIGHT, PointerEventButton::Right),
+                    ] {
+                        match (previous & mask != 0, buttons & mask != 0) {
+                            (false, true) => events.push(WindowEvent::PointerPressed { position, button }),
+                            (true, false) => events.push(WindowEvent::PointerReleased { position, button }),
+                            _ => {}
+                        }
+                    }
+                    // 滚轮以按下表示一格
+                    let pressed = buttons & !previous;
+                    for (mask, delta_x, delta_y) in [
+                        (WHEEL_UP, 0.0, scroll_step),
+                        (WHEEL_DOWN, 0.0, -scroll_step),
+                        (WHEEL_LEFT, scroll_step, 0.0),
+                        (WHEEL_RIGHT, -scroll_step, 0.0),
+                    ] {
+                        if pressed & mask != 0 {
+                            events.push(WindowEvent::PointerScrolled { position, delta_x, delta_y });
+                        }
+                    }
+                }
+                RemoteInput::Key { keysym, down } => {
+                    if let Some(text) = keysym_to_text(keysym) {
+                        events.push(if down {
+                            WindowEvent::KeyPressed { text }
+                        } else {
+                            WindowEvent::KeyReleased { text }
+                        });
+                    }
+                }
+            }
+        }
+        events
+    }
+}
+
+/// 合并损坏区域，数量过多时退化为外接矩形
+fn add_damage(damage: &mut Vec<Rect>, new: Vec<Rect>) {
+    for rect in new {
+        if !damage.contains(&rect) {
+            damage.push(rect);
+        }
+    }
+    if damage.len() > MAX_DAMAGE_RECTS {
+        let x0 = damage.iter().map(|r| r.x).min().unwrap_or(0);
+        let y0 = damage.iter().map(|r| r.y).min().unwrap_or(0);
+        let x1 = damage.iter().map(|r| r.x + r.w).max().unwrap_or(0);
+        let y1 = damage.iter().map(|r| r.y + r.h).max().unwrap_or(0);
+        *damage = vec![Rect { x: x0, y: y0, w: x1 - x0, h: y1 - y0 }];
+    }
+}
+
+/// 把 X11 keysym 转换为 Slint 按键文本
+fn keysym_to_text(keysym: u32) -> Option<SharedString> {
+    let special = match keysym {
+        0xff08 => key_codes::Backspace,
+        0xff09 => key_codes::Tab,
+        0xfe20 => key_codes::Backtab,
+        0xff0d | 0xff8d => key_codes::Return,
+        0xff1b => key_codes::Escape,
+        0xffff => key_codes::Delete,
+        0xff63 => key_codes::Insert,
+        0xff50 => key_codes::Home,
+        0xff57 => key_codes::End,
+        0xff55 => key_codes::PageUp,
+        0xff56 => key_codes::PageDown,
+        0xff51 => key_codes::LeftArrow,
+        0xff52 => key_codes::UpArrow,
+        0xff53 => key_codes::RightArrow,
+        0xff54 => key_codes::DownArrow,
+        0xffe1 => key_codes::Shift,
+        0xffe2 => key_codes::ShiftR,
+        0xffe3 => key_codes::Control,
+        0xffe4 => key_codes::ControlR,
+        0xffe9 => key_codes::Alt,
+        0xffea | 0xfe03 => key_codes::AltGr,
+        0xffe7 | 0xffeb => key_codes::Meta,
+        0xffe8 | 0xffec => key_codes::MetaR,
+        0xffbe..=0xffc9 => {
+            const F_KEYS: [char; 12] = [
+                key_codes::F1,
+                key_codes::F2,
+                key_codes::F3,
+                key_codes::F4,
+                key_codes::F5,
+                key_codes::F6,
+                key_codes::F7,
+                key_codes::F8,
+                key_codes::F9,
+                key_codes::F10,
+                key_codes::F11,
+                key_codes::F12,
+            ];
+            F_KEYS[(keysym - 0xffbe) as usize]
+        }
+        // Latin-1 与直接编码的 Unicode keysym
+        0x20..=0x7e | 0xa0..=0xff => char::from_u32(keysym)?,
+        0x0100_0000..=0x0110_ffff => char::from_u32(keysym - 0x0100_0000)?,
+        _ => return None,
+    };
+    Some(special.into())
+}
+
+fn spawn_client(shared: Arc<Shared>, stream: TcpStream, id: u64) {
+    let peer = stream.peer_addr().map_or_else(|_| "?".to_string(), |addr| addr.to_string());
+    let result = thread::Builder::new().name("linuxfb-vnc-client".into()).spawn(move || {
+        tracing::info!("VNC 客户端已连接: {}", peer);
+        if let Err(e) = serve_client(&shared, stream, id) {
+            tracing::info!("VNC 客户端 {} 断开: {}", peer, e);
+        }
+        let mut state = shared.state.lock().unwrap();
+        if state.client.as_ref().is_some_and(|client| client.id == id) {
+            state.client = None;
+        }
+        shared.changed.notify_all();
+    });
+    if let Err(e) = result {
+        tracing::warn!("无法创建 VNC 客户端线程: {}", e);
+    }
+}
+
+/// 握手后在当前线程读取客户端消息，另起线程发送帧更新
+fn serve_client(shared: &Arc<Shared>, mut stream: TcpStream, id: u64) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let (width, height) = handshake(shared, &mut stream)?;
+
+    // 替换旧连接
+    {
+        let mut state = shared.state.lock().unwrap();
+        if let Some(old) = state.client.as_mut() {
+            old.closed = true;
+        }
+        state.client = Some(ClientState {
+            id,
+            damage: vec![Rect { x: 0, y: 0, w: width as i32, h: height as i32 }],
+            requested: false,
+            format: ClientPixelFormat::DEFAULT,
+            closed: false,
+        });
+        shared.changed.notify_all();
+    }
+
+    let writer_shared = shared.clone();
+    let writer_stream = stream.try_clone()?;
+    thread::Builder::new().name("linuxfb-vnc-send".into()).spawn(move || {
+        let mut stream = writer_stream;
+        if let Err(e) = send_updates(&writer_shared, &mut stream, id) {
+            tracing::debug!("VNC 发送失败: {}", e);
+        }
+        let _ = stream.shutdown(Shutdown::Both);
+    })?;
+
+    let mut reader = BufReader::new(stream);
+    loop {
+        let message_type = read_u8(&mut reader)?;
+        match message_type {
+            // SetPixelFormat
+            0 => {
+                let mut bytes = [0u8; 19];
+                reader.read_exact(&mut bytes)?;
+                let format = ClientPixelFormat::parse(bytes[3..].try_into().unwrap())?;
+                with_client(shared, id, |client| client.format = format)?;
+            }
+            // SetEncodings：只使用 Raw 编码，忽略客户端的偏好
+            2 => {
+                let mut header = [0u8; 3];
+                reader.read_exact(&mut header)?;
+                let count = u16::from_be_bytes([header[1], header[2]]) as usize;
+                io::copy(&mut (&mut reader).take(count as u64 * 4), &mut io::sink())?;
+            }
+            // FramebufferUpdateRequest
+            3 => {
+                let mut bytes = [0u8; 9];
+                reader.read_exact(&mut bytes)?;
+                let incremental = bytes[0] != 0;
+                with_client(shared, id, |client| {
+                    if !incremental {
+                        client.damage = vec![Rect { x: 0, y: 0, w: width as i32, h: height as i32 }];
+                    }
+                    client.requested = true;
+                })?;
+            }
+            // KeyEvent
+            4 => {
+                let mut bytes = [0u8; 7];
+                reader.read_exact(&mut bytes)?;
+                let keysym = u32::from_be_bytes([bytes[3], bytes[4], bytes[5], bytes[6]]);
+                push_input(shared, RemoteInput::Key { keysym, down: bytes[0] != 0 });
+            }
+            // PointerEvent
+            5 => {
+                let mut bytes = [0u8; 5];
+                reader.read_exact(&mut bytes)?;
+                let x = u16::from_be_bytes([bytes[1], bytes[2]]);
+                let y = u16::from_be_bytes([bytes[3], bytes[4]]);
+                push_input(shared, RemoteInput::Pointer { x, y, buttons: bytes[0] });
+            }
+            // ClientCutText：不支持，丢弃
+            6 => {
+                let mut bytes = [0u8; 7];
+                reader.read_exact(&mut bytes)?;
+                let length = u32::from_be_bytes([bytes[3], bytes[4], bytes[5], bytes[6]]);
+                io::copy(&mut (&mut reader).take(length.into()), &mut io::sink())?;
+            }
+            other => {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!("未知的客户端消息类型 {}", other)));
+            }
+        }
+    }
+}
+
+/// RFB 握手：协商版本、"None" 安全类型，发送 ServerInit，返回屏幕尺寸
+fn handshake(shared: &Shared, stream: &mut TcpStream) -> io::Result<(u32, u32)> {
+    stream.write_all(b"RFB 003.008\n")?;
+    let mut version = [0u8; 12];
+    stream.read_exact(&mut version)?;
+    let minor = match &version {
+        b"RFB 003.003\n" => 3,
+        b"RFB 003.007\n" => 7,
+        v if v.starts_with(b"RFB 003.") => 8,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "不是 RFB 客户端")),
+    };
+    if minor == 3 {
+        // 3.3：服务器直接指定安全类型
+        stream.write_all(&1u32.to_be_bytes())?;
+    } else {
+        stream.write_all(&[1, 1])?;
+        if read_u8(stream)? != 1 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "客户端选择了不支持的安全类型"));
+        }
+        if minor == 8 {
+            stream.write_all(&0u32.to_be_bytes())?;
+        }
+    }
+    // ClientInit (共享标志)，始终只服务一个客户端
+    read_u8(stream)?;
+
+    let (width, height) = {
+        let state = shared.state.lock().unwrap();
+        (state.mirror.width, state.mirror.height)
+    };
+    let mut init = Vec::new();
+    init.extend_from_slice(&(width as u16).to_be_bytes());
+    init.extend_from_slice(&(height as u16).to_be_bytes());
+    init.extend_from_slice(&ClientPixelFormat::DEFAULT.to_bytes());
+    init.extend_from_slice(&(DESKTOP_NAME.len() as u32).to_be_bytes());
+    init.extend_from_slice(DESKTOP_NAME.as_bytes());
+    stream.write_all(&init)?;
+    Ok((width, height))
+}
+
+/// 等待客户端请求且有损坏区域时发送 FramebufferUpdate
+fn send_updates(shared: &Shared, stream: &mut TcpStream, id: u64) -> io::Result<()> {
+    let mut message = Vec::new();
+    loop {
+        {
+            let mut state = shared.state.lock().unwrap();
+            loop {
+                match state.client.as_ref() {
+                    Some(client) if client.id == id && !client.closed => {
+                        if client.requested && !client.damage.is_empty() {
+                            break;
+                        }
+                    }
+                    _ => return Ok(()),
+                }
+                state = shared.changed.wait(state).unwrap();
+            }
+            let width = state.mirror.width as i32;
+            let client = state.client.as_mut().unwrap();
+            let damage = std::mem::take(&mut client.damage);
+            let format = client.format;
+            client.requested = false;
+
+            message.clear();
+            message.extend_from_slice(&[0, 0]);
+            message.extend_from_slice(&(damage.len() as u16).to_be_bytes());
+            for rect in damage {
+                for value in [rect.x, rect.y, rect.w, rect.h] {
+                    message.extend_from_slice(&(value as u16).to_be_bytes());
+                }
+                // Raw 编码
+                message.extend_from_slice(&0i32.to_be_bytes());
+                for y in rect.y..rect.y + rect.h {
+                    let row = (y * width + rect.x) as usize;
+                    for &pixel in &state.mirror.pixels[row..row + rect.w as usize] {
+                        format.encode(pixel, &mut message);
+                    }
+                }
+            }
+        }
+        stream.write_all(&message)?;
+    }
+}
+
+fn with_client(shared: &Shared, id: u64, f: impl FnOnce(&mut ClientState)) -> io::Result<()> {
+    let mut state = shared.state.lock().unwrap();
+    match state.client.as_mut().filter(|client| client.id == id && !client.closed) {
+        Some(client) => {
+            f(client);
+            shared.changed.notify_all();
+            Ok(())
+        }
+        None => Err(io::Error::new(io::ErrorKind::ConnectionAborted, "连接已被新的客户端替换")),
+    }
+}
+
+fn push_input(shared: &Shared, input: RemoteInput) {
+    shared.state.lock().unwrap().input.push(input);
+    (shared.wake)();
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixel_and_key_conversion() {
+        let mut out = Vec::new();
+        ClientPixelFormat::DEFAULT.encode(0x0012_3456, &mut out);
+        assert_eq!(out, [0x56, 0x34, 0x12, 0x00]);
+        let rgb565 = ClientPixelFormat { bits_per_pixel: 16, big_endian: false, max: [31, 63, 31], shift: [11, 5, 0] };
+        out.clear();
+        rgb565.encode(0x00ff_0000, &mut out);
+        assert_eq!(out, 0xf800u16.to_le_bytes());
+        assert_eq!(ClientPixelFormat::parse(&rgb565.to_bytes()).unwrap(), rgb565);
+
+        assert_eq!(keysym_to_text(0x61), Some("a".into()));
+        assert_eq!(keysym_to_text(0xff0d), Some(key_codes::Return.into()));
+        assert_eq!(keysym_to_text(0x0100_4e2d), Some("中".into()));
+        assert_eq!(keysym_to_text(0xfd01), None);
+    }
+}
@@ -0,0 +1,133 @@
+//! 事件循环卡顿看门狗
+//!
+//! 部分驱动在 `FBIO_WAITFORVSYNC` 或其他 ioctl 中可能长时间不返回，
+//! 此时事件循环线程被阻塞，无法自行发现问题。看门狗在独立线程中监视循环的心跳，
+//! 一旦某次迭代的耗时超过预算，就在看门狗线程上调用用户回调 (或记录日志)。
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// 卡顿回调：参数为当前迭代已经持续的时长，在看门狗线程上调用
+pub type StallCallback = Box<dyn Fn(Duration) + Send + Sync>;
+
+/// 事件循环与看门狗线程共享的状态
+struct Heartbeat {
+    /// 计时基准
+    epoch: Instant,
+    /// 当前迭代开始的时间 (相对 `epoch` 的纳秒数，加 1 以区分)，0 表示循环空闲
+    iteration_start: AtomicU64,
+    /// 当前迭代是否已经报告过卡顿
+    reported: AtomicBool,
+    stop: AtomicBool,
+}
+
+/// 事件循环看门狗
+pub(crate) struct Watchdog {
+    budget: Duration,
+    heartbeat: Arc<Heartbeat>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// 启动看门狗线程
+    pub fn new(budget: Duration, callback: Option<StallCallback>) -> std::io::Result<Self> {
+        let heartbeat = Arc::new(Heartbeat {
+            epoch: Instant::now(),
+            iteration_start: AtomicU64::new(0),
+            reported: AtomicBool::new(false),
+            stop: AtomicBool::new(false),
+        });
+
+        let shared = heartbeat.clone();
+        // 检查间隔取预算的 1/4，使卡顿在超出预算后不久即被发现
+        let check_interval = (budget / 4).max(Duration::from_millis(1));
+        let handle = thread::Builder::new()
+            .name("linuxfb-watchdog".into())
+            .spawn(move || {
+                while !shared.stop.load(Ordering::Relaxed) {
+                    thread::park_timeout(check_interval);
+
+                    let start = shared.iteration_start.load(Ordering::Acquire);
+                    if start == 0 || shared.reported.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    let now = shared.epoch.elapsed().as_nanos() as u64 + 1;
+                    let stalled = Duration::from_nanos(now.saturating_sub(start));
+                    if stalled > budget {
+                        shared.reported.store(true, Ordering::Relaxed);
+                        match &callback {
+                            Some(callback) => callback(stalled),
+                            None => tracing::error!(
+                                "事件循环已卡顿 {:?} (预算 {:?})，可能是驱动 ioctl 未返回",
+                                stalled,
+                                budget
+                            ),
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self { budget, heartbeat, handle: Some(handle) })
+    }
+
+    /// 单次迭代的耗时预算
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// 标记一次迭代开始
+    pub fn iteration_started(&self) {
+        let now = self.heartbeat.epoch.elapsed().as_nanos() as u64 + 1;
+        self.heartbeat.reported.store(false, Ordering::Relaxed);
+        self.heartbeat.iteration_start.store(now, Ordering::Release);
+    }
+
+    /// 标记一次迭代结束 (即将进入休眠)，返回本次迭代的耗时
+    pub fn iteration_finished(&self) -> Duration {
+        let start = self.heartbeat.iteration_start.swap(0, Ordering::AcqRel);
+        if start == 0 {
+            return Duration::ZERO;
+        }
+        let now = self.heartbeat.epoch.elapsed().as_nanos() as u64 + 1;
+        Duration::from_nanos(now.saturating_sub(start))
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.heartbeat.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_stall_reported_once() {
+        let (sender, receiver) = channel();
+        let sender = std::sync::Mutex::new(sender);
+        let watchdog = Watchdog::new(
+            Duration::from_millis(10),
+            Some(Box::new(move |stalled| {
+                let _ = sender.lock().unwrap().send(stalled);
+            })),
+        )
+        .unwrap();
+
+        watchdog.iteration_started();
+        thread::sleep(Duration::from_millis(50));
+        assert!(watchdog.iteration_finished() >= Duration::from_millis(50));
+
+        let stalled = receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(stalled > Duration::from_millis(10));
+        assert!(receiver.try_recv().is_err());
+    }
+}
@@ -1,54 +1,340 @@
+use crate::canvas::Rect;
 use crate::error::Error;
 use crate::pixels::{PixelAbgr8888, PixelBgra8888, PixelFormat, PixelRgb565, PixelRgba8888};
-use i_slint_core::platform::{software_renderer::SoftwareRenderer, WindowAdapter};
-use crate::linuxfb::double;
-use std::cell::RefCell;
+use crate::osd::OsdLayers;
+use crate::overlay::DebugOverlay;
+use crate::presenter::{Presenter, SwapDamage};
+use crate::virtual_keyboard::VirtualKeyboard;
+use i_slint_core::api::PhysicalSize;
+use i_slint_core::lengths::{LogicalPoint, LogicalRect, LogicalSize};
+use i_slint_core::partial_renderer::DirtyRegion;
+use i_slint_core::platform::software_renderer::{
+    PhysicalRegion, PremultipliedRgbaColor, RenderingRotation, SoftwareRenderer, TargetPixel,
+};
+use i_slint_core::platform::WindowAdapter;
+use i_slint_core::renderer::RendererSealed;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
+/// 显示旋转方向 (顺时针)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    Rotate0,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+impl Rotation {
+    /// 旋转 90 或 270 度时宽高互换
+    pub fn is_transposed(self) -> bool {
+        matches!(self, Rotation::Rotate90 | Rotation::Rotate270)
+    }
+
+    /// 反方向的旋转
+    pub fn inverse(self) -> Self {
+        match self {
+            Rotation::Rotate90 => Rotation::Rotate270,
+            Rotation::Rotate270 => Rotation::Rotate90,
+            other => other,
+        }
+    }
+
+    pub(crate) fn to_rendering_rotation(self) -> RenderingRotation {
+        match self {
+            Rotation::Rotate0 => RenderingRotation::NoRotation,
+            Rotation::Rotate90 => RenderingRotation::Rotate90,
+            Rotation::Rotate180 => RenderingRotation::Rotate180,
+            Rotation::Rotate270 => RenderingRotation::Rotate270,
+        }
+    }
+
+    /// 旋转后的尺寸
+    pub fn rotate_size(self, width: u32, height: u32) -> (u32, u32) {
+        if self.is_transposed() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// 将尺寸为 `width` x `height` 的区域中的点旋转到目标区域
+    ///
+    /// 与 Slint 软件渲染器的变换一致：以窗口坐标调用得到屏幕坐标，
+    /// 以 [`Rotation::inverse`] 和屏幕坐标调用则得到窗口坐标。
+    pub fn rotate_point(self, x: i32, y: i32, width: u32, height: u32) -> (i32, i32) {
+        let (width, height) = (width as i32, height as i32);
+        match self {
+            Rotation::Rotate0 => (x, y),
+            Rotation::Rotate90 => (height - 1 - y, x),
+            Rotation::Rotate180 => (width - 1 - x, height - 1 - y),
+            Rotation::Rotate270 => (y, width - 1 - x),
+        }
+    }
+
+    /// 旋转矩形，参数含义与 [`Rotation::rotate_point`] 相同
+    pub(crate) fn rotate_rect(self, rect: Rect, width: u32, height: u32) -> Rect {
+        if rect.w <= 0 || rect.h <= 0 {
+            return rect;
+        }
+        let (x0, y0) = self.rotate_point(rect.x, rect.y, width, height);
+        let (x1, y1) = self.rotate_point(rect.x + rect.w - 1, rect.y + rect.h - 1, width, height);
+        Rect {
+            x: x0.min(x1),
+            y: y0.min(y1),
+            w: (x1 - x0).abs() + 1,
+            h: (y1 - y0).abs() + 1,
+        }
+    }
+}
+
+/// 窗口在屏幕 (旋转前，`screen_width` x `screen_height`) 上占据的区域
+///
+/// `window_size` 为旋转后的窗口尺寸，窗口居中显示，超出屏幕的部分被截掉；`None` 表示占满屏幕。
+pub(crate) fn letterbox_rect(window_size: Option<(u32, u32)>, rotation: Rotation, screen_width: u32, screen_height: u32) -> Rect {
+    let Some((width, height)) = window_size else {
+        return Rect { x: 0, y: 0, w: screen_width as i32, h: screen_height as i32 };
+    };
+    let (width, height) = rotation.rotate_size(width, height);
+    let (width, height) = (width.min(screen_width), height.min(screen_height));
+    Rect {
+        x: ((screen_width - width) / 2) as i32,
+        y: ((screen_height - height) / 2) as i32,
+        w: width as i32,
+        h: height as i32,
+    }
+}
+
+/// 屏幕上窗口 `window` 之外的区域 (上、下、左、右，省略空的部分)
+pub(crate) fn border_rects(screen_width: u32, screen_height: u32, window: Rect) -> Vec<Rect> {
+    let (screen_width, screen_height) = (screen_width as i32, screen_height as i32);
+    let bottom = window.y + window.h;
+    let right = window.x + window.w;
+    [
+        Rect { x: 0, y: 0, w: screen_width, h: window.y },
+        Rect { x: 0, y: bottom, w: screen_width, h: screen_height - bottom },
+        Rect { x: 0, y: window.y, w: window.x, h: window.h },
+        Rect { x: right, y: window.y, w: screen_width - right, h: window.h },
+    ]
+    .into_iter()
+    .filter(|rect| rect.w > 0 && rect.h > 0)
+    .collect()
+}
+
+/// 窗口小于屏幕时的渲染缓冲区 (窗口大小，内容在帧之间保留)
+///
+/// Slint 要求渲染目标的每一行都有完整的 stride，窗口不贴着屏幕左边缘又延伸到最后一行时无法直接渲染到屏幕的子区域，
+/// 因此先渲染到这里，再把重绘区域复制到屏幕上的窗口区域。
+#[derive(Default)]
+pub(crate) struct WindowBuffer {
+    pixels: Vec<u8>,
+    /// 上一帧复制的区域 (窗口缓冲区坐标)：缓冲区交替使用时 (直接模式和呈现线程模式)，当前的后缓冲区还缺少这些内容
+    previous: Vec<Rect>,
+}
+
 pub struct LinuxFbWindowAdapter {
     pub window: Rc<i_slint_core::api::Window>,
-    pub fb_buffer: RefCell<double::Buffer>,
+    /// 帧呈现方式 (直接渲染到 Framebuffer 或经由呈现线程)
+    pub presenter: RefCell<Presenter>,
     pub renderer: SoftwareRenderer,
-    pub pixel_format: PixelFormat,
+    /// 直接模式下前后缓冲区在渲染器之外被改写的区域
+    pub(crate) swap_damage: RefCell<SwapDamage>,
+    /// 渲染目标的像素格式 (显示模式被外部改变后随之更新)
+    pub pixel_format: Cell<PixelFormat>,
+    /// 显示旋转方向 (窗口尺寸为旋转后的尺寸)
+    pub rotation: Rotation,
     pub needs_redraw: RefCell<bool>,
+    /// 调试叠加层 (仅在构建器启用时存在)
+    pub debug_overlay: Option<RefCell<DebugOverlay>>,
+    /// 屏幕虚拟键盘 (仅在构建器启用时存在)
+    pub virtual_keyboard: Option<RefCell<VirtualKeyboard>>,
+    /// 应用设置的 OSD 图层 (与平台共享)
+    pub(crate) osd: Rc<RefCell<OsdLayers>>,
+    /// 软件亮度 (0.0 ~ 1.0)，小于 1 时把每帧重绘的区域压暗
+    ///
+    /// 只处理重绘区域，因此修改后需要调用 [`invalidate_all`](Self::invalidate_all)。
+    pub brightness: Cell<f32>,
+    /// 窗口是否可见；隐藏期间不渲染也不翻转
+    pub visible: Cell<bool>,
+    /// 可见性改变，等待事件循环处理 (隐藏时清空屏幕)
+    pub visibility_changed: Cell<bool>,
+    /// 窗口尺寸 (旋转后)，`None` 表示占满屏幕
+    pub(crate) window_size: Option<(u32, u32)>,
+    /// 设置了窗口尺寸时 Slint 渲染到的缓冲区
+    pub(crate) window_buffer: RefCell<WindowBuffer>,
 }
 
 impl LinuxFbWindowAdapter {
     /// 负责在 `draw_if_needed` 闭包中实际执行渲染
     /// 它在运行时分发到正确的 TargetPixel 实现
-    pub fn render_frame(&self, renderer: &SoftwareRenderer) -> Result<(), Error> {
-        // 1. 获取 fb_buffer 的可变借用
-        let mut fb_buffer = self.fb_buffer.borrow_mut();
+    ///
+    /// 返回本帧重绘的区域；如果启用了虚拟键盘或调试叠加层，会在渲染结果上绘制它们。
+    /// 设置了窗口尺寸时区域相对于窗口在屏幕上的左上角。
+    pub fn render_frame(&self, renderer: &SoftwareRenderer) -> Result<PhysicalRegion, Error> {
+        // 1. 获取 presenter 的可变借用
+        let mut presenter = self.presenter.borrow_mut();
 
         // 2. 获取所有不可变属性 (stride)
         //    stride 是像素数量，不是字节数
-        let stride = fb_buffer.width as usize;
+        let (width, height) = presenter.size();
+        let stride = width as usize;
+        let window = letterbox_rect(self.window_size, self.rotation, width, height);
+        let swapped = matches!(*presenter, Presenter::Direct(_) | Presenter::Threaded(_));
 
-        // 3. 获取可变切片
-        let mmap_slice: &mut [u8] = fb_buffer.as_mut_slice();
+        // 3. 获取渲染目标 (后缓冲区或影子缓冲区) 的可变切片
+        //    并运行时分发到正确的 TargetPixel 实现
+        presenter.with_render_target(|mmap_slice| {
+            if self.window_size.is_none() {
+                return self.render_dispatch(renderer, mmap_slice, stride, PhysicalSize::new(width, height));
+            }
 
-        // 4. 运行时分发到正确的 TargetPixel 实现
-        match self.pixel_format {
-            PixelFormat::Abgr8888 => {
-                let pixel_slice: &mut [PixelAbgr8888] = bytemuck::cast_slice_mut(mmap_slice);
-                renderer.render(pixel_slice, stride);
+            // 窗口小于屏幕：渲染到窗口缓冲区，再复制本帧 (以及直接模式下上一帧) 的重绘区域
+            let bytes_per_pixel = self.pixel_format.get().bytes_per_pixel();
+            let mut buffer = self.window_buffer.borrow_mut();
+            let len = window.w as usize * window.h as usize * bytes_per_pixel;
+            if buffer.pixels.len() != len {
+                buffer.pixels = vec![0; len];
+                buffer.previous.clear();
+                self.mark_all_dirty();
             }
-            PixelFormat::Rgba8888 => {
-                let pixel_slice: &mut [PixelRgba8888] = bytemuck::cast_slice_mut(mmap_slice);
-                renderer.render(pixel_slice, stride);
+            let size = PhysicalSize::new(window.w as u32, window.h as u32);
+            let region = self.render_dispatch(renderer, &mut buffer.pixels, window.w as usize, size)?;
+
+            let rects: Vec<Rect> = region
+                .iter()
+                .map(|(origin, size)| Rect { x: origin.x, y: origin.y, w: size.width as i32, h: size.height as i32 })
+                .collect();
+            let previous = std::mem::replace(&mut buffer.previous, if swapped { rects.clone() } else { Vec::new() });
+            for rect in rects.iter().chain(&previous) {
+                let row_len = rect.w as usize * bytes_per_pixel;
+                for y in rect.y..rect.y + rect.h {
+                    let src = (y as usize * window.w as usize + rect.x as usize) * bytes_per_pixel;
+                    let dst = ((window.y + y) as usize * stride + (window.x + rect.x) as usize) * bytes_per_pixel;
+                    mmap_slice[dst..dst + row_len].copy_from_slice(&buffer.pixels[src..src + row_len]);
+                }
             }
-            PixelFormat::Bgra8888 => {
-                let pixel_slice: &mut [PixelBgra8888] = bytemuck::cast_slice_mut(mmap_slice);
-                renderer.render(pixel_slice, stride);
+            Ok(region)
+        })
+    }
+
+    /// 按像素格式分发到 [`render_pixels`](Self::render_pixels)
+    fn render_dispatch(
+        &self,
+        renderer: &SoftwareRenderer,
+        bytes: &mut [u8],
+        stride: usize,
+        size: PhysicalSize,
+    ) -> Result<PhysicalRegion, Error> {
+        let region = match self.pixel_format.get() {
+            PixelFormat::Abgr8888 => self.render_pixels::<PixelAbgr8888>(renderer, bytes, stride, size),
+            PixelFormat::Rgba8888 => self.render_pixels::<PixelRgba8888>(renderer, bytes, stride, size),
+            PixelFormat::Bgra8888 => self.render_pixels::<PixelBgra8888>(renderer, bytes, stride, size),
+            PixelFormat::Rgb565 => self.render_pixels::<PixelRgb565>(renderer, bytes, stride, size),
+            _ => return Err(Error::UnsupportedPixelFormat),
+        };
+        Ok(region)
+    }
+
+    /// 窗口在屏幕 (旋转前) 上占据的区域
+    pub(crate) fn window_rect(&self) -> Rect {
+        let (width, height) = self.presenter.borrow().size();
+        letterbox_rect(self.window_size, self.rotation, width, height)
+    }
+
+    /// 窗口左上角在旋转后的屏幕坐标中的位置，输入设备报告的坐标减去它得到窗口坐标
+    pub(crate) fn window_origin(&self) -> (i32, i32) {
+        let (width, height) = self.presenter.borrow().size();
+        let window = letterbox_rect(self.window_size, self.rotation, width, height);
+        let window = self.rotation.inverse().rotate_rect(window, width, height);
+        (window.x, window.y)
+    }
+
+    /// 将整个窗口标记为脏区并请求重绘
+    ///
+    /// 用于 Framebuffer 内容可能已被外部改变的场合 (例如系统挂起恢复后)。
+    pub fn invalidate_all(&self) {
+        self.mark_all_dirty();
+        self.request_redraw();
+    }
+
+    /// 将整个窗口标记为脏区 (不请求重绘)
+    fn mark_all_dirty(&self) {
+        let size = self.size().to_logical(self.window.scale_factor());
+        let mut region = DirtyRegion::default();
+        region.add_rect(LogicalRect::new(
+            LogicalPoint::default(),
+            LogicalSize::new(size.width, size.height),
+        ));
+        self.renderer.mark_dirty_region(region);
+    }
+
+    /// 将屏幕坐标 (旋转后的物理像素) 中的区域标记为脏区，在下一次渲染时重绘 (不请求重绘)
+    pub(crate) fn mark_screen_rects_dirty(&self, rects: &[Rect]) {
+        if rects.is_empty() {
+            return;
+        }
+        let window = self.window_rect();
+        let (width, height) = (window.w as u32, window.h as u32);
+        let scale_factor = self.window.scale_factor();
+        let mut region = DirtyRegion::default();
+        for rect in rects {
+            // 转换到窗口区域内的坐标，只保留与窗口相交的部分
+            let x0 = (rect.x - window.x).max(0);
+            let y0 = (rect.y - window.y).max(0);
+            let x1 = (rect.x + rect.w - window.x).min(window.w);
+            let y1 = (rect.y + rect.h - window.y).min(window.h);
+            if x1 <= x0 || y1 <= y0 {
+                continue;
             }
-            PixelFormat::Rgb565 => {
-                let pixel_slice: &mut [PixelRgb565] = bytemuck::cast_slice_mut(mmap_slice);
-                renderer.render(pixel_slice, stride);
+            let rect = Rect { x: x0, y: y0, w: x1 - x0, h: y1 - y0 };
+            let rect = self.rotation.inverse().rotate_rect(rect, width, height);
+            region.add_rect(LogicalRect::new(
+                LogicalPoint::new(rect.x as f32 / scale_factor, rect.y as f32 / scale_factor),
+                LogicalSize::new(rect.w as f32 / scale_factor, rect.h as f32 / scale_factor),
+            ));
+        }
+        self.renderer.mark_dirty_region(region);
+    }
+
+    /// 将字节切片转换为具体的像素类型并渲染
+    fn render_pixels<P: TargetPixel + bytemuck::Pod>(
+        &self,
+        renderer: &SoftwareRenderer,
+        bytes: &mut [u8],
+        stride: usize,
+        size: PhysicalSize,
+    ) -> PhysicalRegion {
+        let pixel_slice: &mut [P] = bytemuck::cast_slice_mut(bytes);
+        let region = renderer.render(pixel_slice, stride);
+
+        if let Some(keyboard) = &self.virtual_keyboard {
+            keyboard.borrow_mut().draw(pixel_slice, stride, size, self.rotation);
+        }
+
+        self.osd.borrow_mut().draw(pixel_slice, stride, size, self.rotation);
+
+        if let Some(overlay) = &self.debug_overlay {
+            overlay.borrow_mut().draw(pixel_slice, stride, size, self.rotation, &region);
+        }
+
+        let brightness = self.brightness.get();
+        if brightness < 1.0 {
+            let shade = PremultipliedRgbaColor {
+                red: 0,
+                green: 0,
+                blue: 0,
+                alpha: ((1.0 - brightness.max(0.0)) * 255.0).round() as u8,
+            };
+            for (origin, rect_size) in region.iter() {
+                for y in origin.y..origin.y + rect_size.height as i32 {
+                    let start = y as usize * stride + origin.x as usize;
+                    P::blend_slice(&mut pixel_slice[start..start + rect_size.width as usize], shade);
+                }
             }
-            _ => return Err(Error::UnsupportedPixelFormat),
         }
 
-        Ok(())
+        region
     }
 }
 
@@ -65,8 +351,60 @@ impl WindowAdapter for LinuxFbWindowAdapter {
         *self.needs_redraw.borrow_mut() = true;
     }
 
+    fn set_visible(&self, visible: bool) -> Result<(), i_slint_core::api::PlatformError> {
+        if self.visible.replace(visible) != visible {
+            self.visibility_changed.set(true);
+            if visible {
+                // 隐藏期间屏幕被清空，重新显示时整屏重绘
+                self.invalidate_all();
+            }
+        }
+        Ok(())
+    }
+
     fn size(&self) -> i_slint_core::api::PhysicalSize {
-        let fb = self.fb_buffer.borrow();
-        i_slint_core::api::PhysicalSize::new(fb.width, fb.height)
+        let window = self.window_rect();
+        let (width, height) = self.rotation.rotate_size(window.w as u32, window.h as u32);
+        i_slint_core::api::PhysicalSize::new(width, height)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_round_trip() {
+        // 800x480 的屏幕旋转 90 度后窗口为 480x800，窗口左上角位于屏幕右上角
+        let rotation = Rotation::Rotate90;
+        assert_eq!(rotation.rotate_size(800, 480), (480, 800));
+        assert_eq!(rotation.rotate_point(0, 0, 480, 800), (799, 0));
+        assert_eq!(rotation.inverse().rotate_point(799, 0, 800, 480), (0, 0));
+
+        for rotation in [Rotation::Rotate0, Rotation::Rotate90, Rotation::Rotate180, Rotation::Rotate270] {
+            let (width, height) = rotation.rotate_size(800, 480);
+            let (x, y) = rotation.rotate_point(10, 20, 800, 480);
+            assert_eq!(rotation.inverse().rotate_point(x, y, width, height), (10, 20));
+        }
+
+        let rect = Rect { x: 10, y: 20, w: 30, h: 40 };
+        assert_eq!(Rotation::Rotate270.rotate_rect(rect, 480, 800), Rect { x: 20, y: 440, w: 40, h: 30 });
+    }
+
+    #[test]
+    fn test_letterbox_rect() {
+        assert_eq!(letterbox_rect(None, Rotation::Rotate0, 800, 480), Rect { x: 0, y: 0, w: 800, h: 480 });
+        // 4:3 的窗口在 800x480 的屏幕上左右留边，紧贴最后一行
+        let window = letterbox_rect(Some((640, 480)), Rotation::Rotate0, 800, 480);
+        assert_eq!(window, Rect { x: 80, y: 0, w: 640, h: 480 });
+        assert_eq!(
+            border_rects(800, 480, window),
+            [Rect { x: 0, y: 0, w: 80, h: 480 }, Rect { x: 720, y: 0, w: 80, h: 480 }]
+        );
+        // 旋转 90 度时窗口尺寸为旋转后的尺寸，超出屏幕的部分被截掉
+        assert_eq!(
+            letterbox_rect(Some((400, 1000)), Rotation::Rotate90, 800, 480),
+            Rect { x: 0, y: 40, w: 800, h: 400 }
+        );
+        assert!(border_rects(800, 480, Rect { x: 0, y: 0, w: 800, h: 480 }).is_empty());
+    }
+}